@@ -0,0 +1,136 @@
+// Fuzz MappingEngine::process_batch with arbitrary event sequences
+//
+// Input format: the first byte picks the engine settings, then every 6 bytes are
+// one event: [kind, code, value as i32 little-endian]. `kind % 3` is a button,
+// an axis or a sync; `code` indexes `ButtonCode::all()`/`AxisCode::all()`, one past
+// the end being `Unknown`. Buttons are pressed when the value is non-zero.
+#![no_main]
+
+use std::time::{Duration, Instant};
+
+use blazeremap::{
+    event::{AxisCode, ButtonCode, InputEvent, OutputEvent},
+    mapping::{
+        MappingEngine,
+        profile::{Profile, ProfileFormat},
+    },
+};
+use libfuzzer_sys::fuzz_target;
+
+/// Every kind of rule, so all engine paths are reachable
+const PROFILE: &str = r#"name = "fuzz"
+description = "Every rule kind"
+
+[[mappings]]
+source_name = "South"
+target_type = "Keyboard"
+target_name = "Space"
+
+[[mappings]]
+source_name = "South"
+target_type = "Keyboard"
+target_name = "Enter"
+
+[[mappings]]
+source_name = "North"
+target_type = "Keyboard"
+target_name = "R"
+
+[[mappings]]
+source_name = "North"
+target_type = "Keyboard"
+target_name = "F"
+condition_modifier = "Left Shoulder"
+priority = "high"
+
+[[mappings]]
+source_name = "West"
+target_type = "Keyboard"
+target_name = "E"
+condition_modifier = "Right Shoulder"
+
+[[mappings]]
+source_name = "East"
+target_type = "Rumble"
+target_name = "50:100:200"
+
+[[mappings]]
+source_name = "Mode"
+target_type = "Media"
+target_name = "Play/Pause"
+
+[[mappings]]
+source_name = "DPadY"
+source_direction = "Negative"
+target_type = "Keyboard"
+target_name = "Up"
+
+[[mappings]]
+source_name = "DPadX"
+source_direction = "Positive"
+target_type = "Keyboard"
+target_name = "Right"
+
+[[mappings]]
+source_name = "LeftX"
+target_type = "GamepadAxis"
+target_name = "RightX"
+scale = 2.0
+invert = true
+"#;
+
+const EVENT_SIZE: usize = 6;
+
+fn engine(settings: u8) -> MappingEngine {
+    let mut profile = Profile::from_str_as(PROFILE, ProfileFormat::Toml).unwrap();
+    profile.settings.allow_multiple = settings & 1 != 0;
+    profile.settings.vibration_enabled = settings & 2 != 0;
+    let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+    engine.set_timing_enabled(settings & 4 != 0);
+    engine
+}
+
+fn decode(bytes: &[u8], start: Instant) -> Vec<InputEvent> {
+    bytes
+        .chunks_exact(EVENT_SIZE)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let code = usize::from(chunk[1]);
+            let value = i32::from_le_bytes([chunk[2], chunk[3], chunk[4], chunk[5]]);
+            let timestamp = start + Duration::from_millis(index as u64);
+            match chunk[0] % 3 {
+                0 => InputEvent::Button {
+                    code: ButtonCode::all().get(code).copied().unwrap_or(ButtonCode::Unknown),
+                    pressed: value != 0,
+                    timestamp,
+                },
+                1 => InputEvent::Axis {
+                    code: AxisCode::all().get(code).copied().unwrap_or(AxisCode::Unknown),
+                    value,
+                    timestamp,
+                },
+                _ => InputEvent::Sync { timestamp },
+            }
+        })
+        .collect()
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&settings, events)) = data.split_first() else {
+        return;
+    };
+    let events = decode(events, Instant::now());
+
+    let Ok(outputs) = engine(settings).process_batch(&events) else {
+        return;
+    };
+
+    // Processing the same events again from scratch gives the same outputs
+    assert_eq!(engine(settings).process_batch(&events).unwrap(), outputs);
+
+    for output in &outputs {
+        if let OutputEvent::GamepadAxis { code, .. } = output {
+            assert_eq!(*code, AxisCode::RightX);
+        }
+    }
+});
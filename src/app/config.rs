@@ -0,0 +1,168 @@
+// Top-level configuration - `<config_dir>/config.toml`, separate from remapping profiles
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::mapping::profile::{config_dir, profiles_dir};
+
+/// Contents of `config.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigFile {
+    /// Profile `run` loads without `--profile`; relative paths are resolved
+    /// against the profiles directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<PathBuf>,
+    pub log_level: String,
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        Self { default_profile: None, log_level: "info".to_string() }
+    }
+}
+
+impl ConfigFile {
+    /// `<config_dir>/config.toml`
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("config.toml"))
+    }
+
+    /// Load the config at `path`; a missing file gives the defaults
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("Invalid config {}", path.display()))
+    }
+
+    /// Write the config to `path`, creating its directory if needed
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let contents = toml::to_string_pretty(self).context("Failed to serialize config")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// `default_profile`, joined to `profiles` when it is relative
+    pub fn default_profile_path(&self, profiles: &Path) -> Option<PathBuf> {
+        self.default_profile.as_ref().map(|path| profiles.join(path))
+    }
+}
+
+/// The profile `run` uses without `--profile`, `None` for the built-in mappings
+///
+/// That is the config's `default_profile`, else `default.toml` in the profiles
+/// directory if it exists.
+pub fn resolve_default_profile(config_dir: &Path) -> Result<Option<PathBuf>> {
+    let profiles = config_dir.join("profiles");
+    let config = ConfigFile::load_from_file(&config_dir.join("config.toml"))?;
+    if let Some(path) = config.default_profile_path(&profiles) {
+        return Ok(Some(path));
+    }
+
+    let default = profiles.join("default.toml");
+    Ok(default.is_file().then_some(default))
+}
+
+/// Tracing filter directives: `rust_log` (the `RUST_LOG` variable) if set,
+/// else the `log_level` of the config in `config_dir`
+///
+/// A config that can't be read gives the default level; `run` reports it when
+/// it loads the config for the default profile.
+pub fn log_filter(rust_log: Option<String>, config_dir: Option<&Path>) -> String {
+    if let Some(directives) = rust_log.filter(|directives| !directives.is_empty()) {
+        return directives;
+    }
+    config_dir
+        .and_then(|dir| ConfigFile::load_from_file(&dir.join("config.toml")).ok())
+        .unwrap_or_default()
+        .log_level
+}
+
+/// How `path` is written as `default_profile`: just the file name when it is
+/// directly in the profiles directory, else an absolute path
+pub fn default_profile_entry(path: &Path) -> Result<PathBuf> {
+    let path = std::path::absolute(path)
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+    let in_profiles_dir = profiles_dir()
+        .and_then(|dir| std::path::absolute(dir).ok())
+        .is_some_and(|dir| path.parent() == Some(dir.as_path()));
+
+    Ok(match path.file_name() {
+        Some(name) if in_profiles_dir => PathBuf::from(name),
+        _ => path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_round_trip_and_defaults() {
+        assert_eq!(toml::from_str::<ConfigFile>("").unwrap(), ConfigFile::default());
+
+        let config = ConfigFile {
+            default_profile: Some(PathBuf::from("celeste.toml")),
+            log_level: "debug".to_string(),
+        };
+        let toml = toml::to_string_pretty(&config).unwrap();
+        assert!(toml.contains("default_profile = \"celeste.toml\""));
+        assert_eq!(toml::from_str::<ConfigFile>(&toml).unwrap(), config);
+
+        let profiles = Path::new("/home/sam/.config/blazeremap/profiles");
+        assert_eq!(config.default_profile_path(profiles), Some(profiles.join("celeste.toml")));
+        let absolute = ConfigFile { default_profile: Some("/srv/pad.toml".into()), ..config };
+        assert_eq!(absolute.default_profile_path(profiles), Some(PathBuf::from("/srv/pad.toml")));
+    }
+
+    #[test]
+    fn test_log_filter() {
+        let dir = std::env::temp_dir().join(format!("blazeremap-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = ConfigFile { log_level: "debug".to_string(), ..Default::default() };
+        config.save_to_file(&dir.join("config.toml")).unwrap();
+
+        let configured = log_filter(None, Some(&dir));
+        let overridden = log_filter(Some("blazeremap=trace".to_string()), Some(&dir));
+        let empty_override = log_filter(Some(String::new()), Some(&dir));
+        std::fs::write(dir.join("config.toml"), "log_level = [").unwrap();
+        let broken = log_filter(None, Some(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(configured, "debug");
+        assert_eq!(overridden, "blazeremap=trace");
+        assert_eq!(empty_override, "debug");
+        assert_eq!(broken, "info");
+        assert_eq!(log_filter(None, None), "info");
+    }
+
+    #[test]
+    fn test_resolve_default_profile() {
+        let dir = std::env::temp_dir().join(format!("blazeremap-config-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("profiles")).unwrap();
+
+        let nothing = resolve_default_profile(&dir).unwrap();
+        std::fs::write(dir.join("profiles/default.toml"), "").unwrap();
+        let default = resolve_default_profile(&dir).unwrap();
+        let config = ConfigFile { default_profile: Some("fps.toml".into()), ..Default::default() };
+        config.save_to_file(&dir.join("config.toml")).unwrap();
+        let configured = resolve_default_profile(&dir).unwrap();
+        std::fs::write(dir.join("config.toml"), "default_profile = [").unwrap();
+        let broken = resolve_default_profile(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(nothing, None);
+        assert_eq!(default, Some(dir.join("profiles/default.toml")));
+        assert_eq!(configured, Some(dir.join("profiles/fps.toml")));
+        assert!(broken.is_err());
+    }
+}
@@ -1,6 +1,10 @@
 // Application composition
+pub mod config;
+
 use crate::cli;
 
+pub use config::ConfigFile;
+
 pub struct App;
 
 impl App {
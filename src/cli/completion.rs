@@ -0,0 +1,121 @@
+// Completion command - shell completion scripts, with controller paths for --device
+use std::ffi::OsStr;
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+use clap_complete::{
+    CompleteEnv,
+    engine::{ArgValueCompleter, CompletionCandidate},
+    env::Shells,
+};
+
+use crate::{input::GamepadInfo, platform::new_input_manager};
+
+/// Environment variable the registered scripts set when asking for completions
+const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Build the 'completion' command
+pub fn command() -> Command {
+    Command::new("completion")
+        .about("Print a shell completion script")
+        .after_help(
+            "Load it from your shell's startup file, e.g. for bash:\n  \
+             source <(blazeremap completion bash)",
+        )
+        .arg(
+            clap::Arg::new("shell")
+                .help("Shell to generate the script for")
+                .required(true)
+                .value_parser(["bash", "zsh", "fish", "elvish", "powershell"])
+                .index(1),
+        )
+}
+
+/// CLI handle for the 'completion' command
+///
+/// The script calls back into `blazeremap` for every completion, so the
+/// controllers offered for `--device` are the ones connected at that moment.
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let shell = matches.get_one::<String>("shell").unwrap();
+    let shells = Shells::builtins();
+    let completer =
+        shells.completer(shell).with_context(|| format!("Unsupported shell '{}'", shell))?;
+    completer.write_registration(
+        COMPLETE_VAR,
+        "blazeremap",
+        "blazeremap",
+        "blazeremap",
+        &mut std::io::stdout(),
+    )?;
+    Ok(())
+}
+
+/// Answer a completion request from a registered script and exit; returns
+/// when the binary was not started for completion
+pub fn complete_from_env() {
+    CompleteEnv::with_factory(super::build_cli).var(COMPLETE_VAR).complete();
+}
+
+/// Completer for arguments that take a controller path, like `--device`
+pub fn device_completer() -> ArgValueCompleter {
+    ArgValueCompleter::new(complete_devices)
+}
+
+/// Detected controllers whose path starts with `current`; nothing if detection
+/// fails, so a broken setup doesn't spill errors into the command line
+fn complete_devices(current: &OsStr) -> Vec<CompletionCandidate> {
+    match new_input_manager().and_then(|manager| manager.list_gamepads()) {
+        Ok(result) => device_candidates(&result.gamepad_info, current),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// `path` candidates described by the controller's name
+fn device_candidates(gamepads: &[GamepadInfo], current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    gamepads
+        .iter()
+        .filter(|info| info.path.starts_with(current.as_ref()))
+        .map(|info| CompletionCandidate::new(&info.path).help(Some(info.name.clone().into())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_candidates() {
+        let gamepads = [
+            GamepadInfo::test("/dev/input/event3", "Xbox One Controller"),
+            GamepadInfo::test("/dev/input/event15", "Wireless Controller"),
+        ];
+
+        let candidates = device_candidates(&gamepads, OsStr::new("/dev/input/event1"));
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].get_value(), "/dev/input/event15");
+        assert_eq!(candidates[0].get_help().unwrap().to_string(), "Wireless Controller");
+
+        assert_eq!(device_candidates(&gamepads, OsStr::new("")).len(), 2);
+    }
+
+    #[test]
+    fn test_device_arguments_complete_controllers() {
+        let mut cli = crate::cli::build_cli();
+        cli.build();
+
+        for path in [
+            &["run"][..],
+            &["read"],
+            &["test-mapping"],
+            &["profile", "verify-controller"],
+            &["service", "install"],
+        ] {
+            let command = path.iter().fold(&cli, |command, name| {
+                command.find_subcommand(name).unwrap_or_else(|| panic!("no '{}'", name))
+            });
+            let device = command.get_arguments().find(|arg| arg.get_id() == "device").unwrap();
+            assert!(device.get::<ArgValueCompleter>().is_some(), "{:?}", path);
+        }
+    }
+}
@@ -1,35 +1,125 @@
 // Detect command - list connected gamepads
-use crate::platform;
+use crate::{
+    input::{GamepadInfo, GamepadType},
+    platform,
+};
+use anyhow::Context;
 use clap::{ArgMatches, Command};
+use regex::{Regex, RegexBuilder};
 use std::io::Write;
 
+use super::style::OutputStyle;
+
 pub fn command() -> Command {
-    Command::new("detect").about("Detect gamepads connected to your computer").arg(
-        clap::Arg::new("verbose")
-            .short('v')
-            .long("verbose")
-            .help("Show detailed information")
-            .action(clap::ArgAction::SetTrue),
-    )
+    Command::new("detect")
+        .about("Detect gamepads connected to your computer")
+        .visible_aliases(["devices", "controllers"])
+        .arg(
+            clap::Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Show detailed information")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new("raw-names")
+                .long("raw-names")
+                .help("Show the device names reported by the kernel instead of product names")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new("filter")
+                .long("filter")
+                .visible_alias("device-filter")
+                .value_name("REGEX")
+                .help("Only show gamepads whose name matches this pattern (case-insensitive)"),
+        )
+        .arg(
+            clap::Arg::new("filter-type")
+                .long("filter-type")
+                .value_name("TYPE")
+                .help("Only show gamepads of this kind")
+                .value_parser(["xbox", "playstation", "generic"]),
+        )
+}
+
+/// Which gamepads `detect` lists
+#[derive(Debug, Default)]
+struct GamepadFilter {
+    name: Option<Regex>,
+    kind: Option<String>,
+}
+
+impl GamepadFilter {
+    fn from_matches(matches: &ArgMatches) -> anyhow::Result<Self> {
+        let name = matches
+            .get_one::<String>("filter")
+            .map(|pattern| {
+                RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .with_context(|| format!("Invalid --filter pattern '{}'", pattern))
+            })
+            .transpose()?;
+        Ok(Self { name, kind: matches.get_one::<String>("filter-type").cloned() })
+    }
+
+    fn is_active(&self) -> bool {
+        self.name.is_some() || self.kind.is_some()
+    }
+
+    /// The product name and the kernel name are both matched against the pattern
+    fn matches(&self, info: &GamepadInfo) -> bool {
+        let name_matches = self
+            .name
+            .as_ref()
+            .is_none_or(|regex| regex.is_match(info.display_name()) || regex.is_match(&info.name));
+        let kind_matches = self.kind.as_deref().is_none_or(|kind| {
+            kind == match info.gamepad_type {
+                GamepadType::XboxOne | GamepadType::XboxSeries | GamepadType::XboxElite => "xbox",
+                GamepadType::DualShock4 | GamepadType::DualSense => "playstation",
+                GamepadType::SteamController | GamepadType::Generic | GamepadType::Unknown => {
+                    "generic"
+                }
+            }
+        });
+        name_matches && kind_matches
+    }
 }
 
 pub fn handle(matches: &ArgMatches) -> anyhow::Result<()> {
     let verbose = matches.get_flag("verbose");
+    let raw_names = matches.get_flag("raw-names");
+    let filter = GamepadFilter::from_matches(matches)?;
 
     println!("Detecting gamepads...\n");
 
-    let device_manager = platform::new_input_manager();
-    let result = device_manager.list_gamepads()?;
+    let device_manager = platform::new_input_manager()?;
+    let mut result = device_manager.list_gamepads()?;
+
+    if filter.is_active() {
+        let found = result.gamepad_info.len();
+        result.gamepad_info.retain(|info| filter.matches(info));
+        if found > 0 && result.gamepad_info.is_empty() {
+            println!("No gamepads match the filter ({} found).", found);
+            return Ok(());
+        }
+    }
 
-    display_results(&result, verbose);
+    display_results(&result, verbose, raw_names, OutputStyle::from_matches(matches));
 
     Ok(())
 }
 
 /// Display detection results in a user-friendly format
-fn display_results(result: &crate::input::InputDetectionResult, verbose: bool) {
+fn display_results(
+    result: &crate::input::InputDetectionResult,
+    verbose: bool,
+    raw_names: bool,
+    style: OutputStyle,
+) {
     let mut output = std::io::stdout();
-    write_results(&mut output, result, verbose).unwrap();
+    write_results(&mut output, result, verbose, raw_names, style).unwrap();
 }
 
 /// Internal function that writes to any writer (testable!)
@@ -37,6 +127,8 @@ fn write_results<W: Write>(
     writer: &mut W,
     result: &crate::input::InputDetectionResult,
     verbose: bool,
+    raw_names: bool,
+    style: OutputStyle,
 ) -> std::io::Result<()> {
     use crate::input::gamepad::capabilities_to_strings;
 
@@ -46,7 +138,7 @@ fn write_results<W: Write>(
         if !result.errors.is_empty() {
             writeln!(writer, "\nErrors encountered:")?;
             for error in &result.errors {
-                writeln!(writer, "  • {}", error)?;
+                writeln!(writer, "  {} {}", style.bullet(), error)?;
             }
         }
 
@@ -56,21 +148,26 @@ fn write_results<W: Write>(
     writeln!(writer, "Found {} gamepad(s):\n", result.gamepad_info.len())?;
 
     for (i, info) in result.gamepad_info.iter().enumerate() {
-        writeln!(writer, "[{}] {} ({})", i, info.name, info.path)?;
-        writeln!(writer, " ├─ Type: {}", info.gamepad_type)?;
-        writeln!(writer, " ├─ Vendor:")?;
-        writeln!(writer, " │  ├─ ID: {:04X}", info.vendor_id)?;
-        writeln!(writer, " │  └─ Name: {}", info.vendor_name)?;
-        writeln!(writer, " ├─ Product ID: {:04X}", info.product_id)?;
-        writeln!(writer, " └─ Capabilities:")?;
+        let name = if raw_names { info.name.as_str() } else { info.display_name() };
+        match info.bus_type.short_name() {
+            Some(bus) => writeln!(writer, "[{}] {} ({}) [{}]", i, name, info.path, bus)?,
+            None => writeln!(writer, "[{}] {} ({})", i, name, info.path)?,
+        }
+        let (branch, last, trunk) = (style.branch(), style.last_branch(), style.trunk());
+        writeln!(writer, " {} Type: {}", branch, info.gamepad_type)?;
+        writeln!(writer, " {} Vendor:", branch)?;
+        writeln!(writer, " {}  {} ID: {:04X}", trunk, branch, info.vendor_id)?;
+        writeln!(writer, " {}  {} Name: {}", trunk, last, info.vendor_name)?;
+        writeln!(writer, " {} Product ID: {:04X}", branch, info.product_id)?;
+        writeln!(writer, " {} Capabilities:", last)?;
 
         let caps = capabilities_to_strings(&info.capabilities);
         if caps.is_empty() {
-            writeln!(writer, "    └─ None detected")?;
+            writeln!(writer, "    {} None detected", last)?;
         } else {
             for (j, cap) in caps.iter().enumerate() {
-                let prefix = if j == caps.len() - 1 { "    └─ " } else { "    ├─ " };
-                writeln!(writer, "{}{}", prefix, cap)?;
+                let prefix = if j == caps.len() - 1 { last } else { branch };
+                writeln!(writer, "    {} {}", prefix, cap)?;
             }
         }
 
@@ -81,6 +178,8 @@ fn write_results<W: Write>(
         writeln!(writer, "Verbose Information:")?;
         for (i, info) in result.gamepad_info.iter().enumerate() {
             writeln!(writer, "  [{}] Full path: {}", i, info.path)?;
+            writeln!(writer, "  [{}] Device name: {}", i, info.name)?;
+            writeln!(writer, "  [{}] Bus: {}, version {:04X}", i, info.bus_type, info.version)?;
         }
     }
 
@@ -90,27 +189,48 @@ fn write_results<W: Write>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::gamepad::BusType;
     use crate::input::{GamepadCapability, GamepadInfo, GamepadType, InputDetectionResult};
 
     /// Helper to create a test gamepad
     fn make_test_gamepad(name: &str) -> GamepadInfo {
         GamepadInfo {
-            path: "/dev/input/event99".to_string(),
-            name: name.to_string(),
             gamepad_type: GamepadType::DualShock4,
             vendor_id: 0x054C,
             vendor_name: "Sony".to_string(),
             product_id: 0x09CC,
             capabilities: vec![GamepadCapability::ForceFeedback],
+            ..GamepadInfo::test("/dev/input/event99", name)
         }
     }
 
+    #[test]
+    fn test_gamepad_filter() {
+        let mut xbox = make_test_gamepad("Microsoft X-Box One pad");
+        xbox.gamepad_type = GamepadType::XboxOne;
+        let dualshock = make_test_gamepad("Wireless Controller");
+
+        let matches = command().get_matches_from(["detect", "--filter", "xbox|dualsense"]);
+        let filter = GamepadFilter::from_matches(&matches).unwrap();
+        assert!(filter.matches(&xbox));
+        assert!(!filter.matches(&dualshock));
+
+        let matches = command().get_matches_from(["detect", "--filter-type", "playstation"]);
+        let filter = GamepadFilter::from_matches(&matches).unwrap();
+        assert!(!filter.matches(&xbox));
+        assert!(filter.matches(&dualshock));
+
+        let matches = command().get_matches_from(["detect", "--filter", "Xbox("]);
+        let err = GamepadFilter::from_matches(&matches).unwrap_err();
+        assert!(err.to_string().contains("Invalid --filter pattern 'Xbox('"));
+    }
+
     #[test]
     fn test_display_no_gamepads() {
         let result = InputDetectionResult { gamepad_info: vec![], errors: vec![] };
 
         let mut output = Vec::new();
-        write_results(&mut output, &result, false).unwrap();
+        write_results(&mut output, &result, false, false, OutputStyle::FANCY).unwrap();
 
         let text = String::from_utf8(output).unwrap();
         assert!(text.contains("No gamepads found"));
@@ -124,7 +244,7 @@ mod tests {
         };
 
         let mut output = Vec::new();
-        write_results(&mut output, &result, false).unwrap();
+        write_results(&mut output, &result, false, true, OutputStyle::FANCY).unwrap();
 
         let text = String::from_utf8(output).unwrap();
 
@@ -145,7 +265,7 @@ mod tests {
         };
 
         let mut output = Vec::new();
-        write_results(&mut output, &result, false).unwrap();
+        write_results(&mut output, &result, false, true, OutputStyle::FANCY).unwrap();
 
         let text = String::from_utf8(output).unwrap();
 
@@ -163,13 +283,13 @@ mod tests {
 
         // Test without verbose
         let mut output = Vec::new();
-        write_results(&mut output, &result, false).unwrap();
+        write_results(&mut output, &result, false, false, OutputStyle::FANCY).unwrap();
         let text = String::from_utf8(output).unwrap();
         assert!(!text.contains("Verbose Information"));
 
         // Test with verbose
         let mut output = Vec::new();
-        write_results(&mut output, &result, true).unwrap();
+        write_results(&mut output, &result, true, false, OutputStyle::FANCY).unwrap();
         let text = String::from_utf8(output).unwrap();
         assert!(text.contains("Verbose Information"));
         assert!(text.contains("Full path: /dev/input/event99"));
@@ -181,7 +301,7 @@ mod tests {
             InputDetectionResult { gamepad_info: vec![make_test_gamepad("Test")], errors: vec![] };
 
         let mut output = Vec::new();
-        write_results(&mut output, &result, false).unwrap();
+        write_results(&mut output, &result, false, false, OutputStyle::FANCY).unwrap();
         let text = String::from_utf8(output).unwrap();
 
         // Check for tree characters
@@ -189,4 +309,67 @@ mod tests {
         assert!(text.contains("└─"));
         assert!(text.contains("│"));
     }
+
+    #[test]
+    fn test_plain_formatting() {
+        let result =
+            InputDetectionResult { gamepad_info: vec![make_test_gamepad("Test")], errors: vec![] };
+
+        let mut output = Vec::new();
+        write_results(&mut output, &result, false, false, OutputStyle::PLAIN).unwrap();
+        let text = String::from_utf8(output).unwrap();
+
+        assert!(text.is_ascii());
+        assert!(text.contains(" +- Type: DualShock 4"));
+        assert!(text.contains(" |  `- Name: Sony"));
+        assert!(text.contains("    `- Force Feedback"));
+    }
+
+    #[test]
+    fn test_display_uses_product_names() {
+        let result = InputDetectionResult {
+            gamepad_info: vec![make_test_gamepad("Wireless Controller")],
+            errors: vec![],
+        };
+
+        let mut output = Vec::new();
+        write_results(&mut output, &result, false, false, OutputStyle::FANCY).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("[0] DualShock 4 (/dev/input/event99)"));
+        assert!(!text.contains("Wireless Controller"));
+
+        // --raw-names shows what the kernel reports
+        let mut output = Vec::new();
+        write_results(&mut output, &result, false, true, OutputStyle::FANCY).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("[0] Wireless Controller (/dev/input/event99)"));
+
+        // Verbose output always includes the raw name
+        let mut output = Vec::new();
+        write_results(&mut output, &result, true, false, OutputStyle::FANCY).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Device name: Wireless Controller"));
+    }
+
+    #[test]
+    fn test_display_connection_type() {
+        let mut gamepad = make_test_gamepad("Wireless Controller");
+        gamepad.bus_type = BusType::Bluetooth;
+        gamepad.version = 0x8111;
+        let result = InputDetectionResult { gamepad_info: vec![gamepad], errors: vec![] };
+
+        let mut output = Vec::new();
+        write_results(&mut output, &result, true, false, OutputStyle::FANCY).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("[0] DualShock 4 (/dev/input/event99) [BT]\n"));
+        assert!(text.contains("Bus: Bluetooth, version 8111"));
+
+        let mut gamepad = make_test_gamepad("Virtual Pad");
+        gamepad.bus_type = BusType::Other;
+        let result = InputDetectionResult { gamepad_info: vec![gamepad], errors: vec![] };
+        let mut output = Vec::new();
+        write_results(&mut output, &result, false, true, OutputStyle::FANCY).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("[0] Virtual Pad (/dev/input/event99)\n"));
+    }
 }
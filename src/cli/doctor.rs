@@ -0,0 +1,315 @@
+// Doctor command - find and fix common setup problems
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    process::Command as Process,
+};
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::mapping::profile::{Profile, config_dir, profiles_dir};
+
+/// Device node the virtual keyboard and gamepad are created through
+const UINPUT_PATH: &str = "/dev/uinput";
+
+/// Group that owns /dev/input/event* on most distributions
+const INPUT_GROUP: &str = "input";
+
+/// udev rule that gives the input group access to /dev/uinput on every boot
+const UINPUT_UDEV_RULE: &str = r#"KERNEL=="uinput", GROUP="input", MODE="0660""#;
+
+/// Build the 'doctor' command
+pub fn command() -> Command {
+    Command::new("doctor")
+        .about("Find and fix common setup problems (asks before each change)")
+        .arg(
+            clap::Arg::new("dry-run")
+                .long("dry-run")
+                .help("Only show what would be done")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// CLI handle for the 'doctor' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let dry_run = matches.get_flag("dry-run");
+    let problems = find_problems(&SetupState::probe());
+
+    if problems.is_empty() {
+        println!("No problems found.");
+        return Ok(());
+    }
+
+    let stdin = std::io::stdin();
+    let mut input = stdin.lock();
+    for problem in &problems {
+        println!("Problem: {}", problem.description);
+        if dry_run {
+            println!("  would: {}", problem.fix);
+            continue;
+        }
+
+        if !confirm(&mut input, &format!("  Fix: {}?", problem.fix))? {
+            println!("  skipped");
+            continue;
+        }
+        match problem.fix.apply() {
+            Ok(()) => println!("  done"),
+            Err(e) => println!("  failed: {:#}", e),
+        }
+        if let Some(hint) = &problem.hint {
+            println!("  {}", hint);
+        }
+    }
+
+    Ok(())
+}
+
+/// Ask a `[y/N]` question; anything but y/yes means no
+fn confirm(input: &mut impl BufRead, question: &str) -> Result<bool> {
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer).context("Failed to read answer")?;
+    Ok(matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes"))
+}
+
+/// What `doctor` found out about the system
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct SetupState {
+    uinput_writable: bool,
+    /// Login name, if it could be determined
+    user: Option<String>,
+    in_input_group: bool,
+    config_dir: Option<PathBuf>,
+    config_dir_exists: bool,
+    has_profiles: bool,
+}
+
+impl SetupState {
+    fn probe() -> Self {
+        let config_dir = config_dir();
+
+        Self {
+            uinput_writable: std::fs::OpenOptions::new().write(true).open(UINPUT_PATH).is_ok(),
+            user: std::env::var("USER").ok().filter(|user| !user.is_empty()),
+            in_input_group: current_groups().iter().any(|group| group == INPUT_GROUP),
+            config_dir_exists: config_dir.as_ref().is_some_and(|dir| dir.is_dir()),
+            has_profiles: profiles_dir().is_some_and(|dir| contains_profiles(&dir)),
+            config_dir,
+        }
+    }
+}
+
+/// Groups of the current process, from `id -nG`
+fn current_groups() -> Vec<String> {
+    Process::new("id")
+        .arg("-nG")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout).split_whitespace().map(String::from).collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether `dir` holds at least one .toml/.yaml/.json file
+fn contains_profiles(dir: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        path.is_file()
+            && path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| matches!(ext, "toml" | "yaml" | "yml" | "json"))
+    })
+}
+
+/// A change `doctor` can make
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Fix {
+    /// Run a command, typically through sudo
+    Run(Vec<String>),
+    CreateDir(PathBuf),
+    WriteDefaultProfile(PathBuf),
+}
+
+impl Fix {
+    fn run(args: &[&str]) -> Self {
+        Self::Run(args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    fn apply(&self) -> Result<()> {
+        match self {
+            Fix::Run(args) => {
+                let status = Process::new(&args[0])
+                    .args(&args[1..])
+                    .status()
+                    .with_context(|| format!("Failed to run {}", args.join(" ")))?;
+                if !status.success() {
+                    anyhow::bail!("{} failed ({})", args.join(" "), status);
+                }
+                Ok(())
+            }
+            Fix::CreateDir(dir) => std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display())),
+            Fix::WriteDefaultProfile(path) => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create {}", parent.display()))?;
+                }
+                Profile::default_profile().save_to_file(path)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Fix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fix::Run(args) => write!(f, "run `{}`", args.join(" ")),
+            Fix::CreateDir(dir) => write!(f, "create {}", dir.display()),
+            Fix::WriteDefaultProfile(path) => {
+                write!(f, "write the default profile to {}", path.display())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Problem {
+    description: String,
+    fix: Fix,
+    /// Printed after the fix is applied
+    hint: Option<String>,
+}
+
+/// Problems in `state`, in the order they should be fixed
+fn find_problems(state: &SetupState) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    if !state.uinput_writable {
+        problems.push(Problem {
+            description: format!(
+                "{} is not writable, so no virtual devices can be created",
+                UINPUT_PATH
+            ),
+            fix: Fix::run(&["sudo", "chmod", "a+rw", UINPUT_PATH]),
+            hint: Some(format!(
+                "This lasts until reboot. To make it permanent, add this udev rule to \
+                 /etc/udev/rules.d/99-blazeremap.rules:\n    {}",
+                UINPUT_UDEV_RULE
+            )),
+        });
+    }
+
+    if let Some(user) = state.user.as_ref().filter(|_| !state.in_input_group) {
+        problems.push(Problem {
+            description: format!(
+                "{} is not in the '{}' group, so gamepads cannot be read",
+                user, INPUT_GROUP
+            ),
+            fix: Fix::run(&["sudo", "usermod", "-aG", INPUT_GROUP, user]),
+            hint: Some("Log out and back in for the new group to take effect.".to_string()),
+        });
+    }
+
+    if let Some(dir) = &state.config_dir {
+        if !state.config_dir_exists {
+            problems.push(Problem {
+                description: format!("Config directory {} does not exist", dir.display()),
+                fix: Fix::CreateDir(dir.clone()),
+                hint: None,
+            });
+        }
+        if !state.has_profiles {
+            let profiles = dir.join("profiles");
+            problems.push(Problem {
+                description: format!("No profiles in {}", profiles.display()),
+                fix: Fix::WriteDefaultProfile(profiles.join("default.toml")),
+                hint: None,
+            });
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn healthy_state() -> SetupState {
+        SetupState {
+            uinput_writable: true,
+            user: Some("alex".to_string()),
+            in_input_group: true,
+            config_dir: Some(PathBuf::from("/home/alex/.config/blazeremap")),
+            config_dir_exists: true,
+            has_profiles: true,
+        }
+    }
+
+    #[test]
+    fn test_healthy_setup_has_no_problems() {
+        assert!(find_problems(&healthy_state()).is_empty());
+    }
+
+    #[test]
+    fn test_find_problems_in_fix_order() {
+        let state = SetupState {
+            uinput_writable: false,
+            in_input_group: false,
+            config_dir_exists: false,
+            has_profiles: false,
+            ..healthy_state()
+        };
+
+        let fixes: Vec<Fix> = find_problems(&state).into_iter().map(|p| p.fix).collect();
+
+        assert_eq!(
+            fixes,
+            vec![
+                Fix::run(&["sudo", "chmod", "a+rw", "/dev/uinput"]),
+                Fix::run(&["sudo", "usermod", "-aG", "input", "alex"]),
+                Fix::CreateDir(PathBuf::from("/home/alex/.config/blazeremap")),
+                Fix::WriteDefaultProfile(PathBuf::from(
+                    "/home/alex/.config/blazeremap/profiles/default.toml"
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_user_skips_group_check() {
+        let state = SetupState { user: None, in_input_group: false, ..healthy_state() };
+        assert!(find_problems(&state).is_empty());
+    }
+
+    #[test]
+    fn test_write_default_profile_creates_directories() {
+        let dir = std::env::temp_dir().join(format!("blazeremap-doctor-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("profiles").join("default.toml");
+
+        Fix::WriteDefaultProfile(path.clone()).apply().unwrap();
+
+        assert_eq!(Profile::load_from_file(&path).unwrap().name, "Default");
+        assert!(contains_profiles(&dir.join("profiles")));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_confirm() {
+        assert!(confirm(&mut "y\n".as_bytes(), "Fix?").unwrap());
+        assert!(confirm(&mut "YES\n".as_bytes(), "Fix?").unwrap());
+        assert!(!confirm(&mut "\n".as_bytes(), "Fix?").unwrap());
+        assert!(!confirm(&mut "".as_bytes(), "Fix?").unwrap());
+    }
+}
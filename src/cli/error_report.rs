@@ -0,0 +1,198 @@
+// Error reporting - plain text or JSON on stderr, controlled by --error-format
+use std::{str::FromStr, sync::OnceLock};
+
+use clap::{Arg, ArgMatches};
+use serde::Serialize;
+
+use crate::{
+    cli::ExitCodeError, input::InputDeviceError, mapping::profile::ProfileError,
+    mapping::rules::MappingRuleError,
+};
+
+/// Format chosen with `--error-format`, set once the command line is parsed
+static ERROR_FORMAT: OnceLock<ErrorFormat> = OnceLock::new();
+
+/// Global `--error-format FORMAT` argument
+pub fn error_format_arg() -> Arg {
+    Arg::new("error-format")
+        .long("error-format")
+        .value_name("FORMAT")
+        .help("How errors are printed on stderr: text or json (one object per error)")
+        .value_parser(["text", "json"])
+        .default_value("text")
+        .global(true)
+}
+
+/// How a failed command reports its error
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// `Error: <message>`
+    #[default]
+    Text,
+    /// `{"error": "...", "code": "...", "line": 12, "column": 5}`
+    Json,
+}
+
+impl FromStr for ErrorFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => anyhow::bail!("Unknown error format '{}' (expected text or json)", s),
+        }
+    }
+}
+
+impl ErrorFormat {
+    /// Resolve `--error-format`, defaulting to text
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        matches
+            .try_get_one::<String>("error-format")
+            .ok()
+            .flatten()
+            .and_then(|format| format.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Make this the format used by `ErrorReporter::report`; only the first call counts
+    pub fn set_global(self) {
+        let _ = ERROR_FORMAT.set(self);
+    }
+
+    /// The format set with `set_global`, or text
+    pub fn global() -> Self {
+        ERROR_FORMAT.get().copied().unwrap_or_default()
+    }
+}
+
+/// Error as printed with `--error-format json`; location fields are left out when unknown
+#[derive(Debug, Serialize)]
+struct JsonError {
+    error: String,
+    code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+}
+
+/// Prints a command's error in the globally selected format
+pub struct ErrorReporter;
+
+impl ErrorReporter {
+    /// Print `error` to stderr and return the process exit code
+    pub fn report(error: &anyhow::Error) -> i32 {
+        eprintln!("{}", Self::format(error, ErrorFormat::global()));
+        error.downcast_ref::<ExitCodeError>().map_or(1, |e| e.code)
+    }
+
+    /// Render `error` the way `report` prints it
+    pub fn format(error: &anyhow::Error, format: ErrorFormat) -> String {
+        match format {
+            ErrorFormat::Text => format!("Error: {}", error),
+            ErrorFormat::Json => {
+                let location = error
+                    .chain()
+                    .find_map(|cause| cause.downcast_ref::<ProfileError>())
+                    .and_then(ProfileError::location);
+                let json = JsonError {
+                    error: format!("{:#}", error),
+                    code: error_code(error),
+                    line: location.map(|(line, _)| line),
+                    column: location.map(|(_, column)| column),
+                };
+                serde_json::to_string(&json)
+                    .unwrap_or_else(|_| format!("{{\"error\": {:?}}}", json.error))
+            }
+        }
+    }
+}
+
+/// Stable code for the most specific known error in the chain
+fn error_code(error: &anyhow::Error) -> &'static str {
+    for cause in error.chain() {
+        if let Some(e) = cause.downcast_ref::<ProfileError>() {
+            return e.code();
+        }
+        if cause.is::<MappingRuleError>() {
+            return "invalid_mapping";
+        }
+        if cause.is::<InputDeviceError>() {
+            return "device";
+        }
+        if cause.is::<std::io::Error>() {
+            return "io";
+        }
+    }
+
+    if error.is::<ExitCodeError>() { "exit_code" } else { "error" }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context;
+    use clap::Command;
+
+    use super::*;
+    use crate::mapping::profile::{Profile, ProfileFormat};
+
+    fn parse(args: &[&str]) -> ErrorFormat {
+        let cmd = Command::new("test").arg(error_format_arg());
+        ErrorFormat::from_matches(&cmd.get_matches_from(args))
+    }
+
+    #[test]
+    fn test_error_format_from_matches() {
+        assert_eq!(parse(&["test"]), ErrorFormat::Text);
+        assert_eq!(parse(&["test", "--error-format", "json"]), ErrorFormat::Json);
+    }
+
+    #[test]
+    fn test_text_format_matches_previous_output() {
+        let error = anyhow::anyhow!("No gamepads found");
+        assert_eq!(ErrorReporter::format(&error, ErrorFormat::Text), "Error: No gamepads found");
+    }
+
+    #[test]
+    fn test_json_format_profile_parse_error() {
+        let contents = "name = \"Broken\"\ndescription = \"x\"\nmappings = [\n  oops\n]\n";
+        let error = Profile::from_str_as(contents, ProfileFormat::Toml)
+            .context("Failed to load profile broken.toml")
+            .unwrap_err();
+
+        let json = ErrorReporter::format(&error, ErrorFormat::Json);
+
+        assert!(json.contains("\"error\":\"Failed to load profile broken.toml: "));
+        assert!(json.contains("\"code\":\"profile_parse\""));
+        assert!(json.contains("\"line\":4"));
+        assert!(json.contains("\"column\":3"));
+    }
+
+    #[test]
+    fn test_json_format_unknown_key_has_no_location() {
+        let contents = "name = \"Broken\"\ndescription = \"x\"\n\n[[mappings]]\nsource_name = \
+                        \"South\"\ntarget_type = \"Keyboard\"\ntarget_name = \"NotAKey\"\n";
+        let error = Profile::from_str_as(contents, ProfileFormat::Toml).unwrap_err();
+
+        let json = ErrorReporter::format(&error, ErrorFormat::Json);
+
+        assert!(json.contains("\"code\":\"profile_unknown_key\""));
+        assert!(!json.contains("\"line\""));
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let io = anyhow::Error::from(std::io::Error::other("disk on fire")).context("Saving");
+        assert_eq!(error_code(&io), "io");
+
+        let rule = anyhow::Error::from(MappingRuleError::InvalidAxisScale(3.0));
+        assert_eq!(error_code(&rule), "invalid_mapping");
+
+        let exit = anyhow::Error::from(ExitCodeError { code: 2, message: "Stopped".to_string() });
+        assert_eq!(error_code(&exit), "exit_code");
+
+        assert_eq!(error_code(&anyhow::anyhow!("Something else")), "error");
+    }
+}
@@ -0,0 +1,161 @@
+// Init command - create the config directory with a default profile and examples
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::mapping::profile::{Profile, ProfileFormat, config_dir};
+
+/// Written to `config.toml`; `default_profile` is relative to the profiles directory
+const DEFAULT_CONFIG: &str = r#"# BlazeRemap configuration
+
+# Profile 'run' loads when --profile is not given
+default_profile = "default.toml"
+
+# One of: error, warn, info, debug, trace
+log_level = "info"
+"#;
+
+/// Build the 'init' command
+pub fn command() -> Command {
+    Command::new("init")
+        .about("Create ~/.config/blazeremap with a default profile and example profiles")
+        .arg(
+            clap::Arg::new("force")
+                .long("force")
+                .help("Overwrite files that already exist")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new("minimal")
+                .long("minimal")
+                .help("Only create the profiles directory and the default profile")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// What 'init' did with one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum InitAction {
+    Created(PathBuf),
+    Overwritten(PathBuf),
+    Skipped(PathBuf),
+}
+
+/// CLI handle for the 'init' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let dir = config_dir().context("Cannot find the config directory (HOME is not set)")?;
+
+    let actions = init_config_dir(&dir, matches.get_flag("force"), matches.get_flag("minimal"))?;
+
+    let mut skipped = false;
+    for action in &actions {
+        match action {
+            InitAction::Created(path) => println!("Created {}", path.display()),
+            InitAction::Overwritten(path) => println!("Overwrote {}", path.display()),
+            InitAction::Skipped(path) => {
+                skipped = true;
+                println!("  warning: {} already exists, skipped", path.display());
+            }
+        }
+    }
+    if skipped {
+        println!("\nRe-run with --force to overwrite existing files.");
+    }
+
+    Ok(())
+}
+
+/// Create `<dir>/profiles` and write the default files into `dir`
+///
+/// Existing files are only replaced when `force` is set. `minimal` writes just the
+/// default profile, without the examples and `config.toml`.
+fn init_config_dir(dir: &Path, force: bool, minimal: bool) -> Result<Vec<InitAction>> {
+    let profiles = dir.join("profiles");
+    std::fs::create_dir_all(&profiles)
+        .with_context(|| format!("Failed to create {}", profiles.display()))?;
+
+    let mut files = vec![(
+        profiles.join("default.toml"),
+        Profile::default_profile().to_string_as(ProfileFormat::Toml)?,
+    )];
+    if !minimal {
+        for example in Profile::examples() {
+            let path = profiles.join(format!("{}.toml", example.name));
+            files.push((path, example.to_string_as(ProfileFormat::Toml)?));
+        }
+        files.push((dir.join("config.toml"), DEFAULT_CONFIG.to_string()));
+    }
+
+    let mut actions = Vec::new();
+    for (path, contents) in files {
+        let existed = path.exists();
+        if existed && !force {
+            actions.push(InitAction::Skipped(path));
+            continue;
+        }
+
+        std::fs::write(&path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        actions.push(if existed {
+            InitAction::Overwritten(path)
+        } else {
+            InitAction::Created(path)
+        });
+    }
+    Ok(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::ConfigFile;
+
+    fn temp_config_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("blazeremap-init-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_init_writes_profiles_and_config() {
+        let dir = temp_config_dir("full");
+
+        let actions = init_config_dir(&dir, false, false).unwrap();
+        let default = Profile::load_from_file(&dir.join("profiles/default.toml")).unwrap();
+        let fps = Profile::load_from_file(&dir.join("profiles/fps.toml")).unwrap();
+        let config = std::fs::read_to_string(dir.join("config.toml")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(actions.len(), Profile::examples().len() + 2);
+        assert!(actions.iter().all(|action| matches!(action, InitAction::Created(_))));
+        assert_eq!(default.mappings, Profile::default_profile().mappings);
+        assert_eq!(fps.name, "fps");
+        assert_eq!(fps.mappings, Profile::example("fps").unwrap().mappings);
+        let config: ConfigFile = toml::from_str(&config).unwrap();
+        assert_eq!(config.default_profile, Some(PathBuf::from("default.toml")));
+    }
+
+    #[test]
+    fn test_init_minimal_and_force() {
+        let dir = temp_config_dir("minimal");
+
+        let first = init_config_dir(&dir, false, true).unwrap();
+        std::fs::write(dir.join("profiles/default.toml"), "edited").unwrap();
+        let second = init_config_dir(&dir, false, true).unwrap();
+        let edited = std::fs::read_to_string(dir.join("profiles/default.toml")).unwrap();
+        let forced = init_config_dir(&dir, true, true).unwrap();
+        let restored = Profile::load_from_file(&dir.join("profiles/default.toml"));
+        let has_config = dir.join("config.toml").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let default = dir.join("profiles/default.toml");
+        assert_eq!(first, vec![InitAction::Created(default.clone())]);
+        assert_eq!(second, vec![InitAction::Skipped(default.clone())]);
+        assert_eq!(edited, "edited");
+        assert_eq!(forced, vec![InitAction::Overwritten(default)]);
+        assert!(restored.is_ok());
+        assert!(!has_config);
+    }
+}
@@ -0,0 +1,54 @@
+// List keys command - print every keyboard key name usable in profiles
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+use crate::event::KEYBOARD_CODE_NAMES;
+
+/// Build the 'list-keys' command
+pub fn command() -> Command {
+    Command::new("list-keys").about("List keyboard key names that can be used as mapping targets")
+}
+
+/// CLI handle for the 'list-keys' command
+pub fn handle(_matches: &ArgMatches) -> Result<()> {
+    for line in key_lines() {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// A `name  description` header followed by one line per key, names padded to line up
+fn key_lines() -> Vec<String> {
+    let rows: Vec<(String, String)> = KEYBOARD_CODE_NAMES
+        .iter()
+        .map(|(_, code)| (format!("{:?}", code), code.to_string()))
+        .collect();
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max("name".len());
+
+    std::iter::once(("name".to_string(), "description".to_string()))
+        .chain(rows)
+        .map(|(name, description)| format!("{:<width$}  {}", name, description))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::KeyboardCode;
+
+    #[test]
+    fn test_key_lines() {
+        let lines = key_lines();
+
+        assert_eq!(lines.len(), KEYBOARD_CODE_NAMES.len() + 1);
+        assert!(lines[0].starts_with("name "));
+        assert!(lines[0].ends_with("  description"));
+        assert!(lines.iter().any(|l| l.starts_with("LeftControl ") && l.ends_with("Left Control")));
+
+        // Every listed name parses back to its key
+        for line in &lines[1..] {
+            let name = line.split_whitespace().next().unwrap();
+            assert_ne!(KeyboardCode::from(name), KeyboardCode::Unknown, "{}", name);
+        }
+    }
+}
@@ -1,34 +1,101 @@
 // CLI module - command definitions and handling
+mod completion;
 mod detect;
+mod doctor;
+mod error_report;
+mod init;
+mod list_keys;
+mod profile;
+mod profile_backup;
+mod profile_convert;
+mod profile_diff;
+mod profile_import;
+mod profile_list;
+mod profile_merge;
+mod profile_new;
+mod profile_optimize;
+mod profile_set_default;
+mod profile_settings;
+mod profile_show;
+mod profile_test;
+mod profile_validate;
+mod profile_verify_controller;
 mod read;
 mod run;
+mod service;
+mod style;
 mod test_keyboard;
+mod test_mapping;
+mod watch;
+
+use std::sync::OnceLock;
 
 use clap::Command;
 
+use crate::platform::current_platform;
+
+pub use error_report::{ErrorFormat, ErrorReporter};
+
+/// Error that makes the binary exit with `code` instead of the usual 1
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct ExitCodeError {
+    pub code: i32,
+    pub message: String,
+}
+
 /// Build the root CLI command structure
 pub fn build_cli() -> Command {
     Command::new("blazeremap")
         .version(env!("CARGO_PKG_VERSION"))
+        .long_version(long_version())
         .author("Muhammad Arif Rohman Hakim")
         .about("Linux keyboard-to-gamepad remapping software")
         .subcommand_required(true)
         .arg_required_else_help(true)
+        .arg(style::color_arg())
+        .arg(style::no_color_arg())
+        .arg(error_report::error_format_arg())
+        .subcommand(completion::command())
         .subcommand(detect::command())
+        .subcommand(doctor::command())
+        .subcommand(init::command())
+        .subcommand(list_keys::command())
+        .subcommand(profile::command())
         .subcommand(read::command())
         .subcommand(run::command())
+        .subcommand(service::command())
         .subcommand(test_keyboard::command())
+        .subcommand(test_mapping::command())
+        .subcommand(watch::command())
+}
+
+/// Version shown by `--version`, including the platform
+fn long_version() -> &'static str {
+    static LONG_VERSION: OnceLock<String> = OnceLock::new();
+    LONG_VERSION.get_or_init(|| format!("{} ({})", env!("CARGO_PKG_VERSION"), current_platform()))
 }
 
 /// Execute the CLI and handle the result
 pub fn execute() -> anyhow::Result<()> {
+    completion::complete_from_env();
+
     let matches = build_cli().get_matches();
+    ErrorFormat::from_matches(&matches).set_global();
 
     match matches.subcommand() {
+        Some(("completion", sub_matches)) => completion::handle(sub_matches),
         Some(("detect", sub_matches)) => detect::handle(sub_matches),
+        Some(("doctor", sub_matches)) => doctor::handle(sub_matches),
+        Some(("init", sub_matches)) => init::handle(sub_matches),
+        Some(("list-keys", sub_matches)) => list_keys::handle(sub_matches),
+        Some(("profile", sub_matches)) => profile::handle(sub_matches),
         Some(("read", sub_matches)) => read::handle(sub_matches),
         Some(("run", sub_matches)) => run::handle(sub_matches),
+        Some(("service", sub_matches)) => service::handle(sub_matches),
         Some(("test-keyboard", sub_matches)) => test_keyboard::handle(sub_matches),
+        Some(("test-mapping", sub_matches)) => test_mapping::handle(sub_matches),
+        Some(("watch", sub_matches)) => watch::handle(sub_matches),
         _ => unreachable!("Subcommand required"),
     }
 }
@@ -0,0 +1,65 @@
+// Profile command - manage remapping profiles
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+use crate::mapping::schema::profile_schema;
+
+use super::{
+    profile_backup, profile_convert, profile_diff, profile_import, profile_list, profile_merge,
+    profile_new, profile_optimize, profile_set_default, profile_settings, profile_show,
+    profile_test, profile_validate, profile_verify_controller,
+};
+
+/// Build the 'profile' command
+pub fn command() -> Command {
+    Command::new("profile")
+        .about("Manage remapping profiles")
+        .arg_required_else_help(true)
+        .args_conflicts_with_subcommands(true)
+        .arg(
+            clap::Arg::new("print-schema")
+                .long("print-schema")
+                .help("Print a JSON Schema for profile files (for editor autocompletion)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .subcommand(profile_backup::command())
+        .subcommand(profile_convert::command())
+        .subcommand(profile_diff::command())
+        .subcommand(profile_import::command())
+        .subcommand(profile_list::command())
+        .subcommand(profile_merge::command())
+        .subcommand(profile_new::command())
+        .subcommand(profile_optimize::command())
+        .subcommand(profile_set_default::command())
+        .subcommand(profile_settings::command())
+        .subcommand(profile_show::command())
+        .subcommand(profile_test::command())
+        .subcommand(profile_validate::command())
+        .subcommand(profile_verify_controller::command())
+}
+
+/// CLI handle for the 'profile' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    if matches.get_flag("print-schema") {
+        println!("{}", serde_json::to_string_pretty(&profile_schema())?);
+        return Ok(());
+    }
+
+    match matches.subcommand() {
+        Some(("backup", sub_matches)) => profile_backup::handle(sub_matches),
+        Some(("convert", sub_matches)) => profile_convert::handle(sub_matches),
+        Some(("diff", sub_matches)) => profile_diff::handle(sub_matches),
+        Some(("import", sub_matches)) => profile_import::handle(sub_matches),
+        Some(("list", sub_matches)) => profile_list::handle(sub_matches),
+        Some(("merge", sub_matches)) => profile_merge::handle(sub_matches),
+        Some(("new", sub_matches)) => profile_new::handle(sub_matches),
+        Some(("optimize", sub_matches)) => profile_optimize::handle(sub_matches),
+        Some(("set-default", sub_matches)) => profile_set_default::handle(sub_matches),
+        Some(("settings", sub_matches)) => profile_settings::handle(sub_matches),
+        Some(("show", sub_matches)) => profile_show::handle(sub_matches),
+        Some(("test", sub_matches)) => profile_test::handle(sub_matches),
+        Some(("validate", sub_matches)) => profile_validate::handle(sub_matches),
+        Some(("verify-controller", sub_matches)) => profile_verify_controller::handle(sub_matches),
+        _ => unreachable!("Subcommand required"),
+    }
+}
@@ -0,0 +1,213 @@
+// Profile backup command - timestamped copies of a profile before editing
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::mapping::profile::{BACKUP_MARKER, is_backup_path};
+
+/// Build the 'profile backup' command
+pub fn command() -> Command {
+    Command::new("backup")
+        .about("Copy a profile to <file>.bak.<UTC timestamp>")
+        .arg(clap::Arg::new("file").help("Profile file to back up").required(true).index(1))
+        .arg(
+            clap::Arg::new("keep-last")
+                .long("keep-last")
+                .value_name("N")
+                .help("Delete all but the N most recent backups of this profile")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+}
+
+/// CLI handle for the 'profile backup' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let path = Path::new(matches.get_one::<String>("file").unwrap());
+
+    let backup = backup_profile(path, SystemTime::now())?;
+    println!("Backed up {} → {}", path.display(), backup.display());
+
+    if let Some(&keep) = matches.get_one::<u64>("keep-last") {
+        for removed in trim_backups(path, keep as usize)? {
+            println!("Removed old backup {}", removed.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy `path` to `<path>.bak.<timestamp>` and return the backup path
+pub(super) fn backup_profile(path: &Path, now: SystemTime) -> Result<PathBuf> {
+    if !path.is_file() {
+        anyhow::bail!("Profile {} does not exist", path.display());
+    }
+
+    let mut name = path.as_os_str().to_owned();
+    name.push(BACKUP_MARKER);
+    name.push(format_utc(now));
+    let backup = PathBuf::from(name);
+
+    if backup.exists() {
+        anyhow::bail!("Backup {} already exists, try again in a second", backup.display());
+    }
+
+    std::fs::copy(path, &backup)
+        .with_context(|| format!("Failed to copy {} to {}", path.display(), backup.display()))?;
+    Ok(backup)
+}
+
+/// Existing backups of `path`, oldest first
+fn list_backups(path: &Path) -> Result<Vec<PathBuf>> {
+    let file_name = path.file_name().and_then(|n| n.to_str()).context("Invalid profile path")?;
+    let prefix = format!("{}{}", file_name, BACKUP_MARKER);
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to list {}", dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|backup| {
+            is_backup_path(backup)
+                && backup
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with(&prefix))
+        })
+        .collect();
+
+    // Timestamps sort chronologically as strings
+    backups.sort();
+    Ok(backups)
+}
+
+/// Delete all but the `keep` most recent backups of `path`, returning the deleted ones
+fn trim_backups(path: &Path, keep: usize) -> Result<Vec<PathBuf>> {
+    let backups = list_backups(path)?;
+    let excess = backups.len().saturating_sub(keep);
+
+    let removed: Vec<PathBuf> = backups.into_iter().take(excess).collect();
+    for backup in &removed {
+        std::fs::remove_file(backup)
+            .with_context(|| format!("Failed to remove {}", backup.display()))?;
+    }
+    Ok(removed)
+}
+
+/// `YYYY-MM-DDTHH:MM:SS` in UTC
+pub(super) fn format_utc(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("blazeremap-backup-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(UNIX_EPOCH), "1970-01-01T00:00:00");
+        assert_eq!(
+            format_utc(UNIX_EPOCH + Duration::from_secs(1_736_951_520)),
+            "2025-01-15T14:32:00"
+        );
+        // Leap day
+        assert_eq!(
+            format_utc(UNIX_EPOCH + Duration::from_secs(1_709_210_096)),
+            "2024-02-29T12:34:56"
+        );
+    }
+
+    #[test]
+    fn test_backup_copies_file() {
+        let dir = temp_dir("copy");
+        let profile = dir.join("foo.toml");
+        std::fs::write(&profile, "name = \"foo\"").unwrap();
+
+        let backup =
+            backup_profile(&profile, UNIX_EPOCH + Duration::from_secs(1_736_951_520)).unwrap();
+
+        assert_eq!(backup, dir.join("foo.toml.bak.2025-01-15T14:32:00"));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "name = \"foo\"");
+        assert!(is_backup_path(&backup));
+
+        // Same second again
+        assert!(backup_profile(&profile, UNIX_EPOCH + Duration::from_secs(1_736_951_520)).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_trim_keeps_most_recent() {
+        let dir = temp_dir("trim");
+        let profile = dir.join("foo.toml");
+        std::fs::write(&profile, "").unwrap();
+        std::fs::write(dir.join("bar.toml.bak.2020-01-01T00:00:00"), "").unwrap();
+
+        for day in 0..4 {
+            let time = UNIX_EPOCH + Duration::from_secs(1_736_951_520 + day * 86_400);
+            backup_profile(&profile, time).unwrap();
+        }
+
+        let removed = trim_backups(&profile, 2).unwrap();
+
+        assert_eq!(
+            removed,
+            vec![
+                dir.join("foo.toml.bak.2025-01-15T14:32:00"),
+                dir.join("foo.toml.bak.2025-01-16T14:32:00"),
+            ]
+        );
+        assert_eq!(
+            list_backups(&profile).unwrap(),
+            vec![
+                dir.join("foo.toml.bak.2025-01-17T14:32:00"),
+                dir.join("foo.toml.bak.2025-01-18T14:32:00"),
+            ]
+        );
+        // Other profiles' backups are left alone
+        assert!(dir.join("bar.toml.bak.2020-01-01T00:00:00").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_backup_missing_file() {
+        let result = backup_profile(Path::new("/nonexistent/foo.toml"), SystemTime::now());
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,167 @@
+// Profile convert command - migrate profiles between file formats
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::mapping::{
+    MappingEngine,
+    profile::{Profile, ProfileFormat},
+};
+
+/// Build the 'profile convert' command
+pub fn command() -> Command {
+    Command::new("convert")
+        .about("Convert a profile between TOML, YAML and JSON")
+        .arg(clap::Arg::new("input").help("Source profile file").required(true).index(1))
+        .arg(clap::Arg::new("output").help("Destination profile file").required(true).index(2))
+        .arg(
+            clap::Arg::new("from")
+                .long("from")
+                .help("Source format (inferred from the file extension if omitted)")
+                .value_parser(["toml", "yaml", "json"]),
+        )
+        .arg(
+            clap::Arg::new("to")
+                .long("to")
+                .help("Target format (inferred from the file extension if omitted)")
+                .value_parser(["toml", "yaml", "json"]),
+        )
+        .arg(
+            clap::Arg::new("force")
+                .short('f')
+                .long("force")
+                .help("Overwrite the output file if it already exists")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// CLI handle for the 'profile convert' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let input = Path::new(matches.get_one::<String>("input").unwrap());
+    let output = Path::new(matches.get_one::<String>("output").unwrap());
+
+    let from = resolve_format(matches.get_one::<String>("from"), input)?;
+    let to = resolve_format(matches.get_one::<String>("to"), output)?;
+
+    convert_profile(input, from, output, to, matches.get_flag("force"))?;
+
+    println!("Converted {} ({}) → {} ({})", input.display(), from, output.display(), to);
+    Ok(())
+}
+
+/// Use the explicit format if given, otherwise infer it from the file extension
+fn resolve_format(explicit: Option<&String>, path: &Path) -> Result<ProfileFormat> {
+    match explicit {
+        Some(name) => name.parse(),
+        None => ProfileFormat::from_path(path).with_context(|| {
+            format!("Cannot infer profile format of {}, use --from/--to", path.display())
+        }),
+    }
+}
+
+/// Load, validate and re-save a profile in another format
+fn convert_profile(
+    input: &Path,
+    from: ProfileFormat,
+    output: &Path,
+    to: ProfileFormat,
+    force: bool,
+) -> Result<()> {
+    if output.exists() && !force {
+        anyhow::bail!(
+            "Output file {} already exists. Use --force to overwrite it.",
+            output.display()
+        );
+    }
+
+    let profile = Profile::load_from_file_as(input, from)
+        .with_context(|| format!("Failed to load {}", input.display()))?;
+
+    // Refuse to write a profile the mapping engine would reject
+    MappingEngine::load_from_profile(&profile).context("Profile contains invalid mappings")?;
+
+    profile.save_to_file_as(output, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Helper to create an empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("blazeremap_convert_{}", name));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Convert a synthetic profile from one format to another and read it back
+    fn assert_converts(name: &str, from: ProfileFormat, to: ProfileFormat) {
+        let dir = scratch_dir(name);
+        let input = dir.join("input");
+        let output = dir.join("output");
+
+        let profile = Profile::default_profile();
+        profile.save_to_file_as(&input, from).unwrap();
+
+        convert_profile(&input, from, &output, to, false).unwrap();
+
+        let converted = Profile::load_from_file_as(&output, to).unwrap();
+        assert_eq!(converted.name, profile.name);
+        assert_eq!(converted.mappings.len(), profile.mappings.len());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_convert_toml_to_yaml() {
+        assert_converts("toml_yaml", ProfileFormat::Toml, ProfileFormat::Yaml);
+    }
+
+    #[test]
+    fn test_convert_yaml_to_toml() {
+        assert_converts("yaml_toml", ProfileFormat::Yaml, ProfileFormat::Toml);
+    }
+
+    #[test]
+    fn test_convert_json_to_toml() {
+        assert_converts("json_toml", ProfileFormat::Json, ProfileFormat::Toml);
+    }
+
+    #[test]
+    fn test_convert_toml_to_json() {
+        assert_converts("toml_json", ProfileFormat::Toml, ProfileFormat::Json);
+    }
+
+    #[test]
+    fn test_convert_refuses_to_overwrite_without_force() {
+        let dir = scratch_dir("overwrite");
+        let input = dir.join("in.toml");
+        let output = dir.join("out.yaml");
+
+        Profile::default_profile().save_to_file(&input).unwrap();
+        std::fs::write(&output, "existing").unwrap();
+
+        let result =
+            convert_profile(&input, ProfileFormat::Toml, &output, ProfileFormat::Yaml, false);
+        assert!(result.unwrap_err().to_string().contains("--force"));
+
+        convert_profile(&input, ProfileFormat::Toml, &output, ProfileFormat::Yaml, true).unwrap();
+        assert!(Profile::load_from_file_as(&output, ProfileFormat::Yaml).is_ok());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_resolve_format() {
+        let explicit = "json".to_string();
+        assert_eq!(
+            resolve_format(Some(&explicit), Path::new("a.toml")).unwrap(),
+            ProfileFormat::Json
+        );
+        assert_eq!(resolve_format(None, Path::new("a.yml")).unwrap(), ProfileFormat::Yaml);
+        assert!(resolve_format(None, Path::new("a.txt")).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+// Profile diff command - show what changed between two profiles
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::mapping::profile::{Profile, ProfileDiff, ProfileFormat};
+
+use super::style::{GREEN, OutputStyle, RED, YELLOW};
+
+/// Build the 'profile diff' command
+pub fn command() -> Command {
+    Command::new("diff")
+        .about("Show added, removed and changed mappings between two profiles")
+        .arg(clap::Arg::new("base").help("Original profile").required(true).index(1))
+        .arg(clap::Arg::new("modified").help("Modified profile").required(true).index(2))
+}
+
+/// CLI handle for the 'profile diff' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let base = load(Path::new(matches.get_one::<String>("base").unwrap()))?;
+    let modified = load(Path::new(matches.get_one::<String>("modified").unwrap()))?;
+
+    let diff = Profile::diff(&base, &modified);
+
+    if diff.is_empty() {
+        println!("Profiles are identical");
+        return Ok(());
+    }
+
+    for line in format_diff(&diff, OutputStyle::from_matches(matches)) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<Profile> {
+    let format = ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Toml);
+    Profile::load_from_file_as(path, format)
+        .with_context(|| format!("Failed to load {}", path.display()))
+}
+
+/// `+` added, `-` removed, `~` changed
+fn format_diff(diff: &ProfileDiff, style: OutputStyle) -> Vec<String> {
+    let paint = |code: &str, line: String| style.paint(code, &line);
+
+    let mut lines = Vec::new();
+
+    for mapping in &diff.removed {
+        lines.push(paint(RED, format!("- {}", mapping)));
+    }
+    for mapping in &diff.added {
+        lines.push(paint(GREEN, format!("+ {}", mapping)));
+    }
+    for (old, new) in &diff.changed {
+        lines.push(paint(YELLOW, format!("~ {} (was {})", new, old)));
+    }
+    if diff.settings_changed {
+        lines.push(paint(YELLOW, "~ settings changed".to_string()));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::{Mapping, types::TargetType};
+
+    fn mapping(source: &str, target: &str) -> Mapping {
+        Mapping {
+            source_name: source.to_string(),
+            source_direction: None,
+            target_type: TargetType::Keyboard,
+            target_name: target.to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_format_diff() {
+        let diff = ProfileDiff {
+            added: vec![mapping("Mode", "Tab")],
+            removed: vec![mapping("North", "W")],
+            changed: vec![(mapping("South", "S"), mapping("South", "Space"))],
+            settings_changed: true,
+        };
+
+        assert_eq!(
+            format_diff(&diff, OutputStyle::PLAIN),
+            vec![
+                "- North → W",
+                "+ Mode → Tab",
+                "~ South → Space (was South → S)",
+                "~ settings changed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_diff_color() {
+        let diff = ProfileDiff { added: vec![mapping("Mode", "Tab")], ..Default::default() };
+
+        assert_eq!(format_diff(&diff, OutputStyle::FANCY), vec!["\x1b[32m+ Mode → Tab\x1b[0m"]);
+    }
+}
@@ -0,0 +1,605 @@
+// Profile import command - convert configurations from other remapping tools
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+use quick_xml::{Reader, events::Event};
+
+use crate::{
+    event::{AxisCode, AxisDirection, ButtonCode, KeyboardCode},
+    mapping::{
+        Mapping, MappingEngine,
+        profile::{Profile, ProfileFormat, ProfileSettings},
+        types::TargetType,
+    },
+};
+
+/// Build the 'profile import' command
+pub fn command() -> Command {
+    Command::new("import")
+        .about("Import a profile from another remapping tool")
+        .arg(
+            clap::Arg::new("from")
+                .long("from")
+                .help("Format of the file to import")
+                .required(true)
+                .value_parser(["antimicro"]),
+        )
+        .arg(
+            clap::Arg::new("in")
+                .long("in")
+                .value_name("FILE")
+                .help("File to import (e.g. an antimicro .amgp profile)")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("out")
+                .long("out")
+                .value_name("FILE")
+                .help("Profile file to write (format inferred from the extension, TOML otherwise)")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("force")
+                .short('f')
+                .long("force")
+                .help("Overwrite the output file if it already exists")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// CLI handle for the 'profile import' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let input = Path::new(matches.get_one::<String>("in").unwrap());
+    let output = Path::new(matches.get_one::<String>("out").unwrap());
+
+    let warnings = import_antimicro(input, output, matches.get_flag("force"))?;
+    for warning in &warnings {
+        println!("  warning: {}", warning);
+    }
+
+    println!("Imported {} → {}", input.display(), output.display());
+    Ok(())
+}
+
+/// Convert an antimicro profile file into a blazeremap profile file
+///
+/// Returns a warning for every antimicro feature that could not be imported.
+fn import_antimicro(input: &Path, output: &Path, force: bool) -> Result<Vec<String>> {
+    if output.exists() && !force {
+        anyhow::bail!(
+            "Output file {} already exists. Use --force to overwrite it.",
+            output.display()
+        );
+    }
+
+    let xml = std::fs::read_to_string(input)
+        .with_context(|| format!("Failed to read {}", input.display()))?;
+    let (mappings, warnings) = parse_antimicro(&xml)
+        .with_context(|| format!("Failed to parse antimicro profile {}", input.display()))?;
+
+    // Everything up to the first dot: "racing.gamecontroller.amgp" → "racing"
+    let file_name = input.file_name().and_then(|n| n.to_str()).unwrap_or("imported");
+    let profile = Profile {
+        name: file_name.split('.').next().unwrap_or(file_name).to_string(),
+        description: format!("Imported from antimicro ({})", file_name),
+        game_name: None,
+        mappings,
+        settings: ProfileSettings::default(),
+        metadata: BTreeMap::from([("imported_from".to_string(), "antimicro".to_string())]),
+    };
+
+    MappingEngine::load_from_profile(&profile).context("Imported profile is invalid")?;
+
+    let format = ProfileFormat::from_path(output).unwrap_or(ProfileFormat::Toml);
+    profile.save_to_file_as(output, format)?;
+
+    Ok(warnings)
+}
+
+/// Gamepad input an antimicro control corresponds to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    Button(ButtonCode),
+    DPad(AxisCode, AxisDirection),
+}
+
+impl Source {
+    fn describe(&self) -> String {
+        match self {
+            Self::Button(code) => code.to_string(),
+            Self::DPad(code, direction) => format!("{} {}", code, direction),
+        }
+    }
+}
+
+/// One `<slot>` of an antimicro control
+#[derive(Debug, Default)]
+struct Slot {
+    code: Option<String>,
+    mode: Option<String>,
+}
+
+/// Parse an antimicro game controller profile (`.amgp`) into mappings
+///
+/// Only keyboard slots on buttons, the d-pad and the trigger clicks of set 1
+/// are imported; everything else becomes a warning.
+fn parse_antimicro(xml: &str) -> Result<(Vec<Mapping>, Vec<String>)> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut mappings: Vec<Mapping> = Vec::new();
+    let mut warnings = Vec::new();
+
+    // Open elements with their `index` attribute
+    let mut stack: Vec<(String, Option<u32>)> = Vec::new();
+    let mut slots: Vec<Slot> = Vec::new();
+    let mut ignored_sets: Vec<u32> = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => {
+                let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+                if stack.is_empty() && name != "gamecontroller" {
+                    anyhow::bail!(
+                        "Only antimicro game controller profiles are supported, found <{}>",
+                        name
+                    );
+                }
+                let index = match start.try_get_attribute("index")? {
+                    Some(attribute) => attribute.unescape_value()?.trim().parse().ok(),
+                    None => None,
+                };
+                if name == "slot" {
+                    slots.push(Slot::default());
+                }
+                stack.push((name, index));
+            }
+            Event::Text(text) => {
+                let [.., (parent, _), (element, _)] = stack.as_slice() else {
+                    continue;
+                };
+                if parent != "slot" {
+                    continue;
+                }
+                let Some(slot) = slots.last_mut() else { continue };
+                match element.as_str() {
+                    "code" => slot.code = Some(text.unescape()?.into_owned()),
+                    "mode" => slot.mode = Some(text.unescape()?.into_owned()),
+                    _ => {}
+                }
+            }
+            Event::End(_) => {
+                let Some((name, index)) = stack.pop() else { continue };
+                if !is_control(&name) {
+                    continue;
+                }
+                let control_slots = std::mem::take(&mut slots);
+                if control_slots.is_empty() {
+                    continue;
+                }
+
+                let set = set_index(&stack);
+                if set != 1 {
+                    if !ignored_sets.contains(&set) {
+                        ignored_sets.push(set);
+                        warnings.push(format!("set {} ignored (only set 1 is imported)", set));
+                    }
+                    continue;
+                }
+
+                let parent = stack.last().cloned().unwrap_or_default();
+                let Some(source) = control_source(&name, index, &parent) else {
+                    warnings.push(format!(
+                        "{} {} ({} {}) is not supported (only buttons, triggers and the d-pad)",
+                        name,
+                        index.unwrap_or(0),
+                        parent.0,
+                        parent.1.unwrap_or(0)
+                    ));
+                    continue;
+                };
+
+                if let Some(mapping) = slots_to_mapping(source, &control_slots, &mut warnings) {
+                    let duplicate = mappings.iter().any(|m| {
+                        m.source_name == mapping.source_name
+                            && m.source_direction == mapping.source_direction
+                    });
+                    if duplicate {
+                        warnings.push(format!(
+                            "{} is mapped more than once, keeping the first",
+                            source.describe()
+                        ));
+                    } else {
+                        mappings.push(mapping);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok((mappings, warnings))
+}
+
+/// Elements that hold `<slots>` for one input
+fn is_control(name: &str) -> bool {
+    matches!(name, "button" | "dpadbutton" | "triggerbutton" | "stickbutton" | "axisbutton")
+}
+
+/// Index of the enclosing `<set>`, 1 if the control is not inside one
+fn set_index(stack: &[(String, Option<u32>)]) -> u32 {
+    stack.iter().rev().find(|(name, _)| name == "set").and_then(|(_, index)| *index).unwrap_or(1)
+}
+
+/// Map an antimicro control to a gamepad input
+///
+/// antimicro numbers SDL game controller buttons and axes from 1.
+fn control_source(
+    name: &str,
+    index: Option<u32>,
+    parent: &(String, Option<u32>),
+) -> Option<Source> {
+    let index = index?;
+    match (name, parent.0.as_str(), parent.1) {
+        ("button", _, _) => match index {
+            1 => Some(Source::Button(ButtonCode::South)),
+            2 => Some(Source::Button(ButtonCode::East)),
+            3 => Some(Source::Button(ButtonCode::West)),
+            4 => Some(Source::Button(ButtonCode::North)),
+            5 => Some(Source::Button(ButtonCode::Select)),
+            6 => Some(Source::Button(ButtonCode::Mode)),
+            7 => Some(Source::Button(ButtonCode::Start)),
+            8 => Some(Source::Button(ButtonCode::LeftStick)),
+            9 => Some(Source::Button(ButtonCode::RightStick)),
+            10 => Some(Source::Button(ButtonCode::LeftShoulder)),
+            11 => Some(Source::Button(ButtonCode::RightShoulder)),
+            12 => Some(Source::DPad(AxisCode::DPadY, AxisDirection::Negative)),
+            13 => Some(Source::DPad(AxisCode::DPadY, AxisDirection::Positive)),
+            14 => Some(Source::DPad(AxisCode::DPadX, AxisDirection::Negative)),
+            15 => Some(Source::DPad(AxisCode::DPadX, AxisDirection::Positive)),
+            _ => None,
+        },
+        // Diagonals (3, 6, 9, 12) have no single-axis equivalent
+        ("dpadbutton", "dpad", _) => match index {
+            1 => Some(Source::DPad(AxisCode::DPadY, AxisDirection::Negative)),
+            2 => Some(Source::DPad(AxisCode::DPadX, AxisDirection::Positive)),
+            4 => Some(Source::DPad(AxisCode::DPadY, AxisDirection::Positive)),
+            8 => Some(Source::DPad(AxisCode::DPadX, AxisDirection::Negative)),
+            _ => None,
+        },
+        // Button 2 of a trigger axis is the pressed direction
+        ("triggerbutton" | "axisbutton", "trigger" | "axis", Some(5)) if index == 2 => {
+            Some(Source::Button(ButtonCode::LeftTrigger))
+        }
+        ("triggerbutton" | "axisbutton", "trigger" | "axis", Some(6)) if index == 2 => {
+            Some(Source::Button(ButtonCode::RightTrigger))
+        }
+        _ => None,
+    }
+}
+
+/// Turn a control's slots into a keyboard mapping, warning about what is dropped
+fn slots_to_mapping(source: Source, slots: &[Slot], warnings: &mut Vec<String>) -> Option<Mapping> {
+    let target = source.describe();
+    if slots.len() > 1 {
+        warnings.push(format!("{}: only the first of {} slots is imported", target, slots.len()));
+    }
+
+    let slot = &slots[0];
+    let mode = slot.mode.as_deref().unwrap_or("keyboard");
+    if mode != "keyboard" {
+        warnings.push(format!("{}: '{}' slots are not supported", target, mode));
+        return None;
+    }
+
+    let code = slot.code.as_deref().unwrap_or_default();
+    let Some(key) = parse_qt_key(code).and_then(qt_key_to_keyboard_code) else {
+        warnings.push(format!("{}: unknown key code '{}'", target, code));
+        return None;
+    };
+
+    let (source_name, source_direction) = match source {
+        Source::Button(code) => (format!("{:?}", code), None),
+        Source::DPad(code, direction) => (format!("{:?}", code), Some(direction.to_string())),
+    };
+    Some(Mapping {
+        source_name,
+        source_direction,
+        target_type: TargetType::Keyboard,
+        target_name: key.to_string(),
+        scale: None,
+        invert: None,
+        condition_modifier: None,
+        priority: None,
+        enabled: true,
+    })
+}
+
+/// antimicro writes key codes as hex (`0x20`) or decimal
+fn parse_qt_key(code: &str) -> Option<u32> {
+    let code = code.trim();
+    match code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => code.parse().ok(),
+    }
+}
+
+/// Qt key codes (`Qt::Key`) as stored by antimicro keyboard slots
+const QT_KEYS: &[(u32, KeyboardCode)] = &[
+    (0x20, KeyboardCode::Space),
+    (0x27, KeyboardCode::Apostrophe),
+    (0x2c, KeyboardCode::Comma),
+    (0x2d, KeyboardCode::Minus),
+    (0x2e, KeyboardCode::Dot),
+    (0x2f, KeyboardCode::Slash),
+    (0x30, KeyboardCode::Num0),
+    (0x31, KeyboardCode::Num1),
+    (0x32, KeyboardCode::Num2),
+    (0x33, KeyboardCode::Num3),
+    (0x34, KeyboardCode::Num4),
+    (0x35, KeyboardCode::Num5),
+    (0x36, KeyboardCode::Num6),
+    (0x37, KeyboardCode::Num7),
+    (0x38, KeyboardCode::Num8),
+    (0x39, KeyboardCode::Num9),
+    (0x3b, KeyboardCode::Semicolon),
+    (0x3d, KeyboardCode::Equal),
+    (0x41, KeyboardCode::A),
+    (0x42, KeyboardCode::B),
+    (0x43, KeyboardCode::C),
+    (0x44, KeyboardCode::D),
+    (0x45, KeyboardCode::E),
+    (0x46, KeyboardCode::F),
+    (0x47, KeyboardCode::G),
+    (0x48, KeyboardCode::H),
+    (0x49, KeyboardCode::I),
+    (0x4a, KeyboardCode::J),
+    (0x4b, KeyboardCode::K),
+    (0x4c, KeyboardCode::L),
+    (0x4d, KeyboardCode::M),
+    (0x4e, KeyboardCode::N),
+    (0x4f, KeyboardCode::O),
+    (0x50, KeyboardCode::P),
+    (0x51, KeyboardCode::Q),
+    (0x52, KeyboardCode::R),
+    (0x53, KeyboardCode::S),
+    (0x54, KeyboardCode::T),
+    (0x55, KeyboardCode::U),
+    (0x56, KeyboardCode::V),
+    (0x57, KeyboardCode::W),
+    (0x58, KeyboardCode::X),
+    (0x59, KeyboardCode::Y),
+    (0x5a, KeyboardCode::Z),
+    (0x5b, KeyboardCode::LeftBrace),
+    (0x5c, KeyboardCode::Backslash),
+    (0x5d, KeyboardCode::RightBrace),
+    (0x60, KeyboardCode::Grave),
+    (0x0100_0000, KeyboardCode::Escape),
+    (0x0100_0001, KeyboardCode::Tab),
+    (0x0100_0003, KeyboardCode::Backspace),
+    (0x0100_0004, KeyboardCode::Enter),
+    (0x0100_0005, KeyboardCode::KpEnter),
+    (0x0100_0006, KeyboardCode::Insert),
+    (0x0100_0007, KeyboardCode::Delete),
+    (0x0100_0008, KeyboardCode::Pause),
+    (0x0100_0009, KeyboardCode::SysRq),
+    (0x0100_0010, KeyboardCode::Home),
+    (0x0100_0011, KeyboardCode::End),
+    (0x0100_0012, KeyboardCode::Left),
+    (0x0100_0013, KeyboardCode::Up),
+    (0x0100_0014, KeyboardCode::Right),
+    (0x0100_0015, KeyboardCode::Down),
+    (0x0100_0016, KeyboardCode::PageUp),
+    (0x0100_0017, KeyboardCode::PageDown),
+    (0x0100_0020, KeyboardCode::LeftShift),
+    (0x0100_0021, KeyboardCode::LeftControl),
+    (0x0100_0022, KeyboardCode::LeftMeta),
+    (0x0100_0023, KeyboardCode::LeftAlt),
+    (0x0100_0024, KeyboardCode::CapsLock),
+    (0x0100_0025, KeyboardCode::NumLock),
+    (0x0100_0026, KeyboardCode::ScrollLock),
+    (0x0100_0030, KeyboardCode::F1),
+    (0x0100_0031, KeyboardCode::F2),
+    (0x0100_0032, KeyboardCode::F3),
+    (0x0100_0033, KeyboardCode::F4),
+    (0x0100_0034, KeyboardCode::F5),
+    (0x0100_0035, KeyboardCode::F6),
+    (0x0100_0036, KeyboardCode::F7),
+    (0x0100_0037, KeyboardCode::F8),
+    (0x0100_0038, KeyboardCode::F9),
+    (0x0100_0039, KeyboardCode::F10),
+];
+
+fn qt_key_to_keyboard_code(qt_key: u32) -> Option<KeyboardCode> {
+    QT_KEYS.iter().find(|(key, _)| *key == qt_key).map(|(_, code)| *code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gamecontroller configversion="19" appversion="2.23">
+    <sdlname>Xbox Series X Controller</sdlname>
+    <sets>
+        <set index="1">
+            <button index="1">
+                <slots>
+                    <slot>
+                        <code>0x20</code>
+                        <mode>keyboard</mode>
+                    </slot>
+                </slots>
+            </button>
+            <button index="2">
+                <slots>
+                    <slot>
+                        <code>1</code>
+                        <mode>mousebutton</mode>
+                    </slot>
+                </slots>
+            </button>
+            <button index="3">
+                <slots>
+                    <slot>
+                        <code>0x01000020</code>
+                        <mode>keyboard</mode>
+                    </slot>
+                    <slot>
+                        <code>0x41</code>
+                        <mode>keyboard</mode>
+                    </slot>
+                </slots>
+            </button>
+            <button index="7">
+                <slots>
+                    <slot>
+                        <code>0x0100003b</code>
+                        <mode>keyboard</mode>
+                    </slot>
+                </slots>
+            </button>
+            <dpad index="1">
+                <dpadbutton index="1">
+                    <slots>
+                        <slot>
+                            <code>0x01000013</code>
+                            <mode>keyboard</mode>
+                        </slot>
+                    </slots>
+                </dpadbutton>
+            </dpad>
+            <trigger index="6">
+                <triggerbutton index="2">
+                    <slots>
+                        <slot>
+                            <code>0x45</code>
+                            <mode>keyboard</mode>
+                        </slot>
+                    </slots>
+                </triggerbutton>
+            </trigger>
+            <stick index="1">
+                <stickbutton index="1">
+                    <slots>
+                        <slot>
+                            <code>0x57</code>
+                            <mode>keyboard</mode>
+                        </slot>
+                    </slots>
+                </stickbutton>
+            </stick>
+        </set>
+        <set index="2">
+            <button index="1">
+                <slots>
+                    <slot>
+                        <code>0x51</code>
+                        <mode>keyboard</mode>
+                    </slot>
+                </slots>
+            </button>
+        </set>
+    </sets>
+</gamecontroller>
+"#;
+
+    fn mapping(source: &str, direction: Option<&str>, target: &str) -> Mapping {
+        Mapping {
+            source_name: source.to_string(),
+            source_direction: direction.map(str::to_string),
+            target_type: TargetType::Keyboard,
+            target_name: target.to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_parse_antimicro_mappings() {
+        let (mappings, _) = parse_antimicro(SAMPLE).unwrap();
+
+        assert_eq!(
+            mappings,
+            vec![
+                mapping("South", None, "Space"),
+                mapping("West", None, "Left Shift"),
+                mapping("DPadY", Some("Negative"), "Up"),
+                mapping("RightTrigger", None, "E"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_antimicro_warnings() {
+        let (_, warnings) = parse_antimicro(SAMPLE).unwrap();
+
+        assert_eq!(
+            warnings,
+            vec![
+                "East: 'mousebutton' slots are not supported",
+                "West: only the first of 2 slots is imported",
+                "Start: unknown key code '0x0100003b'",
+                "stickbutton 1 (stick 1) is not supported (only buttons, triggers and the d-pad)",
+                "set 2 ignored (only set 1 is imported)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_antimicro_keeps_first_duplicate() {
+        let xml = r#"<gamecontroller><sets><set index="1">
+            <button index="12"><slots><slot><code>0x57</code><mode>keyboard</mode></slot></slots></button>
+            <dpad index="1"><dpadbutton index="1"><slots><slot><code>0x01000013</code><mode>keyboard</mode></slot></slots></dpadbutton></dpad>
+        </set></sets></gamecontroller>"#;
+
+        let (mappings, warnings) = parse_antimicro(xml).unwrap();
+
+        assert_eq!(mappings, vec![mapping("DPadY", Some("Negative"), "W")]);
+        assert_eq!(warnings, vec!["DPad Y Negative is mapped more than once, keeping the first"]);
+    }
+
+    #[test]
+    fn test_parse_antimicro_rejects_joystick_profiles() {
+        let err = parse_antimicro("<joystick><sets/></joystick>").unwrap_err();
+        assert!(err.to_string().contains("<joystick>"));
+    }
+
+    #[test]
+    fn test_parse_qt_key() {
+        assert_eq!(parse_qt_key("0x20"), Some(0x20));
+        assert_eq!(parse_qt_key(" 65 "), Some(65));
+        assert_eq!(parse_qt_key("space"), None);
+    }
+
+    #[test]
+    fn test_import_antimicro_writes_valid_profile() {
+        let dir = std::env::temp_dir().join("blazeremap_import_antimicro");
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("racing.gamecontroller.amgp");
+        let output: PathBuf = dir.join("racing.toml");
+        std::fs::write(&input, SAMPLE).unwrap();
+
+        let warnings = import_antimicro(&input, &output, false).unwrap();
+        assert_eq!(warnings.len(), 5);
+
+        let profile = Profile::load_from_file(&output).unwrap();
+        assert_eq!(profile.name, "racing");
+        assert_eq!(profile.mappings.len(), 4);
+        assert_eq!(profile.metadata["imported_from"], "antimicro");
+        assert!(MappingEngine::load_from_profile(&profile).is_ok());
+
+        // A second import refuses to overwrite without --force
+        assert!(import_antimicro(&input, &output, false).is_err());
+        assert!(import_antimicro(&input, &output, true).is_ok());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,249 @@
+// Profile list command - show available profiles
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+use serde::Serialize;
+
+use crate::mapping::profile::{Profile, ProfileFormat, is_backup_path, profiles_dir};
+
+use super::profile_backup::format_utc;
+
+/// Build the 'profile list' command
+pub fn command() -> Command {
+    Command::new("list")
+        .about("List available profiles")
+        .arg(
+            clap::Arg::new("examples")
+                .long("examples")
+                .help("List the built-in example profiles")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new("filter")
+                .long("filter")
+                .value_name("KEY=VALUE")
+                .help("Only list profiles whose metadata has KEY set to VALUE (repeatable)")
+                .action(clap::ArgAction::Append)
+                .value_parser(parse_filter),
+        )
+        .arg(
+            clap::Arg::new("path")
+                .long("path")
+                .value_name("DIR")
+                .help("Directory to scan (default: ~/.config/blazeremap/profiles)")
+                .conflicts_with("examples"),
+        )
+        .arg(
+            clap::Arg::new("json")
+                .long("json")
+                .help("Print the saved profiles as a JSON array")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("examples"),
+        )
+}
+
+/// A profile file found by 'profile list'
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ProfileEntry {
+    name: String,
+    file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    game: Option<String>,
+    mappings: usize,
+    /// `YYYY-MM-DDTHH:MM:SS` in UTC
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modified: Option<String>,
+}
+
+/// Split `key=value`, e.g. `game=Dark Souls`
+fn parse_filter(filter: &str) -> Result<(String, String), String> {
+    match filter.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{}'", filter)),
+    }
+}
+
+/// CLI handle for the 'profile list' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let filters: Vec<(String, String)> =
+        matches.get_many::<(String, String)>("filter").unwrap_or_default().cloned().collect();
+
+    if !matches.get_flag("examples") {
+        let dir = match matches.get_one::<String>("path") {
+            Some(path) => PathBuf::from(path),
+            None => profiles_dir().context("Cannot find the config directory (HOME is not set)")?,
+        };
+        let entries = scan_profiles(&dir, &filters)?;
+
+        if matches.get_flag("json") {
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        } else if entries.is_empty() {
+            println!("No profiles in {}", dir.display());
+        } else {
+            for line in table_lines(&entries) {
+                println!("{}", line);
+            }
+        }
+        return Ok(());
+    }
+
+    println!("Built-in example profiles:\n");
+    for line in example_lines(&filters) {
+        println!("  {}", line);
+    }
+    println!("\nShow one with: blazeremap profile show --example <name>");
+
+    Ok(())
+}
+
+/// Every loadable profile in `dir` matching `filters`, sorted by file name
+///
+/// Backups are skipped; files that fail to load are reported and skipped.
+fn scan_profiles(dir: &Path, filters: &[(String, String)]) -> Result<Vec<ProfileEntry>> {
+    let read_dir =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+
+    let mut paths: Vec<PathBuf> = read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file() && ProfileFormat::from_path(path).is_some() && !is_backup_path(path)
+        })
+        .collect();
+    paths.sort();
+
+    let mut entries = Vec::new();
+    for path in paths {
+        let profile = match Profile::load_from_file(&path) {
+            Ok(profile) => profile,
+            Err(e) => {
+                eprintln!("  warning: skipping {}: {:#}", path.display(), e);
+                continue;
+            }
+        };
+        if !profile.matches_metadata(filters) {
+            continue;
+        }
+
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        entries.push(ProfileEntry {
+            name: profile.name,
+            file: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            game: profile.game_name,
+            mappings: profile.mappings.len(),
+            modified: modified.map(format_utc),
+        });
+    }
+    Ok(entries)
+}
+
+/// `Name | File | Game | Mappings | Modified` table, header first
+fn table_lines(entries: &[ProfileEntry]) -> Vec<String> {
+    let header = ["Name", "File", "Game", "Mappings", "Modified"].map(String::from);
+    let rows: Vec<[String; 5]> = std::iter::once(header)
+        .chain(entries.iter().map(|entry| {
+            [
+                entry.name.clone(),
+                entry.file.clone(),
+                entry.game.clone().unwrap_or_else(|| "-".to_string()),
+                entry.mappings.to_string(),
+                entry.modified.clone().unwrap_or_else(|| "-".to_string()),
+            ]
+        }))
+        .collect();
+
+    let mut widths = [0; 5];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    rows.iter()
+        .map(|row| {
+            let cells: Vec<String> =
+                row.iter().zip(widths).map(|(cell, width)| format!("{:<width$}", cell)).collect();
+            cells.join(" | ").trim_end().to_string()
+        })
+        .collect()
+}
+
+/// One `name  description` line per example matching `filters`, names padded to line up
+fn example_lines(filters: &[(String, String)]) -> Vec<String> {
+    let examples: Vec<Profile> =
+        Profile::examples().into_iter().filter(|p| p.matches_metadata(filters)).collect();
+    let width = examples.iter().map(|p| p.name.len()).max().unwrap_or(0);
+
+    examples
+        .iter()
+        .map(|profile| format!("{:<width$}  {}", profile.name, profile.description))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_lines() {
+        let lines = example_lines(&[]);
+
+        assert_eq!(lines.len(), Profile::examples().len());
+        assert!(lines[0].starts_with("platformer  "));
+        assert!(lines[1].starts_with("fps         "));
+    }
+
+    #[test]
+    fn test_filter_argument() {
+        let matches = command()
+            .try_get_matches_from(["list", "--examples", "--filter", "game=Dark Souls"])
+            .unwrap();
+        let filters: Vec<&(String, String)> = matches.get_many("filter").unwrap().collect();
+        assert_eq!(filters, vec![&("game".to_string(), "Dark Souls".to_string())]);
+
+        assert!(command().try_get_matches_from(["list", "--filter", "game"]).is_err());
+        assert!(command().try_get_matches_from(["list", "--filter", "=x"]).is_err());
+
+        // No example carries metadata
+        assert!(example_lines(&filters.into_iter().cloned().collect::<Vec<_>>()).is_empty());
+    }
+
+    #[test]
+    fn test_scan_profiles() {
+        let dir = std::env::temp_dir().join(format!("blazeremap-list-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut celeste = Profile::default_profile();
+        celeste.name = "Celeste".to_string();
+        celeste.game_name = Some("Celeste".to_string());
+        celeste.save_to_file(&dir.join("celeste.toml")).unwrap();
+        Profile::default_profile().save_to_file(&dir.join("default.json")).unwrap();
+        Profile::default_profile()
+            .save_to_file(&dir.join("default.json.bak.2024-01-01T00:00:00"))
+            .unwrap();
+        std::fs::write(dir.join("broken.toml"), "not a profile").unwrap();
+        std::fs::write(dir.join("notes.txt"), "hello").unwrap();
+
+        let entries = scan_profiles(&dir, &[]).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let files: Vec<&str> = entries.iter().map(|e| e.file.as_str()).collect();
+        assert_eq!(files, vec!["celeste.toml", "default.json"]);
+        assert_eq!(entries[0].game.as_deref(), Some("Celeste"));
+        assert_eq!(entries[1].mappings, 10);
+
+        let lines = table_lines(&entries);
+        assert!(lines[0].starts_with("Name    | File         | Game    | Mappings | Modified"));
+        assert!(lines[2].starts_with("Default | default.json | -       | 10       | "));
+    }
+
+    #[test]
+    fn test_list_missing_directory() {
+        let matches = command().get_matches_from(vec!["list", "--path", "/nonexistent/profiles"]);
+        assert!(handle(&matches).is_err());
+    }
+}
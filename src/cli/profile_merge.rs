@@ -0,0 +1,125 @@
+// Profile merge command - layer a game-specific profile over a shared base
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::mapping::{
+    MappingEngine,
+    profile::{Profile, ProfileFormat},
+};
+
+/// Build the 'profile merge' command
+pub fn command() -> Command {
+    Command::new("merge")
+        .about("Merge an override profile on top of a base profile")
+        .arg(
+            clap::Arg::new("base")
+                .long("base")
+                .value_name("FILE")
+                .help("Profile with the shared defaults")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("override")
+                .long("override")
+                .value_name("FILE")
+                .help("Profile whose mappings replace the base mappings with the same source")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("out")
+                .long("out")
+                .value_name("FILE")
+                .help("Where to write the merged profile (format from the extension)")
+                .required_unless_present("dry-run"),
+        )
+        .arg(
+            clap::Arg::new("dry-run")
+                .long("dry-run")
+                .help("Print the merged profile instead of writing it")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new("diff")
+                .long("diff")
+                .help("Show which profile each merged mapping came from")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// CLI handle for the 'profile merge' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let base = load(Path::new(matches.get_one::<String>("base").unwrap()))?;
+    let overlay = load(Path::new(matches.get_one::<String>("override").unwrap()))?;
+
+    let merged = Profile::merge(&base, &overlay);
+    MappingEngine::load_from_profile(&merged)
+        .context("Merged profile contains invalid mappings")?;
+
+    if matches.get_flag("diff") {
+        for line in describe_origins(&merged, &overlay) {
+            println!("{}", line);
+        }
+        println!();
+    }
+
+    let out = matches.get_one::<String>("out").map(Path::new);
+    let format = out.and_then(ProfileFormat::from_path).unwrap_or(ProfileFormat::Toml);
+
+    match out {
+        Some(path) if !matches.get_flag("dry-run") => {
+            merged.save_to_file_as(path, format)?;
+            println!(
+                "Merged {} mappings ({} from override) into {}",
+                merged.mappings.len(),
+                overlay.mappings.len(),
+                path.display()
+            );
+        }
+        _ => print!("{}", merged.to_string_as(format)?),
+    }
+
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<Profile> {
+    let format = ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Toml);
+    Profile::load_from_file_as(path, format)
+        .with_context(|| format!("Failed to load {}", path.display()))
+}
+
+/// One line per merged mapping, tagged with the profile it came from
+fn describe_origins(merged: &Profile, overlay: &Profile) -> Vec<String> {
+    merged
+        .mappings
+        .iter()
+        .map(|mapping| {
+            let from_override =
+                overlay.mappings.iter().any(|m| m.source_key() == mapping.source_key());
+            let origin = if from_override { "override" } else { "base" };
+            format!("{:<8}  {}", origin, mapping)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_origins() {
+        let base = Profile::default_profile();
+        let mut overlay = Profile::default_profile();
+        overlay.mappings.retain(|m| m.source_name == "South");
+        overlay.mappings[0].target_name = "Space".to_string();
+
+        let merged = Profile::merge(&base, &overlay);
+        let lines = describe_origins(&merged, &overlay);
+
+        assert_eq!(lines.len(), base.mappings.len());
+        assert!(lines.contains(&"override  South → Space".to_string()));
+        assert_eq!(lines.iter().filter(|line| line.starts_with("override")).count(), 1);
+        assert!(lines.iter().any(|line| line.starts_with("base      North")));
+    }
+}
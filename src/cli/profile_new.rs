@@ -0,0 +1,441 @@
+// Profile new command - create a profile by answering a few questions
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::{
+    input::GamepadType,
+    mapping::profile::{Profile, ProfileFormat, profiles_dir},
+};
+
+/// Controllers `--controller` accepts, in the order the wizard lists them
+const CONTROLLERS: &[(&str, GamepadType)] = &[
+    ("xbox-one", GamepadType::XboxOne),
+    ("xbox-series", GamepadType::XboxSeries),
+    ("xbox-elite", GamepadType::XboxElite),
+    ("dualshock4", GamepadType::DualShock4),
+    ("dualsense", GamepadType::DualSense),
+    ("steam-controller", GamepadType::SteamController),
+    ("generic", GamepadType::Generic),
+];
+
+/// Template with the built-in default mappings, used when none is picked
+const DEFAULT_TEMPLATE: &str = "default";
+
+/// Template without any mappings
+const EMPTY_TEMPLATE: &str = "empty";
+
+/// Metadata key the chosen controller is stored under
+const CONTROLLER_KEY: &str = "controller";
+
+/// Build the 'profile new' command
+pub fn command() -> Command {
+    Command::new("new")
+        .about("Create a profile in ~/.config/blazeremap/profiles by answering a few questions")
+        .arg(
+            clap::Arg::new("name")
+                .long("name")
+                .value_name("NAME")
+                .help("Profile name; the file is named after it")
+                .value_parser(parse_name),
+        )
+        .arg(
+            clap::Arg::new("controller")
+                .long("controller")
+                .value_name("TYPE")
+                .help("Controller the profile is for")
+                .value_parser(clap::builder::PossibleValuesParser::new(
+                    CONTROLLERS.iter().map(|(id, _)| *id),
+                )),
+        )
+        .arg(clap::Arg::new("game").long("game").value_name("GAME").help("Game the profile is for"))
+        .arg(
+            clap::Arg::new("template")
+                .long("template")
+                .value_name("TEMPLATE")
+                .help("Mappings to start from: default, empty or a built-in example (e.g. fps)")
+                .value_parser(parse_template),
+        )
+        .arg(
+            clap::Arg::new("non-interactive")
+                .long("non-interactive")
+                .help("Don't ask; fail if --name or --controller is missing")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new("force")
+                .long("force")
+                .help("Overwrite a profile with the same file name")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Answers to the wizard's questions, `None` while not answered
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Answers {
+    name: Option<String>,
+    controller: Option<&'static str>,
+    /// `Some(None)` when asked and left empty
+    game: Option<Option<String>>,
+    template: Option<String>,
+}
+
+impl Answers {
+    /// The answers given as flags
+    fn from_matches(matches: &ArgMatches) -> Self {
+        Self {
+            name: matches.get_one::<String>("name").cloned(),
+            controller: matches
+                .get_one::<String>("controller")
+                .and_then(|id| find_controller(id))
+                .map(|(id, _)| *id),
+            game: matches.get_one::<String>("game").map(|game| Some(game.clone())),
+            template: matches.get_one::<String>("template").cloned(),
+        }
+    }
+
+    /// The new profile, for `--non-interactive`
+    ///
+    /// Fails if the name or the controller is missing; the game is optional and the
+    /// template defaults to the default mappings.
+    fn into_new_profile(self) -> Result<NewProfile> {
+        let missing: Vec<&str> = [
+            self.name.is_none().then_some("--name"),
+            self.controller.is_none().then_some("--controller"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !missing.is_empty() {
+            anyhow::bail!("--non-interactive needs {}", missing.join(" and "));
+        }
+
+        Ok(NewProfile {
+            name: self.name.unwrap(),
+            controller: self.controller.unwrap(),
+            game: self.game.flatten(),
+            template: self.template.unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()),
+        })
+    }
+}
+
+/// Everything a new profile is made of
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NewProfile {
+    name: String,
+    /// Identifier from [`CONTROLLERS`]
+    controller: &'static str,
+    game: Option<String>,
+    /// Name from [`template_names`]
+    template: String,
+}
+
+impl NewProfile {
+    /// The template's mappings and settings under the new name
+    fn to_profile(&self) -> Profile {
+        let mut profile = template(&self.template).expect("template names are validated");
+        let label = find_controller(self.controller).map_or(GamepadType::Generic, |(_, t)| *t);
+
+        profile.name = self.name.clone();
+        profile.description = match &self.game {
+            Some(game) => format!("{} profile for {}", label, game),
+            None => format!("{} profile", label),
+        };
+        profile.game_name = self.game.clone();
+        profile.metadata.insert(CONTROLLER_KEY.to_string(), self.controller.to_string());
+        profile
+    }
+}
+
+/// CLI handle for the 'profile new' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let given = Answers::from_matches(matches);
+    let new_profile = if matches.get_flag("non-interactive") {
+        given.into_new_profile()?
+    } else {
+        ask(&mut std::io::stdin().lock(), given)?
+    };
+
+    let dir = profiles_dir().context("Cannot find the config directory (HOME is not set)")?;
+    let path = write_profile(&dir, &new_profile.to_profile(), matches.get_flag("force"))?;
+
+    println!("\nCreated {}", path.display());
+    println!("Start remapping with: blazeremap run --profile {}", path.display());
+    Ok(())
+}
+
+/// Ask for every answer not given as a flag, repeating a question until its
+/// answer is valid
+fn ask(input: &mut impl BufRead, given: Answers) -> Result<NewProfile> {
+    let name = match given.name {
+        Some(name) => name,
+        None => ask_until_valid(input, "Profile name: ", parse_name)?,
+    };
+
+    let controller = match given.controller {
+        Some(controller) => controller,
+        None => {
+            println!("\nController:");
+            for (i, (id, gamepad_type)) in CONTROLLERS.iter().enumerate() {
+                println!("[{}] {} ({})", i, gamepad_type, id);
+            }
+            ask_until_valid(input, "Select controller: ", |answer| {
+                pick(answer, CONTROLLERS, |(id, _)| id).map(|(id, _)| *id)
+            })?
+        }
+    };
+
+    let game = match given.game {
+        Some(game) => game,
+        None => {
+            let game = ask_line(input, "\nGame (optional): ")?;
+            (!game.is_empty()).then_some(game)
+        }
+    };
+
+    let template = match given.template {
+        Some(template) => template,
+        None => {
+            let names = template_names();
+            println!("\nStart from a template:");
+            for (i, name) in names.iter().enumerate() {
+                let description = template(name).map(|profile| profile.description);
+                println!("[{}] {} - {}", i, name, description.unwrap_or_default());
+            }
+            ask_until_valid(input, "Select template [0]: ", |answer| match answer {
+                "" => Ok(DEFAULT_TEMPLATE.to_string()),
+                answer => pick(answer, &names, |name| name).cloned(),
+            })?
+        }
+    };
+
+    Ok(NewProfile { name, controller, game, template })
+}
+
+/// Print `prompt` and read one trimmed line; fails at the end of the input
+fn ask_line(input: &mut impl BufRead, prompt: &str) -> Result<String> {
+    print!("{}", prompt);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    if input.read_line(&mut answer).context("Failed to read answer")? == 0 {
+        anyhow::bail!("No answer to '{}'", prompt.trim());
+    }
+    Ok(answer.trim().to_string())
+}
+
+/// Ask `prompt` until `parse` accepts the answer, printing why it didn't
+fn ask_until_valid<T>(
+    input: &mut impl BufRead,
+    prompt: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<T> {
+    loop {
+        match parse(&ask_line(input, prompt)?) {
+            Ok(value) => return Ok(value),
+            Err(e) => println!("  {}", e),
+        }
+    }
+}
+
+/// The item numbered `answer` in a list printed from 0, or the one `id` names
+fn pick<'a, T>(answer: &str, items: &'a [T], id: impl Fn(&T) -> &str) -> Result<&'a T, String> {
+    let item = match answer.parse::<usize>() {
+        Ok(index) => items.get(index),
+        Err(_) => items.iter().find(|item| id(item).eq_ignore_ascii_case(answer)),
+    };
+    item.ok_or_else(|| format!("Enter a number from 0 to {}", items.len() - 1))
+}
+
+fn find_controller(id: &str) -> Option<&'static (&'static str, GamepadType)> {
+    CONTROLLERS.iter().find(|(known, _)| *known == id)
+}
+
+fn parse_name(value: &str) -> Result<String, String> {
+    let name = value.trim();
+    if file_stem(name).is_empty() {
+        return Err("The name needs at least one letter or digit".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// `default`, then the built-in examples, then `empty`
+fn template_names() -> Vec<String> {
+    std::iter::once(DEFAULT_TEMPLATE.to_string())
+        .chain(Profile::examples().into_iter().map(|example| example.name))
+        .chain(std::iter::once(EMPTY_TEMPLATE.to_string()))
+        .collect()
+}
+
+fn template(name: &str) -> Option<Profile> {
+    match name {
+        DEFAULT_TEMPLATE => Some(Profile::default_profile()),
+        EMPTY_TEMPLATE => {
+            let mut profile = Profile::default_profile();
+            profile.mappings.clear();
+            profile.description = "No mappings".to_string();
+            Some(profile)
+        }
+        name => Profile::example(name),
+    }
+}
+
+fn parse_template(value: &str) -> Result<String, String> {
+    let name = value.to_ascii_lowercase();
+    match template(&name) {
+        Some(_) => Ok(name),
+        None => Err(format!("unknown template, expected one of: {}", template_names().join(", "))),
+    }
+}
+
+/// File name for a profile name: lowercase letters and digits, words joined by `-`
+///
+/// `Dark Souls III` becomes `dark-souls-iii`.
+fn file_stem(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Write `profile` to `<dir>/<file stem>.toml`, creating `dir` if needed
+///
+/// An existing file is only replaced when `force` is set.
+fn write_profile(dir: &Path, profile: &Profile, force: bool) -> Result<PathBuf> {
+    let path = dir.join(format!("{}.toml", file_stem(&profile.name)));
+    if path.exists() && !force {
+        anyhow::bail!("{} already exists (use --force to overwrite it)", path.display());
+    }
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    profile
+        .save_to_file_as(&path, ProfileFormat::Toml)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_profiles_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "blazeremap-profile-new-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_file_stem() {
+        assert_eq!(file_stem("Dark Souls III"), "dark-souls-iii");
+        assert_eq!(file_stem("  My   Profile! "), "my-profile");
+        assert_eq!(file_stem("Ōkami"), "ōkami");
+        assert_eq!(file_stem("!!!"), "");
+        assert!(parse_name("!!!").is_err());
+    }
+
+    #[test]
+    fn test_ask_repeats_invalid_answers() {
+        let answers = "\n  \nMy Profile\n9\nxbox-two\n4\nDark Souls III\nchess\n2\n";
+
+        let new_profile = ask(&mut answers.as_bytes(), Answers::default()).unwrap();
+
+        assert_eq!(
+            new_profile,
+            NewProfile {
+                name: "My Profile".to_string(),
+                controller: "dualsense",
+                game: Some("Dark Souls III".to_string()),
+                template: template_names()[2].clone(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_ask_skips_given_answers() {
+        let given = Answers {
+            name: Some("Celeste".to_string()),
+            controller: Some("xbox-one"),
+            ..Answers::default()
+        };
+
+        // The game is left empty and the default template picked
+        let new_profile = ask(&mut "\n\n".as_bytes(), given).unwrap();
+
+        assert_eq!(new_profile.name, "Celeste");
+        assert_eq!(new_profile.game, None);
+        assert_eq!(new_profile.template, DEFAULT_TEMPLATE);
+        assert!(ask(&mut "".as_bytes(), Answers::default()).is_err());
+    }
+
+    #[test]
+    fn test_non_interactive_requires_name_and_controller() {
+        let parse = |args: &[&str]| {
+            let matches = command().try_get_matches_from(args).unwrap();
+            Answers::from_matches(&matches).into_new_profile()
+        };
+
+        let err = parse(&["new", "--non-interactive"]).unwrap_err();
+        assert_eq!(err.to_string(), "--non-interactive needs --name and --controller");
+        let err = parse(&["new", "--name", "Celeste"]).unwrap_err();
+        assert_eq!(err.to_string(), "--non-interactive needs --controller");
+
+        let new_profile = parse(&["new", "--name", "Celeste", "--controller", "generic"]).unwrap();
+        assert_eq!(new_profile.template, DEFAULT_TEMPLATE);
+        assert!(command().try_get_matches_from(["new", "--controller", "xbox-two"]).is_err());
+        assert!(command().try_get_matches_from(["new", "--template", "chess"]).is_err());
+    }
+
+    #[test]
+    fn test_to_profile() {
+        let new_profile = NewProfile {
+            name: "Dark Souls".to_string(),
+            controller: "xbox-one",
+            game: Some("Dark Souls III".to_string()),
+            template: "fps".to_string(),
+        };
+
+        let profile = new_profile.to_profile();
+
+        assert_eq!(profile.name, "Dark Souls");
+        assert_eq!(profile.description, "Xbox One profile for Dark Souls III");
+        assert_eq!(profile.game_name.as_deref(), Some("Dark Souls III"));
+        assert_eq!(profile.metadata[CONTROLLER_KEY], "xbox-one");
+        assert_eq!(profile.mappings, Profile::example("fps").unwrap().mappings);
+
+        let empty = NewProfile { template: EMPTY_TEMPLATE.to_string(), ..new_profile };
+        assert!(empty.to_profile().mappings.is_empty());
+    }
+
+    #[test]
+    fn test_write_profile() {
+        let dir = temp_profiles_dir("write");
+        let profile = NewProfile {
+            name: "Dark Souls III".to_string(),
+            controller: "dualsense",
+            game: None,
+            template: DEFAULT_TEMPLATE.to_string(),
+        }
+        .to_profile();
+
+        let path = write_profile(&dir, &profile, false).unwrap();
+        let loaded = Profile::load_from_file(&path).unwrap();
+        let again = write_profile(&dir, &profile, false);
+        let forced = write_profile(&dir, &profile, true);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(path, dir.join("dark-souls-iii.toml"));
+        let toml = |profile: &Profile| profile.to_string_as(ProfileFormat::Toml).unwrap();
+        assert_eq!(toml(&loaded), toml(&profile));
+        assert!(again.unwrap_err().to_string().contains("already exists"));
+        assert!(forced.is_ok());
+    }
+}
@@ -0,0 +1,67 @@
+// Profile optimize command - remove dead mappings and sort the rest
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::mapping::profile::{Profile, ProfileFormat};
+
+/// Build the 'profile optimize' command
+pub fn command() -> Command {
+    Command::new("optimize")
+        .about("Remove duplicate and unreachable mappings and sort the rest")
+        .arg(
+            clap::Arg::new("in")
+                .long("in")
+                .value_name("FILE")
+                .help("Profile to optimize")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("out")
+                .long("out")
+                .value_name("FILE")
+                .help("Where to write the optimized profile (format from the extension)")
+                .required_unless_present("dry-run"),
+        )
+        .arg(
+            clap::Arg::new("dry-run")
+                .long("dry-run")
+                .help("Only report what would be removed")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// CLI handle for the 'profile optimize' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let input = Path::new(matches.get_one::<String>("in").unwrap());
+    let profile = Profile::load_from_file(input)
+        .with_context(|| format!("Failed to load {}", input.display()))?;
+
+    let (optimized, removed) = profile.optimize();
+
+    for removal in &removed {
+        println!("  removed {}", removal);
+    }
+
+    let out = matches.get_one::<String>("out").map(Path::new);
+    match out {
+        Some(path) if !matches.get_flag("dry-run") => {
+            let format = ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Toml);
+            optimized.save_to_file_as(path, format)?;
+            println!(
+                "Wrote {} mappings ({} removed) to {}",
+                optimized.mappings.len(),
+                removed.len(),
+                path.display()
+            );
+        }
+        _ => println!(
+            "Would keep {} mappings and remove {}",
+            optimized.mappings.len(),
+            removed.len()
+        ),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+// Profile set-default command - the profile 'run' loads without --profile
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::{
+    app::config::{ConfigFile, default_profile_entry},
+    mapping::profile::Profile,
+};
+
+/// Build the 'profile set-default' command
+pub fn command() -> Command {
+    Command::new("set-default")
+        .about("Make a profile the one 'run' loads without --profile")
+        .arg(clap::Arg::new("file").help("Profile to use by default").index(1))
+        .arg(
+            clap::Arg::new("profile")
+                .short('p')
+                .long("profile")
+                .value_name("FILE")
+                .help("Same as FILE")
+                .conflicts_with("file"),
+        )
+        .group(clap::ArgGroup::new("source").args(["file", "profile"]).required(true))
+}
+
+/// CLI handle for the 'profile set-default' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let path = matches
+        .get_one::<String>("file")
+        .or_else(|| matches.get_one::<String>("profile"))
+        .map(Path::new)
+        .unwrap();
+
+    let profile = Profile::load_from_file(path)
+        .with_context(|| format!("Failed to load profile {}", path.display()))?;
+
+    let config_path =
+        ConfigFile::path().context("Cannot find the config directory (HOME is not set)")?;
+    let mut config = ConfigFile::load_from_file(&config_path)?;
+    config.default_profile = Some(default_profile_entry(path)?);
+    config.save_to_file(&config_path)?;
+
+    println!("Default profile is now '{}' ({})", profile.name, path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_default_arguments() {
+        let positional = command().try_get_matches_from(["set-default", "celeste.toml"]).unwrap();
+        assert_eq!(positional.get_one::<String>("file").unwrap(), "celeste.toml");
+        let flag = command().try_get_matches_from(["set-default", "-p", "celeste.toml"]).unwrap();
+        assert_eq!(flag.get_one::<String>("profile").unwrap(), "celeste.toml");
+
+        assert!(command().try_get_matches_from(["set-default"]).is_err());
+        assert!(command().try_get_matches_from(["set-default", "a.toml", "-p", "b.toml"]).is_err());
+    }
+
+    #[test]
+    fn test_set_default_rejects_missing_profile() {
+        let matches = command().get_matches_from(["set-default", "/nonexistent/profile.toml"]);
+        assert!(handle(&matches).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+// Profile settings command - read or change one setting without editing the file
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::mapping::profile::{Profile, ProfileFormat, SETTING_NAMES};
+
+/// Build the 'profile settings' command
+pub fn command() -> Command {
+    Command::new("settings")
+        .about("Show or change a profile setting")
+        .after_help(format!("Settings: {}", SETTING_NAMES.join(", ")))
+        .subcommand_required(true)
+        .arg(
+            clap::Arg::new("profile")
+                .short('p')
+                .long("profile")
+                .value_name("FILE")
+                .help("Profile file to read or modify")
+                .required(true),
+        )
+        .subcommand(
+            Command::new("get")
+                .about("Print the current value of a setting")
+                .arg(clap::Arg::new("name").help("Setting name").required(true).index(1)),
+        )
+        .subcommand(
+            Command::new("set")
+                .about("Change a setting and save the profile")
+                .arg(clap::Arg::new("name").help("Setting name").required(true).index(1))
+                .arg(clap::Arg::new("value").help("New value").required(true).index(2)),
+        )
+}
+
+/// CLI handle for the 'profile settings' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let path = Path::new(matches.get_one::<String>("profile").unwrap());
+    let format = ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Toml);
+    let mut profile = Profile::load_from_file_as(path, format)
+        .with_context(|| format!("Failed to load {}", path.display()))?;
+
+    match matches.subcommand() {
+        Some(("get", sub_matches)) => {
+            let name = sub_matches.get_one::<String>("name").unwrap();
+            println!("{}", profile.settings.get(name)?);
+        }
+        Some(("set", sub_matches)) => {
+            let name = sub_matches.get_one::<String>("name").unwrap();
+            let value = sub_matches.get_one::<String>("value").unwrap();
+            profile.settings.set(name, value)?;
+            profile
+                .save_to_file_as(path, format)
+                .with_context(|| format!("Failed to save {}", path.display()))?;
+            println!("{} = {}", name, profile.settings.get(name)?);
+        }
+        _ => unreachable!("Subcommand required"),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_get_set_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("blazeremap-settings-{}.toml", std::process::id()));
+        Profile::default_profile().save_to_file(&path).unwrap();
+        let profile_arg = path.to_str().unwrap();
+
+        let matches = command()
+            .try_get_matches_from([
+                "settings",
+                "--profile",
+                profile_arg,
+                "set",
+                "vibration_intensity",
+                "80",
+            ])
+            .unwrap();
+        handle(&matches).unwrap();
+
+        let profile = Profile::load_from_file(&path).unwrap();
+        assert_eq!(profile.settings.vibration_intensity, 80);
+        assert_eq!(profile.mappings, Profile::default_profile().mappings);
+
+        let matches = command()
+            .try_get_matches_from(["settings", "--profile", profile_arg, "set", "speed", "1"])
+            .unwrap();
+        assert!(handle(&matches).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
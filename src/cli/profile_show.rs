@@ -0,0 +1,68 @@
+// Profile show command - print a profile as TOML
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{ArgGroup, ArgMatches, Command};
+
+use crate::mapping::profile::{Profile, ProfileFormat};
+
+/// Build the 'profile show' command
+pub fn command() -> Command {
+    Command::new("show")
+        .about("Print a profile as TOML")
+        .arg(clap::Arg::new("file").help("Profile file to show").index(1))
+        .arg(
+            clap::Arg::new("example")
+                .long("example")
+                .value_name("NAME")
+                .help("Show a built-in example profile (see 'profile list --examples')"),
+        )
+        .group(ArgGroup::new("source").args(["file", "example"]).required(true))
+}
+
+/// CLI handle for the 'profile show' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let profile = match matches.get_one::<String>("example") {
+        Some(name) => find_example(name)?,
+        None => {
+            let path = Path::new(matches.get_one::<String>("file").unwrap());
+            let format = ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Toml);
+            Profile::load_from_file_as(path, format)
+                .with_context(|| format!("Failed to load {}", path.display()))?
+        }
+    };
+
+    print!("{}", profile.to_string_as(ProfileFormat::Toml)?);
+    Ok(())
+}
+
+fn find_example(name: &str) -> Result<Profile> {
+    Profile::example(name).with_context(|| {
+        let names: Vec<String> = Profile::examples().into_iter().map(|p| p.name).collect();
+        format!("Unknown example profile '{}'. Available: {}", name, names.join(", "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_example() {
+        assert_eq!(find_example("rpg").unwrap().name, "rpg");
+
+        let err = find_example("racing").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Unknown example profile 'racing'. Available: platformer, fps, fighting, rpg"
+        );
+    }
+
+    #[test]
+    fn test_show_requires_file_or_example() {
+        assert!(command().try_get_matches_from(vec!["show"]).is_err());
+        assert!(
+            command().try_get_matches_from(vec!["show", "foo.toml", "--example", "fps"]).is_err()
+        );
+    }
+}
@@ -0,0 +1,243 @@
+// Profile test command - check a profile's outputs against recorded expectations
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+use serde::{Deserialize, de::DeserializeOwned};
+
+use crate::{
+    event::{InputRecord, OutputEvent, OutputRecord},
+    mapping::{
+        MappingEngine,
+        profile::{Profile, ProfileFormat},
+    },
+};
+
+/// Build the 'profile test' command
+pub fn command() -> Command {
+    Command::new("test")
+        .about("Run recorded input events through a profile and compare the outputs")
+        .arg(
+            clap::Arg::new("profile")
+                .short('p')
+                .long("profile")
+                .value_name("FILE")
+                .help("Profile to test")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("input")
+                .long("input")
+                .value_name("FILE")
+                .help("Input events, one JSON object per line (an event log also works)")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("expected")
+                .long("expected")
+                .value_name("FILE")
+                .help("Expected outputs, one JSON array per input line")
+                .required(true),
+        )
+}
+
+/// CLI handle for the 'profile test' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let profile_path = Path::new(matches.get_one::<String>("profile").unwrap());
+    let input_path = matches.get_one::<String>("input").unwrap();
+    let expected_path = matches.get_one::<String>("expected").unwrap();
+
+    let format = ProfileFormat::from_path(profile_path).unwrap_or(ProfileFormat::Toml);
+    let profile = Profile::load_from_file_as(profile_path, format)
+        .with_context(|| format!("Failed to load {}", profile_path.display()))?;
+    let mut engine = MappingEngine::load_from_profile(&profile)?;
+
+    let inputs = std::fs::read_to_string(input_path)
+        .with_context(|| format!("Failed to read {}", input_path))?;
+    let expected = std::fs::read_to_string(expected_path)
+        .with_context(|| format!("Failed to read {}", expected_path))?;
+
+    let cases = parse_cases(&inputs, &expected)?;
+    let mismatches = run_cases(&mut engine, &cases)?;
+
+    if mismatches.is_empty() {
+        println!("All {} events match.", cases.len());
+        return Ok(());
+    }
+
+    for mismatch in &mismatches {
+        println!("{}", mismatch);
+    }
+    println!();
+
+    anyhow::bail!("{} of {} events did not match", mismatches.len(), cases.len())
+}
+
+/// An input event and the outputs it should produce
+#[derive(Debug, Clone, PartialEq)]
+struct TestCase {
+    /// 1-based line in the input file
+    line: usize,
+    input: InputRecord,
+    expected: Vec<OutputEvent>,
+}
+
+/// A line of the input file: a bare event, or a line of an event log written by
+/// `run --log-events-to` or `read --append`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InputLine {
+    Event(InputRecord),
+    Logged { input: InputRecord },
+}
+
+impl From<InputLine> for InputRecord {
+    fn from(line: InputLine) -> Self {
+        match line {
+            InputLine::Event(input) | InputLine::Logged { input } => input,
+        }
+    }
+}
+
+/// Pair each input line with the expected line at the same position; blank lines are skipped
+fn parse_cases(inputs: &str, expected: &str) -> Result<Vec<TestCase>> {
+    let inputs: Vec<(usize, InputLine)> = parse_lines(inputs, "input")?;
+    let expected: Vec<(usize, Vec<OutputRecord>)> = parse_lines(expected, "expected")?;
+
+    if inputs.len() != expected.len() {
+        anyhow::bail!(
+            "Input has {} events but expected has {} lines (one per event)",
+            inputs.len(),
+            expected.len()
+        );
+    }
+
+    Ok(inputs
+        .into_iter()
+        .zip(expected)
+        .map(|((line, input), (_, outputs))| TestCase {
+            line,
+            input: input.into(),
+            expected: outputs.into_iter().map(OutputEvent::from).collect(),
+        })
+        .collect())
+}
+
+/// Non-blank lines of a JSON Lines file with their 1-based line numbers
+fn parse_lines<T: DeserializeOwned>(contents: &str, file: &str) -> Result<Vec<(usize, T)>> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let value = serde_json::from_str(line)
+                .with_context(|| format!("Invalid {} line {}", file, index + 1))?;
+            Ok((index + 1, value))
+        })
+        .collect()
+}
+
+/// Process every case in order and describe each one whose outputs differ
+fn run_cases(engine: &mut MappingEngine, cases: &[TestCase]) -> Result<Vec<String>> {
+    let mut mismatches = Vec::new();
+
+    for case in cases {
+        let actual = engine.process(&case.input.to_event())?;
+        if actual != case.expected {
+            mismatches.push(format!(
+                "line {}: expected \"{}\" got \"{}\"",
+                case.line,
+                describe_outputs(&case.expected),
+                describe_outputs(&actual)
+            ));
+        }
+    }
+
+    Ok(mismatches)
+}
+
+fn describe_outputs(outputs: &[OutputEvent]) -> String {
+    if outputs.is_empty() {
+        return "(unmapped)".to_string();
+    }
+
+    outputs.iter().map(OutputEvent::summary).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{ButtonCode, KeyboardCode, KeyboardEventType};
+
+    const INPUTS: &str = r#"{"type": "button", "code": "South", "pressed": true}
+{"type": "sync"}
+
+{"type": "button", "code": "South", "pressed": false}
+{"type": "axis", "code": "DPadY", "value": -1}
+"#;
+
+    #[test]
+    fn test_parse_cases_skips_blank_lines() {
+        let expected = r#"[{"type": "keyboard", "code": "S", "event_type": "press"}]
+[]
+[{"type": "keyboard", "code": "S", "event_type": "release"}]
+
+[{"type": "keyboard", "code": "Up", "event_type": "press"}]
+"#;
+
+        let cases = parse_cases(INPUTS, expected).unwrap();
+
+        let lines: Vec<usize> = cases.iter().map(|case| case.line).collect();
+        assert_eq!(lines, vec![1, 2, 4, 5]);
+        assert_eq!(cases[1].input, InputRecord::Sync);
+        assert_eq!(
+            cases[3].expected,
+            vec![OutputEvent::Keyboard {
+                code: KeyboardCode::Up,
+                event_type: KeyboardEventType::Press
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_cases_accepts_event_log_lines() {
+        let inputs = r#"{"ts_us": 0, "time_us": 1700000000000000, "input": {"type": "button", "code": "South", "pressed": true}, "outputs": []}"#;
+
+        let cases = parse_cases(inputs, "[]\n").unwrap();
+
+        assert_eq!(cases[0].input, InputRecord::Button { code: ButtonCode::South, pressed: true });
+    }
+
+    #[test]
+    fn test_parse_cases_rejects_count_mismatch() {
+        let err = parse_cases(INPUTS, "[]\n").unwrap_err();
+        assert!(err.to_string().contains("Input has 4 events but expected has 1 lines"));
+    }
+
+    #[test]
+    fn test_parse_cases_reports_bad_line() {
+        let err = parse_cases("{\"type\": \"button\"}\n", "[]\n").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid input line 1");
+    }
+
+    #[test]
+    fn test_run_cases_reports_mismatches() {
+        let expected = r#"[{"type": "keyboard", "code": "S", "event_type": "press"}]
+[]
+[]
+[{"type": "keyboard", "code": "Down", "event_type": "press"}]
+"#;
+        let cases = parse_cases(INPUTS, expected).unwrap();
+        let mut engine = MappingEngine::new_hardcoded();
+
+        let mismatches = run_cases(&mut engine, &cases).unwrap();
+
+        assert_eq!(
+            mismatches,
+            vec![
+                "line 4: expected \"(unmapped)\" got \"S (release)\"",
+                "line 5: expected \"Down (press)\" got \"Up (press)\"",
+            ]
+        );
+    }
+}
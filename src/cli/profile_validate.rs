@@ -0,0 +1,92 @@
+// Profile validate command - list problems in a profile by severity
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::mapping::{
+    Severity, ValidationIssue,
+    profile::{Profile, ProfileFormat},
+};
+
+use super::style::{OutputStyle, RED, YELLOW};
+
+/// Build the 'profile validate' command
+pub fn command() -> Command {
+    Command::new("validate")
+        .about("Check a profile for errors, likely mistakes and advice")
+        .arg(clap::Arg::new("profile").help("Profile to check").required(true).index(1))
+}
+
+/// CLI handle for the 'profile validate' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let path = Path::new(matches.get_one::<String>("profile").unwrap());
+    let format = ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Toml);
+    let profile = Profile::load_from_file_as(path, format)
+        .with_context(|| format!("Failed to load {}", path.display()))?;
+
+    let issues = profile.validate();
+    if issues.is_empty() {
+        println!("{} is valid", path.display());
+        return Ok(());
+    }
+
+    for line in format_issues(&issues, OutputStyle::from_matches(matches)) {
+        println!("{}", line);
+    }
+
+    let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+    if errors > 0 {
+        println!();
+        anyhow::bail!("{} has {} error(s)", path.display(), errors);
+    }
+    Ok(())
+}
+
+/// `error: mapping 2: ...`, colored by severity
+fn format_issues(issues: &[ValidationIssue], style: OutputStyle) -> Vec<String> {
+    issues
+        .iter()
+        .map(|issue| {
+            let line = format!("{}: {} [{}]", issue.severity, issue, issue.code);
+            match issue.severity {
+                Severity::Error => style.paint(RED, &line),
+                Severity::Warning => style.paint(YELLOW, &line),
+                Severity::Info => line,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(
+        severity: Severity,
+        code: &'static str,
+        mapping_index: Option<usize>,
+    ) -> ValidationIssue {
+        ValidationIssue { severity, code, message: "Something".to_string(), mapping_index }
+    }
+
+    #[test]
+    fn test_format_issues() {
+        let issues = [
+            issue(Severity::Error, "invalid_mapping", Some(2)),
+            issue(Severity::Info, "shared_target", None),
+        ];
+
+        assert_eq!(
+            format_issues(&issues, OutputStyle::PLAIN),
+            vec![
+                "error: mapping 2: Something [invalid_mapping]",
+                "info: Something [shared_target]",
+            ]
+        );
+        assert_eq!(
+            format_issues(&issues[..1], OutputStyle::FANCY),
+            vec!["\x1b[31merror: mapping 2: Something [invalid_mapping]\x1b[0m"]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+// Profile verify-controller command - check a profile against a connected gamepad
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::{
+    event::{AxisCode, ButtonCode},
+    input::gamepad::Gamepad,
+    mapping::{
+        Mapping,
+        profile::{Profile, ProfileFormat},
+        types::TargetType,
+    },
+    platform::linux::LinuxGamepad,
+};
+
+/// Build the 'profile verify-controller' command
+pub fn command() -> Command {
+    Command::new("verify-controller")
+        .about("Check that a gamepad has every input a profile maps")
+        .arg(
+            clap::Arg::new("profile")
+                .short('p')
+                .long("profile")
+                .value_name("FILE")
+                .help("Profile to check")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("device")
+                .short('d')
+                .long("device")
+                .help("Device path (e.g., /dev/input/event3)")
+                .required(true)
+                .add(super::completion::device_completer()),
+        )
+}
+
+/// CLI handle for the 'profile verify-controller' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let profile_path = Path::new(matches.get_one::<String>("profile").unwrap());
+    let device_path = matches.get_one::<String>("device").unwrap();
+
+    let format = ProfileFormat::from_path(profile_path).unwrap_or(ProfileFormat::Toml);
+    let profile = Profile::load_from_file_as(profile_path, format)
+        .with_context(|| format!("Failed to load {}", profile_path.display()))?;
+
+    let gamepad = LinuxGamepad::open(device_path)?;
+    println!("Checking '{}' against {}\n", profile.name, gamepad.name());
+
+    let warnings =
+        find_missing_inputs(&profile, &gamepad.supported_buttons(), &gamepad.supported_axes());
+
+    if warnings.is_empty() {
+        println!("All {} mappings use inputs this controller has.", profile.mappings.len());
+        return Ok(());
+    }
+
+    for warning in &warnings {
+        println!("  warning: {}", warning);
+    }
+    println!();
+
+    // Warnings only: the profile may be meant for several controllers
+    anyhow::bail!("{} of {} mappings may not work", warnings.len(), profile.mappings.len())
+}
+
+/// One warning per enabled mapping whose source the controller does not report
+fn find_missing_inputs(
+    profile: &Profile,
+    buttons: &[ButtonCode],
+    axes: &[AxisCode],
+) -> Vec<String> {
+    profile
+        .mappings
+        .iter()
+        .filter(|mapping| mapping.enabled)
+        .filter_map(|mapping| check_mapping(mapping, buttons, axes))
+        .collect()
+}
+
+fn check_mapping(mapping: &Mapping, buttons: &[ButtonCode], axes: &[AxisCode]) -> Option<String> {
+    let name = mapping.source_name.as_str();
+
+    // Axis mappings are the ones with a direction or an axis target
+    if mapping.source_direction.is_some() || mapping.target_type == TargetType::GamepadAxis {
+        return match AxisCode::from(name) {
+            AxisCode::Unknown => Some(format!("'{}' is not a known axis ({})", name, mapping)),
+            axis if !axes.contains(&axis) => {
+                Some(format!("controller has no {} axis ({})", axis, mapping))
+            }
+            _ => None,
+        };
+    }
+
+    match ButtonCode::from(name) {
+        ButtonCode::Unknown => Some(format!("'{}' is not a known button ({})", name, mapping)),
+        button if !buttons.contains(&button) => {
+            Some(format!("controller has no {} button ({})", button, mapping))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn button_mapping(source: &str) -> Mapping {
+        Mapping {
+            source_name: source.to_string(),
+            source_direction: None,
+            target_type: TargetType::Keyboard,
+            target_name: "A".to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_default_profile_on_full_gamepad() {
+        let buttons = [
+            ButtonCode::South,
+            ButtonCode::East,
+            ButtonCode::North,
+            ButtonCode::West,
+            ButtonCode::Select,
+            ButtonCode::Start,
+        ];
+        let axes = [AxisCode::DPadX, AxisCode::DPadY];
+
+        assert!(find_missing_inputs(&Profile::default_profile(), &buttons, &axes).is_empty());
+    }
+
+    #[test]
+    fn test_missing_paddle_and_dpad() {
+        let mut profile = Profile::default_profile();
+        profile.mappings.push(button_mapping("Paddle1"));
+        let buttons = [
+            ButtonCode::South,
+            ButtonCode::East,
+            ButtonCode::North,
+            ButtonCode::West,
+            ButtonCode::Select,
+            ButtonCode::Start,
+        ];
+
+        let warnings = find_missing_inputs(&profile, &buttons, &[AxisCode::DPadX]);
+
+        assert_eq!(
+            warnings,
+            vec![
+                "controller has no DPad Y axis (DPad Y Negative → Up)",
+                "controller has no DPad Y axis (DPad Y Positive → Down)",
+                "controller has no Paddle 1 button (Paddle 1 → A)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_axis_to_axis_source() {
+        let mut profile = Profile::default_profile();
+        let mut mapping = button_mapping("LeftX");
+        mapping.target_type = TargetType::GamepadAxis;
+        mapping.target_name = "RightX".to_string();
+        profile.mappings = vec![mapping];
+
+        assert!(find_missing_inputs(&profile, &[], &[AxisCode::LeftX]).is_empty());
+        assert_eq!(
+            find_missing_inputs(&profile, &[], &[AxisCode::DPadX]),
+            vec!["controller has no Left X axis (Left X → GamepadAxis RightX)"]
+        );
+    }
+
+    #[test]
+    fn test_disabled_mappings_are_skipped() {
+        let mut profile = Profile::default_profile();
+        let mut paddle = button_mapping("Paddle1");
+        paddle.enabled = false;
+        profile.mappings = vec![paddle];
+
+        assert!(find_missing_inputs(&profile, &[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_source_name() {
+        let mut profile = Profile::default_profile();
+        profile.mappings = vec![button_mapping("Turbo")];
+
+        assert_eq!(
+            find_missing_inputs(&profile, &[], &[]),
+            vec!["'Turbo' is not a known button (Turbo → A)"]
+        );
+    }
+}
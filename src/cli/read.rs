@@ -1,35 +1,331 @@
-use std::time::Instant;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
 
+use crate::cli::ExitCodeError;
+use crate::event::{
+    EventLog, EventRate, InputEvent, OutputEvent, RateHistogram, instant_to_system_time,
+    last_logged_time,
+};
 use crate::input::gamepad::Gamepad;
-use crate::platform::linux::LinuxGamepad;
+use crate::mapping::MappingEngine;
+use crate::mapping::profile::config_dir;
+use crate::platform::new_input_manager;
+
+use super::run::load_profile;
 use anyhow::Result;
 use clap::Command;
+use crossbeam::channel::{self, RecvTimeoutError};
+
+/// Exit status when `--timeout` expires before any event arrived
+const NO_EVENTS_EXIT_CODE: i32 = 2;
+
+/// Width of the raw event column in `--map` mode, so the outputs line up
+const EVENT_COLUMN_WIDTH: usize = 22;
+
+/// How often `--histogram` prints the distribution while reading
+const HISTOGRAM_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Width of the longest `--histogram` bar
+const HISTOGRAM_BAR_WIDTH: usize = 40;
 
 pub fn command() -> Command {
-    Command::new("read").about("Read and display gamepad events (debugging)").arg(
-        clap::Arg::new("device")
-            .help("Device path (e.g., /dev/input/event3)")
-            .required(true)
-            .index(1),
-    )
+    Command::new("read")
+        .about("Read and display gamepad events (debugging)")
+        .arg(
+            clap::Arg::new("device")
+                .help("Device path (e.g., /dev/input/event3)")
+                .required(true)
+                .index(1)
+                .add(super::completion::device_completer()),
+        )
+        .arg(
+            clap::Arg::new("count")
+                .short('n')
+                .long("count")
+                .value_name("N")
+                .help("Exit after printing N events (sync events are not counted)")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .arg(
+            clap::Arg::new("filter")
+                .long("filter")
+                .help("Only show events of this kind")
+                .value_parser(["button", "axis"]),
+        )
+        .arg(
+            clap::Arg::new("timeout")
+                .long("timeout")
+                .value_name("SECONDS")
+                .help("Exit after SECONDS without input (exit code 2 if nothing was received)")
+                .value_parser(parse_timeout),
+        )
+        .arg(
+            clap::Arg::new("map")
+                .long("map")
+                .help("Show what each event maps to (nothing is sent to a virtual keyboard)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new("profile")
+                .short('p')
+                .long("profile")
+                .value_name("FILE")
+                .help("Profile used by --map (the built-in default profile if not specified)")
+                .requires("map"),
+        )
+        .arg(
+            clap::Arg::new("since")
+                .long("since")
+                .value_name("FILE")
+                .help("Only show events newer than the last event in FILE (an event log)"),
+        )
+        .arg(
+            clap::Arg::new("append")
+                .long("append")
+                .value_name("FILE")
+                .help("Append the shown events to FILE in the --log-events-to format"),
+        )
+        .arg(
+            clap::Arg::new("events-per-second")
+                .long("events-per-second")
+                .help(
+                    "Show how many events of the same kind (button or axis) arrived in the \
+                     last second",
+                )
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new("histogram")
+                .long("histogram")
+                .help("Print a histogram of the events-per-second readings every 10s and on exit")
+                .action(clap::ArgAction::SetTrue)
+                .requires("events-per-second"),
+        )
+        .arg(
+            clap::Arg::new("format")
+                .long("format")
+                .help("Print events as text or as JSON lines with microsecond timestamps")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .conflicts_with_all(["map", "events-per-second"]),
+        )
+}
+
+/// Where a `read` session resumes from, where it is recorded and what it measures
+#[derive(Default)]
+struct Session {
+    /// Events at or before this time are not shown
+    since: Option<SystemTime>,
+    log: Option<EventLog>,
+    /// `--events-per-second`
+    rates: Option<EventRates>,
+    /// `--format json`: one JSON line per event, timed from the first event shown,
+    /// with status messages on stderr
+    json: bool,
+}
+
+/// Events per second, counted separately for buttons and axes
+#[derive(Default)]
+struct EventRates {
+    buttons: EventRate,
+    axes: EventRate,
+    /// `--histogram`, with the time it was last printed
+    histogram: Option<(RateHistogram, Option<Instant>)>,
+}
+
+impl EventRates {
+    fn new(histogram: bool) -> Self {
+        Self { histogram: histogram.then(|| (RateHistogram::new(), None)), ..Self::default() }
+    }
+
+    /// Count `event`, returning the rate of its kind; `None` for sync events
+    fn record(&mut self, event: &InputEvent) -> Option<u32> {
+        let rate = match event {
+            InputEvent::Button { timestamp, .. } => self.buttons.record(*timestamp),
+            InputEvent::Axis { timestamp, .. } => self.axes.record(*timestamp),
+            InputEvent::Sync { .. } => return None,
+        };
+        if let Some((histogram, _)) = self.histogram.as_mut() {
+            histogram.record(rate);
+        }
+        Some(rate)
+    }
+
+    /// Print the histogram if it is enabled and, unless `force`, it was last
+    /// printed more than [`HISTOGRAM_INTERVAL`] before `now`
+    fn print_histogram<W: Write>(
+        &mut self,
+        now: Instant,
+        force: bool,
+        writer: &mut W,
+    ) -> Result<()> {
+        let Some((histogram, printed)) = self.histogram.as_mut() else {
+            return Ok(());
+        };
+        let last = *printed.get_or_insert(now);
+        if histogram.is_empty()
+            || !(force || now.saturating_duration_since(last) >= HISTOGRAM_INTERVAL)
+        {
+            return Ok(());
+        }
+
+        writeln!(writer, "Events per second:")?;
+        for line in histogram.render(HISTOGRAM_BAR_WIDTH) {
+            writeln!(writer, "  {}", line)?;
+        }
+        *printed = Some(now);
+        Ok(())
+    }
+}
+
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let seconds: f64 = value.parse().map_err(|_| format!("'{}' is not a number", value))?;
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err("timeout must be greater than 0".to_string());
+    }
+    Ok(Duration::from_secs_f64(seconds))
 }
 
 pub fn handle(matches: &clap::ArgMatches) -> Result<()> {
     let device_path = matches.get_one::<String>("device").unwrap();
+    let count = matches.get_one::<u64>("count").copied();
+    let filter = matches.get_one::<String>("filter").map(String::as_str);
+    let timeout = matches.get_one::<Duration>("timeout").copied();
+    let engine = if matches.get_flag("map") {
+        let profile = load_profile(matches, config_dir().as_deref())?;
+        Some(MappingEngine::load_from_profile(&profile)?)
+    } else {
+        None
+    };
+    // Read --since before --append opens the same file
+    let since = match matches.get_one::<String>("since") {
+        Some(path) => last_logged_time(Path::new(path))?,
+        None => None,
+    };
+    let log = matches
+        .get_one::<String>("append")
+        .map(|path| EventLog::open(Path::new(path), None))
+        .transpose()?;
+    let rates = matches
+        .get_flag("events-per-second")
+        .then(|| EventRates::new(matches.get_flag("histogram")));
+    let json = matches.get_one::<String>("format").is_some_and(|format| format == "json");
 
-    println!("Opening device: {}", device_path);
-    let mut gamepad = LinuxGamepad::open(device_path)?;
+    // Keep stdout to the events themselves when it is going to be parsed
+    if json {
+        eprintln!("Opening device: {}", device_path);
+    } else {
+        println!("Opening device: {}", device_path);
+    }
+    let gamepad = new_input_manager()?.open_gamepad(device_path)?;
+    // Show forwarded axes rescaled the way `run` would send them
+    let engine = engine.map(|mut engine| {
+        if engine.forwards_axes() {
+            engine.set_device_ranges(&gamepad.get_info());
+        }
+        engine
+    });
 
-    println!("Reading events (Ctrl+C to stop)...\n");
-    println!("Format: [elapsed since first event][Δ from previous] Event\n");
+    if !json {
+        if count.is_none() && timeout.is_none() {
+            println!("Reading events (Ctrl+C to stop)...\n");
+        }
+        if rates.is_some() {
+            println!(
+                "Format: [elapsed since first event][Δ from previous][events/s of its kind] Event\n"
+            );
+        } else {
+            println!("Format: [elapsed since first event][Δ from previous] Event\n");
+        }
+    }
+
+    read_events(
+        gamepad,
+        count,
+        filter,
+        timeout,
+        engine,
+        Session { since, log, rates, json },
+        &mut std::io::stdout(),
+    )
+}
 
+/// Print events until the device disconnects, until `count` events were shown,
+/// or until nothing arrived for `timeout`
+///
+/// With an `engine`, every event also goes through the mapping engine and the
+/// outputs are printed next to it. Events from before `session.since` are
+/// skipped, and shown events are appended to `session.log`. With `session.rates`,
+/// each line shows the events per second, and the histogram is printed on exit.
+///
+/// Returns an error if the device disconnects before `count` events arrived, and an
+/// `ExitCodeError` with code 2 if `timeout` expires before any event arrived.
+fn read_events<W>(
+    gamepad: Box<dyn Gamepad>,
+    count: Option<u64>,
+    filter: Option<&str>,
+    timeout: Option<Duration>,
+    mut engine: Option<MappingEngine>,
+    mut session: Session,
+    writer: &mut W,
+) -> Result<()>
+where
+    W: Write,
+{
     let mut first_event_timestamp: Option<Instant> = None;
     let mut last_timestamp: Option<Instant> = None;
+    let mut shown: u64 = 0;
+    let mut received_any = false;
 
-    loop {
-        match gamepad.read_event()? {
+    let events = spawn_reader(gamepad);
+
+    while count.is_none_or(|count| shown < count) {
+        let next = match timeout {
+            Some(timeout) => match events.recv_timeout(timeout) {
+                Ok(next) => next,
+                Err(RecvTimeoutError::Timeout) if received_any => {
+                    let message = format!("No input for {:.1}s", timeout.as_secs_f64());
+                    write_status(&session, writer, &message)?;
+                    break;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(ExitCodeError {
+                        code: NO_EVENTS_EXIT_CODE,
+                        message: format!(
+                            "No events received within {:.1}s (is the device path right?)",
+                            timeout.as_secs_f64()
+                        ),
+                    }
+                    .into());
+                }
+                Err(RecvTimeoutError::Disconnected) => Ok(None),
+            },
+            None => events.recv().unwrap_or(Ok(None)),
+        };
+        received_any = true;
+
+        match next? {
             Some(event) => {
-                if !matches!(event, crate::event::InputEvent::Sync { .. }) {
+                // Counted before filtering, so the rate is the device's
+                let rate = session.rates.as_mut().and_then(|rates| rates.record(&event));
+
+                // Every event goes through the engine so axis state stays right,
+                // even when the filter hides it
+                let outputs = match engine.as_mut() {
+                    Some(engine) => Some(engine.process(&event)?),
+                    None => None,
+                };
+
+                let wanted = match filter {
+                    Some("button") => matches!(event, InputEvent::Button { .. }),
+                    Some("axis") => matches!(event, InputEvent::Axis { .. }),
+                    _ => true,
+                } && session
+                    .since
+                    .is_none_or(|since| instant_to_system_time(event.timestamp()) > since);
+
+                if wanted && !matches!(event, InputEvent::Sync { .. }) {
                     let timestamp = event.timestamp();
 
                     // Initialize start time on the first actual event received
@@ -43,29 +339,103 @@ pub fn handle(matches: &clap::ArgMatches) -> Result<()> {
                         0
                     };
 
-                    println!(
-                        "[{:>8.5}ms][Δ {:>8}µs] {}",
-                        elapsed.as_secs_f64() * 1000.0,
-                        delta,
-                        event
-                    );
+                    if !session.json {
+                        write!(
+                            writer,
+                            "[{:>8.5}ms][Δ {:>8}µs] ",
+                            elapsed.as_secs_f64() * 1000.0,
+                            delta
+                        )?;
+                    }
+                    if let Some(rate) = rate {
+                        write!(writer, "[EPS: {:>5}] ", rate)?;
+                    }
+                    match &outputs {
+                        _ if session.json => writeln!(writer, "{}", event.to_json(first)?)?,
+                        Some(outputs) => writeln!(
+                            writer,
+                            "{:<width$} → {}",
+                            event.to_string(),
+                            describe_outputs(outputs),
+                            width = EVENT_COLUMN_WIDTH
+                        )?,
+                        None => writeln!(writer, "{}", event)?,
+                    }
+                    if let Some(log) = session.log.as_mut() {
+                        log.write(&event, outputs.as_deref().unwrap_or_default())?;
+                    }
 
                     last_timestamp = Some(timestamp);
+                    shown += 1;
+                }
+
+                if let Some(rates) = session.rates.as_mut() {
+                    rates.print_histogram(event.timestamp(), false, writer)?;
                 }
             }
             None => {
-                println!("Device disconnected");
+                write_status(&session, writer, "Device disconnected")?;
+                if let Some(count) = count {
+                    print_final_histogram(&mut session, writer)?;
+                    anyhow::bail!("Device disconnected after {} of {} events", shown, count);
+                }
                 break;
             }
         }
     }
 
+    print_final_histogram(&mut session, writer)
+}
+
+/// Write a message that is not an event; on stderr with `--format json`
+fn write_status<W: Write>(session: &Session, writer: &mut W, message: &str) -> Result<()> {
+    if session.json {
+        eprintln!("{}", message);
+    } else {
+        writeln!(writer, "{}", message)?;
+    }
     Ok(())
 }
 
+fn print_final_histogram<W: Write>(session: &mut Session, writer: &mut W) -> Result<()> {
+    match session.rates.as_mut() {
+        Some(rates) => rates.print_histogram(Instant::now(), true, writer),
+        None => Ok(()),
+    }
+}
+
+/// `S (press), Up (release)`, or `(unmapped)` when nothing was produced
+fn describe_outputs(outputs: &[OutputEvent]) -> String {
+    if outputs.is_empty() {
+        return "(unmapped)".to_string();
+    }
+    outputs.iter().map(OutputEvent::summary).collect::<Vec<_>>().join(", ")
+}
+
+/// Read events on a background thread so the caller can wait with a timeout
+///
+/// The thread stops after forwarding a disconnect or an error. While it is blocked
+/// in `read_event` it cannot be cancelled, so it ends with the process.
+fn spawn_reader(mut gamepad: Box<dyn Gamepad>) -> channel::Receiver<Result<Option<InputEvent>>> {
+    let (sender, receiver) = channel::unbounded();
+
+    std::thread::spawn(move || {
+        loop {
+            let next = gamepad.read_event();
+            let finished = !matches!(next, Ok(Some(_)));
+            if sender.send(next).is_err() || finished {
+                break;
+            }
+        }
+    });
+
+    receiver
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mapping::profile::Profile;
 
     #[test]
     fn test_command_structure() {
@@ -86,4 +456,328 @@ mod tests {
         assert!(device_arg.is_required_set());
         assert!(device_arg.get_help().unwrap().to_string().contains("/dev/input/event"));
     }
+
+    fn mock_gamepad(events: Vec<InputEvent>) -> crate::input::gamepad::MockGamepad {
+        let mut gamepad = crate::input::gamepad::MockGamepad::new();
+        let mut events = events.into_iter();
+        gamepad.expect_read_event().returning(move || Ok(events.next()));
+        gamepad
+    }
+
+    #[test]
+    fn test_read_events_stops_after_count() {
+        use crate::event::ButtonCode;
+
+        let gamepad = mock_gamepad(vec![
+            InputEvent::button_press(ButtonCode::South),
+            InputEvent::sync(),
+            InputEvent::button_release(ButtonCode::South),
+            InputEvent::button_press(ButtonCode::East),
+        ]);
+        let mut output = Vec::new();
+
+        read_events(Box::new(gamepad), Some(2), None, None, None, Session::default(), &mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 2);
+        assert!(!output.contains("East"));
+    }
+
+    #[test]
+    fn test_read_events_filter_button() {
+        use crate::event::{AxisCode, ButtonCode};
+
+        let gamepad = mock_gamepad(vec![
+            InputEvent::axis_move(AxisCode::LeftX, 200),
+            InputEvent::button_press(ButtonCode::North),
+        ]);
+        let mut output = Vec::new();
+
+        read_events(
+            Box::new(gamepad),
+            Some(1),
+            Some("button"),
+            None,
+            None,
+            Session::default(),
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains("North"));
+    }
+
+    #[test]
+    fn test_read_events_disconnect_before_count_fails() {
+        use crate::event::ButtonCode;
+
+        let gamepad = mock_gamepad(vec![InputEvent::button_press(ButtonCode::South)]);
+        let mut output = Vec::new();
+
+        let result = read_events(
+            Box::new(gamepad),
+            Some(3),
+            None,
+            None,
+            None,
+            Session::default(),
+            &mut output,
+        );
+
+        assert_eq!(result.unwrap_err().to_string(), "Device disconnected after 1 of 3 events");
+    }
+
+    #[test]
+    fn test_read_events_disconnect_without_count_succeeds() {
+        let gamepad = mock_gamepad(vec![]);
+        let mut output = Vec::new();
+
+        assert!(
+            read_events(Box::new(gamepad), None, None, None, None, Session::default(), &mut output)
+                .is_ok()
+        );
+        assert_eq!(String::from_utf8(output).unwrap(), "Device disconnected\n");
+    }
+
+    #[test]
+    fn test_read_events_json_format() {
+        use crate::event::{AxisCode, ButtonCode, InputRecord, TimedInputRecord};
+
+        let start = Instant::now();
+        let gamepad = mock_gamepad(vec![
+            InputEvent::Button { code: ButtonCode::South, pressed: true, timestamp: start },
+            InputEvent::Sync { timestamp: start },
+            InputEvent::Axis {
+                code: AxisCode::LeftX,
+                value: 120,
+                timestamp: start + Duration::from_micros(800),
+            },
+        ]);
+        let mut output = Vec::new();
+        let session = Session { json: true, ..Session::default() };
+
+        read_events(Box::new(gamepad), None, None, None, None, session, &mut output).unwrap();
+
+        // The disconnect message went to stderr
+        let records: Vec<TimedInputRecord> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            records,
+            [
+                TimedInputRecord {
+                    event: InputRecord::Button { code: ButtonCode::South, pressed: true },
+                    timestamp_us: 0
+                },
+                TimedInputRecord {
+                    event: InputRecord::Axis { code: AxisCode::LeftX, value: 120 },
+                    timestamp_us: 800
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_conflicts_with_map() {
+        let result =
+            command().try_get_matches_from(["read", "/dev/input/event3", "--format", "json"]);
+        assert!(result.is_ok());
+
+        let result = command().try_get_matches_from([
+            "read",
+            "/dev/input/event3",
+            "--format",
+            "json",
+            "--map",
+        ]);
+        assert!(result.is_err());
+    }
+
+    /// A gamepad that delivers `events`, then goes silent without disconnecting
+    fn silent_gamepad(events: Vec<InputEvent>) -> crate::input::gamepad::MockGamepad {
+        let mut gamepad = crate::input::gamepad::MockGamepad::new();
+        let mut events = events.into_iter();
+        gamepad.expect_read_event().returning(move || match events.next() {
+            Some(event) => Ok(Some(event)),
+            None => loop {
+                std::thread::sleep(Duration::from_secs(60));
+            },
+        });
+        gamepad
+    }
+
+    #[test]
+    fn test_read_events_timeout_after_input() {
+        use crate::event::ButtonCode;
+
+        let gamepad = silent_gamepad(vec![InputEvent::button_press(ButtonCode::South)]);
+        let mut output = Vec::new();
+
+        read_events(
+            Box::new(gamepad),
+            None,
+            None,
+            Some(Duration::from_millis(50)),
+            None,
+            Session::default(),
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("South (pressed)"));
+        assert!(output.ends_with("No input for 0.1s\n"));
+    }
+
+    #[test]
+    fn test_read_events_timeout_without_input() {
+        let gamepad = silent_gamepad(vec![]);
+        let mut output = Vec::new();
+
+        let err = read_events(
+            Box::new(gamepad),
+            None,
+            None,
+            Some(Duration::from_millis(50)),
+            None,
+            Session::default(),
+            &mut output,
+        )
+        .unwrap_err();
+
+        assert_eq!(err.downcast_ref::<ExitCodeError>().unwrap().code, 2);
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(parse_timeout("0.5"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_timeout("5"), Ok(Duration::from_secs(5)));
+        assert!(parse_timeout("0").is_err());
+        assert!(parse_timeout("-1").is_err());
+        assert!(parse_timeout("soon").is_err());
+    }
+
+    #[test]
+    fn test_read_events_with_map() {
+        use crate::event::{AxisCode, ButtonCode};
+
+        let gamepad = mock_gamepad(vec![
+            InputEvent::button_press(ButtonCode::South),
+            InputEvent::button_press(ButtonCode::North),
+            InputEvent::button_press(ButtonCode::LeftShoulder),
+            InputEvent::axis_move(AxisCode::DPadY, -1),
+        ]);
+        let mut output = Vec::new();
+
+        read_events(
+            Box::new(gamepad),
+            None,
+            None,
+            None,
+            Some(MappingEngine::load_from_profile(&Profile::default_profile()).unwrap()),
+            Session::default(),
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].ends_with("South (pressed)        → S (press)"));
+        assert!(lines[1].ends_with("North (pressed)        → W (press)"));
+        assert!(lines[2].ends_with("Left Shoulder (pressed) → (unmapped)"));
+        assert!(lines[3].ends_with("DPad Y: -1             → Up (press)"));
+    }
+
+    #[test]
+    fn test_read_events_since_and_append() {
+        use crate::event::ButtonCode;
+
+        let old = InputEvent::button_press(ButtonCode::South);
+        std::thread::sleep(Duration::from_millis(2));
+        let since = instant_to_system_time(Instant::now());
+        std::thread::sleep(Duration::from_millis(2));
+        let new = InputEvent::button_press(ButtonCode::East);
+
+        let path = std::env::temp_dir().join(format!("blazeremap_read_{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let session = Session {
+            since: Some(since),
+            log: Some(EventLog::open(&path, None).unwrap()),
+            rates: None,
+            json: false,
+        };
+        let mut output = Vec::new();
+
+        read_events(
+            Box::new(mock_gamepad(vec![old, new])),
+            None,
+            None,
+            None,
+            None,
+            session,
+            &mut output,
+        )
+        .unwrap();
+
+        let logged = std::fs::read_to_string(&path).unwrap();
+        let last = last_logged_time(&path).unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(!output.contains("South"));
+        assert!(output.contains("East (pressed)"));
+        assert_eq!(logged.lines().count(), 1);
+        assert!(last > since);
+    }
+
+    #[test]
+    fn test_read_events_per_second() {
+        use crate::event::{AxisCode, ButtonCode};
+
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let gamepad = mock_gamepad(vec![
+            InputEvent::Axis { code: AxisCode::LeftX, value: 1, timestamp: at(0) },
+            InputEvent::Axis { code: AxisCode::LeftX, value: 2, timestamp: at(4) },
+            InputEvent::Button { code: ButtonCode::South, pressed: true, timestamp: at(8) },
+            InputEvent::Axis { code: AxisCode::LeftX, value: 3, timestamp: at(1002) },
+        ]);
+        let session = Session { rates: Some(EventRates::new(true)), ..Session::default() };
+        let mut output = Vec::new();
+
+        read_events(Box::new(gamepad), None, None, None, None, session, &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].ends_with("[EPS:     1] Left X: 1"));
+        assert!(lines[1].ends_with("[EPS:     2] Left X: 2"));
+        assert!(lines[2].ends_with("[EPS:     1] South (pressed)"));
+        // The first axis event left the one-second window
+        assert!(lines[3].ends_with("[EPS:     2] Left X: 3"));
+        assert_eq!(lines[4], "Device disconnected");
+        assert_eq!(lines[5], "Events per second:");
+        let bar = "#".repeat(HISTOGRAM_BAR_WIDTH);
+        assert_eq!(
+            lines[6..],
+            ["  0-0 |  0".to_string(), format!("  1-1 | {} 2", bar), format!("  2-2 | {} 2", bar)]
+        );
+    }
+
+    #[test]
+    fn test_histogram_requires_events_per_second() {
+        let result = command().try_get_matches_from(["read", "/dev/input/event3", "--histogram"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_profile_requires_map() {
+        let result =
+            command().try_get_matches_from(["read", "/dev/input/event3", "--profile", "a.toml"]);
+        assert!(result.is_err());
+    }
 }
@@ -1,29 +1,171 @@
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, SystemTime},
+};
+
 use anyhow::{Context, Result};
 use clap::Command;
 
+use super::profile_backup::backup_profile;
+
 use crate::{
     InputManager,
-    event::EventLoop,
-    mapping::MappingEngine,
-    output::keyboard::VirtualKeyboard,
-    platform::{new_input_manager, new_virtual_keyboard},
+    app::config::resolve_default_profile,
+    event::{
+        DEFAULT_STATS_INTERVAL, EventLog, EventLoop, EventStream, FallbackProfile, IdleWatchdog,
+        KeyboardCode, StatsExporter,
+    },
+    input::{Gamepad, GamepadInfo, ReconnectConfig, gamepad::find_gamepad_by_name},
+    mapping::{
+        Severity,
+        profile::{Profile, config_dir},
+    },
+    output::{gamepad::VIRTUAL_GAMEPAD_NAME, keyboard::VirtualKeyboard},
+    platform::{
+        linux::{PidFile, install_stop_handler, interrupt_stopping_thread, stop_requested},
+        new_input_manager, new_virtual_gamepad, new_virtual_keyboard_with_keys, watch_file,
+    },
 };
 
 /// Build the 'run' command
 pub fn command() -> Command {
-    Command::new("run").about("Run the remapping daemon").arg(
-        clap::Arg::new("device")
-            .short('d')
-            .long("device")
-            .help("Specific device path (auto-detect if not specified)"),
-    )
+    Command::new("run")
+        .about("Run the remapping daemon")
+        .arg(
+            clap::Arg::new("device")
+                .short('d')
+                .long("device")
+                .help("Specific device path (auto-detect if not specified)")
+                .add(super::completion::device_completer()),
+        )
+        .arg(
+            clap::Arg::new("device-name")
+                .long("device-name")
+                .value_name("NAME")
+                .help("Use the gamepad whose name contains NAME (case-insensitive)")
+                .conflicts_with("device"),
+        )
+        .arg(
+            clap::Arg::new("device-select")
+                .long("device-select")
+                .value_name("HOW")
+                .help("Which detected gamepad to use without --device or --device-name")
+                .value_parser(["first", "last", "interactive"])
+                .default_value("first")
+                .conflicts_with_all(["device", "device-name"]),
+        )
+        .arg(
+            clap::Arg::new("profile")
+                .short('p')
+                .long("profile")
+                .value_name("FILE")
+                .help("Profile to load (the default profile if not specified)"),
+        )
+        .arg(clap::Arg::new("emit-events").long("emit-events").value_name("SOCKET").help(
+            "Stream processed events as JSON Lines to a Unix socket at this path; \
+                     clients can send {\"action\":\"health\"} to get the loop's health",
+        ))
+        .arg(
+            clap::Arg::new("log-events-to")
+                .long("log-events-to")
+                .value_name("FILE")
+                .help("Append every processed event to FILE as JSON Lines (for bug reports)"),
+        )
+        .arg(
+            clap::Arg::new("log-max-size-mb")
+                .long("log-max-size-mb")
+                .value_name("MB")
+                .help("Move the event log to FILE.1 and start a new one when it reaches MB")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .requires("log-events-to"),
+        )
+        .arg(
+            clap::Arg::new("stats-file")
+                .long("stats-file")
+                .value_name("FILE")
+                .help("Keep FILE updated with JSON statistics (event count, latency, errors)"),
+        )
+        .arg(
+            clap::Arg::new("stats-interval")
+                .long("stats-interval")
+                .value_name("SECONDS")
+                .help("How often --stats-file is rewritten")
+                .value_parser(parse_interval)
+                .default_value(default_stats_interval())
+                .requires("stats-file"),
+        )
+        .arg(
+            clap::Arg::new("timeout")
+                .long("timeout")
+                .value_name("SECONDS")
+                .help("Stop after SECONDS without controller input (0 = never)")
+                .value_parser(parse_delay)
+                .default_value("0"),
+        )
+        .arg(
+            clap::Arg::new("watch")
+                .long("watch")
+                .help("Reload the profile when the file changes")
+                .requires("profile")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new("auto-backup")
+                .long("auto-backup")
+                .help("Back up the profile, as 'profile backup' does, before each reload")
+                .requires("watch")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new("fallback-profile")
+                .long("fallback-profile")
+                .value_name("FILE")
+                .help("Profile to use when --profile fails to load or reload"),
+        )
+        .arg(
+            clap::Arg::new("reconnect-delay")
+                .long("reconnect-delay")
+                .value_name("SECONDS")
+                .help(
+                    "Keep running when the controller disconnects, retrying after SECONDS \
+                     (doubling each attempt)",
+                )
+                .value_parser(parse_delay),
+        )
+        .arg(
+            clap::Arg::new("reconnect-max-delay")
+                .long("reconnect-max-delay")
+                .value_name("SECONDS")
+                .help("Longest wait between reconnect attempts")
+                .value_parser(parse_delay)
+                .default_value("5")
+                .requires("reconnect-delay"),
+        )
+        .arg(
+            clap::Arg::new("pid-file")
+                .long("pid-file")
+                .value_name("FILE")
+                .help("Write the process ID to FILE, removing it on exit"),
+        )
+        .arg(
+            clap::Arg::new("allow-multiple")
+                .long("allow-multiple")
+                .help("Don't fail when the PID file names a running instance")
+                .requires("pid-file")
+                .action(clap::ArgAction::SetTrue),
+        )
 }
 
 /// CLI handle for the 'run' command
 pub fn handle(matches: &clap::ArgMatches) -> Result<()> {
-    let manager = new_input_manager();
+    let manager = new_input_manager()?;
 
-    run_internal(matches, manager.as_ref(), new_virtual_keyboard)
+    run_internal(matches, config_dir().as_deref(), manager.as_ref(), new_virtual_keyboard_with_keys)
 }
 
 /// Internal run logic that is decoupled from platform-specific implementations for testing
@@ -34,68 +176,366 @@ pub fn handle(matches: &clap::ArgMatches) -> Result<()> {
 /// - Independent testing of business logic vs. platform integration
 fn run_internal<F>(
     matches: &clap::ArgMatches,
+    config_dir: Option<&Path>,
     manager: &dyn InputManager,
     make_keyboard: F,
 ) -> Result<()>
 where
-    F: FnOnce(&str) -> Result<Box<dyn VirtualKeyboard>>,
+    F: FnOnce(&str, &[KeyboardCode]) -> Result<Box<dyn VirtualKeyboard>>,
 {
     tracing::info!("BlazeRemap v{} starting...", env!("CARGO_PKG_VERSION"));
 
-    // Get device path
-    let device_path = if let Some(path) = matches.get_one::<String>("device") {
-        path.clone() // User specified a device path
-    } else {
-        // Auto-detect first controller
-        println!("Detecting controllers...");
-        let gamepads = manager.list_gamepads()?;
-
-        if gamepads.gamepad_info.is_empty() {
-            anyhow::bail!("No controllers detected. Please connect a controller.");
+    // Held until the loop returns, then removed
+    let _pid_file = match matches.get_one::<String>("pid-file") {
+        Some(path) if matches.get_flag("allow-multiple") => {
+            Some(PidFile::overwrite(Path::new(path))?)
         }
-
-        println!("Found {} gamepad(s)", gamepads.gamepad_info.len());
-        println!("Using: {}", gamepads.gamepad_info[0].name);
-        gamepads.gamepad_info[0].path.clone()
+        Some(path) => Some(PidFile::create(Path::new(path))?),
+        None => None,
     };
 
+    // Get device path
+    let device_path = select_device_path(matches, manager)?;
+
     // Open controller
     println!("Opening device: {}", device_path);
     let controller = manager.open_gamepad(&device_path).context("Failed to open controller")?;
+    // After a reconnect, a gamepad picked by --device stays at that path; otherwise
+    // it is looked up by name in case it came back under another path
+    let reconnect_name = (matches.contains_id("reconnect-delay") && !matches.contains_id("device"))
+        .then(|| controller.name().to_string());
+
+    // Load profile
+    let fallback = load_fallback_profile(matches)?;
+    let profile = match (load_profile(matches, config_dir), &fallback) {
+        (Ok(profile), _) => profile,
+        (Err(e), Some(fallback)) => {
+            println!(
+                "Profile failed to load, using fallback: {} ({:#})",
+                fallback.path.display(),
+                e
+            );
+            fallback.profile.clone()
+        }
+        (Err(e), None) => return Err(e),
+    };
+    let mappings = describe_mappings(&profile);
+    let profile_name = profile.name.clone();
+    for issue in profile.validate() {
+        if issue.severity == Severity::Warning {
+            println!("  warning: {}", issue);
+        }
+    }
 
-    // Create mapping engine
-    println!("Loading hardcoded mappings...");
-    let engine = MappingEngine::new_hardcoded();
-
-    // Create virtual keyboard
+    // Create mapping engine and virtual keyboard
     println!("Creating virtual keyboard...");
-    let keyboard = make_keyboard("BlazeRemap Virtual Keyboard")
-        .context("Failed to create virtual keyboard")?;
+    let mut event_loop = match fallback {
+        Some(fallback) => {
+            EventLoop::new_with_fallback(controller, profile, fallback, make_keyboard)?
+        }
+        None => EventLoop::new_with_profile(controller, profile, make_keyboard)?,
+    };
+
+    let gamepad_axes = event_loop.target_axes();
+    if !gamepad_axes.is_empty() {
+        println!("Creating virtual gamepad...");
+        let virtual_gamepad = new_virtual_gamepad(VIRTUAL_GAMEPAD_NAME, &gamepad_axes)?;
+        event_loop = event_loop.with_virtual_gamepad(virtual_gamepad);
+    }
+
+    if let Some(socket_path) = matches.get_one::<String>("emit-events") {
+        let event_stream =
+            EventStream::bind(Path::new(socket_path))?.with_health(event_loop.health_monitor());
+        println!("Streaming events to {}", socket_path);
+        event_loop = event_loop.with_event_stream(event_stream);
+    }
+
+    if let Some(log_path) = matches.get_one::<String>("log-events-to") {
+        let max_size = matches.get_one::<u64>("log-max-size-mb").map(|mb| mb * 1024 * 1024);
+        let event_log = EventLog::open(Path::new(log_path), max_size)?;
+        println!("Logging events to {}", log_path);
+        event_loop = event_loop.with_event_log(event_log);
+    }
+
+    if let Some(&initial_delay) = matches.get_one::<Duration>("reconnect-delay") {
+        let config = ReconnectConfig {
+            initial_delay,
+            max_delay: *matches.get_one::<Duration>("reconnect-max-delay").unwrap(),
+        };
+        event_loop = event_loop.with_reconnect(config, move || {
+            reopen_gamepad(new_input_manager()?.as_ref(), &device_path, reconnect_name.as_deref())
+        });
+    }
+
+    if matches.get_flag("watch") {
+        let profile_path = PathBuf::from(matches.get_one::<String>("profile").unwrap());
+        let events = watch_file(profile_path.clone())?;
+        println!("Watching {} for changes", profile_path.display());
+        event_loop = event_loop
+            .with_profile_watch(profile_path, events)
+            .with_keyboard_factory(new_virtual_keyboard_with_keys);
+        if matches.get_flag("auto-backup") {
+            event_loop = event_loop.with_before_reload(|path| {
+                match backup_profile(path, SystemTime::now()) {
+                    Ok(backup) => {
+                        tracing::info!("Backed up {} to {}", path.display(), backup.display())
+                    }
+                    Err(e) => tracing::warn!("Profile backup failed: {:#}", e),
+                }
+            });
+        }
+    }
+
+    let stats_exporter = matches.get_one::<String>("stats-file").map(|stats_path| {
+        let interval = *matches.get_one::<Duration>("stats-interval").unwrap();
+        println!("Writing statistics to {} every {:.1}s", stats_path, interval.as_secs_f64());
+        StatsExporter::new(PathBuf::from(stats_path), interval)
+            .spawn(event_loop.health_monitor(), profile_name)
+    });
 
     println!("\nBlazeRemap is now running!");
     println!("Mappings:");
-    println!("  D-pad button → Arrow");
-    println!("  South button → S");
-    println!("  West button → A");
-    println!("  East button → D");
+    for line in mappings {
+        println!("  {}", line);
+    }
     println!("\nPress Ctrl+C to exit.\n");
 
-    // Create and run event loop
-    let event_loop = EventLoop::new(controller, engine, keyboard);
-    event_loop.run()?;
+    // Stop on Ctrl+C, SIGTERM or --timeout by unwinding, so the PID file,
+    // socket and virtual devices are cleaned up by their Drop impls
+    install_stop_handler()?;
+    let timeout = *matches.get_one::<Duration>("timeout").unwrap();
+    let (event_loop, idle) =
+        with_stop_conditions(event_loop, stop_requested, timeout, interrupt_stopping_thread);
+
+    // Run event loop
+    let result = event_loop.run();
+    if let Some(stats_exporter) = stats_exporter {
+        stats_exporter.stop();
+    }
+    result?;
 
+    if idle.load(Ordering::SeqCst) {
+        println!("Controller idle for {}s, stopping", timeout.as_secs_f64());
+    }
     println!("BlazeRemap stopped.");
     Ok(())
 }
 
+/// Stop `event_loop` once `stop` returns true or the controller was idle for
+/// `idle_timeout` (0 disables it); the returned flag is set if the timeout fired
+///
+/// The loop is blocked reading the controller while it is idle, so the
+/// watchdog calls `interrupt` to break that read.
+fn with_stop_conditions<S, I>(
+    event_loop: EventLoop,
+    stop: S,
+    idle_timeout: Duration,
+    interrupt: I,
+) -> (EventLoop, Arc<AtomicBool>)
+where
+    S: Fn() -> bool + Send + 'static,
+    I: FnOnce() -> Result<()> + Send + 'static,
+{
+    let idle = Arc::new(AtomicBool::new(false));
+    if !idle_timeout.is_zero() {
+        let timed_out = idle.clone();
+        IdleWatchdog::new(idle_timeout).spawn(event_loop.health_monitor(), move || {
+            timed_out.store(true, Ordering::SeqCst);
+            if let Err(e) = interrupt() {
+                tracing::warn!("Failed to stop after idle timeout: {:#}", e);
+            }
+        });
+    }
+
+    let idle_fired = idle.clone();
+    let event_loop = event_loop.with_stop_when(move || stop() || idle_fired.load(Ordering::SeqCst));
+    (event_loop, idle)
+}
+
+/// Use `--device` if given, else the gamepad matching `--device-name`, else the
+/// detected gamepad picked by `--device-select` (the first one by default)
+pub(super) fn select_device_path(
+    matches: &clap::ArgMatches,
+    manager: &dyn InputManager,
+) -> Result<String> {
+    if let Some(path) = matches.get_one::<String>("device") {
+        return Ok(path.clone()); // User specified a device path
+    }
+
+    if let Some(pattern) = matches.get_one::<String>("device-name") {
+        let gamepads = manager.list_gamepads()?;
+        let info = find_gamepad_by_name(&gamepads.gamepad_info, pattern)?;
+        println!("Using: {}", info.name);
+        return Ok(info.path.clone());
+    }
+
+    // Auto-detect controller
+    println!("Detecting controllers...");
+    let gamepads = manager.list_gamepads()?.gamepad_info;
+
+    if gamepads.is_empty() {
+        anyhow::bail!("No controllers detected. Please connect a controller.");
+    }
+
+    println!("Found {} gamepad(s)", gamepads.len());
+    // Commands without --device-select always take the first gamepad
+    let how = matches.try_get_one::<String>("device-select").ok().flatten();
+    let index = match how.map(String::as_str) {
+        Some("last") => gamepads.len() - 1,
+        Some("interactive") => prompt_for_gamepad(&mut std::io::stdin().lock(), &gamepads)?,
+        _ => 0,
+    };
+    println!("Using: {}", gamepads[index].name);
+    Ok(gamepads[index].path.clone())
+}
+
+/// List the gamepads and ask for one by number until the answer is valid
+///
+/// An empty answer picks the first gamepad.
+fn prompt_for_gamepad(input: &mut impl BufRead, gamepads: &[GamepadInfo]) -> Result<usize> {
+    for (i, info) in gamepads.iter().enumerate() {
+        println!("[{}] {} ({})", i, info.display_name(), info.path);
+    }
+
+    loop {
+        print!("Select controller [0]: ");
+        std::io::stdout().flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer).context("Failed to read answer")? == 0 {
+            anyhow::bail!("No controller selected");
+        }
+
+        match answer.trim() {
+            "" => return Ok(0),
+            answer => match answer.parse::<usize>() {
+                Ok(index) if index < gamepads.len() => return Ok(index),
+                _ => println!("Enter a number from 0 to {}", gamepads.len() - 1),
+            },
+        }
+    }
+}
+
+/// Open the gamepad at `path` again, or the one called `name` if it came back
+/// under a different path
+fn reopen_gamepad(
+    manager: &dyn InputManager,
+    path: &str,
+    name: Option<&str>,
+) -> Result<Box<dyn Gamepad>> {
+    let error = match manager.open_gamepad(path) {
+        Ok(gamepad) => return Ok(gamepad),
+        Err(e) => e,
+    };
+    let Some(name) = name else {
+        return Err(error);
+    };
+
+    let gamepads = manager.list_gamepads_fresh()?.gamepad_info;
+    let info = gamepads
+        .iter()
+        .find(|info| info.name == name)
+        .with_context(|| format!("{} is not connected", name))?;
+    manager.open_gamepad(&info.path)
+}
+
+/// Seconds as a `Duration`; 0 is allowed
+fn parse_delay(value: &str) -> Result<Duration, String> {
+    let seconds: f64 = value.parse().map_err(|_| format!("'{}' is not a number", value))?;
+    if !seconds.is_finite() || seconds < 0.0 {
+        return Err("delay must not be negative".to_string());
+    }
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+fn parse_interval(value: &str) -> Result<Duration, String> {
+    match parse_delay(value)? {
+        Duration::ZERO => Err("interval must be greater than 0".to_string()),
+        interval => Ok(interval),
+    }
+}
+
+/// [`DEFAULT_STATS_INTERVAL`] in seconds, as the `--stats-interval` default
+fn default_stats_interval() -> &'static str {
+    static DEFAULT: OnceLock<String> = OnceLock::new();
+    DEFAULT.get_or_init(|| DEFAULT_STATS_INTERVAL.as_secs_f64().to_string())
+}
+
+/// Load `--profile` if given, otherwise the default profile from `config.toml`
+/// or the profiles directory, otherwise the built-in default profile
+pub(super) fn load_profile(
+    matches: &clap::ArgMatches,
+    config_dir: Option<&Path>,
+) -> Result<Profile> {
+    if let Some(path) = matches.get_one::<String>("profile") {
+        println!("Loading profile: {}", path);
+        return Profile::load_from_file(Path::new(path));
+    }
+
+    let default = match config_dir {
+        Some(dir) => resolve_default_profile(dir)?,
+        None => None,
+    };
+    match default {
+        Some(path) => {
+            println!("Loading default profile: {}", path.display());
+            Profile::load_from_file(&path)
+                .with_context(|| format!("Failed to load default profile {}", path.display()))
+        }
+        None => {
+            println!("Loading default mappings...");
+            Ok(Profile::default_profile())
+        }
+    }
+}
+
+/// Load `--fallback-profile` if given
+fn load_fallback_profile(matches: &clap::ArgMatches) -> Result<Option<FallbackProfile>> {
+    let Some(path) = matches.get_one::<String>("fallback-profile") else {
+        return Ok(None);
+    };
+    let path = PathBuf::from(path);
+    let profile = Profile::load_from_file(&path)
+        .with_context(|| format!("Failed to load fallback profile {}", path.display()))?;
+    Ok(Some(FallbackProfile { path, profile }))
+}
+
+/// One line per mapping, e.g. `South → S` or `DPad Y Negative → Up`
+fn describe_mappings(profile: &Profile) -> Vec<String> {
+    profile.mappings.iter().map(ToString::to_string).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::input::InputDetectionResult;
-    use crate::input::gamepad::{GamepadInfo, GamepadType, MockGamepad};
+    use crate::input::gamepad::{GamepadInfo, MockGamepad};
     use crate::input::manager::MockInputManager;
     use crate::output::keyboard::MockVirtualKeyboard;
 
+    /// A config directory that doesn't exist, so no default profile is configured
+    fn empty_config_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("blazeremap-no-config-{}", std::process::id()))
+    }
+
+    #[test]
+    fn test_load_profile_from_config_dir() {
+        let dir =
+            std::env::temp_dir().join(format!("blazeremap-run-config-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("profiles")).unwrap();
+        let mut profile = Profile::default_profile();
+        profile.name = "Configured".to_string();
+        profile.save_to_file(&dir.join("profiles/default.toml")).unwrap();
+        let matches = command().get_matches_from(["run"]);
+
+        let configured = load_profile(&matches, Some(&dir)).unwrap();
+        let built_in = load_profile(&matches, Some(&empty_config_dir())).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(configured.name, "Configured");
+        assert_eq!(built_in.name, Profile::default_profile().name);
+    }
+
     #[test]
     fn test_run_logic_auto_detect_success() {
         let mut mock_manager = MockInputManager::new();
@@ -104,15 +544,7 @@ mod tests {
         // Mock gamepad listing
         mock_manager.expect_list_gamepads().returning(move || {
             Ok(InputDetectionResult {
-                gamepad_info: vec![GamepadInfo {
-                    path: gamepad_path.to_string(),
-                    name: "Test Gamepad".to_string(),
-                    gamepad_type: GamepadType::XboxOne,
-                    vendor_id: 0,
-                    vendor_name: "".to_string(),
-                    product_id: 0,
-                    capabilities: vec![],
-                }],
+                gamepad_info: vec![GamepadInfo::test(gamepad_path, "Test Gamepad")],
                 errors: vec![],
             })
         });
@@ -129,8 +561,9 @@ mod tests {
 
         let matches = command().get_matches_from(vec!["run"]);
 
-        let result =
-            run_internal(&matches, &mock_manager, |_| Ok(Box::new(MockVirtualKeyboard::new())));
+        let result = run_internal(&matches, Some(&empty_config_dir()), &mock_manager, |_, _| {
+            Ok(Box::new(MockVirtualKeyboard::resettable()))
+        });
 
         assert!(result.is_ok());
     }
@@ -145,8 +578,9 @@ mod tests {
 
         let matches = command().get_matches_from(vec!["run"]);
 
-        let result =
-            run_internal(&matches, &mock_manager, |_| Ok(Box::new(MockVirtualKeyboard::new())));
+        let result = run_internal(&matches, Some(&empty_config_dir()), &mock_manager, |_, _| {
+            Ok(Box::new(MockVirtualKeyboard::resettable()))
+        });
 
         assert!(result.is_err());
         assert_eq!(
@@ -173,8 +607,9 @@ mod tests {
 
         let matches = command().get_matches_from(vec!["run", "--device", manual_path]);
 
-        let result =
-            run_internal(&matches, &mock_manager, |_| Ok(Box::new(MockVirtualKeyboard::new())));
+        let result = run_internal(&matches, Some(&empty_config_dir()), &mock_manager, |_, _| {
+            Ok(Box::new(MockVirtualKeyboard::resettable()))
+        });
 
         assert!(result.is_ok());
     }
@@ -197,8 +632,8 @@ mod tests {
             Ok(Box::new(mock_gamepad))
         });
 
-        let mut mock_keyboard = MockVirtualKeyboard::new();
-        // The hardcoded engine maps ButtonCode::South to KeyboardCode::S
+        let mut mock_keyboard = MockVirtualKeyboard::resettable();
+        // Without --profile the default profile is used; it maps South to S
         mock_keyboard
             .expect_press_key()
             .with(mockall::predicate::eq(KeyboardCode::S))
@@ -207,8 +642,196 @@ mod tests {
 
         let matches = command().get_matches_from(vec!["run", "--device", manual_path]);
 
-        let result = run_internal(&matches, &mock_manager, |_| Ok(Box::new(mock_keyboard)));
+        let result = run_internal(&matches, Some(&empty_config_dir()), &mock_manager, |_, _| {
+            Ok(Box::new(mock_keyboard))
+        });
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_run_logic_invalid_profile_path() {
+        let mut mock_manager = MockInputManager::new();
+
+        mock_manager.expect_open_gamepad().returning(|_| Ok(Box::new(MockGamepad::new())));
+
+        let matches = command().get_matches_from(vec![
+            "run",
+            "--device",
+            "/dev/input/eventX",
+            "--profile",
+            "/nonexistent/profile.toml",
+        ]);
+
+        let result = run_internal(&matches, Some(&empty_config_dir()), &mock_manager, |_, _| {
+            Ok(Box::new(MockVirtualKeyboard::resettable()))
+        });
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_run_logic_device_name() {
+        let mut mock_manager = MockInputManager::new();
+
+        mock_manager.expect_list_gamepads().returning(|| {
+            Ok(InputDetectionResult {
+                gamepad_info: vec![
+                    GamepadInfo::test("/dev/input/event3", "Microsoft X-Box One pad"),
+                    GamepadInfo::test("/dev/input/event5", "DualSense Wireless Controller"),
+                ],
+                errors: vec![],
+            })
+        });
+
+        let matches = command().get_matches_from(vec!["run", "--device-name", "dualsense"]);
+        assert_eq!(select_device_path(&matches, &mock_manager).unwrap(), "/dev/input/event5");
+
+        let matches = command().get_matches_from(vec!["run", "--device-name", "8BitDo"]);
+        assert_eq!(
+            select_device_path(&matches, &mock_manager).unwrap_err().to_string(),
+            "No device matching '8BitDo' found"
+        );
+
+        let matches = command().get_matches_from(vec!["run", "--device-select", "last"]);
+        assert_eq!(select_device_path(&matches, &mock_manager).unwrap(), "/dev/input/event5");
+    }
+
+    #[test]
+    fn test_prompt_for_gamepad() {
+        let gamepads = [
+            GamepadInfo::test("/dev/input/event3", "Pad"),
+            GamepadInfo::test("/dev/input/event5", "Pad"),
+        ];
+        let prompt = |answers: &str| prompt_for_gamepad(&mut answers.as_bytes(), &gamepads);
+
+        assert_eq!(prompt("\n").unwrap(), 0);
+        assert_eq!(prompt("1\n").unwrap(), 1);
+        // Invalid answers ask again
+        assert_eq!(prompt("two\n7\n1\n").unwrap(), 1);
+        assert!(prompt("7\n").is_err());
+    }
+
+    #[test]
+    fn test_reopen_gamepad_finds_moved_device_by_name() {
+        let mut mock_manager = MockInputManager::new();
+        mock_manager
+            .expect_open_gamepad()
+            .with(mockall::predicate::eq("/dev/input/event3"))
+            .returning(|path| anyhow::bail!("{} is gone", path));
+        mock_manager
+            .expect_open_gamepad()
+            .with(mockall::predicate::eq("/dev/input/event7"))
+            .times(1)
+            .returning(|_| Ok(Box::new(MockGamepad::new())));
+        mock_manager.expect_list_gamepads_fresh().returning(|| {
+            Ok(InputDetectionResult {
+                gamepad_info: vec![GamepadInfo::test("/dev/input/event7", "Pad")],
+                errors: vec![],
+            })
+        });
+
+        assert!(reopen_gamepad(&mock_manager, "/dev/input/event3", Some("Pad")).is_ok());
+        // Pinned with --device: only the original path is tried
+        assert!(reopen_gamepad(&mock_manager, "/dev/input/event3", None).is_err());
+        assert!(reopen_gamepad(&mock_manager, "/dev/input/event3", Some("Other")).is_err());
+    }
+
+    #[test]
+    fn test_reconnect_delay_arguments() {
+        let matches = command().get_matches_from(["run", "--reconnect-delay", "0"]);
+        assert_eq!(matches.get_one::<Duration>("reconnect-delay"), Some(&Duration::ZERO));
+        assert_eq!(
+            matches.get_one::<Duration>("reconnect-max-delay"),
+            Some(&Duration::from_secs(5))
+        );
+
+        assert!(command().try_get_matches_from(["run", "--reconnect-delay", "-1"]).is_err());
+        assert!(command().try_get_matches_from(["run", "--reconnect-max-delay", "10"]).is_err());
+    }
+
+    #[test]
+    fn test_stats_file_arguments() {
+        let matches = command().get_matches_from(["run", "--stats-file", "/tmp/stats.json"]);
+        assert_eq!(matches.get_one::<Duration>("stats-interval"), Some(&Duration::from_secs(30)));
+        let matches = command().get_matches_from([
+            "run",
+            "--stats-file",
+            "/tmp/stats.json",
+            "--stats-interval",
+            "2.5",
+        ]);
+        assert_eq!(
+            matches.get_one::<Duration>("stats-interval"),
+            Some(&Duration::from_millis(2500))
+        );
+
+        assert!(command().try_get_matches_from(["run", "--stats-interval", "10"]).is_err());
+        let zero = ["run", "--stats-file", "/tmp/stats.json", "--stats-interval", "0"];
+        assert!(command().try_get_matches_from(zero).is_err());
+    }
+
+    #[test]
+    fn test_timeout_argument() {
+        let matches = command().try_get_matches_from(["run"]).unwrap();
+        assert_eq!(matches.get_one::<Duration>("timeout"), Some(&Duration::ZERO));
+
+        let matches = command().try_get_matches_from(["run", "--timeout", "300"]).unwrap();
+        assert_eq!(matches.get_one::<Duration>("timeout"), Some(&Duration::from_secs(300)));
+
+        assert!(command().try_get_matches_from(["run", "--timeout", "-1"]).is_err());
+    }
+
+    #[test]
+    fn test_idle_timeout_stops_loop() {
+        use crate::mapping::MappingEngine;
+
+        // The read blocks like an idle controller until the watchdog interrupts it
+        let (interrupt, interrupted) = crossbeam::channel::bounded(1);
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_read_event().returning(move || {
+            match interrupted.recv_timeout(Duration::from_secs(5)) {
+                Ok(()) => anyhow::bail!("Failed to read event: Interrupted system call"),
+                Err(_) => Ok(None),
+            }
+        });
+        // Held keys are released by EventLoop::finish
+        let mut keyboard = MockVirtualKeyboard::new();
+        keyboard.expect_reset().times(1).returning(|| Ok(()));
+
+        let event_loop =
+            EventLoop::new(Box::new(gamepad), MappingEngine::new_hardcoded(), Box::new(keyboard));
+        let (event_loop, idle) = with_stop_conditions(
+            event_loop,
+            || false,
+            Duration::from_millis(50),
+            move || Ok(interrupt.send(())?),
+        );
+
+        assert!(event_loop.run().is_ok());
+        assert!(idle.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_auto_backup_requires_watch() {
+        assert!(command().try_get_matches_from(["run", "--auto-backup", "-p", "a.toml"]).is_err());
+        let matches = command()
+            .try_get_matches_from(["run", "--watch", "--auto-backup", "-p", "a.toml"])
+            .unwrap();
+        assert!(matches.get_flag("auto-backup"));
+    }
+
+    #[test]
+    fn test_watch_requires_profile() {
+        assert!(command().try_get_matches_from(["run", "--watch"]).is_err());
+        assert!(command().try_get_matches_from(["run", "--watch", "-p", "game.toml"]).is_ok());
+    }
+
+    #[test]
+    fn test_describe_mappings() {
+        let lines = describe_mappings(&Profile::default_profile());
+
+        assert!(lines.contains(&"South → S".to_string()));
+        assert!(lines.contains(&"DPad Y Negative → Up".to_string()));
+    }
 }
@@ -0,0 +1,121 @@
+// Service command - install/uninstall the systemd unit
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::platform::linux::systemd::{self, ServiceConfig, UNIT_NAME, UNIT_PATH};
+
+/// Build the 'service' command
+pub fn command() -> Command {
+    Command::new("service")
+        .about("Manage the BlazeRemap systemd service")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("install")
+                .about("Install and enable the systemd service (requires root)")
+                .arg(
+                    clap::Arg::new("profile")
+                        .short('p')
+                        .long("profile")
+                        .value_name("FILE")
+                        .help("Profile for the service to load"),
+                )
+                .arg(
+                    clap::Arg::new("device")
+                        .short('d')
+                        .long("device")
+                        .help("Device path for the service to open (auto-detect if not specified)")
+                        .add(super::completion::device_completer()),
+                ),
+        )
+        .subcommand(
+            Command::new("uninstall").about("Stop and remove the systemd service (requires root)"),
+        )
+}
+
+/// CLI handle for the 'service' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    if !systemd::is_root() {
+        anyhow::bail!(
+            "Managing the systemd service requires root. Re-run with sudo, e.g.:\n  sudo {} {}",
+            std::env::current_exe()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| "blazeremap".to_string()),
+            std::env::args().skip(1).collect::<Vec<_>>().join(" ")
+        );
+    }
+
+    match matches.subcommand() {
+        Some(("install", sub_matches)) => install(sub_matches),
+        Some(("uninstall", _)) => uninstall(),
+        _ => unreachable!("Subcommand required"),
+    }
+}
+
+fn install(matches: &ArgMatches) -> Result<()> {
+    let profile_path = matches
+        .get_one::<String>("profile")
+        .map(|path| absolute_path(Path::new(path)))
+        .transpose()?;
+
+    let config = ServiceConfig {
+        binary_path: std::env::current_exe().context("Failed to locate the blazeremap binary")?,
+        profile_path,
+        device_path: matches.get_one::<String>("device").cloned(),
+        // Run as the user who invoked sudo, if any
+        user: std::env::var("SUDO_USER").ok().filter(|user| user != "root"),
+    };
+
+    systemd::write_unit_file(Path::new(UNIT_PATH), &config)?;
+    println!("Wrote {}", UNIT_PATH);
+
+    systemd::systemctl(&["daemon-reload"])?;
+    systemd::systemctl(&["enable", UNIT_NAME])?;
+    println!("Enabled {}. Start it with: sudo systemctl start {}", UNIT_NAME, UNIT_NAME);
+
+    Ok(())
+}
+
+fn uninstall() -> Result<()> {
+    let unit_path = Path::new(UNIT_PATH);
+    if !unit_path.exists() {
+        println!("{} is not installed", UNIT_NAME);
+        return Ok(());
+    }
+
+    // Stopping/disabling fails harmlessly if the unit is already inactive
+    if let Err(e) = systemd::systemctl(&["disable", "--now", UNIT_NAME]) {
+        tracing::warn!("{}", e);
+    }
+
+    std::fs::remove_file(unit_path)
+        .with_context(|| format!("Failed to remove {}", unit_path.display()))?;
+    systemd::systemctl(&["daemon-reload"])?;
+
+    println!("Removed {}", UNIT_PATH);
+    Ok(())
+}
+
+/// The service runs from `/`, so relative profile paths must be resolved now
+fn absolute_path(path: &Path) -> Result<PathBuf> {
+    path.canonicalize().with_context(|| format!("Profile {} not found", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_requires_subcommand() {
+        assert!(command().try_get_matches_from(vec!["service"]).is_err());
+        assert!(command().try_get_matches_from(vec!["service", "uninstall"]).is_ok());
+    }
+
+    #[test]
+    fn test_absolute_path_missing_profile() {
+        let err = absolute_path(Path::new("/nonexistent/profile.toml")).unwrap_err();
+        assert_eq!(err.to_string(), "Profile /nonexistent/profile.toml not found");
+    }
+}
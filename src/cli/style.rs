@@ -0,0 +1,144 @@
+// Output styling - color and box-drawing characters, controlled by --color/--no-color
+use std::io::IsTerminal;
+
+use clap::{Arg, ArgAction, ArgMatches};
+
+pub const GREEN: &str = "\x1b[32m";
+pub const RED: &str = "\x1b[31m";
+pub const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// Global `--color WHEN` argument
+///
+/// The value is mandatory so `--color` never swallows a following positional.
+pub fn color_arg() -> Arg {
+    Arg::new("color")
+        .long("color")
+        .value_name("WHEN")
+        .help("Use colors and Unicode box drawing: always, auto (terminal only) or never")
+        .value_parser(["always", "auto", "never"])
+        .global(true)
+}
+
+/// Global `--no-color` argument, same as `--color never`
+pub fn no_color_arg() -> Arg {
+    Arg::new("no-color")
+        .long("no-color")
+        .help("Plain ASCII output without colors (same as --color never)")
+        .action(ArgAction::SetTrue)
+        .conflicts_with("color")
+        .global(true)
+}
+
+/// How CLI output is decorated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputStyle {
+    pub use_color: bool,
+    pub use_unicode: bool,
+}
+
+impl OutputStyle {
+    /// Colors and Unicode box drawing
+    pub const FANCY: Self = Self { use_color: true, use_unicode: true };
+
+    /// ASCII only, no escape codes; stable for scripts
+    pub const PLAIN: Self = Self { use_color: false, use_unicode: false };
+
+    /// Resolve `--color`/`--no-color`; `auto` decorates only when stdout is a terminal
+    /// and `NO_COLOR` is unset
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        if matches.try_get_one::<bool>("no-color").ok().flatten() == Some(&true) {
+            return Self::PLAIN;
+        }
+
+        let when = matches.try_get_one::<String>("color").ok().flatten().map(String::as_str);
+        match when {
+            Some("always") => Self::FANCY,
+            Some("never") => Self::PLAIN,
+            _ => {
+                let fancy =
+                    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+                if fancy { Self::FANCY } else { Self::PLAIN }
+            }
+        }
+    }
+
+    /// Wrap `text` in an ANSI color code when colors are enabled
+    pub fn paint(&self, color: &str, text: &str) -> String {
+        if self.use_color { format!("{}{}{}", color, text, RESET) } else { text.to_string() }
+    }
+
+    /// Tree connector for an item followed by siblings
+    pub fn branch(&self) -> &'static str {
+        if self.use_unicode { "├─" } else { "+-" }
+    }
+
+    /// Tree connector for the last item
+    pub fn last_branch(&self) -> &'static str {
+        if self.use_unicode { "└─" } else { "`-" }
+    }
+
+    /// Vertical line continuing a tree
+    pub fn trunk(&self) -> &'static str {
+        if self.use_unicode { "│" } else { "|" }
+    }
+
+    /// List bullet
+    pub fn bullet(&self) -> &'static str {
+        if self.use_unicode { "•" } else { "*" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Command;
+
+    use super::*;
+
+    fn style_from(args: &[&str]) -> OutputStyle {
+        let cmd = Command::new("test").arg(color_arg()).arg(no_color_arg());
+        OutputStyle::from_matches(&cmd.get_matches_from(args))
+    }
+
+    #[test]
+    fn test_style_from_flags() {
+        assert_eq!(style_from(&["test", "--color=always"]), OutputStyle::FANCY);
+        assert_eq!(style_from(&["test", "--color", "always"]), OutputStyle::FANCY);
+        assert_eq!(style_from(&["test", "--color=never"]), OutputStyle::PLAIN);
+        assert_eq!(style_from(&["test", "--no-color"]), OutputStyle::PLAIN);
+    }
+
+    #[test]
+    fn test_color_value_before_positionals() {
+        let cmd = Command::new("test")
+            .arg(color_arg())
+            .arg(no_color_arg())
+            .arg(Arg::new("files").num_args(1..));
+        let matches = cmd.get_matches_from(["test", "--color", "never", "a.toml", "b.toml"]);
+
+        assert_eq!(OutputStyle::from_matches(&matches), OutputStyle::PLAIN);
+        let files: Vec<&String> = matches.get_many("files").unwrap().collect();
+        assert_eq!(files, ["a.toml", "b.toml"]);
+    }
+
+    #[test]
+    fn test_color_requires_value() {
+        let cmd = Command::new("test")
+            .arg(color_arg())
+            .arg(no_color_arg())
+            .arg(Arg::new("files").num_args(1..));
+        assert!(cmd.try_get_matches_from(["test", "--color", "a.toml", "b.toml"]).is_err());
+    }
+
+    #[test]
+    fn test_no_color_conflicts_with_color() {
+        let cmd = Command::new("test").arg(color_arg()).arg(no_color_arg());
+        assert!(cmd.try_get_matches_from(["test", "--no-color", "--color=always"]).is_err());
+    }
+
+    #[test]
+    fn test_paint() {
+        assert_eq!(OutputStyle::FANCY.paint(GREEN, "+ x"), "\x1b[32m+ x\x1b[0m");
+        assert_eq!(OutputStyle::PLAIN.paint(GREEN, "+ x"), "+ x");
+    }
+}
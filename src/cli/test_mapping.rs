@@ -0,0 +1,156 @@
+// Test mapping command - dry-run a profile against a real gamepad
+use std::io::Write;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use clap::Command;
+
+use crate::{
+    InputManager,
+    event::{InputEvent, OutputEvent},
+    input::gamepad::Gamepad,
+    mapping::{MappingEngine, profile::config_dir},
+    platform::new_input_manager,
+};
+
+use super::run::{load_profile, select_device_path};
+
+/// Build the 'test-mapping' command
+pub fn command() -> Command {
+    Command::new("test-mapping")
+        .about("Print the keys a profile would send, without creating a virtual keyboard")
+        .arg(
+            clap::Arg::new("device")
+                .short('d')
+                .long("device")
+                .help("Specific device path (auto-detect if not specified)")
+                .add(super::completion::device_completer()),
+        )
+        .arg(
+            clap::Arg::new("device-name")
+                .long("device-name")
+                .value_name("NAME")
+                .help("Use the gamepad whose name contains NAME (case-insensitive)")
+                .conflicts_with("device"),
+        )
+        .arg(
+            clap::Arg::new("profile")
+                .short('p')
+                .long("profile")
+                .value_name("FILE")
+                .help("Profile to test (the built-in default profile if not specified)"),
+        )
+}
+
+/// CLI handle for the 'test-mapping' command
+pub fn handle(matches: &clap::ArgMatches) -> Result<()> {
+    let manager = new_input_manager()?;
+
+    test_mapping_internal(matches, manager.as_ref(), &mut std::io::stdout())
+}
+
+fn test_mapping_internal<W: Write>(
+    matches: &clap::ArgMatches,
+    manager: &dyn InputManager,
+    writer: &mut W,
+) -> Result<()> {
+    let device_path = select_device_path(matches, manager)?;
+
+    println!("Opening device: {}", device_path);
+    let mut gamepad = manager.open_gamepad(&device_path).context("Failed to open controller")?;
+
+    let profile = load_profile(matches, config_dir().as_deref())?;
+    let mut engine = MappingEngine::load_from_profile(&profile)?;
+
+    println!("\nPress buttons on the controller to see their mapping (Ctrl+C to stop).");
+    println!("No keys are sent to the system.\n");
+
+    print_mappings(gamepad.as_mut(), &mut engine, writer)?;
+
+    println!("Device disconnected");
+    Ok(())
+}
+
+/// Print one line per input event until the gamepad disconnects
+fn print_mappings<W: Write>(
+    gamepad: &mut dyn Gamepad,
+    engine: &mut MappingEngine,
+    writer: &mut W,
+) -> Result<()> {
+    let mut first_event_timestamp: Option<Instant> = None;
+
+    while let Some(event) = gamepad.read_event()? {
+        if matches!(event, InputEvent::Sync { .. }) {
+            continue;
+        }
+
+        let first = *first_event_timestamp.get_or_insert(event.timestamp());
+        let elapsed = event.timestamp().saturating_duration_since(first);
+
+        writeln!(
+            writer,
+            "[{:>10.3}ms] {} → {}",
+            elapsed.as_secs_f64() * 1000.0,
+            event,
+            describe_outputs(&engine.process(&event)?)
+        )?;
+    }
+
+    Ok(())
+}
+
+fn describe_outputs(outputs: &[OutputEvent]) -> String {
+    if outputs.is_empty() {
+        return "(no mapping)".to_string();
+    }
+
+    outputs.iter().map(OutputEvent::summary).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{AxisCode, ButtonCode};
+    use crate::input::gamepad::MockGamepad;
+    use crate::mapping::profile::Profile;
+
+    fn run_events(events: Vec<InputEvent>) -> Vec<String> {
+        let mut gamepad = MockGamepad::new();
+        let mut events = events.into_iter();
+        gamepad.expect_read_event().returning(move || Ok(events.next()));
+
+        let mut engine = MappingEngine::load_from_profile(&Profile::default_profile()).unwrap();
+        let mut output = Vec::new();
+        print_mappings(&mut gamepad, &mut engine, &mut output).unwrap();
+
+        // Drop the "[   time ms] " prefix
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| line.split_once("] ").unwrap().1.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_print_mappings() {
+        let lines = run_events(vec![
+            InputEvent::button_press(ButtonCode::South),
+            InputEvent::sync(),
+            InputEvent::button_release(ButtonCode::South),
+            InputEvent::button_press(ButtonCode::North),
+            InputEvent::button_press(ButtonCode::LeftShoulder),
+            InputEvent::axis_move(AxisCode::DPadY, -1),
+        ]);
+
+        assert_eq!(
+            lines,
+            vec![
+                "South (pressed) → S (press)",
+                "South (released) → S (release)",
+                "North (pressed) → W (press)",
+                "Left Shoulder (pressed) → (no mapping)",
+                "DPad Y: -1 → Up (press)",
+            ]
+        );
+    }
+}
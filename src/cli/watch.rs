@@ -0,0 +1,194 @@
+// Watch command - report controllers as they are plugged in and unplugged
+use std::{
+    collections::HashMap,
+    process::{Child, Command as Process},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Command};
+
+use crate::{
+    event::DEFAULT_KEYBOARD_NAME,
+    input::{GamepadInfo, HotplugEvent, HotplugWatcher},
+    output::gamepad::VIRTUAL_GAMEPAD_NAME,
+    platform,
+};
+
+/// Build the 'watch' command
+pub fn command() -> Command {
+    Command::new("watch")
+        .about("Print controllers as they connect and disconnect (until Ctrl+C)")
+        .arg(clap::Arg::new("exec").long("exec").value_name("COMMAND").help(
+            "Run COMMAND for each connected controller and stop it on disconnect; \
+                     {path} is replaced by the device path and the shell execs COMMAND, \
+                     so it should be a single command",
+        ))
+        .arg(
+            clap::Arg::new("interval-ms")
+                .long("interval-ms")
+                .value_name("MS")
+                .help("How often to look for controllers")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .default_value("1000"),
+        )
+}
+
+/// CLI handle for the 'watch' command
+pub fn handle(matches: &ArgMatches) -> Result<()> {
+    let exec = matches.get_one::<String>("exec");
+    let interval = Duration::from_millis(*matches.get_one::<u64>("interval-ms").unwrap());
+
+    let input_manager = platform::new_input_manager()?;
+    let mut watcher = HotplugWatcher::new();
+    let mut children: HashMap<String, Child> = HashMap::new();
+
+    println!("Watching for controllers (Ctrl+C to stop)...");
+
+    loop {
+        if exec.is_some() {
+            reap_exited(&mut children);
+        }
+
+        match input_manager.list_gamepads_fresh() {
+            Ok(mut result) => {
+                // A `run` started by --exec adds a virtual gamepad; don't start another on it
+                result.gamepad_info.retain(|info| !is_own_device(info));
+                for event in watcher.update(&result) {
+                    println!("{}", describe_event(&event, SystemTime::now()));
+                    if let Some(template) = exec {
+                        apply_exec(template, &event, &mut children);
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("Gamepad detection failed: {:#}", e),
+        }
+
+        std::thread::sleep(interval);
+    }
+}
+
+/// Whether `info` is one of the virtual devices `run` creates
+fn is_own_device(info: &GamepadInfo) -> bool {
+    info.name == VIRTUAL_GAMEPAD_NAME || info.name == DEFAULT_KEYBOARD_NAME
+}
+
+/// Forget the commands that exited on their own, so they aren't killed later
+fn reap_exited(children: &mut HashMap<String, Child>) {
+    children.retain(|path, child| match child.try_wait() {
+        Ok(Some(status)) => {
+            println!("  Command for {} exited ({})", path, status);
+            false
+        }
+        Ok(None) => true,
+        Err(e) => {
+            println!("  warning: Failed to check the command for {}: {}", path, e);
+            false
+        }
+    });
+}
+
+/// e.g. `[14:32:01] Connected: Xbox One Controller (/dev/input/event3)`
+fn describe_event(event: &HotplugEvent, now: SystemTime) -> String {
+    let clock = format_clock(now);
+    match event {
+        HotplugEvent::Connected { path, name } => {
+            format!("[{}] Connected: {} ({})", clock, name, path)
+        }
+        HotplugEvent::Disconnected { path } => format!("[{}] Disconnected: {}", clock, path),
+    }
+}
+
+/// `HH:MM:SS` in UTC
+fn format_clock(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0) % 86_400;
+    format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+/// Start the command for a new controller or stop the one for a removed controller
+fn apply_exec(template: &str, event: &HotplugEvent, children: &mut HashMap<String, Child>) {
+    match event {
+        HotplugEvent::Connected { path, .. } => match spawn_exec(template, path) {
+            Ok(child) => {
+                children.insert(path.clone(), child);
+            }
+            Err(e) => println!("  warning: {:#}", e),
+        },
+        HotplugEvent::Disconnected { path } => {
+            if let Some(mut child) = children.remove(path) {
+                // The command may have exited since the last reap
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+        }
+    }
+}
+
+fn spawn_exec(template: &str, path: &str) -> Result<Child> {
+    let command = expand_exec(template, path);
+    // exec so the child is the command itself, not a shell that kill() would
+    // stop while leaving the command running
+    Process::new("sh")
+        .arg("-c")
+        .arg(format!("exec {}", command))
+        .spawn()
+        .with_context(|| format!("Failed to run '{}'", command))
+}
+
+/// Replace every `{path}` in `template`
+fn expand_exec(template: &str, path: &str) -> String {
+    template.replace("{path}", path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_event() {
+        // 1970-01-01 14:32:01 UTC
+        let time = UNIX_EPOCH + Duration::from_secs(14 * 3600 + 32 * 60 + 1);
+
+        let connected = HotplugEvent::Connected {
+            path: "/dev/input/event3".to_string(),
+            name: "Xbox One Controller".to_string(),
+        };
+        assert_eq!(
+            describe_event(&connected, time),
+            "[14:32:01] Connected: Xbox One Controller (/dev/input/event3)"
+        );
+
+        let disconnected = HotplugEvent::Disconnected { path: "/dev/input/event3".to_string() };
+        assert_eq!(
+            describe_event(&disconnected, time),
+            "[14:32:01] Disconnected: /dev/input/event3"
+        );
+    }
+
+    #[test]
+    fn test_reap_exited_forgets_finished_commands() {
+        let mut children = HashMap::from([
+            ("/dev/input/event3".to_string(), spawn_exec("true", "").unwrap()),
+            ("/dev/input/event5".to_string(), spawn_exec("sleep 30", "").unwrap()),
+        ]);
+        children.get_mut("/dev/input/event3").unwrap().wait().unwrap();
+
+        reap_exited(&mut children);
+
+        assert_eq!(children.keys().collect::<Vec<_>>(), ["/dev/input/event5"]);
+        let mut sleeping = children.remove("/dev/input/event5").unwrap();
+        sleeping.kill().unwrap();
+        sleeping.wait().unwrap();
+    }
+
+    #[test]
+    fn test_expand_exec() {
+        assert_eq!(
+            expand_exec(
+                "blazeremap run --device {path} --profile default.toml",
+                "/dev/input/event3"
+            ),
+            "blazeremap run --device /dev/input/event3 --profile default.toml"
+        );
+    }
+}
@@ -0,0 +1,177 @@
+// Event loop for tokio applications (feature = "async")
+use anyhow::Result;
+use tokio::sync::mpsc;
+
+use super::{EventLoop, InputEvent};
+
+/// A read handed from the reader task to the async task, with the loop it came from
+type Read = (EventLoop, Result<Option<InputEvent>>);
+
+/// Runs an [`EventLoop`] on a tokio runtime without blocking it
+///
+/// A reader on tokio's blocking thread pool (`spawn_blocking`) waits for gamepad
+/// events and sends them over a `tokio::sync::mpsc` channel; the engine and the
+/// outputs run on the calling task. The loop itself travels with each event and
+/// is handed back for the next read, so rumble still reaches the gamepad and
+/// the loop's stop condition and reconnect settings apply as in
+/// [`EventLoop::run`]. Keyboard writes stay synchronous; they take microseconds.
+pub struct AsyncEventLoop {
+    inner: EventLoop,
+}
+
+impl AsyncEventLoop {
+    pub fn new(event_loop: EventLoop) -> Self {
+        Self { inner: event_loop }
+    }
+
+    /// Run until the gamepad disconnects or a stop is requested; the future is
+    /// `Send`, so it can be passed to `tokio::spawn`
+    pub async fn run(self) -> Result<()> {
+        tracing::info!("Async event loop starting...");
+
+        let (read_sender, mut reads) = mpsc::channel::<Read>(1);
+        let (loop_sender, loops) = std::sync::mpsc::channel::<EventLoop>();
+        let reader = tokio::task::spawn_blocking(move || {
+            // Ends once the async task stops handing the loop back
+            while let Ok(mut event_loop) = loops.recv() {
+                let next = event_loop.next_event();
+                if read_sender.blocking_send((event_loop, next)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        // Can't fail: the reader holds the receiver until this sender is dropped
+        let _ = loop_sender.send(self.inner);
+        let (mut event_loop, result) = loop {
+            let Some((mut event_loop, next)) = reads.recv().await else {
+                drop(loop_sender);
+                reader.await?;
+                anyhow::bail!("Gamepad reader task ended unexpectedly");
+            };
+
+            let handled = match next {
+                Ok(Some(input_event)) => event_loop.handle_input(input_event),
+                Ok(None) => break (event_loop, Ok(())),
+                Err(e) => break (event_loop, Err(e)),
+            };
+            if let Err(e) = handled {
+                break (event_loop, Err(e));
+            }
+            let _ = loop_sender.send(event_loop);
+        };
+
+        drop(loop_sender);
+        event_loop.finish();
+        reader.await?;
+        result
+    }
+}
+
+impl From<EventLoop> for AsyncEventLoop {
+    fn from(event_loop: EventLoop) -> Self {
+        Self::new(event_loop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::{ButtonCode, InputEvent, KeyboardCode},
+        input::gamepad::MockGamepad,
+        mapping::MappingEngine,
+        output::keyboard::MockVirtualKeyboard,
+    };
+
+    fn assert_send<T: Send>(value: T) -> T {
+        value
+    }
+
+    #[test]
+    fn test_run_maps_events_until_disconnect() {
+        let mut events = vec![InputEvent::button_press(ButtonCode::South)].into_iter();
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_read_event().times(2).returning(move || Ok(events.next()));
+
+        let mut keyboard = MockVirtualKeyboard::resettable();
+        keyboard
+            .expect_press_key()
+            .with(mockall::predicate::eq(KeyboardCode::S))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let event_loop =
+            EventLoop::new(Box::new(gamepad), MappingEngine::new_hardcoded(), Box::new(keyboard));
+        let monitor = event_loop.health_monitor();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(assert_send(AsyncEventLoop::new(event_loop).run())).unwrap();
+
+        assert_eq!(monitor.event_count(), 1);
+    }
+
+    #[test]
+    fn test_run_checks_stop_condition() {
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_read_event().never();
+        let event_loop = EventLoop::new(
+            Box::new(gamepad),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        )
+        .with_stop_when(|| true);
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(AsyncEventLoop::new(event_loop).run()).unwrap();
+    }
+
+    #[test]
+    fn test_run_reconnects() {
+        use std::{
+            sync::{
+                Arc,
+                atomic::{AtomicBool, Ordering},
+            },
+            time::Duration,
+        };
+
+        use crate::input::{Gamepad, ReconnectConfig};
+
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_read_event().times(1).returning(|| Ok(None));
+        // The first attempt brings back a gamepad with one press; the next one
+        // fails and stops the loop
+        let stop = Arc::new(AtomicBool::new(false));
+        let signal = stop.clone();
+        let mut attempts = 0;
+        let reopen = move || {
+            attempts += 1;
+            if attempts > 1 {
+                signal.store(true, Ordering::SeqCst);
+                anyhow::bail!("gone");
+            }
+            let mut events = vec![InputEvent::button_press(ButtonCode::South)].into_iter();
+            let mut reconnected = MockGamepad::new();
+            reconnected.expect_read_event().times(2).returning(move || Ok(events.next()));
+            Ok(Box::new(reconnected) as Box<dyn Gamepad>)
+        };
+
+        let mut keyboard = MockVirtualKeyboard::resettable();
+        keyboard
+            .expect_press_key()
+            .with(mockall::predicate::eq(KeyboardCode::S))
+            .times(1)
+            .returning(|_| Ok(()));
+        let event_loop =
+            EventLoop::new(Box::new(gamepad), MappingEngine::new_hardcoded(), Box::new(keyboard))
+                .with_reconnect(
+                    ReconnectConfig { initial_delay: Duration::ZERO, max_delay: Duration::ZERO },
+                    reopen,
+                )
+                .with_stop_when(move || stop.load(Ordering::SeqCst));
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(AsyncEventLoop::new(event_loop).run()).unwrap();
+    }
+}
@@ -0,0 +1,12 @@
+// Changes to watched files, reported by the platform's file watcher
+use std::path::PathBuf;
+
+/// Change to a watched file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileEvent {
+    /// Written, or replaced by an editor's atomic save
+    Modified,
+    Deleted,
+    /// Moved to a new path; the watch follows it
+    Renamed(PathBuf),
+}
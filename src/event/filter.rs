@@ -0,0 +1,222 @@
+// Composable pre-processing of input events before the mapping engine
+use std::{collections::HashMap, time::Duration};
+
+use tracing::Level;
+
+use super::{AxisCode, AxisDirection, ButtonCode, DEFAULT_STICK_DEADZONE, InputEvent};
+
+/// One stage of the pre-processing pipeline
+///
+/// Filters run in order; returning `None` drops the event for every later stage
+/// and the mapping engine.
+pub trait EventFilter: Send {
+    fn filter(&mut self, event: InputEvent) -> Option<InputEvent>;
+}
+
+/// Run `event` through each filter in order
+pub fn apply_filters(
+    filters: &mut [Box<dyn EventFilter>],
+    event: InputEvent,
+) -> Option<InputEvent> {
+    filters.iter_mut().try_fold(event, |event, filter| filter.filter(event))
+}
+
+/// Analog stick dead zone around the center value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadzoneConfig {
+    pub center: i32,
+    pub radius: i32,
+}
+
+impl Default for DeadzoneConfig {
+    /// [`DEFAULT_STICK_DEADZONE`], the fallback of `InputEvent::is_in_deadzone`
+    fn default() -> Self {
+        let (center, radius) = DEFAULT_STICK_DEADZONE;
+        Self { center, radius }
+    }
+}
+
+/// Drops stick movements within the dead zone; triggers and the D-pad pass through
+pub struct DeadzoneFilter {
+    config: DeadzoneConfig,
+}
+
+impl DeadzoneFilter {
+    pub fn new(config: DeadzoneConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl EventFilter for DeadzoneFilter {
+    fn filter(&mut self, event: InputEvent) -> Option<InputEvent> {
+        if let InputEvent::Axis { code, value, .. } = event {
+            let is_stick = matches!(
+                code,
+                AxisCode::LeftX | AxisCode::LeftY | AxisCode::RightX | AxisCode::RightY
+            );
+            let centered =
+                AxisDirection::from_value(value - self.config.center, self.config.radius).is_none();
+            if is_stick && centered {
+                return None;
+            }
+        }
+        Some(event)
+    }
+}
+
+/// Drops contact bounce on worn buttons: a press within `window` of the release
+/// before it, along with the release that ends it
+///
+/// A release that ends a press passed on is never dropped, so a tap shorter than
+/// `window` doesn't leave the mapped key held.
+pub struct DebounceFilter {
+    window: Duration,
+    /// The last change passed on for each button
+    last_change: HashMap<ButtonCode, InputEvent>,
+}
+
+impl DebounceFilter {
+    pub fn new(window_ms: u64) -> Self {
+        Self { window: Duration::from_millis(window_ms), last_change: HashMap::new() }
+    }
+}
+
+impl EventFilter for DebounceFilter {
+    fn filter(&mut self, event: InputEvent) -> Option<InputEvent> {
+        let InputEvent::Button { code, pressed, .. } = event else {
+            return Some(event);
+        };
+
+        if let Some(last) = self.last_change.get(&code) {
+            if last.is_button_pressed() == pressed {
+                // What a dropped bounce left over; the state is unchanged
+                return None;
+            }
+            let since_last = event.timestamp().saturating_duration_since(last.timestamp());
+            if pressed && since_last < self.window {
+                return None;
+            }
+        }
+
+        self.last_change.insert(code, event);
+        Some(event)
+    }
+}
+
+/// Logs every event that reaches it, unchanged
+pub struct LoggingFilter {
+    level: Level,
+}
+
+impl LoggingFilter {
+    pub fn new(level: Level) -> Self {
+        Self { level }
+    }
+}
+
+impl EventFilter for LoggingFilter {
+    fn filter(&mut self, event: InputEvent) -> Option<InputEvent> {
+        // tracing needs the level at compile time
+        match self.level {
+            Level::TRACE => tracing::trace!("Input: {}", event),
+            Level::DEBUG => tracing::debug!("Input: {}", event),
+            Level::INFO => tracing::info!("Input: {}", event),
+            Level::WARN => tracing::warn!("Input: {}", event),
+            Level::ERROR => tracing::error!("Input: {}", event),
+        }
+        Some(event)
+    }
+}
+
+/// Drops axis events whose value lies outside `min..=max`
+pub struct AxisRangeFilter {
+    min: i32,
+    max: i32,
+}
+
+impl AxisRangeFilter {
+    pub fn new(min: i32, max: i32) -> Self {
+        Self { min, max }
+    }
+}
+
+impl EventFilter for AxisRangeFilter {
+    fn filter(&mut self, event: InputEvent) -> Option<InputEvent> {
+        match event {
+            InputEvent::Axis { value, .. } if !(self.min..=self.max).contains(&value) => None,
+            _ => Some(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn test_deadzone_filter() {
+        let mut filter = DeadzoneFilter::new(DeadzoneConfig::default());
+
+        assert!(filter.filter(InputEvent::axis_move(AxisCode::LeftX, 133)).is_none());
+        assert!(filter.filter(InputEvent::axis_move(AxisCode::LeftX, 200)).is_some());
+        assert!(filter.filter(InputEvent::axis_move(AxisCode::LeftTrigger, 128)).is_some());
+        assert!(filter.filter(InputEvent::axis_move(AxisCode::DPadX, 0)).is_some());
+        assert!(filter.filter(InputEvent::button_press(ButtonCode::South)).is_some());
+    }
+
+    #[test]
+    fn test_debounce_filter() {
+        let mut filter = DebounceFilter::new(20);
+        let base = Instant::now();
+        let at = |ms| base + Duration::from_millis(ms);
+
+        assert!(filter.filter(InputEvent::button_press_at(ButtonCode::South, at(0))).is_some());
+        assert!(filter.filter(InputEvent::button_release_at(ButtonCode::South, at(30))).is_some());
+        // Bounce back to pressed, and the release ending it
+        assert!(filter.filter(InputEvent::button_press_at(ButtonCode::South, at(35))).is_none());
+        assert!(filter.filter(InputEvent::button_release_at(ButtonCode::South, at(37))).is_none());
+        // Other buttons are tracked separately
+        assert!(filter.filter(InputEvent::button_press_at(ButtonCode::East, at(38))).is_some());
+        assert!(filter.filter(InputEvent::button_press_at(ButtonCode::South, at(60))).is_some());
+    }
+
+    #[test]
+    fn test_debounce_filter_passes_quick_tap() {
+        let mut filter = DebounceFilter::new(20);
+        let base = Instant::now();
+        let at = |ms| base + Duration::from_millis(ms);
+
+        assert!(filter.filter(InputEvent::button_press_at(ButtonCode::South, at(0))).is_some());
+        // Shorter than the window, but ends a press that was passed on
+        assert!(filter.filter(InputEvent::button_release_at(ButtonCode::South, at(5))).is_some());
+    }
+
+    #[test]
+    fn test_axis_range_filter() {
+        let mut filter = AxisRangeFilter::new(-1, 1);
+
+        assert!(filter.filter(InputEvent::axis_move(AxisCode::DPadY, -1)).is_some());
+        assert!(filter.filter(InputEvent::axis_move(AxisCode::DPadY, 2)).is_none());
+        assert!(filter.filter(InputEvent::button_press(ButtonCode::South)).is_some());
+    }
+
+    #[test]
+    fn test_pipeline_stops_at_first_drop() {
+        let mut filters: Vec<Box<dyn EventFilter>> = vec![
+            Box::new(LoggingFilter::new(Level::DEBUG)),
+            Box::new(AxisRangeFilter::new(0, 255)),
+            Box::new(DeadzoneFilter::new(DeadzoneConfig::default())),
+        ];
+
+        assert!(apply_filters(&mut filters, InputEvent::axis_move(AxisCode::LeftX, 300)).is_none());
+        assert!(apply_filters(&mut filters, InputEvent::axis_move(AxisCode::LeftX, 128)).is_none());
+        assert_eq!(
+            apply_filters(&mut filters, InputEvent::axis_move(AxisCode::LeftX, 250))
+                .map(|event| event.to_string()),
+            Some("Left X: 250".to_string())
+        );
+        assert!(apply_filters(&mut [], InputEvent::sync()).is_some());
+    }
+}
@@ -1,24 +1,79 @@
-use std::time::Instant;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use crossbeam::channel::Receiver;
 
 use crate::{
     Gamepad,
-    event::{KeyboardEventType, OutputEvent},
-    mapping::MappingEngine,
-    output::keyboard::VirtualKeyboard,
+    event::{
+        AxisCode, EventFilter, EventLog, EventStream, FileEvent, HealthMonitor, HealthStatus,
+        InputEvent, KeyboardCode, KeyboardEventType, LATENCY_BUDGET_STREAK, LatencyBudget,
+        OutputEvent, apply_filters,
+    },
+    input::ReconnectConfig,
+    mapping::{MappingEngine, profile::Profile},
+    output::{gamepad::VirtualGamepad, keyboard::VirtualKeyboard},
 };
 
+/// Virtual keyboard name used when the profile does not set one
+pub const DEFAULT_KEYBOARD_NAME: &str = "BlazeRemap Virtual Keyboard";
+
+/// How often the stop condition is checked while waiting to reconnect
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Profile file the event loop reloads when it changes
+struct ProfileWatch {
+    path: PathBuf,
+    events: Receiver<FileEvent>,
+}
+
+/// Called with the profile path before each reload
+type ReloadHook = Box<dyn FnMut(&Path) + Send>;
+
+/// Creates a virtual keyboard with the given name and keys
+type KeyboardFactory =
+    Box<dyn FnMut(&str, &[KeyboardCode]) -> Result<Box<dyn VirtualKeyboard>> + Send>;
+
+/// Reopens the gamepad after it disconnects
+struct Reconnect {
+    config: ReconnectConfig,
+    reopen: Box<dyn FnMut() -> Result<Box<dyn Gamepad>> + Send>,
+}
+
+/// Profile the event loop switches to when reloading the watched profile fails
+pub struct FallbackProfile {
+    pub path: PathBuf,
+    pub profile: Profile,
+}
+
 pub struct EventLoop {
     gamepad: Box<dyn Gamepad>,
+    filters: Vec<Box<dyn EventFilter>>,
     engine: MappingEngine,
     keyboard: Box<dyn VirtualKeyboard>,
-    event_count: u64,
-    total_latency_us: u64,
+    virtual_gamepad: Option<Box<dyn VirtualGamepad>>,
+    event_stream: Option<EventStream>,
+    event_log: Option<EventLog>,
+    /// Keys the virtual keyboard was created with (None = every key)
+    registered_keys: Option<HashSet<KeyboardCode>>,
+    /// Recreates the virtual keyboard when a reloaded profile needs new keys
+    keyboard_factory: Option<KeyboardFactory>,
+    profile_watch: Option<ProfileWatch>,
+    before_reload: Option<ReloadHook>,
+    fallback: Option<FallbackProfile>,
+    reconnect: Option<Reconnect>,
+    /// Asked before each read whether the loop should stop, e.g. on SIGTERM
+    stop_when: Option<Box<dyn Fn() -> bool + Send>>,
+    /// Whether the fallback replaced the watched profile after a failed reload
+    using_fallback: bool,
 
     // Statistics
-    max_latency_us: u64,
-    min_latency_us: u64,
+    health: HealthMonitor,
+    latency_budget: Option<LatencyBudget>,
 }
 
 impl EventLoop {
@@ -29,77 +84,590 @@ impl EventLoop {
     ) -> Self {
         Self {
             gamepad: controller,
+            filters: Vec::new(),
             engine,
             keyboard,
-            event_count: 0,
-            total_latency_us: 0,
-            max_latency_us: 0,
-            min_latency_us: u64::MAX,
+            virtual_gamepad: None,
+            event_stream: None,
+            event_log: None,
+            registered_keys: None,
+            keyboard_factory: None,
+            profile_watch: None,
+            before_reload: None,
+            fallback: None,
+            reconnect: None,
+            stop_when: None,
+            using_fallback: false,
+            health: HealthMonitor::new(),
+            latency_budget: None,
         }
     }
 
+    /// Create an event loop that runs every input event through `filters`, in order,
+    /// before the mapping engine
+    pub fn new_with_filters(
+        gamepad: Box<dyn Gamepad>,
+        filters: Vec<Box<dyn EventFilter>>,
+        engine: MappingEngine,
+        keyboard: Box<dyn VirtualKeyboard>,
+    ) -> Self {
+        Self { filters, ..Self::new(gamepad, engine, keyboard) }
+    }
+
+    /// Create an event loop from a profile
+    ///
+    /// Builds the mapping engine from the profile's mappings and creates the
+    /// virtual keyboard named by `settings.output_device_name`, registering
+    /// only the keys the mappings target.
+    pub fn new_with_profile<F>(
+        gamepad: Box<dyn Gamepad>,
+        profile: Profile,
+        keyboard_factory: F,
+    ) -> Result<Self>
+    where
+        F: FnOnce(&str, &[KeyboardCode]) -> Result<Box<dyn VirtualKeyboard>>,
+    {
+        Self::new_with_profiles(gamepad, profile, None, keyboard_factory)
+    }
+
+    /// Like [`EventLoop::new_with_profile`], with a profile to fall back to when
+    /// reloading the watched profile fails
+    ///
+    /// The virtual keyboard also registers the keys the fallback targets, so the
+    /// switch never needs a restart.
+    pub fn new_with_fallback<F>(
+        gamepad: Box<dyn Gamepad>,
+        profile: Profile,
+        fallback: FallbackProfile,
+        keyboard_factory: F,
+    ) -> Result<Self>
+    where
+        F: FnOnce(&str, &[KeyboardCode]) -> Result<Box<dyn VirtualKeyboard>>,
+    {
+        Self::new_with_profiles(gamepad, profile, Some(fallback), keyboard_factory)
+    }
+
+    fn new_with_profiles<F>(
+        gamepad: Box<dyn Gamepad>,
+        profile: Profile,
+        fallback: Option<FallbackProfile>,
+        keyboard_factory: F,
+    ) -> Result<Self>
+    where
+        F: FnOnce(&str, &[KeyboardCode]) -> Result<Box<dyn VirtualKeyboard>>,
+    {
+        let engine = MappingEngine::load_from_profile(&profile)
+            .with_context(|| format!("Invalid mappings in profile '{}'", profile.name))?;
+
+        let mut keys = engine.target_keys();
+        if let Some(fallback) = &fallback {
+            let fallback_engine = MappingEngine::load_from_profile(&fallback.profile)
+                .with_context(|| {
+                    format!("Invalid mappings in fallback profile '{}'", fallback.profile.name)
+                })?;
+            keys.extend(fallback_engine.target_keys());
+        }
+
+        let keyboard_name =
+            profile.settings.output_device_name.as_deref().unwrap_or(DEFAULT_KEYBOARD_NAME);
+        let mut keys: Vec<KeyboardCode> = keys.into_iter().collect();
+        keys.sort_by_key(|code| *code as u32); // stable order for the kernel and tests
+        let keyboard =
+            keyboard_factory(keyboard_name, &keys).context("Failed to create virtual keyboard")?;
+
+        let latency_budget_us = profile.settings.latency_budget_us;
+        let mut event_loop = Self {
+            registered_keys: Some(keys.into_iter().collect()),
+            fallback,
+            ..Self::new(gamepad, engine, keyboard)
+        };
+        event_loop.update_device_ranges();
+        Ok(match latency_budget_us {
+            Some(budget_us) => event_loop.with_latency_budget(budget_us),
+            None => event_loop,
+        })
+    }
+
+    /// Reload the profile at `path` whenever `events` reports a change
+    ///
+    /// Changes are picked up when the next gamepad event arrives. A profile
+    /// that fails to load, or that needs keys the virtual keyboard cannot
+    /// register, is rejected and the current mappings stay active.
+    pub fn with_profile_watch(mut self, path: PathBuf, events: Receiver<FileEvent>) -> Self {
+        self.profile_watch = Some(ProfileWatch { path, events });
+        self
+    }
+
+    /// Call `hook` with the watched profile's path before each reload, e.g. to
+    /// back up the file
+    pub fn with_before_reload<F>(mut self, hook: F) -> Self
+    where
+        F: FnMut(&Path) + Send + 'static,
+    {
+        self.before_reload = Some(Box::new(hook));
+        self
+    }
+
+    /// Stop the loop once `stop` returns true
+    ///
+    /// It is checked before each read and when a read fails, so a signal that
+    /// interrupts a blocking read stops the loop instead of failing it.
+    pub fn with_stop_when<F>(mut self, stop: F) -> Self
+    where
+        F: Fn() -> bool + Send + 'static,
+    {
+        self.stop_when = Some(Box::new(stop));
+        self
+    }
+
+    /// Create a new virtual keyboard with `factory` when a reloaded profile needs
+    /// keys the current one cannot register
+    ///
+    /// Held keys are released and output pauses while the keyboard is replaced.
+    pub fn with_keyboard_factory<F>(mut self, factory: F) -> Self
+    where
+        F: FnMut(&str, &[KeyboardCode]) -> Result<Box<dyn VirtualKeyboard>> + Send + 'static,
+    {
+        self.keyboard_factory = Some(Box::new(factory));
+        self
+    }
+
+    /// Keep running when the gamepad disconnects, calling `reopen` with growing
+    /// waits in between until it returns a gamepad
+    ///
+    /// Held keys are released while the gamepad is gone.
+    pub fn with_reconnect<F>(mut self, config: ReconnectConfig, reopen: F) -> Self
+    where
+        F: FnMut() -> Result<Box<dyn Gamepad>> + Send + 'static,
+    {
+        self.reconnect = Some(Reconnect { config, reopen: Box::new(reopen) });
+        self
+    }
+
+    /// Warn whenever processing an event takes longer than `budget_us`
+    pub fn with_latency_budget(mut self, budget_us: u64) -> Self {
+        self.latency_budget = Some(LatencyBudget::new(budget_us));
+        self
+    }
+
+    /// Gamepad axes the mappings drive, in a stable order
+    ///
+    /// Empty when the profile has no AxisToAxis rules, in which case no
+    /// virtual gamepad is needed.
+    pub fn target_axes(&self) -> Vec<AxisCode> {
+        let mut axes: Vec<AxisCode> = self.engine.target_axes().into_iter().collect();
+        axes.sort_by_key(|code| *code as u32);
+        axes
+    }
+
+    /// Send gamepad axis outputs (AxisToAxis rules) to the given virtual gamepad
+    pub fn with_virtual_gamepad(mut self, virtual_gamepad: Box<dyn VirtualGamepad>) -> Self {
+        self.virtual_gamepad = Some(virtual_gamepad);
+        self
+    }
+
+    /// Publish every processed event to the given stream
+    pub fn with_event_stream(mut self, event_stream: EventStream) -> Self {
+        self.event_stream = Some(event_stream);
+        self
+    }
+
+    /// Write every processed event to the given log file
+    pub fn with_event_log(mut self, event_log: EventLog) -> Self {
+        self.event_log = Some(event_log);
+        self
+    }
+
+    /// Number of events processed so far
+    pub fn event_count(&self) -> u64 {
+        self.health.event_count()
+    }
+
+    /// Time since the event loop was created
+    pub fn uptime(&self) -> Duration {
+        self.health.uptime()
+    }
+
+    pub fn health(&self) -> HealthStatus {
+        self.health.health()
+    }
+
+    /// Handle for reading health from another thread while `run()` blocks
+    pub fn health_monitor(&self) -> HealthMonitor {
+        self.health.clone()
+    }
+
     /// Run the event loop (blocking)
-    pub fn run(mut self) -> Result<()> {
+    pub fn run(self) -> Result<()> {
+        self.run_for(u64::MAX)
+    }
+
+    /// Run the event loop until `max_events` input events have been read from
+    /// the gamepad or it disconnects, whichever comes first
+    ///
+    /// Sync events, which end every evdev report, don't count towards the limit.
+    /// Events dropped by a filter do count.
+    ///
+    /// Held keys are released when the loop stops, even if it stops with an error.
+    pub fn run_for(mut self, max_events: u64) -> Result<()> {
         tracing::info!("Event loop starting...");
 
+        let result = self.read_loop(max_events);
+        self.finish();
+        result
+    }
+
+    fn read_loop(&mut self, max_events: u64) -> Result<()> {
+        let mut events_read: u64 = 0;
+        while events_read < max_events {
+            let _iteration = tracing::trace_span!("event_loop_iteration").entered();
+
+            let Some(input_event) = self.next_event()? else {
+                return Ok(());
+            };
+            if !matches!(input_event, InputEvent::Sync { .. }) {
+                events_read += 1;
+            }
+            self.handle_input(input_event)?;
+        }
+
+        tracing::info!("Reached event limit ({})", max_events);
+        Ok(())
+    }
+
+    /// Read the next event from the gamepad (blocking), reopening it if it
+    /// disconnects and reconnecting is enabled
+    ///
+    /// Returns `None` when the loop should stop: a stop was requested or the
+    /// gamepad is gone for good.
+    pub(crate) fn next_event(&mut self) -> Result<Option<InputEvent>> {
         loop {
-            match self.gamepad.read_event()? {
-                Some(input_event) => {
-                    let start = Instant::now();
-                    // Process through mapping engine
-                    for output_event in self.engine.process(&input_event)? {
-                        #[cfg(debug_assertions)] // Only trace per button event in debug build to not interrupt latency
-                        tracing::debug!("Gamepad: {} -> {}", input_event, output_event);
-
-                        self.emit_output(output_event)?;
-                    }
-
-                    // Measure ONLY processing latency
-                    let latency_us = start.elapsed().as_micros() as u64;
-
-                    self.event_count += 1;
-                    self.total_latency_us += latency_us;
-                    self.max_latency_us = self.max_latency_us.max(latency_us);
-                    self.min_latency_us = self.min_latency_us.min(latency_us);
-
-                    // Log statistics every 100 events
-                    if self.event_count.is_multiple_of(100) {
-                        let avg = self.total_latency_us / self.event_count;
-                        tracing::info!(
-                            "Stats: {} events | avg: {}µs ({:.2}ms) | min: {}µs | max: {}µs",
-                            self.event_count,
-                            avg,
-                            avg as f64 / 1000.0,
-                            self.min_latency_us,
-                            self.max_latency_us
-                        );
-                    }
+            if self.stop_requested() {
+                tracing::info!("Stop requested");
+                return Ok(None);
+            }
+
+            let read = match self.gamepad.read_event() {
+                Err(_) if self.stop_requested() => {
+                    tracing::info!("Stop requested");
+                    return Ok(None);
                 }
-                None => {
-                    // Controller disconnected
+                read => read?,
+            };
+            if let Some(input_event) = read {
+                return Ok(Some(input_event));
+            }
+
+            // Controller disconnected
+            if !self.reconnect_gamepad() {
+                if self.stop_requested() {
+                    tracing::info!("Stop requested");
+                } else {
                     tracing::warn!("Controller disconnected");
-                    break;
                 }
+                return Ok(None);
             }
         }
+    }
+
+    fn stop_requested(&self) -> bool {
+        self.stop_when.as_ref().is_some_and(|stop| stop())
+    }
+
+    /// Reopen the gamepad if reconnecting is enabled, retrying until it succeeds
+    ///
+    /// Returns false right away when it is not enabled, and as soon as a stop is
+    /// requested while waiting between attempts.
+    fn reconnect_gamepad(&mut self) -> bool {
+        if self.reconnect.is_none() {
+            return false;
+        }
+        self.release_held_inputs();
+        let Some(reconnect) = &mut self.reconnect else {
+            return false;
+        };
+
+        for attempt in 1.. {
+            let delay = reconnect.config.delay_for(attempt);
+            tracing::warn!(
+                "Controller disconnected, retrying in {:.1}s (attempt {})",
+                delay.as_secs_f64(),
+                attempt
+            );
+            if !sleep_unless_stopped(delay, self.stop_when.as_deref()) {
+                return false;
+            }
+
+            match (reconnect.reopen)() {
+                Ok(gamepad) => {
+                    tracing::info!("Controller reconnected (attempt {})", attempt);
+                    self.gamepad = gamepad;
+                    self.update_device_ranges();
+                    return true;
+                }
+                Err(e) => tracing::debug!("Reconnect attempt {} failed: {:#}", attempt, e),
+            }
+        }
+        unreachable!("reconnect attempts are unbounded")
+    }
+
+    /// Release every held key and make the engine forget what was held, so
+    /// buttons and directions still down are pressed again on their next event
+    fn release_held_inputs(&mut self) {
+        if let Err(e) = self.keyboard.reset() {
+            tracing::warn!("Failed to release held keys: {:#}", e);
+        }
+        self.engine.reset_state();
+    }
+
+    /// Rescale forwarded axes from the current gamepad's axis ranges, so
+    /// controllers with other ranges (e.g. 0-255 sticks) need no calibration
+    fn update_device_ranges(&mut self) {
+        // Without forwarded axes there is nothing to rescale; skips copying the info
+        if self.engine.forwards_axes() {
+            self.engine.set_device_ranges(&self.gamepad.get_info());
+        }
+    }
+
+    /// Run one event read from the gamepad through the filters, the engine and
+    /// the outputs, and record it in the statistics
+    ///
+    /// Sync events only mark the end of an evdev report; they are skipped so they
+    /// don't inflate the event count or pull the latency statistics down.
+    pub(crate) fn handle_input(&mut self, input_event: InputEvent) -> Result<()> {
+        self.apply_profile_changes();
+        if matches!(input_event, InputEvent::Sync { .. }) {
+            return Ok(());
+        }
+        let Some(input_event) = apply_filters(&mut self.filters, input_event) else {
+            return Ok(());
+        };
+        // Process through mapping engine
+        let (output_events, elapsed) = self.engine.process_with_timing(&input_event)?;
+        for output_event in &output_events {
+            #[cfg(debug_assertions)] // Only trace per button event in debug build to not interrupt latency
+            tracing::debug!("Gamepad: {} -> {}", input_event, output_event);
+
+            self.emit_output(output_event.clone())?;
+        }
+
+        if let Some(event_stream) = &self.event_stream {
+            event_stream.broadcast(&input_event, &output_events);
+        }
+        self.write_event_log(&input_event, &output_events);
+
+        // Measure ONLY processing latency
+        let latency_us = elapsed.as_micros() as u64;
+
+        let event_count = self.health.record_event(input_event.timestamp());
+        self.health.record_latency(latency_us);
+        self.check_latency_budget(latency_us);
+
+        // Log statistics every 100 events
+        if event_count.is_multiple_of(100) {
+            self.log_stats("Stats");
+        }
+        Ok(())
+    }
+
+    /// Release held keys and log that the loop stopped, with the final statistics
+    pub(crate) fn finish(&mut self) {
+        if let Err(e) = self.keyboard.reset() {
+            tracing::warn!("Failed to release held keys: {:#}", e);
+        }
 
         tracing::info!("Event loop stopped");
         // Print final statistics
-        if self.event_count > 0 {
-            let avg = self.total_latency_us / self.event_count;
-            tracing::info!(
-                "Final: {} events | avg: {}µs ({:.2}ms) | min: {}µs | max: {}µs",
-                self.event_count,
-                avg,
-                avg as f64 / 1000.0,
-                self.min_latency_us,
-                self.max_latency_us
+        if self.health.event_count() > 0 {
+            self.log_stats("Final");
+        }
+    }
+
+    /// Log the event pair; a log that cannot be written is closed so the
+    /// remapping itself keeps running
+    fn write_event_log(&mut self, input_event: &InputEvent, output_events: &[OutputEvent]) {
+        let Some(event_log) = self.event_log.as_mut() else {
+            return;
+        };
+        if let Err(e) = event_log.write(input_event, output_events) {
+            tracing::error!("Event log disabled: {:#}", e);
+            self.event_log = None;
+        }
+    }
+
+    /// Handle pending profile file changes, reloading at most once
+    fn apply_profile_changes(&mut self) {
+        let Some(watch) = self.profile_watch.as_mut() else {
+            return;
+        };
+
+        let mut modified = false;
+        for event in watch.events.try_iter() {
+            match event {
+                FileEvent::Modified => modified = true,
+                FileEvent::Deleted => tracing::warn!(
+                    "Profile {} was deleted; keeping the current mappings",
+                    watch.path.display()
+                ),
+                FileEvent::Renamed(new_path) => {
+                    tracing::info!(
+                        "Profile {} moved to {}",
+                        watch.path.display(),
+                        new_path.display()
+                    );
+                    watch.path = new_path;
+                }
+            }
+        }
+        if !modified {
+            return;
+        }
+
+        let path = watch.path.clone();
+        if let Some(hook) = self.before_reload.as_mut() {
+            hook(&path);
+        }
+        let result =
+            Profile::load_from_file(&path).and_then(|profile| self.apply_profile(&profile));
+        match result {
+            Ok(()) if self.using_fallback => {
+                tracing::info!("Reloaded profile {}, leaving the fallback", path.display());
+                self.using_fallback = false;
+            }
+            Ok(()) => tracing::info!("Reloaded profile {}", path.display()),
+            Err(e) => {
+                self.health.record_error();
+                self.switch_to_fallback(e);
+            }
+        }
+    }
+
+    /// After a failed reload, use the fallback profile until the next successful one
+    fn switch_to_fallback(&mut self, reload_error: anyhow::Error) {
+        let fallback = match &self.fallback {
+            Some(fallback) if !self.using_fallback => fallback,
+            _ => {
+                tracing::warn!(
+                    "Profile reload failed, keeping the current mappings: {:#}",
+                    reload_error
+                );
+                return;
+            }
+        };
+
+        tracing::warn!(
+            "Profile reload failed, using fallback: {} ({:#})",
+            fallback.path.display(),
+            reload_error
+        );
+        let profile = fallback.profile.clone();
+        match self.apply_profile(&profile) {
+            Ok(()) => self.using_fallback = true,
+            Err(e) => {
+                tracing::error!("Fallback profile rejected, keeping the current mappings: {:#}", e)
+            }
+        }
+    }
+
+    /// Replace the mappings with `profile`'s, releasing the keys the old ones held
+    fn apply_profile(&mut self, profile: &Profile) -> Result<()> {
+        let engine = MappingEngine::load_from_profile(profile)
+            .with_context(|| format!("Invalid mappings in profile '{}'", profile.name))?;
+
+        if let Some(registered) = &self.registered_keys {
+            let mut missing: Vec<KeyboardCode> =
+                engine.target_keys().difference(registered).copied().collect();
+            if !missing.is_empty() {
+                missing.sort_by_key(|code| *code as u32);
+                self.register_keys(&missing, profile)?;
+            }
+        }
+
+        // The new engine knows nothing of what the old one pressed, so a key held
+        // now would never be released
+        self.release_held_inputs();
+        self.engine = engine;
+        self.update_device_ranges();
+        self.latency_budget = profile.settings.latency_budget_us.map(LatencyBudget::new);
+        Ok(())
+    }
+
+    /// Make `missing` pressable, replacing the virtual keyboard if it cannot
+    /// register them and a keyboard factory was given
+    fn register_keys(&mut self, missing: &[KeyboardCode], profile: &Profile) -> Result<()> {
+        let mut unregistered: Vec<KeyboardCode> = Vec::new();
+        for &code in missing {
+            if let Err(e) = self.keyboard.register_key(code) {
+                tracing::debug!("{:#}", e);
+                unregistered.push(code);
+            }
+        }
+
+        let mut keys: Vec<KeyboardCode> =
+            self.registered_keys.iter().flatten().chain(missing).copied().collect();
+        keys.sort_by_key(|code| *code as u32);
+        if unregistered.is_empty() {
+            self.registered_keys = Some(keys.into_iter().collect());
+            return Ok(());
+        }
+
+        let Some(factory) = self.keyboard_factory.as_mut() else {
+            let mut names: Vec<String> = unregistered.iter().map(ToString::to_string).collect();
+            names.sort();
+            anyhow::bail!(
+                "Profile uses keys the virtual keyboard was not created with ({}); restart to use them",
+                names.join(", ")
             );
+        };
+
+        tracing::info!("Recreating the virtual keyboard to add {} key(s)", unregistered.len());
+        if let Err(e) = self.keyboard.reset() {
+            tracing::warn!("Failed to release held keys: {:#}", e);
         }
+        let name = profile.settings.output_device_name.as_deref().unwrap_or(DEFAULT_KEYBOARD_NAME);
+        self.keyboard = factory(name, &keys).context("Failed to recreate virtual keyboard")?;
+        self.registered_keys = Some(keys.into_iter().collect());
         Ok(())
     }
 
+    fn check_latency_budget(&mut self, latency_us: u64) {
+        let Some(budget) = self.latency_budget.as_mut() else {
+            return;
+        };
+
+        let consecutive = budget.check(latency_us);
+        if consecutive == 0 {
+            return;
+        }
+        tracing::warn!("Latency budget exceeded: {}µs > {}µs", latency_us, budget.budget_us());
+        if consecutive == LATENCY_BUDGET_STREAK {
+            tracing::error!(
+                "Latency budget exceeded {} events in a row; consider simplifying the profile \
+                 (fewer filters or mappings)",
+                consecutive
+            );
+        }
+    }
+
+    /// Log latency over the last `DEFAULT_LATENCY_CAPACITY` events
+    fn log_stats(&self, label: &str) {
+        let latency = self.health.latency();
+        let avg = latency.mean();
+        tracing::info!(
+            "{}: {} events | avg: {}µs ({:.2}ms) | p99: {}µs | min: {}µs | max: {}µs",
+            label,
+            self.health.event_count(),
+            avg,
+            avg as f64 / 1000.0,
+            latency.percentile(99.0),
+            latency.min(),
+            latency.max()
+        );
+    }
+
     fn emit_output(&mut self, output_event: OutputEvent) -> Result<()> {
+        let _span = tracing::trace_span!("emit_output", output = %output_event).entered();
+
         match output_event {
             OutputEvent::Keyboard { code, event_type } => {
                 if event_type == KeyboardEventType::Press {
@@ -108,8 +676,845 @@ impl EventLoop {
                     self.keyboard.release_key(code)?;
                 }
             }
+            OutputEvent::MediaKey { code, event_type } => {
+                if event_type == KeyboardEventType::Press {
+                    self.keyboard.press_key(code.keyboard_code())?;
+                } else if event_type == KeyboardEventType::Release {
+                    self.keyboard.release_key(code.keyboard_code())?;
+                }
+            }
+            OutputEvent::Rumble { weak, strong, duration_ms } => {
+                // A failed rumble should not stop remapping
+                if let Err(e) = self.gamepad.set_rumble(weak, strong, duration_ms) {
+                    tracing::warn!("Failed to play rumble effect: {}", e);
+                    self.health.record_error();
+                }
+            }
+            OutputEvent::GamepadAxis { code, value } => {
+                let Some(virtual_gamepad) = self.virtual_gamepad.as_mut() else {
+                    tracing::warn!("No virtual gamepad attached, dropping {} = {}", code, value);
+                    self.health.record_error();
+                    return Ok(());
+                };
+                if let Err(e) = virtual_gamepad.set_axis(code, value) {
+                    tracing::warn!("Failed to set gamepad axis {}: {}", code, e);
+                    self.health.record_error();
+                }
+            }
         }
 
         Ok(())
     }
 }
+
+/// Sleep for `duration` in short slices, returning false early once `stop`
+/// returns true
+///
+/// Signals don't cut `thread::sleep` short, so a long wait would ignore them.
+fn sleep_unless_stopped(duration: Duration, stop: Option<&(dyn Fn() -> bool + Send)>) -> bool {
+    let deadline = Instant::now() + duration;
+    loop {
+        if stop.is_some_and(|stop| stop()) {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        std::thread::sleep((deadline - now).min(STOP_POLL_INTERVAL));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::gamepad::MockGamepad;
+    use crate::mapping::{Mapping, types::TargetType};
+    use crate::output::keyboard::MockVirtualKeyboard;
+
+    #[test]
+    fn test_new_with_profile_uses_default_keyboard_name() {
+        let mut requested_name = String::new();
+
+        let result = EventLoop::new_with_profile(
+            Box::new(MockGamepad::new()),
+            Profile::default_profile(),
+            |name, _| {
+                requested_name = name.to_string();
+                Ok(Box::new(MockVirtualKeyboard::resettable()))
+            },
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(requested_name, DEFAULT_KEYBOARD_NAME);
+    }
+
+    #[test]
+    fn test_new_with_profile_uses_output_device_name() {
+        let mut profile = Profile::default_profile();
+        profile.settings.output_device_name = Some("My Game Keyboard".to_string());
+        let mut requested_name = String::new();
+
+        EventLoop::new_with_profile(Box::new(MockGamepad::new()), profile, |name, _| {
+            requested_name = name.to_string();
+            Ok(Box::new(MockVirtualKeyboard::resettable()))
+        })
+        .unwrap();
+
+        assert_eq!(requested_name, "My Game Keyboard");
+    }
+
+    #[test]
+    fn test_new_with_profile_registers_only_target_keys() {
+        let mut requested_keys = Vec::new();
+
+        EventLoop::new_with_profile(
+            Box::new(MockGamepad::new()),
+            Profile::default_profile(),
+            |_, keys| {
+                requested_keys = keys.to_vec();
+                Ok(Box::new(MockVirtualKeyboard::resettable()))
+            },
+        )
+        .unwrap();
+
+        use crate::event::KeyboardCode::*;
+        assert_eq!(requested_keys, vec![Escape, W, Enter, A, S, D, Up, Left, Right, Down]);
+    }
+
+    #[test]
+    fn test_new_with_profile_applies_latency_budget() {
+        let make_keyboard = |_: &str, _: &[KeyboardCode]| -> Result<Box<dyn VirtualKeyboard>> {
+            Ok(Box::new(MockVirtualKeyboard::resettable()))
+        };
+
+        let event_loop = EventLoop::new_with_profile(
+            Box::new(MockGamepad::new()),
+            Profile::default_profile(),
+            make_keyboard,
+        )
+        .unwrap();
+        assert_eq!(event_loop.latency_budget, None);
+
+        let mut profile = Profile::default_profile();
+        profile.settings.latency_budget_us = Some(500);
+        let event_loop =
+            EventLoop::new_with_profile(Box::new(MockGamepad::new()), profile, make_keyboard)
+                .unwrap();
+        assert_eq!(event_loop.latency_budget.map(|b| b.budget_us()), Some(500));
+    }
+
+    #[test]
+    fn test_new_with_profile_rejects_invalid_mappings() {
+        let mut profile = Profile::default_profile();
+        profile.mappings.push(Mapping {
+            source_name: AxisCode::DPadX.to_string(),
+            source_direction: Some("Sideways".to_string()),
+            target_type: TargetType::Keyboard,
+            target_name: "A".to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        });
+
+        let result = EventLoop::new_with_profile(Box::new(MockGamepad::new()), profile, |_, _| {
+            panic!("keyboard should not be created for an invalid profile")
+        });
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_media_key_is_held_with_the_button() {
+        use crate::event::ButtonCode;
+
+        let mut profile = Profile::default_profile();
+        profile.mappings.push(Mapping {
+            source_name: ButtonCode::Mode.to_string(),
+            source_direction: None,
+            target_type: TargetType::Media,
+            target_name: "Play/Pause".to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        });
+        let mut gamepad = MockGamepad::new();
+        let mut pressed = false;
+        gamepad.expect_read_event().times(2).returning(move || {
+            pressed = !pressed;
+            Ok(Some(InputEvent::Button {
+                code: ButtonCode::Mode,
+                pressed,
+                timestamp: std::time::Instant::now(),
+            }))
+        });
+        // Pressed and released with the button instead of a blocking tap
+        let mut keyboard = MockVirtualKeyboard::resettable();
+        keyboard.expect_tap_key().never();
+        keyboard
+            .expect_press_key()
+            .with(mockall::predicate::eq(KeyboardCode::PlayPause))
+            .times(1)
+            .returning(|_| Ok(()));
+        keyboard
+            .expect_release_key()
+            .with(mockall::predicate::eq(KeyboardCode::PlayPause))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let event_loop =
+            EventLoop::new_with_profile(Box::new(gamepad), profile, |_, _| Ok(Box::new(keyboard)))
+                .unwrap();
+
+        assert!(event_loop.run_for(2).is_ok());
+    }
+
+    #[test]
+    fn test_new_with_profile_rescales_forwarded_axes() {
+        use crate::input::gamepad::{AxisRange, BusType, GamepadInfo, GamepadType};
+        use crate::output::gamepad::MockVirtualGamepad;
+
+        let mut profile = Profile::default_profile();
+        profile.mappings.push(Mapping {
+            source_name: AxisCode::LeftX.to_string(),
+            source_direction: None,
+            target_type: TargetType::GamepadAxis,
+            target_name: "LeftX".to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        });
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_get_info().times(1).returning(|| GamepadInfo {
+            path: "/dev/input/event3".to_string(),
+            name: "Wireless Controller".to_string(),
+            gamepad_type: GamepadType::DualShock4,
+            vendor_id: 0x054C,
+            vendor_name: "Sony".to_string(),
+            product_id: 0x09CC,
+            version: 0,
+            bus_type: BusType::Usb,
+            capabilities: vec![],
+            axis_ranges: std::collections::HashMap::from([(
+                AxisCode::LeftX,
+                AxisRange { min: 0, max: 255, flat: 15, fuzz: 0 },
+            )]),
+        });
+        gamepad
+            .expect_read_event()
+            .times(1)
+            .returning(|| Ok(Some(InputEvent::axis_move(AxisCode::LeftX, 255))));
+        let mut virtual_gamepad = MockVirtualGamepad::new();
+        virtual_gamepad
+            .expect_set_axis()
+            .with(mockall::predicate::eq(AxisCode::LeftX), mockall::predicate::eq(32767))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let event_loop = EventLoop::new_with_profile(Box::new(gamepad), profile, |_, _| {
+            Ok(Box::new(MockVirtualKeyboard::resettable()))
+        })
+        .unwrap()
+        .with_virtual_gamepad(Box::new(virtual_gamepad));
+
+        assert!(event_loop.run_for(1).is_ok());
+    }
+
+    #[test]
+    fn test_reconnect_reopens_gamepad_after_disconnect() {
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_read_event().times(1).returning(|| Ok(None));
+
+        let mut attempts = 0;
+        let reopen = move || {
+            attempts += 1;
+            if attempts < 3 {
+                anyhow::bail!("not connected yet");
+            }
+            let mut reconnected = MockGamepad::new();
+            reconnected
+                .expect_read_event()
+                .times(1)
+                .returning(|| Ok(Some(InputEvent::button_press(crate::event::ButtonCode::South))));
+            Ok(Box::new(reconnected) as Box<dyn Gamepad>)
+        };
+
+        let event_loop = EventLoop::new(
+            Box::new(gamepad),
+            MappingEngine::new_empty(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        )
+        .with_reconnect(
+            ReconnectConfig { initial_delay: Duration::ZERO, max_delay: Duration::ZERO },
+            reopen,
+        );
+
+        // Stops after the one event read from the reconnected gamepad
+        assert!(event_loop.run_for(1).is_ok());
+    }
+
+    #[test]
+    fn test_reconnect_presses_held_direction_again() {
+        use crate::event::AxisCode;
+
+        let up = || Ok(Some(InputEvent::axis_move(AxisCode::DPadY, -1)));
+        let mut gamepad = MockGamepad::new();
+        let mut reads = 0;
+        gamepad.expect_read_event().returning(move || {
+            reads += 1;
+            if reads == 1 { up() } else { Ok(None) }
+        });
+        let reopen = move || {
+            let mut reconnected = MockGamepad::new();
+            reconnected.expect_read_event().times(1).returning(up);
+            Ok(Box::new(reconnected) as Box<dyn Gamepad>)
+        };
+
+        let mut keyboard = MockVirtualKeyboard::resettable();
+        keyboard
+            .expect_press_key()
+            .with(mockall::predicate::eq(KeyboardCode::Up))
+            .times(2)
+            .returning(|_| Ok(()));
+        let event_loop =
+            EventLoop::new(Box::new(gamepad), MappingEngine::new_hardcoded(), Box::new(keyboard))
+                .with_reconnect(
+                    ReconnectConfig { initial_delay: Duration::ZERO, max_delay: Duration::ZERO },
+                    reopen,
+                );
+
+        assert!(event_loop.run_for(2).is_ok());
+    }
+
+    #[test]
+    fn test_stop_during_reconnect_backoff() {
+        use std::sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        };
+
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_read_event().times(1).returning(|| Ok(None));
+        let stop = Arc::new(AtomicBool::new(false));
+        let signal = stop.clone();
+        let event_loop = EventLoop::new(
+            Box::new(gamepad),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        )
+        .with_reconnect(
+            ReconnectConfig {
+                initial_delay: Duration::from_secs(60),
+                max_delay: Duration::from_secs(60),
+            },
+            || panic!("reconnect attempted after the stop request"),
+        )
+        .with_stop_when(move || stop.load(Ordering::SeqCst));
+
+        let started = Instant::now();
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            signal.store(true, Ordering::SeqCst);
+        });
+
+        assert!(event_loop.run().is_ok());
+        sender.join().unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_emit_rumble_calls_gamepad() {
+        let mut gamepad = MockGamepad::new();
+        gamepad
+            .expect_set_rumble()
+            .with(
+                mockall::predicate::eq(100),
+                mockall::predicate::eq(200),
+                mockall::predicate::eq(300),
+            )
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let mut event_loop = EventLoop::new(
+            Box::new(gamepad),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        );
+
+        let result = event_loop.emit_output(OutputEvent::Rumble {
+            weak: 100,
+            strong: 200,
+            duration_ms: 300,
+        });
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_emit_gamepad_axis_calls_virtual_gamepad() {
+        use crate::output::gamepad::MockVirtualGamepad;
+
+        let mut virtual_gamepad = MockVirtualGamepad::new();
+        virtual_gamepad
+            .expect_set_axis()
+            .with(mockall::predicate::eq(AxisCode::RightX), mockall::predicate::eq(-1200))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let mut event_loop = EventLoop::new(
+            Box::new(MockGamepad::new()),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        )
+        .with_virtual_gamepad(Box::new(virtual_gamepad));
+
+        let result = event_loop
+            .emit_output(OutputEvent::GamepadAxis { code: AxisCode::RightX, value: -1200 });
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_emit_gamepad_axis_without_virtual_gamepad_is_not_fatal() {
+        let mut event_loop = EventLoop::new(
+            Box::new(MockGamepad::new()),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        );
+
+        let result =
+            event_loop.emit_output(OutputEvent::GamepadAxis { code: AxisCode::RightX, value: 10 });
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_filters_drop_events_before_engine() {
+        use crate::event::{AxisRangeFilter, ButtonCode, InputEvent, KeyboardCode};
+
+        let mut events = vec![
+            InputEvent::axis_move(AxisCode::DPadY, 5), // dropped by the range filter
+            InputEvent::button_press(ButtonCode::South),
+        ]
+        .into_iter();
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_read_event().returning(move || Ok(events.next()));
+
+        let mut keyboard = MockVirtualKeyboard::resettable();
+        keyboard
+            .expect_press_key()
+            .with(mockall::predicate::eq(KeyboardCode::S))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let event_loop = EventLoop::new_with_filters(
+            Box::new(gamepad),
+            vec![Box::new(AxisRangeFilter::new(-1, 1))],
+            MappingEngine::new_hardcoded(),
+            Box::new(keyboard),
+        );
+
+        let monitor = event_loop.health_monitor();
+        assert!(event_loop.run().is_ok());
+
+        // The dropped axis event is not counted
+        assert_eq!(monitor.event_count(), 1);
+        assert!(monitor.health().last_event_at.is_some());
+    }
+
+    /// Run one South press through an event loop watching a profile that
+    /// remaps South to `key`, returning the key that was pressed
+    fn press_south_after_reload(name: &str, key: KeyboardCode) -> KeyboardCode {
+        use crate::event::{ButtonCode, InputEvent};
+
+        let path = std::env::temp_dir().join(format!(
+            "blazeremap-reload-{}-{}.toml",
+            name,
+            std::process::id()
+        ));
+        let mut profile = Profile::default_profile();
+        for mapping in &mut profile.mappings {
+            if mapping.source_name == "South" {
+                mapping.target_name = key.to_string();
+            }
+        }
+        profile.save_to_file(&path).unwrap();
+
+        let mut gamepad = MockGamepad::new();
+        let mut events = vec![InputEvent::button_press(ButtonCode::South)].into_iter();
+        gamepad.expect_read_event().returning(move || Ok(events.next()));
+
+        let pressed = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let pressed_in_mock = pressed.clone();
+        let (tx, rx) = crossbeam::channel::unbounded();
+        tx.send(FileEvent::Modified).unwrap();
+
+        let event_loop =
+            EventLoop::new_with_profile(Box::new(gamepad), Profile::default_profile(), |_, _| {
+                let mut keyboard = MockVirtualKeyboard::resettable();
+                keyboard.expect_press_key().returning(move |code| {
+                    *pressed_in_mock.lock().unwrap() = Some(code);
+                    Ok(())
+                });
+                Ok(Box::new(keyboard))
+            })
+            .unwrap()
+            .with_profile_watch(path.clone(), rx);
+        event_loop.run().unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        pressed.lock().unwrap().expect("a key was pressed")
+    }
+
+    #[test]
+    fn test_profile_change_reloads_mappings() {
+        assert_eq!(press_south_after_reload("ok", KeyboardCode::A), KeyboardCode::A);
+    }
+
+    #[test]
+    fn test_before_reload_hook_sees_profile_path() {
+        use crate::event::{ButtonCode, InputEvent};
+
+        let path = std::env::temp_dir()
+            .join(format!("blazeremap-reload-hook-{}.toml", std::process::id()));
+        Profile::default_profile().save_to_file(&path).unwrap();
+
+        let mut gamepad = MockGamepad::new();
+        let mut events = vec![InputEvent::button_release(ButtonCode::South)].into_iter();
+        gamepad.expect_read_event().returning(move || Ok(events.next()));
+        let (tx, rx) = crossbeam::channel::unbounded();
+        tx.send(FileEvent::Modified).unwrap();
+
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let event_loop =
+            EventLoop::new_with_profile(Box::new(gamepad), Profile::default_profile(), |_, _| {
+                let mut keyboard = MockVirtualKeyboard::resettable();
+                keyboard.expect_release_key().returning(|_| Ok(()));
+                Ok(Box::new(keyboard))
+            })
+            .unwrap()
+            .with_profile_watch(path.clone(), rx)
+            .with_before_reload(move |path| recorded.lock().unwrap().push(path.to_path_buf()));
+        event_loop.run().unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(*seen.lock().unwrap(), vec![path]);
+    }
+
+    #[test]
+    fn test_profile_reload_releases_held_keys() {
+        use crate::event::{ButtonCode, InputEvent};
+
+        let path = std::env::temp_dir()
+            .join(format!("blazeremap-reload-held-{}.toml", std::process::id()));
+        let mut profile = Profile::default_profile();
+        profile.mappings.retain(|m| m.source_name == "South");
+        profile.mappings[0].target_name = KeyboardCode::A.to_string();
+        profile.save_to_file(&path).unwrap();
+
+        // South is pressed with the old mappings and released after the reload
+        let (tx, rx) = crossbeam::channel::unbounded();
+        let mut gamepad = MockGamepad::new();
+        let mut reads = 0;
+        gamepad.expect_read_event().returning(move || {
+            reads += 1;
+            Ok(match reads {
+                1 => Some(InputEvent::button_press(ButtonCode::South)),
+                2 => {
+                    tx.send(FileEvent::Modified).unwrap();
+                    Some(InputEvent::button_release(ButtonCode::South))
+                }
+                _ => None,
+            })
+        });
+
+        let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let event_loop =
+            EventLoop::new_with_profile(Box::new(gamepad), Profile::default_profile(), |_, _| {
+                let mut keyboard = MockVirtualKeyboard::new();
+                let (pressed, released, reset) = (recorded.clone(), recorded.clone(), recorded);
+                keyboard.expect_press_key().returning(move |code| {
+                    pressed.lock().unwrap().push(format!("press {}", code));
+                    Ok(())
+                });
+                keyboard.expect_release_key().returning(move |code| {
+                    released.lock().unwrap().push(format!("release {}", code));
+                    Ok(())
+                });
+                keyboard.expect_reset().returning(move || {
+                    reset.lock().unwrap().push("reset".to_string());
+                    Ok(())
+                });
+                Ok(Box::new(keyboard))
+            })
+            .unwrap()
+            .with_profile_watch(path.clone(), rx);
+        event_loop.run().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls[..2], ["press S", "reset"]);
+    }
+
+    #[test]
+    fn test_profile_reload_rejects_unregistered_keys() {
+        // Z is not in the default profile, so the keyboard cannot press it
+        assert_eq!(press_south_after_reload("new-key", KeyboardCode::Z), KeyboardCode::S);
+    }
+
+    #[test]
+    fn test_profile_reload_recreates_keyboard_for_new_keys() {
+        use crate::event::{ButtonCode, InputEvent};
+
+        let path = std::env::temp_dir()
+            .join(format!("blazeremap-reload-recreate-{}.toml", std::process::id()));
+        let mut profile = Profile::default_profile();
+        profile.mappings.retain(|m| m.source_name == "South");
+        profile.mappings[0].target_name = KeyboardCode::Z.to_string();
+        profile.save_to_file(&path).unwrap();
+
+        let mut gamepad = MockGamepad::new();
+        let mut events = vec![InputEvent::button_press(ButtonCode::South)].into_iter();
+        gamepad.expect_read_event().returning(move || Ok(events.next()));
+
+        let created = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let created_in_factory = created.clone();
+        let (tx, rx) = crossbeam::channel::unbounded();
+        tx.send(FileEvent::Modified).unwrap();
+
+        let event_loop =
+            EventLoop::new_with_profile(Box::new(gamepad), Profile::default_profile(), |_, _| {
+                Ok(Box::new(MockVirtualKeyboard::resettable()))
+            })
+            .unwrap()
+            .with_keyboard_factory(move |_, keys| {
+                created_in_factory.lock().unwrap().push(keys.to_vec());
+                let mut keyboard = MockVirtualKeyboard::resettable();
+                keyboard
+                    .expect_press_key()
+                    .with(mockall::predicate::eq(KeyboardCode::Z))
+                    .times(1)
+                    .returning(|_| Ok(()));
+                Ok(Box::new(keyboard))
+            })
+            .with_profile_watch(path.clone(), rx);
+        event_loop.run().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let created = created.lock().unwrap();
+        assert_eq!(created.len(), 1);
+        // The new keyboard keeps the old keys and adds Z
+        assert!(created[0].contains(&KeyboardCode::Z));
+        assert!(created[0].contains(&KeyboardCode::S));
+    }
+
+    #[test]
+    fn test_failed_reload_switches_to_fallback() {
+        use crate::event::{ButtonCode, InputEvent};
+
+        let path = std::env::temp_dir()
+            .join(format!("blazeremap-reload-broken-{}.toml", std::process::id()));
+        std::fs::write(&path, "name = \"Broken\"\nmappings = [oops]\n").unwrap();
+
+        let mut fallback = Profile::default_profile();
+        fallback.mappings.retain(|m| m.source_name == "South");
+        fallback.mappings[0].target_name = KeyboardCode::A.to_string();
+
+        let mut gamepad = MockGamepad::new();
+        let mut events = vec![InputEvent::button_press(ButtonCode::South)].into_iter();
+        gamepad.expect_read_event().returning(move || Ok(events.next()));
+
+        let pressed = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let pressed_in_mock = pressed.clone();
+        let mut registered = Vec::new();
+        let (tx, rx) = crossbeam::channel::unbounded();
+        tx.send(FileEvent::Modified).unwrap();
+
+        let event_loop = EventLoop::new_with_fallback(
+            Box::new(gamepad),
+            Profile::default_profile(),
+            FallbackProfile { path: PathBuf::from("fallback.toml"), profile: fallback },
+            |_, keys| {
+                registered = keys.to_vec();
+                let mut keyboard = MockVirtualKeyboard::resettable();
+                keyboard.expect_press_key().returning(move |code| {
+                    *pressed_in_mock.lock().unwrap() = Some(code);
+                    Ok(())
+                });
+                Ok(Box::new(keyboard))
+            },
+        )
+        .unwrap()
+        .with_profile_watch(path.clone(), rx);
+        event_loop.run().unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(registered.contains(&KeyboardCode::A));
+        assert_eq!(*pressed.lock().unwrap(), Some(KeyboardCode::A));
+    }
+
+    #[test]
+    fn test_run_for_stops_after_max_events() {
+        use crate::event::{ButtonCode, InputEvent};
+
+        let mut gamepad = MockGamepad::new();
+        gamepad
+            .expect_read_event()
+            .times(3)
+            .returning(|| Ok(Some(InputEvent::button_press(ButtonCode::Mode))));
+
+        let event_loop = EventLoop::new(
+            Box::new(gamepad),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        );
+
+        let monitor = event_loop.health_monitor();
+        assert!(event_loop.run_for(3).is_ok());
+        assert_eq!(monitor.event_count(), 3);
+    }
+
+    #[test]
+    fn test_run_for_does_not_count_sync_events() {
+        use crate::event::{ButtonCode, InputEvent};
+
+        // Each press is followed by the SYN_REPORT ending its evdev report
+        let mut gamepad = MockGamepad::new();
+        let mut reads = 0;
+        gamepad.expect_read_event().times(5).returning(move || {
+            reads += 1;
+            if reads % 2 == 0 {
+                return Ok(Some(InputEvent::sync()));
+            }
+            Ok(Some(InputEvent::button_press(ButtonCode::Mode)))
+        });
+
+        let event_loop = EventLoop::new(
+            Box::new(gamepad),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        );
+
+        let monitor = event_loop.health_monitor();
+        assert!(event_loop.run_for(3).is_ok());
+        assert_eq!(monitor.event_count(), 3);
+    }
+
+    #[test]
+    fn test_stop_interrupts_blocked_read() {
+        use crate::event::{ButtonCode, InputEvent};
+        use std::sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        };
+
+        // The second read is interrupted by the stop signal
+        let stop = Arc::new(AtomicBool::new(false));
+        let signal = stop.clone();
+        let mut gamepad = MockGamepad::new();
+        let mut reads = 0;
+        gamepad.expect_read_event().times(2).returning(move || {
+            reads += 1;
+            if reads == 1 {
+                return Ok(Some(InputEvent::button_press(ButtonCode::Mode)));
+            }
+            signal.store(true, Ordering::SeqCst);
+            Err(anyhow::anyhow!("Failed to read event: Interrupted system call"))
+        });
+
+        let event_loop = EventLoop::new(
+            Box::new(gamepad),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        )
+        .with_stop_when(move || stop.load(Ordering::SeqCst));
+
+        let monitor = event_loop.health_monitor();
+        assert!(event_loop.run().is_ok());
+        assert_eq!(monitor.event_count(), 1);
+    }
+
+    #[test]
+    fn test_event_log_records_unmapped_events() {
+        use crate::event::ButtonCode;
+
+        let path = std::env::temp_dir().join(format!("blazeremap-loop-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut gamepad = MockGamepad::new();
+        gamepad
+            .expect_read_event()
+            .times(2)
+            .returning(|| Ok(Some(InputEvent::button_press(ButtonCode::Mode))));
+
+        let event_loop = EventLoop::new(
+            Box::new(gamepad),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        )
+        .with_event_log(EventLog::open(&path, None).unwrap());
+        event_loop.run_for(2).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_sync_events_are_not_counted() {
+        use crate::event::ButtonCode;
+
+        let mut events = vec![
+            InputEvent::button_press(ButtonCode::Mode),
+            InputEvent::sync(),
+            InputEvent::button_release(ButtonCode::Mode),
+            InputEvent::sync(),
+        ]
+        .into_iter();
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_read_event().returning(move || Ok(events.next()));
+
+        let event_loop = EventLoop::new(
+            Box::new(gamepad),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        );
+
+        let monitor = event_loop.health_monitor();
+        event_loop.run().unwrap();
+        assert_eq!(monitor.event_count(), 2);
+    }
+
+    #[test]
+    fn test_keyboard_reset_when_loop_fails() {
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_read_event().returning(|| Err(anyhow::anyhow!("read failed")));
+
+        let mut keyboard = MockVirtualKeyboard::new();
+        keyboard.expect_reset().times(1).returning(|| Ok(()));
+
+        let event_loop =
+            EventLoop::new(Box::new(gamepad), MappingEngine::new_hardcoded(), Box::new(keyboard));
+
+        assert!(event_loop.run().is_err());
+    }
+
+    #[test]
+    fn test_run_for_zero_reads_nothing() {
+        let mut gamepad = MockGamepad::new();
+        gamepad.expect_read_event().never();
+
+        let event_loop = EventLoop::new(
+            Box::new(gamepad),
+            MappingEngine::new_hardcoded(),
+            Box::new(MockVirtualKeyboard::resettable()),
+        );
+
+        assert!(event_loop.run_for(0).is_ok());
+    }
+}
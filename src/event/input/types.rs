@@ -34,12 +34,17 @@
 
 use std::{
     fmt::{Display, Formatter, Result},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use serde::{Deserialize, Serialize};
 
+/// Stick center and dead zone for devices that report no axis range: ±10
+/// around 128 (DualShock 4 style 0-255 sticks)
+pub const DEFAULT_STICK_DEADZONE: (i32, i32) = (128, 10);
+
 #[derive(Debug, Clone, Copy)] // Copy for performance in event loops
+#[non_exhaustive]
 pub enum InputEvent {
     Button {
         code: ButtonCode,
@@ -74,21 +79,28 @@ impl InputEvent {
         Self::Sync { timestamp: Instant::now() }
     }
 
-    // Method to ignore for Sony DualShock4 analog sticks
-    // implement a dead zone to ignore small movements near center
-    pub fn is_in_deadzone(&self) -> bool {
-        const ANALOG_CENTER: i32 = 128; // For 0-255 range
-        const DEAD_ZONE: i32 = 10; // ±10 from center = ignore
-
+    /// Whether this is a small stick movement near center that should be ignored
+    ///
+    /// `deadzone` is the axis' `(center, flat)`, with `flat` the kernel's dead zone,
+    /// when the device reported a range, else [`DEFAULT_STICK_DEADZONE`] is used.
+    pub fn is_in_deadzone(&self, deadzone: Option<(i32, i32)>) -> bool {
         match self {
             Self::Axis { code, value, .. } => {
                 // Don't apply deadzone to triggers (they have different ranges)
-                if matches!(code, AxisCode::LeftTrigger | AxisCode::RightTrigger) {
+                // or the D-pad (its center is a release). Other hat axes can be
+                // analog, e.g. the Steam Controller's touchpads on Hat 1.
+                if matches!(
+                    code,
+                    AxisCode::LeftTrigger
+                        | AxisCode::RightTrigger
+                        | AxisCode::DPadX
+                        | AxisCode::DPadY
+                ) {
                     return false;
                 }
 
-                let distance_from_center = (value - ANALOG_CENTER).abs();
-                distance_from_center <= DEAD_ZONE
+                let (center, flat) = deadzone.unwrap_or(DEFAULT_STICK_DEADZONE);
+                AxisDirection::from_value(value - center, flat).is_none()
             }
             _ => false, // Only axis events can be in deadzone
         }
@@ -127,6 +139,23 @@ impl InputEvent {
         matches!(self, Self::Axis { .. })
     }
 
+    /// Whether this is a move of a D-pad axis
+    pub fn is_dpad(&self) -> bool {
+        matches!(self, Self::Axis { code, .. } if code.is_dpad())
+    }
+
+    /// The D-pad axis and the direction it is pushed, or `None` when centered
+    /// or not a D-pad event
+    pub fn dpad_direction(&self) -> Option<(AxisCode, AxisDirection)> {
+        match self {
+            Self::Axis { code, value, .. } if code.is_dpad() => {
+                AxisDirection::from_value(*value, DEFAULT_AXIS_THRESHOLD)
+                    .map(|direction| (*code, direction))
+            }
+            _ => None,
+        }
+    }
+
     pub fn timestamp(&self) -> Instant {
         match self {
             Self::Button { timestamp, .. } => *timestamp,
@@ -134,6 +163,28 @@ impl InputEvent {
             Self::Sync { timestamp } => *timestamp,
         }
     }
+
+    /// How long a button was held, with `self` as the release event
+    ///
+    /// Returns zero if `press_event` is not earlier than `self`.
+    pub fn elapsed_since_press(&self, press_event: &InputEvent) -> Duration {
+        self.timestamp().saturating_duration_since(press_event.timestamp())
+    }
+
+    /// Whether this event occurred strictly before `other`
+    pub fn happened_before(&self, other: &InputEvent) -> bool {
+        self.timestamp() < other.timestamp()
+    }
+
+    /// Absolute time between two events in microseconds
+    pub fn delta_us(&self, other: &InputEvent) -> u64 {
+        let delta = if self.happened_before(other) {
+            other.timestamp() - self.timestamp()
+        } else {
+            self.timestamp() - other.timestamp()
+        };
+        delta.as_micros() as u64
+    }
 }
 
 impl Display for InputEvent {
@@ -153,6 +204,7 @@ impl Display for InputEvent {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum ButtonCode {
     South,
     East,
@@ -203,6 +255,124 @@ impl Display for ButtonCode {
     }
 }
 
+impl ButtonCode {
+    /// Every known button, excluding `Unknown`, in declaration order
+    pub const fn all() -> &'static [ButtonCode] {
+        &[
+            Self::South,
+            Self::East,
+            Self::North,
+            Self::West,
+            Self::LeftShoulder,
+            Self::RightShoulder,
+            Self::LeftTrigger,
+            Self::RightTrigger,
+            Self::Select,
+            Self::Start,
+            Self::LeftStick,
+            Self::RightStick,
+            Self::Mode,
+            Self::Misc1,
+            Self::Paddle1,
+            Self::Paddle2,
+            Self::Paddle3,
+            Self::Paddle4,
+            Self::Touchpad,
+        ]
+    }
+
+    /// `South`, `East`, `North` and `West`
+    pub const fn is_face_button(&self) -> bool {
+        matches!(self, Self::South | Self::East | Self::North | Self::West)
+    }
+
+    /// Shoulder buttons and the digital trigger clicks
+    pub const fn is_shoulder(&self) -> bool {
+        matches!(
+            self,
+            Self::LeftShoulder | Self::RightShoulder | Self::LeftTrigger | Self::RightTrigger
+        )
+    }
+
+    /// Stick clicks (L3/R3)
+    pub const fn is_stick(&self) -> bool {
+        matches!(self, Self::LeftStick | Self::RightStick)
+    }
+
+    /// System buttons, back paddles and the touchpad click
+    pub const fn is_misc(&self) -> bool {
+        matches!(
+            self,
+            Self::Select
+                | Self::Start
+                | Self::Mode
+                | Self::Misc1
+                | Self::Paddle1
+                | Self::Paddle2
+                | Self::Paddle3
+                | Self::Paddle4
+                | Self::Touchpad
+        )
+    }
+
+    /// Label printed on Xbox controllers (`A`, `LB`, `View`), for display only
+    ///
+    /// `None` for buttons Xbox controllers don't share a label for, like the paddles.
+    pub const fn to_xbox_name(&self) -> Option<&'static str> {
+        match self {
+            Self::South => Some("A"),
+            Self::East => Some("B"),
+            Self::North => Some("Y"),
+            Self::West => Some("X"),
+            Self::LeftShoulder => Some("LB"),
+            Self::RightShoulder => Some("RB"),
+            Self::LeftTrigger => Some("LT"),
+            Self::RightTrigger => Some("RT"),
+            Self::Select => Some("View"),
+            Self::Start => Some("Menu"),
+            Self::LeftStick => Some("LS"),
+            Self::RightStick => Some("RS"),
+            Self::Mode => Some("Xbox"),
+            Self::Misc1 => Some("Share"),
+            Self::Paddle1
+            | Self::Paddle2
+            | Self::Paddle3
+            | Self::Paddle4
+            | Self::Touchpad
+            | Self::Unknown => None,
+        }
+    }
+
+    /// Label printed on PlayStation controllers (`Cross`, `L1`, `Options`), for
+    /// display only
+    ///
+    /// `None` for buttons PlayStation controllers don't have, like the paddles.
+    pub const fn to_playstation_name(&self) -> Option<&'static str> {
+        match self {
+            Self::South => Some("Cross"),
+            Self::East => Some("Circle"),
+            Self::North => Some("Triangle"),
+            Self::West => Some("Square"),
+            Self::LeftShoulder => Some("L1"),
+            Self::RightShoulder => Some("R1"),
+            Self::LeftTrigger => Some("L2"),
+            Self::RightTrigger => Some("R2"),
+            Self::Select => Some("Share"),
+            Self::Start => Some("Options"),
+            Self::LeftStick => Some("L3"),
+            Self::RightStick => Some("R3"),
+            Self::Mode => Some("PS"),
+            Self::Touchpad => Some("Touchpad"),
+            Self::Misc1
+            | Self::Paddle1
+            | Self::Paddle2
+            | Self::Paddle3
+            | Self::Paddle4
+            | Self::Unknown => None,
+        }
+    }
+}
+
 impl From<&str> for ButtonCode {
     fn from(s: &str) -> Self {
         match s {
@@ -225,12 +395,42 @@ impl From<&str> for ButtonCode {
             "Paddle 3" | "Paddle3" => ButtonCode::Paddle3,
             "Paddle 4" | "Paddle4" => ButtonCode::Paddle4,
             "Touchpad" => ButtonCode::Touchpad,
-            _ => ButtonCode::Unknown,
+            // Raw evdev code, e.g. "304" for BTN_SOUTH
+            _ => s.parse().ok().and_then(ButtonCode::from_raw_code).unwrap_or(ButtonCode::Unknown),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Linux evdev key codes of the buttons the event converter recognizes
+pub const RAW_BUTTON_CODES: &[(u16, ButtonCode)] = &[
+    (0x130, ButtonCode::South),
+    (0x131, ButtonCode::East),
+    (0x133, ButtonCode::North),
+    (0x134, ButtonCode::West),
+    (0x136, ButtonCode::LeftShoulder),
+    (0x137, ButtonCode::RightShoulder),
+    (0x138, ButtonCode::LeftTrigger),
+    (0x139, ButtonCode::RightTrigger),
+    (0x13a, ButtonCode::Select),
+    (0x13b, ButtonCode::Start),
+    (0x13c, ButtonCode::Mode),
+    (0x13d, ButtonCode::LeftStick),
+    (0x13e, ButtonCode::RightStick),
+    (0x2c0, ButtonCode::Paddle1),
+    (0x2c1, ButtonCode::Paddle2),
+    (0x2c2, ButtonCode::Paddle3),
+    (0x2c3, ButtonCode::Paddle4),
+];
+
+impl ButtonCode {
+    /// Button for a raw evdev key code, `None` if it isn't in [`RAW_BUTTON_CODES`]
+    pub fn from_raw_code(code: u16) -> Option<ButtonCode> {
+        RAW_BUTTON_CODES.iter().find(|(raw, _)| *raw == code).map(|(_, button)| *button)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum AxisCode {
     LeftX,
     LeftY,
@@ -240,6 +440,13 @@ pub enum AxisCode {
     RightTrigger,
     DPadX,
     DPadY,
+    /// Secondary POV hats, e.g. on flight sticks
+    Hat1X,
+    Hat1Y,
+    Hat2X,
+    Hat2Y,
+    Hat3X,
+    Hat3Y,
     Unknown,
 }
 
@@ -254,11 +461,73 @@ impl Display for AxisCode {
             Self::RightTrigger => write!(f, "Right Trigger"),
             Self::DPadX => write!(f, "DPad X"),
             Self::DPadY => write!(f, "DPad Y"),
+            Self::Hat1X => write!(f, "Hat 1 X"),
+            Self::Hat1Y => write!(f, "Hat 1 Y"),
+            Self::Hat2X => write!(f, "Hat 2 X"),
+            Self::Hat2Y => write!(f, "Hat 2 Y"),
+            Self::Hat3X => write!(f, "Hat 3 X"),
+            Self::Hat3Y => write!(f, "Hat 3 Y"),
             Self::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
+impl AxisCode {
+    /// Every known axis, excluding `Unknown`, in declaration order
+    pub const fn all() -> &'static [AxisCode] {
+        &[
+            Self::LeftX,
+            Self::LeftY,
+            Self::RightX,
+            Self::RightY,
+            Self::LeftTrigger,
+            Self::RightTrigger,
+            Self::DPadX,
+            Self::DPadY,
+            Self::Hat1X,
+            Self::Hat1Y,
+            Self::Hat2X,
+            Self::Hat2Y,
+            Self::Hat3X,
+            Self::Hat3Y,
+        ]
+    }
+
+    /// D-pad and hat axes report directions (-1, 0, 1) rather than analog positions
+    pub const fn is_dpad(self) -> bool {
+        matches!(
+            self,
+            Self::DPadX
+                | Self::DPadY
+                | Self::Hat1X
+                | Self::Hat1Y
+                | Self::Hat2X
+                | Self::Hat2Y
+                | Self::Hat3X
+                | Self::Hat3Y
+        )
+    }
+
+    /// Value range of this axis on an Xbox-style controller
+    pub const fn standard_range(self) -> (i32, i32) {
+        match self {
+            Self::LeftX | Self::LeftY | Self::RightX | Self::RightY => {
+                (i16::MIN as i32, i16::MAX as i32)
+            }
+            Self::LeftTrigger | Self::RightTrigger => (0, 1023),
+            Self::DPadX
+            | Self::DPadY
+            | Self::Hat1X
+            | Self::Hat1Y
+            | Self::Hat2X
+            | Self::Hat2Y
+            | Self::Hat3X
+            | Self::Hat3Y
+            | Self::Unknown => (-1, 1),
+        }
+    }
+}
+
 impl From<&str> for AxisCode {
     fn from(s: &str) -> Self {
         match s {
@@ -270,17 +539,66 @@ impl From<&str> for AxisCode {
             "RightTrigger" | "Right Trigger" => AxisCode::RightTrigger,
             "DPadX" | "DPad X" => AxisCode::DPadX,
             "DPadY" | "DPad Y" => AxisCode::DPadY,
-            _ => AxisCode::Unknown,
+            "Hat1X" | "Hat 1 X" => AxisCode::Hat1X,
+            "Hat1Y" | "Hat 1 Y" => AxisCode::Hat1Y,
+            "Hat2X" | "Hat 2 X" => AxisCode::Hat2X,
+            "Hat2Y" | "Hat 2 Y" => AxisCode::Hat2Y,
+            "Hat3X" | "Hat 3 X" => AxisCode::Hat3X,
+            "Hat3Y" | "Hat 3 Y" => AxisCode::Hat3Y,
+            // Raw evdev code, e.g. "16" for ABS_HAT0X
+            _ => s.parse().ok().and_then(AxisCode::from_raw_code).unwrap_or(AxisCode::Unknown),
         }
     }
 }
 
+/// Linux evdev absolute axis codes of the axes the event converter recognizes
+pub const RAW_AXIS_CODES: &[(u16, AxisCode)] = &[
+    (0x00, AxisCode::LeftX),
+    (0x01, AxisCode::LeftY),
+    (0x02, AxisCode::LeftTrigger),
+    (0x03, AxisCode::RightX),
+    (0x04, AxisCode::RightY),
+    (0x05, AxisCode::RightTrigger),
+    (0x10, AxisCode::DPadX),
+    (0x11, AxisCode::DPadY),
+    (0x12, AxisCode::Hat1X),
+    (0x13, AxisCode::Hat1Y),
+    (0x14, AxisCode::Hat2X),
+    (0x15, AxisCode::Hat2Y),
+    (0x16, AxisCode::Hat3X),
+    (0x17, AxisCode::Hat3Y),
+];
+
+impl AxisCode {
+    /// Axis for a raw evdev absolute axis code, `None` if it isn't in [`RAW_AXIS_CODES`]
+    pub fn from_raw_code(code: u16) -> Option<AxisCode> {
+        RAW_AXIS_CODES.iter().find(|(raw, _)| *raw == code).map(|(_, axis)| *axis)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum AxisDirection {
     Positive, // Value > 0 (Down, Right)
     Negative, // Value < 0 (Up, Left)
 }
 
+/// Threshold the mapping engine uses for D-pad axes: any non-zero value has a direction
+pub const DEFAULT_AXIS_THRESHOLD: i32 = 0;
+
+impl AxisDirection {
+    /// Direction of an axis value, or `None` if it lies within `threshold` of zero
+    pub fn from_value(value: i32, threshold: i32) -> Option<AxisDirection> {
+        if value > threshold {
+            Some(AxisDirection::Positive)
+        } else if value < -threshold {
+            Some(AxisDirection::Negative)
+        } else {
+            None // Centered/neutral
+        }
+    }
+}
+
 impl Display for AxisDirection {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
@@ -290,6 +608,16 @@ impl Display for AxisDirection {
     }
 }
 
+// Compile-time check that the codes used as `HashMap` keys in the mapping engine
+// stay hashable if their derives are ever edited
+const _: fn() = || {
+    fn assert_hash_key<T: std::hash::Hash + Eq + Copy>() {}
+    assert_hash_key::<ButtonCode>();
+    assert_hash_key::<AxisCode>();
+    assert_hash_key::<AxisDirection>();
+    assert_hash_key::<(AxisCode, AxisDirection)>();
+};
+
 pub fn axis_and_direction_to_string(axis_code: AxisCode, direction: AxisDirection) -> String {
     match axis_code {
         AxisCode::DPadX => match direction {
@@ -300,6 +628,22 @@ pub fn axis_and_direction_to_string(axis_code: AxisCode, direction: AxisDirectio
             AxisDirection::Negative => "DPad Up".to_string(),
             AxisDirection::Positive => "DPad Down".to_string(),
         },
+        AxisCode::Hat1X | AxisCode::Hat2X | AxisCode::Hat3X => {
+            let hat = axis_code.to_string();
+            let hat = hat.trim_end_matches(" X");
+            match direction {
+                AxisDirection::Negative => format!("{} Left", hat),
+                AxisDirection::Positive => format!("{} Right", hat),
+            }
+        }
+        AxisCode::Hat1Y | AxisCode::Hat2Y | AxisCode::Hat3Y => {
+            let hat = axis_code.to_string();
+            let hat = hat.trim_end_matches(" Y");
+            match direction {
+                AxisDirection::Negative => format!("{} Up", hat),
+                AxisDirection::Positive => format!("{} Down", hat),
+            }
+        }
         AxisCode::LeftX | AxisCode::RightX => match direction {
             AxisDirection::Negative => axis_code.to_string() + " Left",
             AxisDirection::Positive => axis_code.to_string() + " Right",
@@ -327,7 +671,7 @@ mod tests {
         assert!(event.is_button_pressed());
         assert!(!event.is_button_released());
         assert!(!event.is_axis_moved());
-        assert!(!event.is_in_deadzone());
+        assert!(!event.is_in_deadzone(None));
     }
 
     #[test]
@@ -424,6 +768,148 @@ mod tests {
         assert_eq!(ButtonCode::RightStick.to_string(), "Right Stick");
     }
 
+    #[test]
+    fn test_button_code_groups() {
+        #[derive(Debug, PartialEq)]
+        enum Group {
+            Face,
+            Shoulder,
+            Stick,
+            Misc,
+            None,
+        }
+
+        fn expected(code: ButtonCode) -> Group {
+            match code {
+                ButtonCode::South | ButtonCode::East | ButtonCode::North | ButtonCode::West => {
+                    Group::Face
+                }
+                ButtonCode::LeftShoulder
+                | ButtonCode::RightShoulder
+                | ButtonCode::LeftTrigger
+                | ButtonCode::RightTrigger => Group::Shoulder,
+                ButtonCode::LeftStick | ButtonCode::RightStick => Group::Stick,
+                ButtonCode::Select
+                | ButtonCode::Start
+                | ButtonCode::Mode
+                | ButtonCode::Misc1
+                | ButtonCode::Paddle1
+                | ButtonCode::Paddle2
+                | ButtonCode::Paddle3
+                | ButtonCode::Paddle4
+                | ButtonCode::Touchpad => Group::Misc,
+                ButtonCode::Unknown => Group::None,
+            }
+        }
+
+        for code in ButtonCode::all().iter().copied().chain([ButtonCode::Unknown]) {
+            let groups = [
+                (code.is_face_button(), Group::Face),
+                (code.is_shoulder(), Group::Shoulder),
+                (code.is_stick(), Group::Stick),
+                (code.is_misc(), Group::Misc),
+            ];
+            let mut matched = groups.into_iter().filter(|(is, _)| *is).map(|(_, g)| g);
+            let actual = matched.next().unwrap_or(Group::None);
+            assert_eq!(actual, expected(code), "{:?}", code);
+            assert!(matched.next().is_none(), "{:?} is in more than one group", code);
+        }
+    }
+
+    #[test]
+    fn test_controller_button_names() {
+        assert_eq!(ButtonCode::South.to_xbox_name(), Some("A"));
+        assert_eq!(ButtonCode::North.to_xbox_name(), Some("Y"));
+        assert_eq!(ButtonCode::Paddle1.to_xbox_name(), None);
+        assert_eq!(ButtonCode::South.to_playstation_name(), Some("Cross"));
+        assert_eq!(ButtonCode::West.to_playstation_name(), Some("Square"));
+        assert_eq!(ButtonCode::Paddle1.to_playstation_name(), None);
+    }
+
+    #[test]
+    fn test_button_code_all() {
+        // Exhaustive match: adding a variant fails to compile until it's listed here
+        fn is_listed(code: ButtonCode) -> bool {
+            match code {
+                ButtonCode::South
+                | ButtonCode::East
+                | ButtonCode::North
+                | ButtonCode::West
+                | ButtonCode::LeftShoulder
+                | ButtonCode::RightShoulder
+                | ButtonCode::LeftTrigger
+                | ButtonCode::RightTrigger
+                | ButtonCode::Select
+                | ButtonCode::Start
+                | ButtonCode::LeftStick
+                | ButtonCode::RightStick
+                | ButtonCode::Mode
+                | ButtonCode::Misc1
+                | ButtonCode::Paddle1
+                | ButtonCode::Paddle2
+                | ButtonCode::Paddle3
+                | ButtonCode::Paddle4
+                | ButtonCode::Touchpad => ButtonCode::all().contains(&code),
+                ButtonCode::Unknown => !ButtonCode::all().contains(&code),
+            }
+        }
+
+        assert_eq!(ButtonCode::all().len(), 19);
+        for code in ButtonCode::all().iter().copied().chain([ButtonCode::Unknown]) {
+            assert!(is_listed(code), "{:?}", code);
+        }
+
+        // Every listed button round-trips through its display name
+        for code in ButtonCode::all() {
+            assert_eq!(ButtonCode::from(code.to_string().as_str()), *code);
+        }
+    }
+
+    #[test]
+    fn test_axis_code_all() {
+        fn is_listed(code: AxisCode) -> bool {
+            match code {
+                AxisCode::LeftX
+                | AxisCode::LeftY
+                | AxisCode::RightX
+                | AxisCode::RightY
+                | AxisCode::LeftTrigger
+                | AxisCode::RightTrigger
+                | AxisCode::DPadX
+                | AxisCode::DPadY
+                | AxisCode::Hat1X
+                | AxisCode::Hat1Y
+                | AxisCode::Hat2X
+                | AxisCode::Hat2Y
+                | AxisCode::Hat3X
+                | AxisCode::Hat3Y => AxisCode::all().contains(&code),
+                AxisCode::Unknown => !AxisCode::all().contains(&code),
+            }
+        }
+
+        assert_eq!(AxisCode::all().len(), 14);
+        for code in AxisCode::all().iter().copied().chain([AxisCode::Unknown]) {
+            assert!(is_listed(code), "{:?}", code);
+        }
+
+        for code in AxisCode::all() {
+            assert_eq!(AxisCode::from(code.to_string().as_str()), *code);
+        }
+    }
+
+    #[test]
+    fn test_codes_from_raw_numbers() {
+        assert_eq!(ButtonCode::from("304"), ButtonCode::South);
+        assert_eq!(ButtonCode::from("704"), ButtonCode::Paddle1);
+        assert_eq!(AxisCode::from("0"), AxisCode::LeftX);
+        assert_eq!(AxisCode::from("17"), AxisCode::DPadY);
+
+        // Numbers without a named variant, and out-of-range numbers
+        assert_eq!(ButtonCode::from("306"), ButtonCode::Unknown);
+        assert_eq!(ButtonCode::from("-304"), ButtonCode::Unknown);
+        assert_eq!(AxisCode::from("70000"), AxisCode::Unknown);
+    }
+
     #[test]
     fn test_axis_code_display() {
         assert_eq!(AxisCode::LeftX.to_string(), "Left X");
@@ -434,6 +920,8 @@ mod tests {
         assert_eq!(AxisCode::RightTrigger.to_string(), "Right Trigger");
         assert_eq!(AxisCode::DPadX.to_string(), "DPad X");
         assert_eq!(AxisCode::DPadY.to_string(), "DPad Y");
+        assert_eq!(AxisCode::Hat1X.to_string(), "Hat 1 X");
+        assert_eq!(AxisCode::Hat3Y.to_string(), "Hat 3 Y");
     }
 
     #[test]
@@ -455,50 +943,106 @@ mod tests {
     fn test_is_in_deadzone() {
         // Test axis events within deadzone
         let center_event = InputEvent::axis_move(AxisCode::LeftX, 128);
-        assert!(center_event.is_in_deadzone());
+        assert!(center_event.is_in_deadzone(None));
 
         let near_center_event = InputEvent::axis_move(AxisCode::LeftX, 125);
-        assert!(near_center_event.is_in_deadzone());
+        assert!(near_center_event.is_in_deadzone(None));
 
         let boundary_low = InputEvent::axis_move(AxisCode::LeftX, 118);
-        assert!(boundary_low.is_in_deadzone());
+        assert!(boundary_low.is_in_deadzone(None));
 
         let boundary_high = InputEvent::axis_move(AxisCode::LeftX, 138);
-        assert!(boundary_high.is_in_deadzone());
+        assert!(boundary_high.is_in_deadzone(None));
 
         // Test axis events outside deadzone
         let outside_low = InputEvent::axis_move(AxisCode::LeftX, 110);
-        assert!(!outside_low.is_in_deadzone());
+        assert!(!outside_low.is_in_deadzone(None));
 
         let outside_high = InputEvent::axis_move(AxisCode::LeftX, 150);
-        assert!(!outside_high.is_in_deadzone());
+        assert!(!outside_high.is_in_deadzone(None));
 
         // Test that triggers are never in deadzone (even at center)
         let trigger_center = InputEvent::axis_move(AxisCode::LeftTrigger, 128);
-        assert!(!trigger_center.is_in_deadzone());
+        assert!(!trigger_center.is_in_deadzone(None));
+
+        // The D-pad never has a deadzone, but a touchpad on Hat 1 does
+        assert!(!InputEvent::axis_move(AxisCode::DPadX, 0).is_in_deadzone(Some((0, 0))));
+        let touchpad = InputEvent::axis_move(AxisCode::Hat1X, 300);
+        assert!(touchpad.is_in_deadzone(Some((0, 500))));
+        assert!(!InputEvent::axis_move(AxisCode::Hat1X, 800).is_in_deadzone(Some((0, 500))));
 
         // Test that non-axis events are not in deadzone
         let button_event = InputEvent::button_press(ButtonCode::South);
-        assert!(!button_event.is_in_deadzone());
+        assert!(!button_event.is_in_deadzone(None));
 
         let sync_event = InputEvent::sync();
-        assert!(!sync_event.is_in_deadzone());
+        assert!(!sync_event.is_in_deadzone(None));
     }
 
     #[test]
     fn test_deadzone_boundary_cases() {
         // Test exact deadzone boundaries (±10 from center)
         let deadzone_min = InputEvent::axis_move(AxisCode::LeftX, 128 - 10);
-        assert!(deadzone_min.is_in_deadzone());
+        assert!(deadzone_min.is_in_deadzone(None));
 
         let deadzone_max = InputEvent::axis_move(AxisCode::LeftX, 128 + 10);
-        assert!(deadzone_max.is_in_deadzone());
+        assert!(deadzone_max.is_in_deadzone(None));
 
         let just_outside_min = InputEvent::axis_move(AxisCode::LeftX, 128 - 11);
-        assert!(!just_outside_min.is_in_deadzone());
+        assert!(!just_outside_min.is_in_deadzone(None));
 
         let just_outside_max = InputEvent::axis_move(AxisCode::LeftX, 128 + 11);
-        assert!(!just_outside_max.is_in_deadzone());
+        assert!(!just_outside_max.is_in_deadzone(None));
+    }
+
+    #[test]
+    fn test_deadzone_from_axis_range() {
+        // Xbox-style stick: centered on 0 with the kernel's flat
+        let xbox = Some((0, 4000));
+        assert!(InputEvent::axis_move(AxisCode::LeftX, 0).is_in_deadzone(xbox));
+        assert!(InputEvent::axis_move(AxisCode::LeftX, -4000).is_in_deadzone(xbox));
+        assert!(!InputEvent::axis_move(AxisCode::LeftX, 4001).is_in_deadzone(xbox));
+        // Without range info 0 is far from the 0-255 center
+        assert!(!InputEvent::axis_move(AxisCode::LeftX, 0).is_in_deadzone(None));
+
+        // D-pad center is a release, never a dead zone
+        assert!(!InputEvent::axis_move(AxisCode::DPadX, 0).is_in_deadzone(Some((0, 0))));
+    }
+
+    #[test]
+    fn test_axis_direction_from_value() {
+        assert_eq!(
+            AxisDirection::from_value(1, DEFAULT_AXIS_THRESHOLD),
+            Some(AxisDirection::Positive)
+        );
+        assert_eq!(
+            AxisDirection::from_value(-1, DEFAULT_AXIS_THRESHOLD),
+            Some(AxisDirection::Negative)
+        );
+        assert_eq!(AxisDirection::from_value(0, DEFAULT_AXIS_THRESHOLD), None);
+
+        // Values up to the threshold are neutral
+        assert_eq!(AxisDirection::from_value(10, 10), None);
+        assert_eq!(AxisDirection::from_value(-10, 10), None);
+        assert_eq!(AxisDirection::from_value(11, 10), Some(AxisDirection::Positive));
+        assert_eq!(AxisDirection::from_value(-11, 10), Some(AxisDirection::Negative));
+    }
+
+    #[test]
+    fn test_dpad_direction() {
+        let up = InputEvent::axis_move(AxisCode::DPadY, -1);
+        assert!(up.is_dpad());
+        assert_eq!(up.dpad_direction(), Some((AxisCode::DPadY, AxisDirection::Negative)));
+
+        let centered = InputEvent::axis_move(AxisCode::DPadX, 0);
+        assert!(centered.is_dpad());
+        assert_eq!(centered.dpad_direction(), None);
+
+        let stick = InputEvent::axis_move(AxisCode::LeftX, 200);
+        assert!(!stick.is_dpad());
+        assert!(InputEvent::axis_move(AxisCode::Hat1Y, 1).is_dpad());
+        assert_eq!(stick.dpad_direction(), None);
+        assert!(!InputEvent::button_press(ButtonCode::South).is_dpad());
     }
 
     #[test]
@@ -523,6 +1067,16 @@ mod tests {
             "DPad Down"
         );
 
+        // Secondary hats
+        assert_eq!(
+            axis_and_direction_to_string(AxisCode::Hat1X, AxisDirection::Negative),
+            "Hat 1 Left"
+        );
+        assert_eq!(
+            axis_and_direction_to_string(AxisCode::Hat2Y, AxisDirection::Positive),
+            "Hat 2 Down"
+        );
+
         // LeftX
         assert_eq!(
             axis_and_direction_to_string(AxisCode::LeftX, AxisDirection::Negative),
@@ -577,4 +1131,31 @@ mod tests {
             "Unknown"
         );
     }
+
+    #[test]
+    fn test_elapsed_since_press() {
+        let start = Instant::now();
+        let press = InputEvent::button_press_at(ButtonCode::South, start);
+        let release =
+            InputEvent::button_release_at(ButtonCode::South, start + Duration::from_millis(250));
+
+        assert_eq!(release.elapsed_since_press(&press), Duration::from_millis(250));
+        // Out-of-order events saturate to zero
+        assert_eq!(press.elapsed_since_press(&release), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_happened_before_and_delta_us() {
+        let start = Instant::now();
+        let first = InputEvent::button_press_at(ButtonCode::South, start);
+        let second =
+            InputEvent::axis_move_at(AxisCode::DPadX, 1, start + Duration::from_micros(1500));
+
+        assert!(first.happened_before(&second));
+        assert!(!second.happened_before(&first));
+        assert!(!first.happened_before(&first));
+
+        assert_eq!(first.delta_us(&second), 1500);
+        assert_eq!(second.delta_us(&first), 1500);
+    }
 }
@@ -0,0 +1,208 @@
+// JSON Lines event log file with size-based rotation
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{InputEvent, InputRecord, OutputEvent, OutputRecord, instant_to_system_time};
+
+/// One line of the log
+#[derive(Debug, Serialize)]
+struct LogRecord {
+    /// Microseconds since the log was opened
+    ts_us: u64,
+    /// Wall-clock time of the event, in microseconds since the Unix epoch
+    time_us: u64,
+    input: InputRecord,
+    outputs: Vec<OutputRecord>,
+}
+
+/// Wall-clock time of the last event in a log written by [`EventLog`]
+///
+/// Returns `None` for a missing or empty log.
+pub fn last_logged_time(path: &Path) -> Result<Option<SystemTime>> {
+    #[derive(Deserialize)]
+    struct Logged {
+        time_us: u64,
+    }
+
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+    let Some(last) = contents.lines().rev().find(|line| !line.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    let logged: Logged = serde_json::from_str(last)
+        .with_context(|| format!("Last line of {} has no event time", path.display()))?;
+    Ok(Some(UNIX_EPOCH + Duration::from_micros(logged.time_us)))
+}
+
+/// Appends every processed event to a file, one JSON line each
+///
+/// Lines are written straight to the file without buffering so the log
+/// survives a crash. With a size limit, a full log is renamed to `<path>.1`
+/// (replacing the previous one) and a new file is started.
+pub struct EventLog {
+    path: PathBuf,
+    file: File,
+    /// Bytes in the current file
+    size: u64,
+    max_size: Option<u64>,
+    started: Instant,
+}
+
+impl EventLog {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: &Path, max_size: Option<u64>) -> Result<Self> {
+        let file = open_append(path)?;
+        let size = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+        Ok(Self { path: path.to_path_buf(), file, size, max_size, started: Instant::now() })
+    }
+
+    /// Path rotated logs are moved to
+    pub fn rotated_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".1");
+        PathBuf::from(path)
+    }
+
+    /// Write one input event and the outputs it produced
+    pub fn write(&mut self, input: &InputEvent, outputs: &[OutputEvent]) -> Result<()> {
+        let time = instant_to_system_time(input.timestamp());
+        let record = LogRecord {
+            ts_us: input.timestamp().saturating_duration_since(self.started).as_micros() as u64,
+            time_us: time.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64,
+            input: InputRecord::from(input),
+            outputs: outputs.iter().map(OutputRecord::from).collect(),
+        };
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+
+        let full = self.max_size.is_some_and(|max| self.size + line.len() as u64 > max);
+        if full && self.size > 0 {
+            self.rotate()?;
+        }
+
+        self.file
+            .write_all(line.as_bytes())
+            .with_context(|| format!("Failed to write to {}", self.path.display()))?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        let rotated = self.rotated_path();
+        std::fs::rename(&self.path, &rotated).with_context(|| {
+            format!("Failed to rotate {} to {}", self.path.display(), rotated.display())
+        })?;
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open event log {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::event::{ButtonCode, KeyboardCode, KeyboardEventType};
+
+    #[derive(Deserialize)]
+    struct Line {
+        input: InputRecord,
+        outputs: Vec<OutputRecord>,
+    }
+
+    fn log_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("blazeremap-{}-{}.log", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn press_s() -> OutputEvent {
+        OutputEvent::Keyboard { code: KeyboardCode::S, event_type: KeyboardEventType::Press }
+    }
+
+    #[test]
+    fn test_write_json_lines() {
+        let path = log_path("events");
+        let mut log = EventLog::open(&path, None).unwrap();
+
+        log.write(&InputEvent::button_press(ButtonCode::South), &[press_s()]).unwrap();
+        log.write(&InputEvent::button_press(ButtonCode::North), &[]).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<Line> =
+            contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].input, InputRecord::Button { code: ButtonCode::South, pressed: true });
+        assert_eq!(lines[0].outputs, vec![OutputRecord::from(&press_s())]);
+        assert!(lines[1].outputs.is_empty());
+    }
+
+    #[test]
+    fn test_last_logged_time() {
+        let path = log_path("last");
+        assert_eq!(last_logged_time(&path).unwrap(), None);
+
+        let mut log = EventLog::open(&path, None).unwrap();
+        let first = InputEvent::button_press(ButtonCode::South);
+        std::thread::sleep(Duration::from_millis(2));
+        let second = InputEvent::button_press(ButtonCode::East);
+        log.write(&first, &[]).unwrap();
+        log.write(&second, &[]).unwrap();
+
+        let last = last_logged_time(&path).unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let expected = instant_to_system_time(second.timestamp());
+        let difference = expected.duration_since(last).unwrap_or_else(|e| e.duration());
+        assert!(difference < Duration::from_micros(1), "{:?}", difference);
+    }
+
+    #[test]
+    fn test_rotates_when_full() {
+        let path = log_path("rotate");
+        // Each line is about 155 bytes, so two fit
+        let mut log = EventLog::open(&path, Some(350)).unwrap();
+        let rotated = log.rotated_path();
+        let _ = std::fs::remove_file(&rotated);
+
+        let event = InputEvent::button_press(ButtonCode::South);
+        for _ in 0..3 {
+            log.write(&event, &[press_s()]).unwrap();
+        }
+
+        let current = std::fs::read_to_string(&path).unwrap();
+        let old = std::fs::read_to_string(&rotated).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+
+        assert!(rotated.to_str().unwrap().ends_with(".log.1"));
+        assert_eq!(old.lines().count(), 2);
+        assert_eq!(current.lines().count(), 1);
+    }
+}
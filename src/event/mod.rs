@@ -3,12 +3,35 @@
 //! Defines event types for gamepad input remapping.
 //! /*
 
+#[cfg(feature = "async")]
+mod async_loop;
+mod file;
+mod filter;
 mod handler;
 mod input;
+mod log;
 mod output;
+mod record;
+mod stats;
+mod stream;
 mod time;
 
-pub use handler::EventLoop;
+#[cfg(feature = "async")]
+pub use async_loop::AsyncEventLoop;
+pub use file::FileEvent;
+pub use filter::{
+    AxisRangeFilter, DeadzoneConfig, DeadzoneFilter, DebounceFilter, EventFilter, LoggingFilter,
+    apply_filters,
+};
+pub use handler::{DEFAULT_KEYBOARD_NAME, EventLoop, FallbackProfile};
 pub use input::types::*;
+pub use log::{EventLog, last_logged_time};
 pub use output::types::*;
+pub use record::{InputRecord, OutputRecord, TimedInputRecord};
+pub use stats::{
+    DEFAULT_LATENCY_CAPACITY, DEFAULT_STATS_INTERVAL, EVENT_RATE_WINDOW, EventRate, HISTOGRAM_BINS,
+    HealthMonitor, HealthStatus, IdleWatchdog, LATENCY_BUDGET_STREAK, LatencyBudget,
+    LatencyTracker, RateHistogram, StatsExporter, StatsExporterHandle, StatsSnapshot,
+};
+pub use stream::EventStream;
 pub use time::*;
@@ -2,12 +2,43 @@ use std::fmt::{Display, Formatter, Result};
 
 use serde::{Deserialize, Serialize};
 
+use crate::event::AxisCode;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OutputEvent {
     Keyboard {
         code: KeyboardCode,
         event_type: KeyboardEventType, // press, release, hold
     },
+    Rumble {
+        weak: u16,
+        strong: u16,
+        duration_ms: u32,
+    },
+    /// Move an axis of the virtual gamepad
+    GamepadAxis {
+        code: AxisCode,
+        value: i32,
+    },
+    /// Press or release a media control key
+    MediaKey {
+        code: MediaKeyCode,
+        event_type: KeyboardEventType,
+    },
+}
+
+impl OutputEvent {
+    /// Short form of the event, e.g. `S (press)`
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Keyboard { code, event_type } => format!("{} ({})", code, event_type),
+            Self::Rumble { weak, strong, duration_ms } => {
+                format!("rumble {}/{} for {}ms", weak, strong, duration_ms)
+            }
+            Self::GamepadAxis { code, value } => format!("gamepad {} = {}", code, value),
+            Self::MediaKey { code, event_type } => format!("media {} ({})", code, event_type),
+        }
+    }
 }
 
 impl Display for OutputEvent {
@@ -16,20 +47,39 @@ impl Display for OutputEvent {
             Self::Keyboard { code, event_type } => {
                 write!(f, "Keyboard: {:?} ({:?})", code, event_type)
             }
+            Self::Rumble { weak, strong, duration_ms } => {
+                write!(f, "Rumble: weak {} strong {} ({}ms)", weak, strong, duration_ms)
+            }
+            Self::GamepadAxis { code, value } => write!(f, "Gamepad axis: {:?} = {}", code, value),
+            Self::MediaKey { code, event_type } => {
+                write!(f, "Media key: {:?} ({:?})", code, event_type)
+            }
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum KeyboardEventType {
     Press,
     Release,
     Hold,
 }
 
+impl Display for KeyboardEventType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Press => write!(f, "press"),
+            Self::Release => write!(f, "release"),
+            Self::Hold => write!(f, "hold"),
+        }
+    }
+}
+
 /// Platform-agnostic keyboard key codes.
 /// These are derived from the `evdev::KeyCode` enum, focusing on standard keyboard keys.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum KeyboardCode {
     Reserved,
     Escape,
@@ -484,238 +534,457 @@ impl Display for KeyboardCode {
     }
 }
 
+/// Lowercase display names for every named [`KeyboardCode`] variant.
+///
+/// This is the lookup table behind [`KeyboardCode::try_from_display`] and the
+/// `From<&str>` conversion used when parsing profile target names.
+pub const KEYBOARD_CODE_NAMES: &[(&str, KeyboardCode)] = &[
+    ("reserved", KeyboardCode::Reserved),
+    ("escape", KeyboardCode::Escape),
+    ("1", KeyboardCode::Num1),
+    ("2", KeyboardCode::Num2),
+    ("3", KeyboardCode::Num3),
+    ("4", KeyboardCode::Num4),
+    ("5", KeyboardCode::Num5),
+    ("6", KeyboardCode::Num6),
+    ("7", KeyboardCode::Num7),
+    ("8", KeyboardCode::Num8),
+    ("9", KeyboardCode::Num9),
+    ("0", KeyboardCode::Num0),
+    ("-", KeyboardCode::Minus),
+    ("=", KeyboardCode::Equal),
+    ("backspace", KeyboardCode::Backspace),
+    ("tab", KeyboardCode::Tab),
+    ("q", KeyboardCode::Q),
+    ("w", KeyboardCode::W),
+    ("e", KeyboardCode::E),
+    ("r", KeyboardCode::R),
+    ("t", KeyboardCode::T),
+    ("y", KeyboardCode::Y),
+    ("u", KeyboardCode::U),
+    ("i", KeyboardCode::I),
+    ("o", KeyboardCode::O),
+    ("p", KeyboardCode::P),
+    ("[", KeyboardCode::LeftBrace),
+    ("]", KeyboardCode::RightBrace),
+    ("enter", KeyboardCode::Enter),
+    ("left control", KeyboardCode::LeftControl),
+    ("a", KeyboardCode::A),
+    ("s", KeyboardCode::S),
+    ("d", KeyboardCode::D),
+    ("f", KeyboardCode::F),
+    ("g", KeyboardCode::G),
+    ("h", KeyboardCode::H),
+    ("j", KeyboardCode::J),
+    ("k", KeyboardCode::K),
+    ("l", KeyboardCode::L),
+    (";", KeyboardCode::Semicolon),
+    ("'", KeyboardCode::Apostrophe),
+    ("`", KeyboardCode::Grave),
+    ("left shift", KeyboardCode::LeftShift),
+    ("\\", KeyboardCode::Backslash),
+    ("z", KeyboardCode::Z),
+    ("x", KeyboardCode::X),
+    ("c", KeyboardCode::C),
+    ("v", KeyboardCode::V),
+    ("b", KeyboardCode::B),
+    ("n", KeyboardCode::N),
+    ("m", KeyboardCode::M),
+    (",", KeyboardCode::Comma),
+    (".", KeyboardCode::Dot),
+    ("/", KeyboardCode::Slash),
+    ("right shift", KeyboardCode::RightShift),
+    ("kp *", KeyboardCode::KpAsterisk),
+    ("left alt", KeyboardCode::LeftAlt),
+    ("space", KeyboardCode::Space),
+    ("caps lock", KeyboardCode::CapsLock),
+    ("f1", KeyboardCode::F1),
+    ("f2", KeyboardCode::F2),
+    ("f3", KeyboardCode::F3),
+    ("f4", KeyboardCode::F4),
+    ("f5", KeyboardCode::F5),
+    ("f6", KeyboardCode::F6),
+    ("f7", KeyboardCode::F7),
+    ("f8", KeyboardCode::F8),
+    ("f9", KeyboardCode::F9),
+    ("f10", KeyboardCode::F10),
+    ("num lock", KeyboardCode::NumLock),
+    ("scroll lock", KeyboardCode::ScrollLock),
+    ("kp 7", KeyboardCode::Kp7),
+    ("kp 8", KeyboardCode::Kp8),
+    ("kp 9", KeyboardCode::Kp9),
+    ("kp -", KeyboardCode::KpMinus),
+    ("kp 4", KeyboardCode::Kp4),
+    ("kp 5", KeyboardCode::Kp5),
+    ("kp 6", KeyboardCode::Kp6),
+    ("kp +", KeyboardCode::KpPlus),
+    ("kp 1", KeyboardCode::Kp1),
+    ("kp 2", KeyboardCode::Kp2),
+    ("kp 3", KeyboardCode::Kp3),
+    ("kp 0", KeyboardCode::Kp0),
+    ("kp .", KeyboardCode::KpDot),
+    ("kp enter", KeyboardCode::KpEnter),
+    ("right control", KeyboardCode::RightControl),
+    ("kp /", KeyboardCode::KpSlash),
+    ("sysrq", KeyboardCode::SysRq),
+    ("right alt", KeyboardCode::RightAlt),
+    ("line feed", KeyboardCode::LineFeed),
+    ("home", KeyboardCode::Home),
+    ("up", KeyboardCode::Up),
+    ("page up", KeyboardCode::PageUp),
+    ("left", KeyboardCode::Left),
+    ("right", KeyboardCode::Right),
+    ("end", KeyboardCode::End),
+    ("down", KeyboardCode::Down),
+    ("page down", KeyboardCode::PageDown),
+    ("insert", KeyboardCode::Insert),
+    ("delete", KeyboardCode::Delete),
+    ("macro", KeyboardCode::Macro),
+    ("mute", KeyboardCode::Mute),
+    ("volume down", KeyboardCode::VolumeDown),
+    ("volume up", KeyboardCode::VolumeUp),
+    ("power", KeyboardCode::Power),
+    ("kp =", KeyboardCode::KpEqual),
+    ("kp +/-", KeyboardCode::KpPlusMinus),
+    ("pause", KeyboardCode::Pause),
+    ("scale", KeyboardCode::Scale),
+    ("kp ,", KeyboardCode::KpComma),
+    ("left meta", KeyboardCode::LeftMeta),
+    ("right meta", KeyboardCode::RightMeta),
+    ("compose", KeyboardCode::Compose),
+    ("stop", KeyboardCode::Stop),
+    ("again", KeyboardCode::Again),
+    ("properties", KeyboardCode::Props),
+    ("undo", KeyboardCode::Undo),
+    ("front", KeyboardCode::Front),
+    ("copy", KeyboardCode::Copy),
+    ("open", KeyboardCode::Open),
+    ("paste", KeyboardCode::Paste),
+    ("find", KeyboardCode::Find),
+    ("cut", KeyboardCode::Cut),
+    ("help", KeyboardCode::Help),
+    ("menu", KeyboardCode::Menu),
+    ("calculator", KeyboardCode::Calc),
+    ("setup", KeyboardCode::Setup),
+    ("sleep", KeyboardCode::Sleep),
+    ("wake up", KeyboardCode::WakeUp),
+    ("file", KeyboardCode::File),
+    ("send file", KeyboardCode::SendFile),
+    ("delete file", KeyboardCode::DeleteFile),
+    ("transfer", KeyboardCode::Xfer),
+    ("program 1", KeyboardCode::Prog1),
+    ("program 2", KeyboardCode::Prog2),
+    ("www", KeyboardCode::Www),
+    ("ms dos", KeyboardCode::Msdos),
+    ("coffee", KeyboardCode::Coffee),
+    ("direction", KeyboardCode::Direction),
+    ("rotate display", KeyboardCode::RotateDisplay),
+    ("cycle windows", KeyboardCode::CycleWindows),
+    ("mail", KeyboardCode::Mail),
+    ("bookmarks", KeyboardCode::Bookmarks),
+    ("computer", KeyboardCode::Computer),
+    ("back", KeyboardCode::Back),
+    ("forward", KeyboardCode::Forward),
+    ("close cd", KeyboardCode::CloseCd),
+    ("eject cd", KeyboardCode::EjectCd),
+    ("eject/close cd", KeyboardCode::EjectCloseCd),
+    ("next song", KeyboardCode::NextSong),
+    ("play/pause", KeyboardCode::PlayPause),
+    ("previous song", KeyboardCode::PreviousSong),
+    ("stop cd", KeyboardCode::StopCd),
+    ("record", KeyboardCode::Record),
+    ("rewind", KeyboardCode::Rewind),
+    ("phone", KeyboardCode::Phone),
+    ("iso", KeyboardCode::Iso),
+    ("configuration", KeyboardCode::Config),
+    ("home page", KeyboardCode::HomePage),
+    ("refresh", KeyboardCode::Refresh),
+    ("exit", KeyboardCode::Exit),
+    ("move", KeyboardCode::Move),
+    ("edit", KeyboardCode::Edit),
+    ("scroll up", KeyboardCode::ScrollUp),
+    ("scroll down", KeyboardCode::ScrollDown),
+    ("kp (", KeyboardCode::KpLeftParen),
+    ("kp )", KeyboardCode::KpRightParen),
+    ("new", KeyboardCode::New),
+    ("redo", KeyboardCode::Redo),
+    ("f13", KeyboardCode::F13),
+    ("f14", KeyboardCode::F14),
+    ("f15", KeyboardCode::F15),
+    ("f16", KeyboardCode::F16),
+    ("f17", KeyboardCode::F17),
+    ("f18", KeyboardCode::F18),
+    ("f19", KeyboardCode::F19),
+    ("f20", KeyboardCode::F20),
+    ("f21", KeyboardCode::F21),
+    ("f22", KeyboardCode::F22),
+    ("f23", KeyboardCode::F23),
+    ("f24", KeyboardCode::F24),
+    ("play cd", KeyboardCode::PlayCd),
+    ("pause cd", KeyboardCode::PauseCd),
+    ("program 3", KeyboardCode::Prog3),
+    ("program 4", KeyboardCode::Prog4),
+    ("dashboard", KeyboardCode::Dashboard),
+    ("suspend", KeyboardCode::Suspend),
+    ("close", KeyboardCode::Close),
+    ("play", KeyboardCode::Play),
+    ("fast forward", KeyboardCode::FastForward),
+    ("bass boost", KeyboardCode::BassBoost),
+    ("print", KeyboardCode::Print),
+    ("hp", KeyboardCode::Hp),
+    ("camera", KeyboardCode::Camera),
+    ("sound", KeyboardCode::Sound),
+    ("question", KeyboardCode::Question),
+    ("email", KeyboardCode::Email),
+    ("chat", KeyboardCode::Chat),
+    ("search", KeyboardCode::Search),
+    ("connect", KeyboardCode::Connect),
+    ("finance", KeyboardCode::Finance),
+    ("sport", KeyboardCode::Sport),
+    ("shop", KeyboardCode::Shop),
+    ("alternate erase", KeyboardCode::AlterErase),
+    ("cancel", KeyboardCode::Cancel),
+    ("brightness down", KeyboardCode::BrightnessDown),
+    ("brightness up", KeyboardCode::BrightnessUp),
+    ("media", KeyboardCode::Media),
+    ("switch video mode", KeyboardCode::SwitchVideoMode),
+    ("keyboard illumination toggle", KeyboardCode::KbdIllumToggle),
+    ("keyboard illumination down", KeyboardCode::KbdIllumDown),
+    ("keyboard illumination up", KeyboardCode::KbdIllumUp),
+    ("send", KeyboardCode::Send),
+    ("reply", KeyboardCode::Reply),
+    ("forward mail", KeyboardCode::ForwardMail),
+    ("save", KeyboardCode::Save),
+    ("documents", KeyboardCode::Documents),
+    ("battery", KeyboardCode::Battery),
+    ("bluetooth", KeyboardCode::Bluetooth),
+    ("wlan", KeyboardCode::Wlan),
+    ("uwb", KeyboardCode::Uwb),
+];
+
+/// Key and shift state that type `c` on a US QWERTY layout
+///
+/// Returns `None` for characters with no single-key equivalent.
+pub fn char_to_key_code(c: char) -> Option<(KeyboardCode, bool)> {
+    use KeyboardCode::*;
+
+    let letters = [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z];
+    let digits = [Num0, Num1, Num2, Num3, Num4, Num5, Num6, Num7, Num8, Num9];
+
+    let key = match c {
+        'a'..='z' => (letters[c as usize - 'a' as usize], false),
+        'A'..='Z' => (letters[c as usize - 'A' as usize], true),
+        '0'..='9' => (digits[c as usize - '0' as usize], false),
+        ' ' => (Space, false),
+        '\n' => (Enter, false),
+        '\t' => (Tab, false),
+        '-' => (Minus, false),
+        '_' => (Minus, true),
+        '=' => (Equal, false),
+        '+' => (Equal, true),
+        '[' => (LeftBrace, false),
+        '{' => (LeftBrace, true),
+        ']' => (RightBrace, false),
+        '}' => (RightBrace, true),
+        ';' => (Semicolon, false),
+        ':' => (Semicolon, true),
+        '\'' => (Apostrophe, false),
+        '"' => (Apostrophe, true),
+        '`' => (Grave, false),
+        '~' => (Grave, true),
+        '\\' => (Backslash, false),
+        '|' => (Backslash, true),
+        ',' => (Comma, false),
+        '<' => (Comma, true),
+        '.' => (Dot, false),
+        '>' => (Dot, true),
+        '/' => (Slash, false),
+        '?' => (Slash, true),
+        '!' => (Num1, true),
+        '@' => (Num2, true),
+        '#' => (Num3, true),
+        '$' => (Num4, true),
+        '%' => (Num5, true),
+        '^' => (Num6, true),
+        '&' => (Num7, true),
+        '*' => (Num8, true),
+        '(' => (Num9, true),
+        ')' => (Num0, true),
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Normalize a key name for comparison: lowercase, without spaces or underscores
+fn normalize_key_name(name: &str) -> String {
+    name.chars().filter(|c| !c.is_whitespace() && *c != '_').flat_map(char::to_lowercase).collect()
+}
+
+impl KeyboardCode {
+    /// Shift, Control, Alt or Meta on either side
+    pub const fn is_modifier(self) -> bool {
+        matches!(
+            self,
+            Self::LeftShift
+                | Self::RightShift
+                | Self::LeftControl
+                | Self::RightControl
+                | Self::LeftAlt
+                | Self::RightAlt
+                | Self::LeftMeta
+                | Self::RightMeta
+        )
+    }
+
+    /// Modifier that must be held to type `c` on a US layout, e.g. Left Shift for `'A'`
+    ///
+    /// `None` for characters typed without a modifier and for characters with no key.
+    pub fn modifier_for(c: char) -> Option<KeyboardCode> {
+        match char_to_key_code(c) {
+            Some((_, true)) => Some(Self::LeftShift),
+            _ => None,
+        }
+    }
+
+    /// Case-insensitive lookup of a key by its display name or variant name
+    ///
+    /// Spaces and underscores are ignored, so `"left_shift"`, `"LeftShift"`,
+    /// `"LEFTSHIFT"` and `"left shift"` all resolve to [`KeyboardCode::LeftShift`].
+    /// Returns `None` when the name does not match any known key.
+    pub fn try_from_display(s: &str) -> Option<Self> {
+        let needle = normalize_key_name(s);
+        if needle.is_empty() {
+            return None;
+        }
+
+        KEYBOARD_CODE_NAMES
+            .iter()
+            .find(|(name, code)| {
+                normalize_key_name(name) == needle
+                    || normalize_key_name(&format!("{:?}", code)) == needle
+            })
+            .map(|(_, code)| *code)
+    }
+}
+
 impl From<&str> for KeyboardCode {
     fn from(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "reserved" => KeyboardCode::Reserved,
-            "escape" => KeyboardCode::Escape,
-            "1" => KeyboardCode::Num1,
-            "2" => KeyboardCode::Num2,
-            "3" => KeyboardCode::Num3,
-            "4" => KeyboardCode::Num4,
-            "5" => KeyboardCode::Num5,
-            "6" => KeyboardCode::Num6,
-            "7" => KeyboardCode::Num7,
-            "8" => KeyboardCode::Num8,
-            "9" => KeyboardCode::Num9,
-            "0" => KeyboardCode::Num0,
-            "-" => KeyboardCode::Minus,
-            "=" => KeyboardCode::Equal,
-            "backspace" => KeyboardCode::Backspace,
-            "tab" => KeyboardCode::Tab,
-            "q" => KeyboardCode::Q,
-            "w" => KeyboardCode::W,
-            "e" => KeyboardCode::E,
-            "r" => KeyboardCode::R,
-            "t" => KeyboardCode::T,
-            "y" => KeyboardCode::Y,
-            "u" => KeyboardCode::U,
-            "i" => KeyboardCode::I,
-            "o" => KeyboardCode::O,
-            "p" => KeyboardCode::P,
-            "[" => KeyboardCode::LeftBrace,
-            "]" => KeyboardCode::RightBrace,
-            "enter" => KeyboardCode::Enter,
-            "left control" => KeyboardCode::LeftControl,
-            "a" => KeyboardCode::A,
-            "s" => KeyboardCode::S,
-            "d" => KeyboardCode::D,
-            "f" => KeyboardCode::F,
-            "g" => KeyboardCode::G,
-            "h" => KeyboardCode::H,
-            "j" => KeyboardCode::J,
-            "k" => KeyboardCode::K,
-            "l" => KeyboardCode::L,
-            ";" => KeyboardCode::Semicolon,
-            "'" => KeyboardCode::Apostrophe,
-            "`" => KeyboardCode::Grave,
-            "left shift" => KeyboardCode::LeftShift,
-            "\\" => KeyboardCode::Backslash,
-            "z" => KeyboardCode::Z,
-            "x" => KeyboardCode::X,
-            "c" => KeyboardCode::C,
-            "v" => KeyboardCode::V,
-            "b" => KeyboardCode::B,
-            "n" => KeyboardCode::N,
-            "m" => KeyboardCode::M,
-            "," => KeyboardCode::Comma,
-            "." => KeyboardCode::Dot,
-            "/" => KeyboardCode::Slash,
-            "right shift" => KeyboardCode::RightShift,
-            "kp *" => KeyboardCode::KpAsterisk,
-            "left alt" => KeyboardCode::LeftAlt,
-            "space" => KeyboardCode::Space,
-            "caps lock" => KeyboardCode::CapsLock,
-            "f1" => KeyboardCode::F1,
-            "f2" => KeyboardCode::F2,
-            "f3" => KeyboardCode::F3,
-            "f4" => KeyboardCode::F4,
-            "f5" => KeyboardCode::F5,
-            "f6" => KeyboardCode::F6,
-            "f7" => KeyboardCode::F7,
-            "f8" => KeyboardCode::F8,
-            "f9" => KeyboardCode::F9,
-            "f10" => KeyboardCode::F10,
-            "num lock" => KeyboardCode::NumLock,
-            "scroll lock" => KeyboardCode::ScrollLock,
-            "kp 7" => KeyboardCode::Kp7,
-            "kp 8" => KeyboardCode::Kp8,
-            "kp 9" => KeyboardCode::Kp9,
-            "kp -" => KeyboardCode::KpMinus,
-            "kp 4" => KeyboardCode::Kp4,
-            "kp 5" => KeyboardCode::Kp5,
-            "kp 6" => KeyboardCode::Kp6,
-            "kp +" => KeyboardCode::KpPlus,
-            "kp 1" => KeyboardCode::Kp1,
-            "kp 2" => KeyboardCode::Kp2,
-            "kp 3" => KeyboardCode::Kp3,
-            "kp 0" => KeyboardCode::Kp0,
-            "kp ." => KeyboardCode::KpDot,
-            "kp enter" => KeyboardCode::KpEnter,
-            "right control" => KeyboardCode::RightControl,
-            "kp /" => KeyboardCode::KpSlash,
-            "sysrq" => KeyboardCode::SysRq,
-            "right alt" => KeyboardCode::RightAlt,
-            "line feed" => KeyboardCode::LineFeed,
-            "home" => KeyboardCode::Home,
-            "up" => KeyboardCode::Up,
-            "page up" => KeyboardCode::PageUp,
-            "left" => KeyboardCode::Left,
-            "right" => KeyboardCode::Right,
-            "end" => KeyboardCode::End,
-            "down" => KeyboardCode::Down,
-            "page down" => KeyboardCode::PageDown,
-            "insert" => KeyboardCode::Insert,
-            "delete" => KeyboardCode::Delete,
-            "macro" => KeyboardCode::Macro,
-            "mute" => KeyboardCode::Mute,
-            "volume down" => KeyboardCode::VolumeDown,
-            "volume up" => KeyboardCode::VolumeUp,
-            "power" => KeyboardCode::Power,
-            "kp =" => KeyboardCode::KpEqual,
-            "kp +/-" => KeyboardCode::KpPlusMinus,
-            "pause" => KeyboardCode::Pause,
-            "scale" => KeyboardCode::Scale,
-            "kp ," => KeyboardCode::KpComma,
-            "left meta" => KeyboardCode::LeftMeta,
-            "right meta" => KeyboardCode::RightMeta,
-            "compose" => KeyboardCode::Compose,
-            "stop" => KeyboardCode::Stop,
-            "again" => KeyboardCode::Again,
-            "properties" => KeyboardCode::Props,
-            "undo" => KeyboardCode::Undo,
-            "front" => KeyboardCode::Front,
-            "copy" => KeyboardCode::Copy,
-            "open" => KeyboardCode::Open,
-            "paste" => KeyboardCode::Paste,
-            "find" => KeyboardCode::Find,
-            "cut" => KeyboardCode::Cut,
-            "help" => KeyboardCode::Help,
-            "menu" => KeyboardCode::Menu,
-            "calculator" => KeyboardCode::Calc,
-            "setup" => KeyboardCode::Setup,
-            "sleep" => KeyboardCode::Sleep,
-            "wake up" => KeyboardCode::WakeUp,
-            "file" => KeyboardCode::File,
-            "send file" => KeyboardCode::SendFile,
-            "delete file" => KeyboardCode::DeleteFile,
-            "transfer" => KeyboardCode::Xfer,
-            "program 1" => KeyboardCode::Prog1,
-            "program 2" => KeyboardCode::Prog2,
-            "www" => KeyboardCode::Www,
-            "ms dos" => KeyboardCode::Msdos,
-            "coffee" => KeyboardCode::Coffee,
-            "direction" => KeyboardCode::Direction,
-            "rotate display" => KeyboardCode::RotateDisplay,
-            "cycle windows" => KeyboardCode::CycleWindows,
-            "mail" => KeyboardCode::Mail,
-            "bookmarks" => KeyboardCode::Bookmarks,
-            "computer" => KeyboardCode::Computer,
-            "back" => KeyboardCode::Back,
-            "forward" => KeyboardCode::Forward,
-            "close cd" => KeyboardCode::CloseCd,
-            "eject cd" => KeyboardCode::EjectCd,
-            "eject/close cd" => KeyboardCode::EjectCloseCd,
-            "next song" => KeyboardCode::NextSong,
-            "play/pause" => KeyboardCode::PlayPause,
-            "previous song" => KeyboardCode::PreviousSong,
-            "stop cd" => KeyboardCode::StopCd,
-            "record" => KeyboardCode::Record,
-            "rewind" => KeyboardCode::Rewind,
-            "phone" => KeyboardCode::Phone,
-            "iso" => KeyboardCode::Iso,
-            "configuration" => KeyboardCode::Config,
-            "home page" => KeyboardCode::HomePage,
-            "refresh" => KeyboardCode::Refresh,
-            "exit" => KeyboardCode::Exit,
-            "move" => KeyboardCode::Move,
-            "edit" => KeyboardCode::Edit,
-            "scroll up" => KeyboardCode::ScrollUp,
-            "scroll down" => KeyboardCode::ScrollDown,
-            "kp (" => KeyboardCode::KpLeftParen,
-            "kp )" => KeyboardCode::KpRightParen,
-            "new" => KeyboardCode::New,
-            "redo" => KeyboardCode::Redo,
-            "f13" => KeyboardCode::F13,
-            "f14" => KeyboardCode::F14,
-            "f15" => KeyboardCode::F15,
-            "f16" => KeyboardCode::F16,
-            "f17" => KeyboardCode::F17,
-            "f18" => KeyboardCode::F18,
-            "f19" => KeyboardCode::F19,
-            "f20" => KeyboardCode::F20,
-            "f21" => KeyboardCode::F21,
-            "f22" => KeyboardCode::F22,
-            "f23" => KeyboardCode::F23,
-            "f24" => KeyboardCode::F24,
-            "play cd" => KeyboardCode::PlayCd,
-            "pause cd" => KeyboardCode::PauseCd,
-            "program 3" => KeyboardCode::Prog3,
-            "program 4" => KeyboardCode::Prog4,
-            "dashboard" => KeyboardCode::Dashboard,
-            "suspend" => KeyboardCode::Suspend,
-            "close" => KeyboardCode::Close,
-            "play" => KeyboardCode::Play,
-            "fast forward" => KeyboardCode::FastForward,
-            "bass boost" => KeyboardCode::BassBoost,
-            "print" => KeyboardCode::Print,
-            "hp" => KeyboardCode::Hp,
-            "camera" => KeyboardCode::Camera,
-            "sound" => KeyboardCode::Sound,
-            "question" => KeyboardCode::Question,
-            "email" => KeyboardCode::Email,
-            "chat" => KeyboardCode::Chat,
-            "search" => KeyboardCode::Search,
-            "connect" => KeyboardCode::Connect,
-            "finance" => KeyboardCode::Finance,
-            "sport" => KeyboardCode::Sport,
-            "shop" => KeyboardCode::Shop,
-            "alternate erase" => KeyboardCode::AlterErase,
-            "cancel" => KeyboardCode::Cancel,
-            "brightness down" => KeyboardCode::BrightnessDown,
-            "brightness up" => KeyboardCode::BrightnessUp,
-            "media" => KeyboardCode::Media,
-            "switch video mode" => KeyboardCode::SwitchVideoMode,
-            "keyboard illumination toggle" => KeyboardCode::KbdIllumToggle,
-            "keyboard illumination down" => KeyboardCode::KbdIllumDown,
-            "keyboard illumination up" => KeyboardCode::KbdIllumUp,
-            "send" => KeyboardCode::Send,
-            "reply" => KeyboardCode::Reply,
-            "forward mail" => KeyboardCode::ForwardMail,
-            "save" => KeyboardCode::Save,
-            "documents" => KeyboardCode::Documents,
-            "battery" => KeyboardCode::Battery,
-            "bluetooth" => KeyboardCode::Bluetooth,
-            "wlan" => KeyboardCode::Wlan,
-            "uwb" => KeyboardCode::Uwb,
-            _ => KeyboardCode::Unknown,
+        KeyboardCode::try_from_display(s).unwrap_or(KeyboardCode::Unknown)
+    }
+}
+
+/// Media controls a gamepad button can trigger, e.g. play/pause from the couch
+///
+/// Each one is sent as the matching [`KeyboardCode`] media key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MediaKeyCode {
+    PlayPause,
+    VolumeUp,
+    VolumeDown,
+    Mute,
+    NextTrack,
+    PreviousTrack,
+    Stop,
+}
+
+impl Display for MediaKeyCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::PlayPause => write!(f, "Play/Pause"),
+            Self::VolumeUp => write!(f, "Volume Up"),
+            Self::VolumeDown => write!(f, "Volume Down"),
+            Self::Mute => write!(f, "Mute"),
+            Self::NextTrack => write!(f, "Next Track"),
+            Self::PreviousTrack => write!(f, "Previous Track"),
+            Self::Stop => write!(f, "Stop"),
         }
     }
 }
 
+impl MediaKeyCode {
+    /// Every media key, in declaration order
+    pub const fn all() -> &'static [MediaKeyCode] {
+        &[
+            Self::PlayPause,
+            Self::VolumeUp,
+            Self::VolumeDown,
+            Self::Mute,
+            Self::NextTrack,
+            Self::PreviousTrack,
+            Self::Stop,
+        ]
+    }
+
+    /// The keyboard key sent for this media key
+    pub const fn keyboard_code(self) -> KeyboardCode {
+        match self {
+            Self::PlayPause => KeyboardCode::PlayPause,
+            Self::VolumeUp => KeyboardCode::VolumeUp,
+            Self::VolumeDown => KeyboardCode::VolumeDown,
+            Self::Mute => KeyboardCode::Mute,
+            Self::NextTrack => KeyboardCode::NextSong,
+            Self::PreviousTrack => KeyboardCode::PreviousSong,
+            // KEY_STOPCD is what media players listen for; KEY_STOP is "cancel"
+            Self::Stop => KeyboardCode::StopCd,
+        }
+    }
+
+    /// Case-insensitive lookup by display name or variant name, ignoring spaces
+    /// and underscores (`"play/pause"`, `"PlayPause"`, `"next_track"`)
+    pub fn try_from_display(s: &str) -> Option<Self> {
+        let needle = normalize_key_name(s);
+        Self::all().iter().copied().find(|code| {
+            normalize_key_name(&code.to_string()) == needle
+                || normalize_key_name(&format!("{:?}", code)) == needle
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::KeyboardCode;
+    use super::{KEYBOARD_CODE_NAMES, KeyboardCode, MediaKeyCode, char_to_key_code};
+
+    #[test]
+    fn test_is_modifier() {
+        let modifiers: Vec<KeyboardCode> =
+            KEYBOARD_CODE_NAMES.iter().map(|(_, code)| *code).filter(|c| c.is_modifier()).collect();
+
+        use KeyboardCode::*;
+        assert_eq!(
+            modifiers,
+            vec![
+                LeftControl,
+                LeftShift,
+                RightShift,
+                LeftAlt,
+                RightControl,
+                RightAlt,
+                LeftMeta,
+                RightMeta
+            ]
+        );
+    }
+
+    #[test]
+    fn test_char_to_key_code() {
+        assert_eq!(char_to_key_code('a'), Some((KeyboardCode::A, false)));
+        assert_eq!(char_to_key_code('Z'), Some((KeyboardCode::Z, true)));
+        assert_eq!(char_to_key_code('0'), Some((KeyboardCode::Num0, false)));
+        assert_eq!(char_to_key_code('!'), Some((KeyboardCode::Num1, true)));
+        assert_eq!(char_to_key_code(' '), Some((KeyboardCode::Space, false)));
+        assert_eq!(char_to_key_code('\n'), Some((KeyboardCode::Enter, false)));
+        assert_eq!(char_to_key_code('?'), Some((KeyboardCode::Slash, true)));
+        assert_eq!(char_to_key_code('é'), None);
+    }
+
+    #[test]
+    fn test_modifier_for() {
+        assert_eq!(KeyboardCode::modifier_for('A'), Some(KeyboardCode::LeftShift));
+        assert_eq!(KeyboardCode::modifier_for('?'), Some(KeyboardCode::LeftShift));
+        assert_eq!(KeyboardCode::modifier_for('a'), None);
+        assert_eq!(KeyboardCode::modifier_for('/'), None);
+        assert_eq!(KeyboardCode::modifier_for('é'), None);
+    }
 
     #[test]
     fn test_from_str_for_keyboard_code() {
@@ -810,4 +1079,45 @@ mod tests {
         assert_eq!(KeyboardCode::from(""), KeyboardCode::Unknown); // Empty string
         assert_eq!(KeyboardCode::from("unknown"), KeyboardCode::Unknown); // The explicit Unknown variant
     }
+
+    #[test]
+    fn test_try_from_display_normalizes_case_and_separators() {
+        for name in ["left_shift", "LeftShift", "LEFTSHIFT", "left shift", "Left Shift"] {
+            assert_eq!(KeyboardCode::try_from_display(name), Some(KeyboardCode::LeftShift));
+        }
+
+        assert_eq!(KeyboardCode::try_from_display("space"), Some(KeyboardCode::Space));
+        assert_eq!(KeyboardCode::try_from_display("SPACE"), Some(KeyboardCode::Space));
+        assert_eq!(KeyboardCode::try_from_display("KpEnter"), Some(KeyboardCode::KpEnter));
+        assert_eq!(KeyboardCode::try_from_display("kp_enter"), Some(KeyboardCode::KpEnter));
+        assert_eq!(KeyboardCode::try_from_display("Num1"), Some(KeyboardCode::Num1));
+    }
+
+    #[test]
+    fn test_try_from_display_unknown_names() {
+        assert_eq!(KeyboardCode::try_from_display(""), None);
+        assert_eq!(KeyboardCode::try_from_display("   "), None);
+        assert_eq!(KeyboardCode::try_from_display("nonexistent"), None);
+        assert_eq!(KeyboardCode::try_from_display("unknown"), None);
+    }
+
+    #[test]
+    fn test_keyboard_code_names_round_trip() {
+        for (name, code) in KEYBOARD_CODE_NAMES {
+            assert_eq!(code.to_string().to_lowercase(), *name);
+            assert_eq!(KeyboardCode::try_from_display(name), Some(*code));
+            assert_eq!(KeyboardCode::try_from_display(&format!("{:?}", code)), Some(*code));
+        }
+    }
+
+    #[test]
+    fn test_media_key_lookup() {
+        assert_eq!(MediaKeyCode::try_from_display("play/pause"), Some(MediaKeyCode::PlayPause));
+        assert_eq!(MediaKeyCode::try_from_display("next_track"), Some(MediaKeyCode::NextTrack));
+        assert_eq!(MediaKeyCode::try_from_display("VolumeUp"), Some(MediaKeyCode::VolumeUp));
+        assert_eq!(MediaKeyCode::try_from_display("Space"), None);
+
+        assert_eq!(MediaKeyCode::NextTrack.keyboard_code(), KeyboardCode::NextSong);
+        assert_eq!(MediaKeyCode::Stop.keyboard_code(), KeyboardCode::StopCd);
+    }
 }
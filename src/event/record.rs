@@ -0,0 +1,205 @@
+// JSON forms of input and output events, for event logs, profile tests and recordings
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AxisCode, ButtonCode, InputEvent, KeyboardCode, KeyboardEventType, MediaKeyCode, OutputEvent,
+};
+
+/// An [`InputEvent`] without its timestamp, e.g. `{"type": "button", "code": "South", "pressed": true}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputRecord {
+    Button { code: ButtonCode, pressed: bool },
+    Axis { code: AxisCode, value: i32 },
+    Sync,
+}
+
+impl InputRecord {
+    /// The event, timestamped now
+    pub fn to_event(self) -> InputEvent {
+        match self {
+            Self::Button { code, pressed: true } => InputEvent::button_press(code),
+            Self::Button { code, pressed: false } => InputEvent::button_release(code),
+            Self::Axis { code, value } => InputEvent::axis_move(code, value),
+            Self::Sync => InputEvent::sync(),
+        }
+    }
+}
+
+impl From<&InputEvent> for InputRecord {
+    fn from(event: &InputEvent) -> Self {
+        match *event {
+            InputEvent::Button { code, pressed, .. } => Self::Button { code, pressed },
+            InputEvent::Axis { code, value, .. } => Self::Axis { code, value },
+            InputEvent::Sync { .. } => Self::Sync,
+        }
+    }
+}
+
+/// An [`InputEvent`] with its timestamp as microseconds since the start of a
+/// recording, e.g. `{"type": "axis", "code": "LeftX", "value": -300, "timestamp_us": 1500}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimedInputRecord {
+    #[serde(flatten)]
+    pub event: InputRecord,
+    pub timestamp_us: u64,
+}
+
+impl TimedInputRecord {
+    /// The record of `event`, timed relative to `base` (events before it are at 0)
+    pub fn new(event: &InputEvent, base: Instant) -> Self {
+        let timestamp_us = event.timestamp().saturating_duration_since(base).as_micros() as u64;
+        Self { event: InputRecord::from(event), timestamp_us }
+    }
+
+    /// The event, timestamped `timestamp_us` after `base`
+    pub fn to_event(self, base: Instant) -> InputEvent {
+        let timestamp = base + Duration::from_micros(self.timestamp_us);
+        match self.event {
+            InputRecord::Button { code, pressed } => {
+                InputEvent::Button { code, pressed, timestamp }
+            }
+            InputRecord::Axis { code, value } => InputEvent::Axis { code, value, timestamp },
+            InputRecord::Sync => InputEvent::Sync { timestamp },
+        }
+    }
+}
+
+impl InputEvent {
+    /// One-line JSON form of the event, timed relative to `base`
+    pub fn to_json(&self, base: Instant) -> Result<String> {
+        serde_json::to_string(&TimedInputRecord::new(self, base))
+            .context("Failed to serialize input event")
+    }
+
+    /// Parse an event written by [`InputEvent::to_json`], timed relative to `base`
+    pub fn from_json(json: &str, base: Instant) -> Result<Self> {
+        let record: TimedInputRecord =
+            serde_json::from_str(json).with_context(|| format!("Invalid input event: {}", json))?;
+        Ok(record.to_event(base))
+    }
+}
+
+/// An [`OutputEvent`], e.g. `{"type": "keyboard", "code": "S", "event_type": "press"}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputRecord {
+    Keyboard { code: KeyboardCode, event_type: KeyboardEventType },
+    Rumble { weak: u16, strong: u16, duration_ms: u32 },
+    GamepadAxis { code: AxisCode, value: i32 },
+    MediaKey { code: MediaKeyCode, event_type: KeyboardEventType },
+}
+
+impl From<&OutputEvent> for OutputRecord {
+    fn from(event: &OutputEvent) -> Self {
+        match *event {
+            OutputEvent::Keyboard { code, event_type } => Self::Keyboard { code, event_type },
+            OutputEvent::Rumble { weak, strong, duration_ms } => {
+                Self::Rumble { weak, strong, duration_ms }
+            }
+            OutputEvent::GamepadAxis { code, value } => Self::GamepadAxis { code, value },
+            OutputEvent::MediaKey { code, event_type } => Self::MediaKey { code, event_type },
+        }
+    }
+}
+
+impl From<OutputRecord> for OutputEvent {
+    fn from(record: OutputRecord) -> Self {
+        match record {
+            OutputRecord::Keyboard { code, event_type } => Self::Keyboard { code, event_type },
+            OutputRecord::Rumble { weak, strong, duration_ms } => {
+                Self::Rumble { weak, strong, duration_ms }
+            }
+            OutputRecord::GamepadAxis { code, value } => Self::GamepadAxis { code, value },
+            OutputRecord::MediaKey { code, event_type } => Self::MediaKey { code, event_type },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_record_round_trip() {
+        let event = InputEvent::axis_move(AxisCode::DPadY, -1);
+        let json = serde_json::to_string(&InputRecord::from(&event)).unwrap();
+
+        let record: InputRecord = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(record, InputRecord::Axis { code: AxisCode::DPadY, value: -1 });
+        assert_eq!(record.to_event().to_string(), event.to_string());
+    }
+
+    #[test]
+    fn test_input_event_json_round_trip() {
+        let base = Instant::now();
+        let events = [
+            InputEvent::Button {
+                code: ButtonCode::South,
+                pressed: true,
+                timestamp: base + Duration::from_micros(1500),
+            },
+            InputEvent::Axis {
+                code: AxisCode::LeftX,
+                value: -300,
+                timestamp: base + Duration::from_millis(20),
+            },
+            InputEvent::Sync { timestamp: base + Duration::from_secs(1) },
+        ];
+
+        for event in events {
+            let json = event.to_json(base).unwrap();
+            let parsed = InputEvent::from_json(&json, base).unwrap();
+            assert_eq!(TimedInputRecord::new(&parsed, base), TimedInputRecord::new(&event, base));
+            assert_eq!(parsed.timestamp(), event.timestamp());
+        }
+    }
+
+    #[test]
+    fn test_input_event_json_format() {
+        let base = Instant::now();
+        let event = InputEvent::Axis {
+            code: AxisCode::LeftX,
+            value: -300,
+            timestamp: base + Duration::from_micros(1500),
+        };
+
+        let record: TimedInputRecord = serde_json::from_str(&event.to_json(base).unwrap()).unwrap();
+        assert_eq!(
+            record,
+            TimedInputRecord {
+                event: InputRecord::Axis { code: AxisCode::LeftX, value: -300 },
+                timestamp_us: 1500
+            }
+        );
+
+        let parsed = InputEvent::from_json(
+            r#"{"type": "button", "code": "North", "pressed": false, "timestamp_us": 250}"#,
+            base,
+        )
+        .unwrap();
+        assert_eq!(
+            TimedInputRecord::new(&parsed, base).event,
+            InputRecord::Button { code: ButtonCode::North, pressed: false }
+        );
+        assert_eq!(parsed.timestamp(), base + Duration::from_micros(250));
+        assert!(InputEvent::from_json(r#"{"type": "button"}"#, base).is_err());
+    }
+
+    #[test]
+    fn test_output_record_json() {
+        let event =
+            OutputEvent::Keyboard { code: KeyboardCode::S, event_type: KeyboardEventType::Press };
+
+        let record: OutputRecord =
+            serde_json::from_str(r#"{"type": "keyboard", "code": "S", "event_type": "press"}"#)
+                .unwrap();
+
+        assert_eq!(record, OutputRecord::from(&event));
+        assert_eq!(OutputEvent::from(record), event);
+    }
+}
@@ -0,0 +1,705 @@
+// Latency and event rate statistics over sliding windows, and event loop health
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::PathBuf,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use crossbeam::channel::{self, RecvTimeoutError, Sender};
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Number of samples kept by [`LatencyTracker::new`]
+pub const DEFAULT_LATENCY_CAPACITY: usize = 1000;
+
+/// Keeps the most recent latency samples so averages and percentiles
+/// reflect current behaviour instead of the whole session
+#[derive(Debug, Clone)]
+pub struct LatencyTracker {
+    samples: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl LatencyTracker {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_LATENCY_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "LatencyTracker capacity must be non-zero");
+        Self { samples: VecDeque::with_capacity(capacity), capacity }
+    }
+
+    /// Record a sample, dropping the oldest once the window is full
+    pub fn push(&mut self, latency_us: u64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency_us);
+    }
+
+    /// Number of samples currently in the window
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Mean of the window, 0 when empty
+    pub fn mean(&self) -> u64 {
+        if self.samples.is_empty() {
+            return 0;
+        }
+        self.samples.iter().sum::<u64>() / self.samples.len() as u64
+    }
+
+    /// Nearest-rank percentile of the window, `p` in 0..=100; 0 when empty
+    pub fn percentile(&self, p: f64) -> u64 {
+        if self.samples.is_empty() {
+            return 0;
+        }
+
+        let mut sorted: Vec<u64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+        sorted[rank.saturating_sub(1)]
+    }
+
+    /// Smallest sample in the window, 0 when empty
+    pub fn min(&self) -> u64 {
+        self.samples.iter().copied().min().unwrap_or(0)
+    }
+
+    /// Largest sample in the window, 0 when empty
+    pub fn max(&self) -> u64 {
+        self.samples.iter().copied().max().unwrap_or(0)
+    }
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Window [`EventRate`] counts events in
+pub const EVENT_RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Events per second, from the timestamps of the events in the last second
+#[derive(Debug, Clone, Default)]
+pub struct EventRate {
+    timestamps: VecDeque<Instant>,
+}
+
+impl EventRate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an event at `at`, returning the events per second including it
+    pub fn record(&mut self, at: Instant) -> u32 {
+        while self
+            .timestamps
+            .front()
+            .is_some_and(|&oldest| at.saturating_duration_since(oldest) >= EVENT_RATE_WINDOW)
+        {
+            self.timestamps.pop_front();
+        }
+        self.timestamps.push_back(at);
+        self.timestamps.len() as u32
+    }
+}
+
+/// Maximum number of bars [`RateHistogram::render`] draws
+pub const HISTOGRAM_BINS: u32 = 10;
+
+/// How often each events-per-second reading occurred
+#[derive(Debug, Clone, Default)]
+pub struct RateHistogram {
+    counts: BTreeMap<u32, u64>,
+}
+
+impl RateHistogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, rate: u32) {
+        *self.counts.entry(rate).or_default() += 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// One line per range of readings, e.g. `  0-  9 | ######## 120`
+    ///
+    /// Readings from 0 to the highest one are split into up to
+    /// [`HISTOGRAM_BINS`] equal ranges; the longest bar is `bar_width` wide.
+    pub fn render(&self, bar_width: usize) -> Vec<String> {
+        let Some(&max_rate) = self.counts.keys().next_back() else {
+            return Vec::new();
+        };
+        let bin_size = (max_rate / HISTOGRAM_BINS) + 1;
+        let mut bins = vec![0u64; (max_rate / bin_size + 1) as usize];
+        for (&rate, &count) in &self.counts {
+            bins[(rate / bin_size) as usize] += count;
+        }
+
+        let tallest = bins.iter().copied().max().unwrap_or(0).max(1);
+        let label_width = (max_rate / bin_size * bin_size + bin_size - 1).to_string().len();
+        bins.iter()
+            .enumerate()
+            .map(|(index, &count)| {
+                let low = index as u32 * bin_size;
+                let bar = (count as usize * bar_width).div_ceil(tallest as usize);
+                format!(
+                    "{:>width$}-{:>width$} | {} {}",
+                    low,
+                    low + bin_size - 1,
+                    "#".repeat(bar),
+                    count,
+                    width = label_width
+                )
+            })
+            .collect()
+    }
+}
+
+/// Consecutive over-budget events after which the event loop logs an error
+pub const LATENCY_BUDGET_STREAK: u32 = 10;
+
+/// Per-event processing budget that tracks consecutive violations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyBudget {
+    budget_us: u64,
+    consecutive: u32,
+}
+
+impl LatencyBudget {
+    pub fn new(budget_us: u64) -> Self {
+        Self { budget_us, consecutive: 0 }
+    }
+
+    pub fn budget_us(&self) -> u64 {
+        self.budget_us
+    }
+
+    /// Check one event's latency, returning how many events in a row have
+    /// now exceeded the budget (0 when this one was within it)
+    pub fn check(&mut self, latency_us: u64) -> u32 {
+        if latency_us > self.budget_us {
+            self.consecutive = self.consecutive.saturating_add(1);
+        } else {
+            self.consecutive = 0;
+        }
+        self.consecutive
+    }
+}
+
+/// Snapshot of an event loop's health
+///
+/// Serializes `uptime` as whole seconds (`uptime_s`) and `last_event_at` as
+/// milliseconds before serialization (`last_event_ms_ago`), since an
+/// `Instant` means nothing outside the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct HealthStatus {
+    pub event_count: u64,
+    #[serde(rename = "uptime_s", serialize_with = "serialize_secs")]
+    pub uptime: Duration,
+    #[serde(rename = "last_event_ms_ago", serialize_with = "serialize_ms_ago")]
+    pub last_event_at: Option<Instant>,
+    /// Non-fatal errors, such as failed rumble effects
+    pub errors: u64,
+    /// Over the last `DEFAULT_LATENCY_CAPACITY` events
+    pub avg_latency_us: u64,
+    pub p95_latency_us: u64,
+}
+
+fn serialize_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_secs())
+}
+
+fn serialize_ms_ago<S: Serializer>(at: &Option<Instant>, serializer: S) -> Result<S::Ok, S::Error> {
+    at.map(|at| at.elapsed().as_millis() as u64).serialize(serializer)
+}
+
+/// Sentinel for "no event yet" in `HealthCounters::last_event_us`
+const NO_EVENT: u64 = u64::MAX;
+
+struct HealthCounters {
+    started: Instant,
+    event_count: AtomicU64,
+    errors: AtomicU64,
+    last_event_us: AtomicU64, // Since `started`
+    latency: Mutex<LatencyTracker>,
+}
+
+/// Event loop counters that can be read from other threads while the loop runs
+///
+/// Clones share the same counters.
+#[derive(Clone)]
+pub struct HealthMonitor {
+    counters: Arc<HealthCounters>,
+}
+
+impl HealthMonitor {
+    pub fn new() -> Self {
+        Self {
+            counters: Arc::new(HealthCounters {
+                started: Instant::now(),
+                event_count: AtomicU64::new(0),
+                errors: AtomicU64::new(0),
+                last_event_us: AtomicU64::new(NO_EVENT),
+                latency: Mutex::new(LatencyTracker::new()),
+            }),
+        }
+    }
+
+    /// Count an event processed at `at`, returning the new total
+    pub(crate) fn record_event(&self, at: Instant) -> u64 {
+        let since_start = at.saturating_duration_since(self.counters.started).as_micros() as u64;
+        self.counters.last_event_us.store(since_start, Ordering::Relaxed);
+        self.counters.event_count.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub(crate) fn record_error(&self) {
+        self.counters.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Add an event's processing latency to the window
+    pub(crate) fn record_latency(&self, latency_us: u64) {
+        self.latency_tracker().push(latency_us);
+    }
+
+    /// Copy of the latency window
+    pub fn latency(&self) -> LatencyTracker {
+        self.latency_tracker().clone()
+    }
+
+    fn latency_tracker(&self) -> std::sync::MutexGuard<'_, LatencyTracker> {
+        // A panic while pushing a sample leaves the tracker usable
+        self.counters.latency.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn event_count(&self) -> u64 {
+        self.counters.event_count.load(Ordering::Relaxed)
+    }
+
+    /// Time since the monitor was created
+    pub fn uptime(&self) -> Duration {
+        self.counters.started.elapsed()
+    }
+
+    /// Time since the last event, or since the monitor was created if there was none
+    pub fn idle_time(&self) -> Duration {
+        let last_event_us = match self.counters.last_event_us.load(Ordering::Relaxed) {
+            NO_EVENT => 0,
+            last_event_us => last_event_us,
+        };
+        self.uptime().saturating_sub(Duration::from_micros(last_event_us))
+    }
+
+    pub fn health(&self) -> HealthStatus {
+        let last_event_us = self.counters.last_event_us.load(Ordering::Relaxed);
+        let latency = self.latency();
+
+        HealthStatus {
+            event_count: self.event_count(),
+            uptime: self.uptime(),
+            last_event_at: (last_event_us != NO_EVENT)
+                .then(|| self.counters.started + Duration::from_micros(last_event_us)),
+            errors: self.counters.errors.load(Ordering::Relaxed),
+            avg_latency_us: latency.mean(),
+            p95_latency_us: latency.percentile(95.0),
+        }
+    }
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Default for [`StatsExporter::interval`]
+pub const DEFAULT_STATS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Contents of a stats file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsSnapshot {
+    pub event_count: u64,
+    pub uptime_s: u64,
+    pub avg_latency_us: u64,
+    pub p95_latency_us: u64,
+    pub errors: u64,
+    pub profile: String,
+}
+
+impl StatsSnapshot {
+    pub fn new(health: &HealthStatus, profile: &str) -> Self {
+        Self {
+            event_count: health.event_count,
+            uptime_s: health.uptime.as_secs(),
+            avg_latency_us: health.avg_latency_us,
+            p95_latency_us: health.p95_latency_us,
+            errors: health.errors,
+            profile: profile.to_string(),
+        }
+    }
+}
+
+/// Rewrites a JSON stats file every `interval` for external dashboards to poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsExporter {
+    pub path: PathBuf,
+    pub interval: Duration,
+}
+
+impl StatsExporter {
+    pub fn new(path: PathBuf, interval: Duration) -> Self {
+        Self { path, interval }
+    }
+
+    /// Replace the file with `snapshot`
+    ///
+    /// The JSON is written next to it first and renamed over it, so readers
+    /// never see a partly written file.
+    pub fn write(&self, snapshot: &StatsSnapshot) -> Result<()> {
+        let mut temp = self.path.clone().into_os_string();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+
+        let json = serde_json::to_string_pretty(snapshot)?;
+        std::fs::write(&temp, json + "\n")
+            .with_context(|| format!("Failed to write {}", temp.display()))?;
+        std::fs::rename(&temp, &self.path)
+            .with_context(|| format!("Failed to replace {}", self.path.display()))
+    }
+
+    /// Write the stats of `monitor` now and then every `interval` on a
+    /// background thread, until the returned handle is stopped or dropped
+    ///
+    /// Write errors are logged and retried at the next interval.
+    pub fn spawn(self, monitor: HealthMonitor, profile: String) -> StatsExporterHandle {
+        let (stop, stopped) = channel::bounded(1);
+        let thread = std::thread::spawn(move || {
+            loop {
+                let snapshot = StatsSnapshot::new(&monitor.health(), &profile);
+                if let Err(e) = self.write(&snapshot) {
+                    tracing::warn!("Failed to write stats: {:#}", e);
+                }
+                match stopped.recv_timeout(self.interval) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        });
+        StatsExporterHandle { stop, thread }
+    }
+}
+
+/// A running [`StatsExporter`]; dropping it stops the thread without waiting
+#[derive(Debug)]
+pub struct StatsExporterHandle {
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl StatsExporterHandle {
+    /// Stop the exporter and wait for a write in progress to finish
+    pub fn stop(self) {
+        let _ = self.stop.send(());
+        let _ = self.thread.join();
+    }
+}
+
+/// Calls back once an event loop saw no events for `timeout`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleWatchdog {
+    pub timeout: Duration,
+}
+
+impl IdleWatchdog {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    /// Watch `monitor` on a background thread and call `on_idle` when it has been
+    /// idle for `timeout`, after which the thread ends
+    ///
+    /// The thread sleeps until the earliest time the timeout could expire instead of
+    /// polling, so it wakes at most once per event-free stretch.
+    pub fn spawn<F>(self, monitor: HealthMonitor, on_idle: F) -> JoinHandle<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        std::thread::spawn(move || {
+            loop {
+                let idle = monitor.idle_time();
+                if idle >= self.timeout {
+                    on_idle();
+                    return;
+                }
+                std::thread::sleep(self.timeout - idle);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_budget_counts_consecutive_violations() {
+        let mut budget = LatencyBudget::new(500);
+        assert_eq!(budget.check(500), 0);
+        assert_eq!(budget.check(501), 1);
+        assert_eq!(budget.check(900), 2);
+        assert_eq!(budget.check(100), 0);
+        assert_eq!(budget.check(600), 1);
+    }
+
+    fn tracker_with(samples: impl IntoIterator<Item = u64>) -> LatencyTracker {
+        let mut tracker = LatencyTracker::new();
+        for sample in samples {
+            tracker.push(sample);
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_empty_tracker() {
+        let tracker = LatencyTracker::new();
+        assert!(tracker.is_empty());
+        assert_eq!(tracker.mean(), 0);
+        assert_eq!(tracker.percentile(99.0), 0);
+        assert_eq!(tracker.min(), 0);
+        assert_eq!(tracker.max(), 0);
+    }
+
+    #[test]
+    fn test_percentiles_on_uniform_data() {
+        // Pushed in reverse so the tracker has to sort
+        let tracker = tracker_with((1..=100).rev());
+
+        assert_eq!(tracker.percentile(0.0), 1);
+        assert_eq!(tracker.percentile(50.0), 50);
+        assert_eq!(tracker.percentile(95.0), 95);
+        assert_eq!(tracker.percentile(99.0), 99);
+        assert_eq!(tracker.percentile(100.0), 100);
+        assert_eq!(tracker.mean(), 50);
+        assert_eq!(tracker.min(), 1);
+        assert_eq!(tracker.max(), 100);
+    }
+
+    #[test]
+    fn test_percentile_with_outliers() {
+        // 990 fast events and 10 slow ones: p99 stays fast, p99.9 sees the spike
+        let tracker =
+            tracker_with(std::iter::repeat_n(50, 990).chain(std::iter::repeat_n(5000, 10)));
+
+        assert_eq!(tracker.percentile(50.0), 50);
+        assert_eq!(tracker.percentile(99.0), 50);
+        assert_eq!(tracker.percentile(99.9), 5000);
+        assert_eq!(tracker.mean(), 99);
+    }
+
+    #[test]
+    fn test_old_samples_are_dropped() {
+        let mut tracker = tracker_with(std::iter::repeat_n(10_000, DEFAULT_LATENCY_CAPACITY));
+        assert_eq!(tracker.max(), 10_000);
+
+        for _ in 0..DEFAULT_LATENCY_CAPACITY {
+            tracker.push(100);
+        }
+
+        assert_eq!(tracker.len(), DEFAULT_LATENCY_CAPACITY);
+        assert_eq!(tracker.max(), 100);
+        assert_eq!(tracker.mean(), 100);
+    }
+
+    #[test]
+    fn test_small_capacity() {
+        let mut tracker = LatencyTracker::with_capacity(3);
+        for sample in [1, 2, 3, 4] {
+            tracker.push(sample);
+        }
+
+        assert_eq!(tracker.len(), 3);
+        assert_eq!(tracker.min(), 2);
+        assert_eq!(tracker.mean(), 3);
+    }
+
+    #[test]
+    fn test_event_rate_counts_the_last_second() {
+        let start = Instant::now();
+        let mut rate = EventRate::new();
+
+        assert_eq!(rate.record(start), 1);
+        assert_eq!(rate.record(start + Duration::from_millis(500)), 2);
+        assert_eq!(rate.record(start + Duration::from_millis(999)), 3);
+        // The first event is now a full second old
+        assert_eq!(rate.record(start + Duration::from_millis(1000)), 3);
+        assert_eq!(rate.record(start + Duration::from_secs(5)), 1);
+    }
+
+    #[test]
+    fn test_rate_histogram_render() {
+        let mut histogram = RateHistogram::new();
+        assert!(histogram.render(10).is_empty());
+
+        for rate in [1, 2, 2, 15, 19] {
+            histogram.record(rate);
+        }
+
+        assert_eq!(
+            histogram.render(6),
+            vec![
+                " 0- 1 | ### 1",
+                " 2- 3 | ###### 2",
+                " 4- 5 |  0",
+                " 6- 7 |  0",
+                " 8- 9 |  0",
+                "10-11 |  0",
+                "12-13 |  0",
+                "14-15 | ### 1",
+                "16-17 |  0",
+                "18-19 | ### 1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_health_monitor_shared_between_threads() {
+        let monitor = HealthMonitor::new();
+        assert_eq!(monitor.health().last_event_at, None);
+
+        let worker = monitor.clone();
+        std::thread::spawn(move || {
+            worker.record_event(Instant::now());
+            worker.record_event(Instant::now());
+            worker.record_error();
+        })
+        .join()
+        .unwrap();
+
+        let health = monitor.health();
+        assert_eq!(health.event_count, 2);
+        assert_eq!(health.errors, 1);
+        assert!(health.last_event_at.is_some());
+        assert!(health.last_event_at.unwrap() <= Instant::now());
+    }
+
+    #[test]
+    fn test_idle_time_resets_on_event() {
+        let monitor = HealthMonitor::new();
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(monitor.idle_time() >= Duration::from_millis(20));
+
+        monitor.record_event(Instant::now());
+        assert!(monitor.idle_time() < Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_idle_watchdog_waits_for_events_to_stop() {
+        let monitor = HealthMonitor::new();
+        let (sender, receiver) = crossbeam::channel::bounded(1);
+        let started = Instant::now();
+        let watchdog = IdleWatchdog::new(Duration::from_millis(100))
+            .spawn(monitor.clone(), move || sender.send(Instant::now()).unwrap());
+
+        // Keep the monitor busy for a while; the watchdog must not fire meanwhile
+        for _ in 0..5 {
+            std::thread::sleep(Duration::from_millis(40));
+            monitor.record_event(Instant::now());
+        }
+        assert!(receiver.try_recv().is_err());
+
+        let fired_at = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        watchdog.join().unwrap();
+        assert!(fired_at.duration_since(started) >= Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_health_latency() {
+        let monitor = HealthMonitor::new();
+        for latency_us in 1..=100 {
+            monitor.record_latency(latency_us);
+        }
+
+        let health = monitor.health();
+        assert_eq!(health.avg_latency_us, 50);
+        assert_eq!(health.p95_latency_us, 95);
+        assert_eq!(monitor.latency().len(), 100);
+    }
+
+    #[test]
+    fn test_health_status_json() {
+        #[derive(Deserialize)]
+        struct Reply {
+            event_count: u64,
+            uptime_s: u64,
+            last_event_ms_ago: Option<u64>,
+            errors: u64,
+        }
+
+        let health = HealthStatus {
+            event_count: 3,
+            uptime: Duration::from_millis(2500),
+            last_event_at: Some(Instant::now()),
+            errors: 1,
+            avg_latency_us: 40,
+            p95_latency_us: 90,
+        };
+        let reply: Reply = serde_json::from_str(&serde_json::to_string(&health).unwrap()).unwrap();
+        assert_eq!(reply.event_count, 3);
+        assert_eq!(reply.uptime_s, 2);
+        assert!(reply.last_event_ms_ago.unwrap() < 1000);
+        assert_eq!(reply.errors, 1);
+    }
+
+    #[test]
+    fn test_stats_exporter_writes_json() {
+        let path =
+            std::env::temp_dir().join(format!("blazeremap-stats-{}.json", std::process::id()));
+        let exporter = StatsExporter::new(path.clone(), Duration::from_millis(10));
+        let monitor = HealthMonitor::new();
+        monitor.record_event(Instant::now());
+        monitor.record_latency(12);
+
+        exporter.write(&StatsSnapshot::new(&monitor.health(), "default")).unwrap();
+        let first: StatsSnapshot =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        monitor.record_event(Instant::now());
+        let exporter = exporter.spawn(monitor, "fps".to_string());
+        std::thread::sleep(Duration::from_millis(100));
+        exporter.stop();
+        let second: StatsSnapshot =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            first,
+            StatsSnapshot {
+                event_count: 1,
+                uptime_s: 0,
+                avg_latency_us: 12,
+                p95_latency_us: 12,
+                errors: 0,
+                profile: "default".to_string(),
+            }
+        );
+        assert_eq!((second.event_count, second.profile.as_str()), (2, "fps"));
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+}
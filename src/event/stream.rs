@@ -0,0 +1,383 @@
+// JSON Lines event stream over a Unix domain socket
+use std::{
+    io::{ErrorKind, Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{HealthMonitor, InputEvent, OutputEvent};
+
+/// Most unsent bytes kept for a client that isn't reading before it is dropped
+const MAX_PENDING_BYTES: usize = 64 * 1024;
+
+/// Longest request line a client may send; longer ones are discarded
+const MAX_REQUEST_BYTES: usize = 4 * 1024;
+
+/// How often clients are checked for requests
+const REQUEST_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A request line sent by a client, e.g. `{"action":"health"}`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum Request {
+    Health,
+}
+
+/// Reply to a request that couldn't be understood
+#[derive(Debug, Serialize)]
+struct ErrorReply {
+    error: String,
+}
+
+/// One line of the stream
+#[derive(Debug, Serialize)]
+struct EventRecord {
+    ts_us: u64,
+    input: String,
+    outputs: Vec<String>,
+}
+
+/// A connected client, the part of the stream it hasn't taken yet and the
+/// part of a request it hasn't finished sending
+struct Client {
+    stream: UnixStream,
+    pending: Vec<u8>,
+    request: Vec<u8>,
+    done_sending: bool,
+}
+
+impl Client {
+    fn new(stream: UnixStream) -> Self {
+        Self { stream, pending: Vec::new(), request: Vec::new(), done_sending: false }
+    }
+
+    /// Read whatever the client has sent without blocking and reply to each
+    /// complete request line; returns false once the client is gone
+    fn answer_requests(&mut self, monitor: &HealthMonitor) -> bool {
+        let mut buf = [0; 512];
+        while !self.done_sending {
+            match self.stream.read(&mut buf) {
+                // Closing its writing half doesn't mean it stopped reading
+                Ok(0) => self.done_sending = true,
+                Ok(read) => self.request.extend_from_slice(&buf[..read]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return false,
+            }
+        }
+
+        while let Some(end) = self.request.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.request.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            if line.trim().is_empty() {
+                continue;
+            }
+            if !self.send(reply(line.trim(), monitor).as_bytes()) {
+                return false;
+            }
+        }
+        if self.request.len() > MAX_REQUEST_BYTES {
+            self.request.clear();
+        }
+        true
+    }
+
+    /// Queue `line` and write as much as the socket takes without blocking
+    ///
+    /// Whatever doesn't fit waits for the next call, so lines are never cut
+    /// off. Returns false once the client is gone or too far behind.
+    fn send(&mut self, line: &[u8]) -> bool {
+        self.pending.extend_from_slice(line);
+        while !self.pending.is_empty() {
+            match self.stream.write(&self.pending) {
+                Ok(0) => return false,
+                Ok(written) => {
+                    self.pending.drain(..written);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return false,
+            }
+        }
+        self.pending.len() <= MAX_PENDING_BYTES
+    }
+}
+
+/// JSON line answering one request line
+fn reply(line: &str, monitor: &HealthMonitor) -> String {
+    let reply = match serde_json::from_str::<Request>(line) {
+        Ok(Request::Health) => serde_json::to_string(&monitor.health()),
+        Err(e) => serde_json::to_string(&ErrorReply { error: format!("Invalid request: {}", e) }),
+    };
+    // Neither type can fail to serialize
+    let mut reply = reply.unwrap();
+    reply.push('\n');
+    reply
+}
+
+/// Broadcasts processed events to every client connected to a Unix socket
+///
+/// Clients are written to without blocking; a client that has disconnected or
+/// falls more than [`MAX_PENDING_BYTES`] behind is dropped so it never delays
+/// the event loop. With [`EventStream::with_health`], clients can also send
+/// `{"action":"health"}` lines and get the loop's [`HealthStatus`] back.
+///
+/// [`HealthStatus`]: super::HealthStatus
+pub struct EventStream {
+    path: PathBuf,
+    clients: Arc<Mutex<Vec<Client>>>,
+    started: Instant,
+    shutdown: Arc<AtomicBool>,
+    accept_thread: Option<JoinHandle<()>>,
+    request_thread: Option<JoinHandle<()>>,
+}
+
+impl EventStream {
+    /// Listen on `path`, replacing a stale socket file if one exists
+    pub fn bind(path: &Path) -> Result<Self> {
+        if path.exists() {
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+        }
+
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+        let clients = Arc::new(Mutex::new(Vec::new()));
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let accepted = Arc::clone(&clients);
+        let stopping = Arc::clone(&shutdown);
+        let accept_thread = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                // Drop connects once to wake this thread up
+                if stopping.load(Ordering::Acquire) {
+                    return;
+                }
+                match stream.and_then(|s| s.set_nonblocking(true).map(|_| s)) {
+                    Ok(stream) => {
+                        tracing::info!("Event stream client connected");
+                        accepted.lock().unwrap().push(Client::new(stream));
+                    }
+                    Err(e) => tracing::warn!("Failed to accept event stream client: {}", e),
+                }
+            }
+        });
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            clients,
+            started: Instant::now(),
+            shutdown,
+            accept_thread: Some(accept_thread),
+            request_thread: None,
+        })
+    }
+
+    /// Answer `{"action":"health"}` requests with the health of `monitor`
+    ///
+    /// Requests are checked every [`REQUEST_POLL_INTERVAL`] on a background
+    /// thread, so they are answered while no events are flowing too.
+    pub fn with_health(mut self, monitor: HealthMonitor) -> Self {
+        let clients = Arc::clone(&self.clients);
+        let stopping = Arc::clone(&self.shutdown);
+        self.request_thread = Some(std::thread::spawn(move || {
+            while !stopping.load(Ordering::Acquire) {
+                clients.lock().unwrap().retain_mut(|client| client.answer_requests(&monitor));
+                std::thread::sleep(REQUEST_POLL_INTERVAL);
+            }
+        }));
+        self
+    }
+
+    /// Number of currently connected clients
+    pub fn client_count(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    /// Send one input event and the outputs it produced to all clients
+    pub fn broadcast(&self, input: &InputEvent, outputs: &[OutputEvent]) {
+        let mut clients = self.clients.lock().unwrap();
+        if clients.is_empty() {
+            return;
+        }
+
+        let line = match self.to_json_line(input, outputs) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to serialize event: {}", e);
+                return;
+            }
+        };
+
+        clients.retain_mut(|client| client.send(line.as_bytes()));
+    }
+
+    fn to_json_line(&self, input: &InputEvent, outputs: &[OutputEvent]) -> Result<String> {
+        let record = EventRecord {
+            ts_us: input.timestamp().saturating_duration_since(self.started).as_micros() as u64,
+            input: input.to_string(),
+            outputs: outputs.iter().map(OutputEvent::summary).collect(),
+        };
+
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+        Ok(line)
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Release);
+        // Only wait for the accept thread if it could be woken up
+        if let (Some(thread), Ok(_)) = (self.accept_thread.take(), UnixStream::connect(&self.path))
+        {
+            let _ = thread.join();
+        }
+        if let Some(thread) = self.request_thread.take() {
+            let _ = thread.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::event::{ButtonCode, KeyboardCode, KeyboardEventType};
+
+    fn socket_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("blazeremap-{}-{}.sock", name, std::process::id()))
+    }
+
+    fn wait_for_clients(stream: &EventStream, count: usize) {
+        for _ in 0..100 {
+            if stream.client_count() == count {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("Timed out waiting for {} client(s)", count);
+    }
+
+    #[test]
+    fn test_broadcast_json_line() {
+        let path = socket_path("broadcast");
+        let stream = EventStream::bind(&path).unwrap();
+
+        let client = UnixStream::connect(&path).unwrap();
+        wait_for_clients(&stream, 1);
+
+        stream.broadcast(
+            &InputEvent::button_press(ButtonCode::South),
+            &[OutputEvent::Keyboard {
+                code: KeyboardCode::S,
+                event_type: KeyboardEventType::Press,
+            }],
+        );
+
+        let mut line = String::new();
+        BufReader::new(client).read_line(&mut line).unwrap();
+
+        assert!(line.ends_with('\n'));
+        assert!(line.contains(r#""input":"South (pressed)""#));
+        assert!(line.contains(r#""outputs":["S (press)"]"#));
+        assert!(line.contains(r#""ts_us":"#));
+    }
+
+    #[test]
+    fn test_disconnected_client_is_dropped() {
+        let path = socket_path("disconnect");
+        let stream = EventStream::bind(&path).unwrap();
+
+        drop(UnixStream::connect(&path).unwrap());
+        wait_for_clients(&stream, 1);
+
+        // The first write may still succeed into the socket buffer; a later one fails
+        for _ in 0..3 {
+            stream.broadcast(&InputEvent::button_press(ButtonCode::South), &[]);
+        }
+
+        assert_eq!(stream.client_count(), 0);
+    }
+
+    #[test]
+    fn test_slow_client_gets_whole_lines() {
+        let (writer, mut reader) = UnixStream::pair().unwrap();
+        writer.set_nonblocking(true).unwrap();
+        let mut client = Client::new(writer);
+        let line = format!("{}\n", "x".repeat(999));
+
+        // Fill the socket until part of a line has to wait
+        let mut sent = 0;
+        while client.pending.is_empty() {
+            assert!(client.send(line.as_bytes()));
+            sent += 1;
+        }
+        let reading = std::thread::spawn(move || {
+            let mut received = String::new();
+            std::io::Read::read_to_string(&mut reader, &mut received).unwrap();
+            received
+        });
+        while !client.pending.is_empty() {
+            assert!(client.send(&[]));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        drop(client);
+
+        assert_eq!(reading.join().unwrap(), line.repeat(sent));
+    }
+
+    #[test]
+    fn test_health_request() {
+        let path = socket_path("health");
+        let monitor = HealthMonitor::new();
+        monitor.record_event(Instant::now());
+        let stream = EventStream::bind(&path).unwrap().with_health(monitor);
+
+        let mut client = UnixStream::connect(&path).unwrap();
+        client.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        client.write_all(b"{\"action\":\"health\"}\n{\"action\":\"reboot\"}\n").unwrap();
+        client.shutdown(std::net::Shutdown::Write).unwrap();
+
+        #[derive(Deserialize)]
+        struct HealthReply {
+            event_count: u64,
+            last_event_ms_ago: Option<u64>,
+        }
+
+        let mut lines = BufReader::new(client).lines();
+        let health: HealthReply = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+        assert_eq!(health.event_count, 1);
+        assert!(health.last_event_ms_ago.is_some());
+
+        let error = lines.next().unwrap().unwrap();
+        assert!(error.starts_with(r#"{"error":"Invalid request"#), "{}", error);
+
+        // Still connected after closing its writing half
+        assert_eq!(stream.client_count(), 1);
+    }
+
+    #[test]
+    fn test_socket_removed_on_drop() {
+        let path = socket_path("cleanup");
+        let stream = EventStream::bind(&path).unwrap();
+        assert!(path.exists());
+
+        // Returns once the accept thread has stopped
+        drop(stream);
+        assert!(!path.exists());
+    }
+}
@@ -23,6 +23,15 @@ impl TimeAnchor {
             Err(err) => self.instant - err.duration(),
         }
     }
+
+    /// Convert Instant back to SystemTime using this anchor
+    fn to_system_time(self, instant: Instant) -> SystemTime {
+        if instant >= self.instant {
+            self.system_time + (instant - self.instant)
+        } else {
+            self.system_time - (self.instant - instant)
+        }
+    }
 }
 
 /// Initialize the global time anchor (call once at startup)
@@ -40,6 +49,12 @@ pub(crate) fn system_time_to_instant(system_time: SystemTime) -> Instant {
     anchor.to_instant(system_time)
 }
 
+/// Convert an event Instant to wall-clock time (internal helper)
+pub(crate) fn instant_to_system_time(instant: Instant) -> SystemTime {
+    let anchor = TIME_ANCHOR.get_or_init(TimeAnchor::new);
+    anchor.to_system_time(instant)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,6 +80,10 @@ mod tests {
 
         // Test exact anchor time
         assert_eq!(anchor.to_instant(anchor_sys), anchor_inst);
+
+        // And back again
+        assert_eq!(anchor.to_system_time(future_inst), future_sys);
+        assert_eq!(anchor.to_system_time(past_inst), past_sys);
     }
 
     #[test]
@@ -86,11 +105,7 @@ mod tests {
         let inst_duration = inst2.saturating_duration_since(inst1);
 
         // Allow for tiny discrepancies (under 500µs) due to clock precision/jitter under load
-        let diff = if inst_duration > sys_duration {
-            inst_duration - sys_duration
-        } else {
-            sys_duration - inst_duration
-        };
+        let diff = inst_duration.abs_diff(sys_duration);
 
         assert!(
             diff < Duration::from_micros(500),
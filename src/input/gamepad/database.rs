@@ -54,6 +54,17 @@ const KNOWN_GAMEPADS: &[GamepadSignature] = &[
         product_id: 0x0ce6,
         gamepad_type: GamepadType::DualSense,
     }, // DualSense (PS5)
+    // Valve
+    GamepadSignature {
+        vendor_id: 0x28de,
+        product_id: 0x1102,
+        gamepad_type: GamepadType::SteamController,
+    }, // Steam Controller (USB)
+    GamepadSignature {
+        vendor_id: 0x28de,
+        product_id: 0x1142,
+        gamepad_type: GamepadType::SteamController,
+    }, // Steam Controller (wireless dongle)
 ];
 
 /// Identify gamepad type based on vendor/product ID
@@ -95,6 +106,12 @@ mod tests {
         assert_eq!(identify_gamepad(0x054c, 0x09cc), GamepadType::DualShock4);
     }
 
+    #[test]
+    fn test_identify_steam_controller() {
+        assert_eq!(identify_gamepad(0x28de, 0x1102), GamepadType::SteamController);
+        assert_eq!(identify_gamepad(0x28de, 0x1142), GamepadType::SteamController);
+    }
+
     #[test]
     fn test_identify_unknown() {
         assert_eq!(identify_gamepad(0xFFFF, 0xFFFF), GamepadType::Generic);
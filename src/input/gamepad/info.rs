@@ -1,5 +1,44 @@
 // Gamepad information
-use super::types::{GamepadCapability, GamepadType};
+use std::collections::HashMap;
+
+use super::types::{BusType, GamepadCapability, GamepadType};
+use crate::event::AxisCode;
+
+/// Value range of one axis as reported by the device (evdev `AbsInfo`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AxisRange {
+    pub min: i32,
+    pub max: i32,
+    /// Values this close to the center are noise and read as centered
+    pub flat: i32,
+    /// Jitter the kernel already filters out
+    pub fuzz: i32,
+}
+
+impl AxisRange {
+    /// Resting value of a centered axis
+    pub const fn center(&self) -> i32 {
+        (self.min + self.max) / 2
+    }
+
+    /// Map `value` from this range onto `target` (`(min, max)`)
+    ///
+    /// Values within `flat` of the center map to the target's center.
+    pub fn rescale(&self, value: i32, target: (i32, i32)) -> f32 {
+        let (target_min, target_max) = target;
+        if self.max <= self.min {
+            return value as f32;
+        }
+
+        let center = (self.min + self.max) as f32 / 2.0;
+        if (value as f32 - center).abs() <= self.flat as f32 {
+            return ((target_min + target_max) / 2) as f32;
+        }
+
+        let position = (value - self.min) as f32 / (self.max - self.min) as f32;
+        target_min as f32 + position * (target_max - target_min) as f32
+    }
+}
 
 /// Information about a detected gamepad
 #[derive(Debug, Clone)]
@@ -10,5 +49,116 @@ pub struct GamepadInfo {
     pub vendor_id: u16,
     pub vendor_name: String,
     pub product_id: u16,
+    /// Hardware/firmware revision from the kernel's `input_id`
+    pub version: u16,
+    pub bus_type: BusType,
     pub capabilities: Vec<GamepadCapability>,
+    /// Range of each axis the device reports (empty if unknown)
+    pub axis_ranges: HashMap<AxisCode, AxisRange>,
+}
+
+impl GamepadInfo {
+    /// Well-known product name for recognized controllers, the raw evdev name otherwise
+    ///
+    /// evdev names are often vague: a DualShock 4 reports itself as "Wireless Controller".
+    pub fn display_name(&self) -> &str {
+        match self.gamepad_type {
+            GamepadType::XboxOne => "Xbox One Controller",
+            GamepadType::XboxSeries => "Xbox Series X/S Controller",
+            GamepadType::XboxElite => "Xbox Elite Controller",
+            GamepadType::DualShock4 => "DualShock 4",
+            GamepadType::DualSense => "DualSense",
+            GamepadType::SteamController => "Steam Controller",
+            GamepadType::Generic | GamepadType::Unknown => &self.name,
+        }
+    }
+
+    /// Generic USB gamepad with no known IDs, capabilities or axis ranges
+    #[cfg(test)]
+    pub(crate) fn test(path: &str, name: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            name: name.to_string(),
+            gamepad_type: GamepadType::Generic,
+            vendor_id: 0,
+            vendor_name: String::new(),
+            product_id: 0,
+            version: 0,
+            bus_type: BusType::Usb,
+            capabilities: vec![],
+            axis_ranges: HashMap::new(),
+        }
+    }
+}
+
+/// The single gamepad whose name contains `pattern` (case-insensitive)
+///
+/// Fails if no gamepad or more than one gamepad matches.
+pub fn find_gamepad_by_name<'a>(
+    gamepads: &'a [GamepadInfo],
+    pattern: &str,
+) -> anyhow::Result<&'a GamepadInfo> {
+    let needle = pattern.to_lowercase();
+    let matching: Vec<&GamepadInfo> =
+        gamepads.iter().filter(|info| info.name.to_lowercase().contains(&needle)).collect();
+
+    match matching.as_slice() {
+        [] => anyhow::bail!("No device matching '{}' found", pattern),
+        [info] => Ok(info),
+        _ => {
+            let list: Vec<String> =
+                matching.iter().map(|info| format!("{} ({})", info.name, info.path)).collect();
+            anyhow::bail!(
+                "Ambiguous: {} devices match '{}': {}",
+                matching.len(),
+                pattern,
+                list.join(", ")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_name() {
+        let mut gamepad = GamepadInfo::test("/dev/input/event3", "Wireless Controller");
+        assert_eq!(gamepad.display_name(), "Wireless Controller");
+
+        gamepad.gamepad_type = GamepadType::DualShock4;
+        assert_eq!(gamepad.display_name(), "DualShock 4");
+
+        gamepad.gamepad_type = GamepadType::XboxOne;
+        assert_eq!(gamepad.display_name(), "Xbox One Controller");
+
+        gamepad.gamepad_type = GamepadType::Unknown;
+        assert_eq!(gamepad.display_name(), "Wireless Controller");
+    }
+
+    #[test]
+    fn test_find_gamepad_by_name() {
+        let gamepads = [
+            GamepadInfo::test(
+                "/dev/input/event3",
+                "Sony Interactive Entertainment DualSense Wireless Controller",
+            ),
+            GamepadInfo::test("/dev/input/event5", "Microsoft X-Box One pad"),
+            GamepadInfo::test("/dev/input/event7", "Microsoft X-Box 360 pad"),
+        ];
+
+        assert_eq!(find_gamepad_by_name(&gamepads, "dualsense").unwrap().path, "/dev/input/event3");
+        assert_eq!(find_gamepad_by_name(&gamepads, "x-box one").unwrap().path, "/dev/input/event5");
+
+        assert_eq!(
+            find_gamepad_by_name(&gamepads, "8BitDo").unwrap_err().to_string(),
+            "No device matching '8BitDo' found"
+        );
+        assert_eq!(
+            find_gamepad_by_name(&gamepads, "X-Box").unwrap_err().to_string(),
+            "Ambiguous: 2 devices match 'X-Box': Microsoft X-Box One pad (/dev/input/event5), \
+             Microsoft X-Box 360 pad (/dev/input/event7)"
+        );
+    }
 }
@@ -6,18 +6,34 @@ pub mod types;
 
 // Re-export commonly used types
 pub use database::{get_known_vendor_database, identify_gamepad};
-pub use info::GamepadInfo;
-pub use types::{GamepadCapability, GamepadType, capabilities_to_strings};
+pub use info::{AxisRange, GamepadInfo, find_gamepad_by_name};
+pub use types::{BusType, GamepadCapability, GamepadType, capabilities_to_strings};
 
+/// A physical gamepad
+///
+/// `Send` so a boxed gamepad can be read on a background thread.
 #[cfg_attr(test, mockall::automock)]
-pub trait Gamepad {
+pub trait Gamepad: Send {
     /// Get detailed info about the gamepad
     fn get_info(&self) -> GamepadInfo;
 
+    /// Capabilities from `get_info`, without cloning the info
+    fn capabilities(&self) -> &[GamepadCapability];
+
+    /// Device name from `get_info`
+    fn name(&self) -> &str;
+
+    /// Device path from `get_info` (e.g., /dev/input/event3)
+    fn path(&self) -> &str;
+
     /// Read the next input event (BLOCKING)
     /// Returns None when device is disconnected
     fn read_event(&mut self) -> anyhow::Result<Option<crate::event::InputEvent>>;
 
+    /// Play a rumble effect for `duration_ms`
+    /// Does nothing on gamepads without force feedback
+    fn set_rumble(&mut self, weak: u16, strong: u16, duration_ms: u32) -> anyhow::Result<()>;
+
     /// Close releases the device
     fn close(self) -> anyhow::Result<()>;
 }
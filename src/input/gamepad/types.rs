@@ -1,9 +1,14 @@
 // Gamepad type definitions
 
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::event::ButtonCode;
+
 /// Represents different gamepad types we can detect
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum GamepadType {
     Unknown,
     XboxOne,
@@ -11,6 +16,7 @@ pub enum GamepadType {
     XboxElite,
     DualShock4,
     DualSense,
+    SteamController,
     Generic,
 }
 
@@ -22,17 +28,85 @@ impl fmt::Display for GamepadType {
             Self::XboxElite => write!(f, "Xbox Elite"),
             Self::DualShock4 => write!(f, "DualShock 4"),
             Self::DualSense => write!(f, "DualSense"),
+            Self::SteamController => write!(f, "Steam Controller"),
             Self::Generic => write!(f, "Generic"),
             Self::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
+impl GamepadType {
+    /// Name of `button` as printed on this controller, falling back to the
+    /// generic name (`South`) for other controllers and unlabeled buttons
+    pub fn button_label(&self, button: ButtonCode) -> String {
+        let label = match self {
+            Self::XboxOne | Self::XboxSeries | Self::XboxElite => button.to_xbox_name(),
+            Self::DualShock4 | Self::DualSense => button.to_playstation_name(),
+            Self::SteamController | Self::Generic | Self::Unknown => None,
+        };
+        label.map_or_else(|| button.to_string(), str::to_string)
+    }
+}
+
+/// How a gamepad is connected, from the kernel's `input_id` bus type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum BusType {
+    Usb,
+    Bluetooth,
+    /// HID over I2C, e.g. built-in handheld controls
+    Hid,
+    Other,
+}
+
+impl BusType {
+    /// Map a Linux `BUS_*` constant (linux/input.h)
+    pub const fn from_linux(bus: u16) -> Self {
+        match bus {
+            0x03 => Self::Usb,       // BUS_USB
+            0x05 => Self::Bluetooth, // BUS_BLUETOOTH
+            0x18 => Self::Hid,       // BUS_I2C
+            _ => Self::Other,
+        }
+    }
+
+    /// Short tag for device listings ("USB", "BT", "HID"); `None` for `Other`
+    pub const fn short_name(self) -> Option<&'static str> {
+        match self {
+            Self::Usb => Some("USB"),
+            Self::Bluetooth => Some("BT"),
+            Self::Hid => Some("HID"),
+            Self::Other => None,
+        }
+    }
+}
+
+impl fmt::Display for BusType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usb => write!(f, "USB"),
+            Self::Bluetooth => write!(f, "Bluetooth"),
+            Self::Hid => write!(f, "HID"),
+            Self::Other => write!(f, "Other"),
+        }
+    }
+}
+
 /// Gamepad capabilities that can be detected
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum GamepadCapability {
     ForceFeedback,
     ElitePaddles,
+    DualTouchpad,
+    /// Connected over Bluetooth (higher latency than USB)
+    Bluetooth,
+    /// Voice-coil haptics (DualSense), finer-grained than the rumble motors of `ForceFeedback`
+    Haptics,
+    /// Triggers with programmable resistance (DualSense)
+    AdaptiveTriggers,
 }
 
 impl fmt::Display for GamepadCapability {
@@ -40,6 +114,10 @@ impl fmt::Display for GamepadCapability {
         match self {
             Self::ForceFeedback => write!(f, "Force Feedback"),
             Self::ElitePaddles => write!(f, "Elite Paddles"),
+            Self::DualTouchpad => write!(f, "Dual Touchpad"),
+            Self::Bluetooth => write!(f, "Bluetooth"),
+            Self::Haptics => write!(f, "Haptics"),
+            Self::AdaptiveTriggers => write!(f, "Adaptive Triggers"),
         }
     }
 }
@@ -53,15 +131,35 @@ pub fn capabilities_to_strings(caps: &[GamepadCapability]) -> Vec<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_button_label() {
+        assert_eq!(GamepadType::XboxSeries.button_label(ButtonCode::East), "B");
+        assert_eq!(GamepadType::DualSense.button_label(ButtonCode::East), "Circle");
+        assert_eq!(GamepadType::XboxElite.button_label(ButtonCode::Paddle2), "Paddle 2");
+        assert_eq!(GamepadType::Generic.button_label(ButtonCode::East), "East");
+    }
+
     #[test]
     fn test_gamepad_type_display() {
         assert_eq!(GamepadType::XboxOne.to_string(), "Xbox One");
         assert_eq!(GamepadType::DualShock4.to_string(), "DualShock 4");
+        assert_eq!(GamepadType::SteamController.to_string(), "Steam Controller");
+    }
+
+    #[test]
+    fn test_bus_type_from_linux() {
+        assert_eq!(BusType::from_linux(0x03), BusType::Usb);
+        assert_eq!(BusType::from_linux(0x05), BusType::Bluetooth);
+        assert_eq!(BusType::from_linux(0x18), BusType::Hid);
+        assert_eq!(BusType::from_linux(0x06), BusType::Other); // BUS_VIRTUAL
+        assert_eq!(BusType::Bluetooth.short_name(), Some("BT"));
+        assert_eq!(BusType::Other.short_name(), None);
     }
 
     #[test]
     fn test_capability_display() {
         assert_eq!(GamepadCapability::ForceFeedback.to_string(), "Force Feedback");
+        assert_eq!(GamepadCapability::DualTouchpad.to_string(), "Dual Touchpad");
     }
 
     #[test]
@@ -70,4 +168,63 @@ mod tests {
         let strings = capabilities_to_strings(&caps);
         assert_eq!(strings, vec!["Force Feedback", "Elite Paddles"]);
     }
+
+    #[test]
+    fn test_gamepad_type_serde_round_trip() {
+        let cases = [
+            (GamepadType::Unknown, "unknown"),
+            (GamepadType::XboxOne, "xbox_one"),
+            (GamepadType::XboxSeries, "xbox_series"),
+            (GamepadType::XboxElite, "xbox_elite"),
+            (GamepadType::DualShock4, "dual_shock4"),
+            (GamepadType::DualSense, "dual_sense"),
+            (GamepadType::SteamController, "steam_controller"),
+            (GamepadType::Generic, "generic"),
+        ];
+
+        for (gamepad_type, name) in cases {
+            let json = serde_json::to_string(&gamepad_type).unwrap();
+            assert_eq!(json, format!("\"{}\"", name));
+            assert_eq!(serde_json::from_str::<GamepadType>(&json).unwrap(), gamepad_type);
+        }
+    }
+
+    #[test]
+    fn test_capability_serde_round_trip() {
+        let cases = [
+            (GamepadCapability::ForceFeedback, "force_feedback"),
+            (GamepadCapability::ElitePaddles, "elite_paddles"),
+            (GamepadCapability::DualTouchpad, "dual_touchpad"),
+            (GamepadCapability::Bluetooth, "bluetooth"),
+            (GamepadCapability::Haptics, "haptics"),
+            (GamepadCapability::AdaptiveTriggers, "adaptive_triggers"),
+        ];
+
+        for (capability, name) in cases {
+            let json = serde_json::to_string(&capability).unwrap();
+            assert_eq!(json, format!("\"{}\"", name));
+            assert_eq!(serde_json::from_str::<GamepadCapability>(&json).unwrap(), capability);
+        }
+    }
+
+    #[test]
+    fn test_capabilities_serialize_in_toml() {
+        #[derive(Serialize, Deserialize)]
+        struct Cached {
+            gamepad_type: GamepadType,
+            capabilities: Vec<GamepadCapability>,
+        }
+
+        let cached = Cached {
+            gamepad_type: GamepadType::XboxElite,
+            capabilities: vec![GamepadCapability::ForceFeedback, GamepadCapability::ElitePaddles],
+        };
+        let text = toml::to_string(&cached).unwrap();
+        assert!(text.contains("gamepad_type = \"xbox_elite\""));
+        assert!(text.contains("capabilities = [\"force_feedback\", \"elite_paddles\"]"));
+
+        let parsed: Cached = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.gamepad_type, GamepadType::XboxElite);
+        assert_eq!(parsed.capabilities, cached.capabilities);
+    }
 }
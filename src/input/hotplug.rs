@@ -0,0 +1,126 @@
+// Hotplug detection - compare successive detection results
+use std::{collections::BTreeMap, time::Duration};
+
+use super::InputDetectionResult;
+
+/// A gamepad appearing or disappearing between two detections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotplugEvent {
+    Connected { path: String, name: String },
+    Disconnected { path: String },
+}
+
+/// Tracks which gamepads are connected and reports the changes
+///
+/// The watcher doesn't poll by itself; feed it each new detection result.
+/// The first result reports every gamepad as connected.
+#[derive(Debug, Default)]
+pub struct HotplugWatcher {
+    /// Device path → display name
+    connected: BTreeMap<String, String>,
+}
+
+impl HotplugWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `result` and return what changed since the previous one,
+    /// disconnections first
+    pub fn update(&mut self, result: &InputDetectionResult) -> Vec<HotplugEvent> {
+        let current: BTreeMap<String, String> = result
+            .gamepad_info
+            .iter()
+            .map(|info| (info.path.clone(), info.display_name().to_string()))
+            .collect();
+
+        let mut events: Vec<HotplugEvent> = self
+            .connected
+            .keys()
+            .filter(|path| !current.contains_key(*path))
+            .map(|path| HotplugEvent::Disconnected { path: path.clone() })
+            .collect();
+        events.extend(current.iter().filter(|(path, _)| !self.connected.contains_key(*path)).map(
+            |(path, name)| HotplugEvent::Connected { path: path.clone(), name: name.clone() },
+        ));
+
+        self.connected = current;
+        events
+    }
+}
+
+/// How long to wait between attempts to reopen a disconnected controller
+///
+/// The wait doubles after every failed attempt, up to `max_delay`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectConfig {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self { initial_delay: Duration::from_secs(1), max_delay: Duration::from_secs(5) }
+    }
+}
+
+impl ReconnectConfig {
+    /// Wait before reconnect `attempt` (counted from 1):
+    /// `min(initial_delay * 2^(attempt - 1), max_delay)`
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.checked_pow(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.initial_delay.checked_mul(factor).unwrap_or(Duration::MAX).min(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::GamepadInfo;
+
+    fn detected(paths: &[&str]) -> InputDetectionResult {
+        InputDetectionResult {
+            gamepad_info: paths.iter().map(|path| GamepadInfo::test(path, "Generic Pad")).collect(),
+            errors: vec![],
+        }
+    }
+
+    #[test]
+    fn test_update_reports_changes() {
+        let mut watcher = HotplugWatcher::new();
+
+        assert_eq!(
+            watcher.update(&detected(&["/dev/input/event3"])),
+            vec![HotplugEvent::Connected {
+                path: "/dev/input/event3".to_string(),
+                name: "Generic Pad".to_string()
+            }]
+        );
+        assert!(watcher.update(&detected(&["/dev/input/event3"])).is_empty());
+
+        assert_eq!(
+            watcher.update(&detected(&["/dev/input/event5"])),
+            vec![
+                HotplugEvent::Disconnected { path: "/dev/input/event3".to_string() },
+                HotplugEvent::Connected {
+                    path: "/dev/input/event5".to_string(),
+                    name: "Generic Pad".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reconnect_delay_doubles_up_to_max() {
+        let config = ReconnectConfig {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(3),
+        };
+        let delays: Vec<Duration> = (1..=5).map(|attempt| config.delay_for(attempt)).collect();
+        assert_eq!(delays, [500, 1000, 2000, 3000, 3000].map(Duration::from_millis));
+
+        let immediate = ReconnectConfig { initial_delay: Duration::ZERO, ..config };
+        assert_eq!(immediate.delay_for(10), Duration::ZERO);
+        assert_eq!(config.delay_for(u32::MAX), Duration::from_secs(3));
+    }
+}
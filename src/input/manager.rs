@@ -9,12 +9,22 @@ pub trait InputManager {
     /// List all connected gamepads
     fn list_gamepads(&self) -> anyhow::Result<InputDetectionResult>;
 
+    /// List all connected gamepads, bypassing any cached result
+    fn list_gamepads_fresh(&self) -> anyhow::Result<InputDetectionResult> {
+        self.list_gamepads()
+    }
+
     /// Open a specific gamepad by path
     fn open_gamepad(&self, path: &str) -> anyhow::Result<Box<dyn Gamepad>>;
+
+    /// Open a gamepad so that no other process receives its events
+    fn open_gamepad_exclusive(&self, path: &str) -> anyhow::Result<Box<dyn Gamepad>> {
+        anyhow::bail!("Exclusive access to {} is not supported", path)
+    }
 }
 
 /// Results of gamepad detection
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct InputDetectionResult {
     pub gamepad_info: Vec<GamepadInfo>,
     pub errors: Vec<InputDeviceError>,
@@ -44,6 +54,17 @@ impl std::fmt::Display for InputDeviceError {
     }
 }
 
+// anyhow::Error is not Clone; keep the message (with its context chain) instead
+impl Clone for InputDeviceError {
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            error_type: self.error_type,
+            source: anyhow::anyhow!("{:#}", self.source),
+        }
+    }
+}
+
 impl InputDeviceError {
     pub fn new(path: String, error_type: ErrorType, source: anyhow::Error) -> Self {
         Self { path, error_type, source }
@@ -1,7 +1,9 @@
 // Input module
 pub mod gamepad;
+pub mod hotplug;
 pub mod manager;
 
 // Re-export main types
 pub use gamepad::{Gamepad, GamepadCapability, GamepadInfo, GamepadType};
+pub use hotplug::{HotplugEvent, HotplugWatcher, ReconnectConfig};
 pub use manager::{ErrorType, InputDetectionResult, InputDeviceError, InputManager};
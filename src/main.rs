@@ -1,11 +1,18 @@
 // Binary entry point for BlazeRemap
-use blazeremap::app::App;
+use blazeremap::app::{App, config::log_filter};
+use blazeremap::cli::ErrorReporter;
 use blazeremap::event::init_time_anchor;
+use blazeremap::mapping::profile::config_dir;
 use std::process;
+use tracing_subscriber::EnvFilter;
 
 fn main() {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
+    // Initialize logging on stderr, leaving stdout to command output
+    let filter = log_filter(std::env::var("RUST_LOG").ok(), config_dir().as_deref());
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(filter))
+        .with_writer(std::io::stderr)
+        .init();
 
     init_time_anchor();
 
@@ -19,9 +26,6 @@ fn run() -> i32 {
 
     match app.run() {
         Ok(_) => 0,
-        Err(e) => {
-            eprintln!("Error: {}", e);
-            1
-        }
+        Err(e) => ErrorReporter::report(&e),
     }
 }
@@ -0,0 +1,139 @@
+// Programmatic construction of mapping engines without a profile
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+
+use crate::{
+    event::{AxisCode, AxisDirection, ButtonCode, KeyboardCode, axis_and_direction_to_string},
+    mapping::MappingEngine,
+};
+
+/// Builds a [`MappingEngine`] rule by rule, for callers that don't start from a profile
+#[derive(Debug, Default)]
+pub struct MappingEngineBuilder {
+    button_rules: Vec<(ButtonCode, KeyboardCode)>,
+    axis_rules: Vec<((AxisCode, AxisDirection), KeyboardCode)>,
+}
+
+impl MappingEngineBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_button_rule(mut self, source: ButtonCode, target: KeyboardCode) -> Self {
+        self.button_rules.push((source, target));
+        self
+    }
+
+    pub fn add_axis_rule(
+        mut self,
+        source: AxisCode,
+        direction: AxisDirection,
+        target: KeyboardCode,
+    ) -> Self {
+        self.axis_rules.push(((source, direction), target));
+        self
+    }
+
+    /// The rules behind `MappingEngine::new_hardcoded`
+    pub fn with_hardcoded_rules(self) -> Self {
+        self.add_button_rule(ButtonCode::South, KeyboardCode::S)
+            .add_button_rule(ButtonCode::East, KeyboardCode::D)
+            .add_button_rule(ButtonCode::West, KeyboardCode::A)
+            .add_axis_rule(AxisCode::DPadY, AxisDirection::Negative, KeyboardCode::Up)
+            .add_axis_rule(AxisCode::DPadY, AxisDirection::Positive, KeyboardCode::Down)
+            .add_axis_rule(AxisCode::DPadX, AxisDirection::Negative, KeyboardCode::Left)
+            .add_axis_rule(AxisCode::DPadX, AxisDirection::Positive, KeyboardCode::Right)
+    }
+
+    /// Build the engine, failing if any source is mapped more than once
+    pub fn build(self) -> Result<MappingEngine> {
+        let mut button_rules = HashMap::new();
+        for (source, target) in self.button_rules {
+            if let Some(existing) = button_rules.insert(source, target) {
+                bail!("{} is mapped twice (to {} and {})", source, existing, target);
+            }
+        }
+
+        let mut axis_rules = HashMap::new();
+        for ((source, direction), target) in self.axis_rules {
+            if let Some(existing) = axis_rules.insert((source, direction), target) {
+                bail!(
+                    "{} is mapped twice (to {} and {})",
+                    axis_and_direction_to_string(source, direction),
+                    existing,
+                    target
+                );
+            }
+        }
+
+        let button_rules =
+            button_rules.into_iter().map(|(source, target)| (source, vec![target])).collect();
+        Ok(MappingEngine::from_rules(button_rules, axis_rules))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{InputEvent, KeyboardEventType, OutputEvent};
+
+    #[test]
+    fn test_build_and_process() {
+        let mut engine = MappingEngineBuilder::new()
+            .add_button_rule(ButtonCode::South, KeyboardCode::Space)
+            .add_axis_rule(AxisCode::DPadY, AxisDirection::Negative, KeyboardCode::Up)
+            .build()
+            .unwrap();
+
+        let outputs = engine.process(&InputEvent::button_press(ButtonCode::South)).unwrap();
+        assert_eq!(
+            outputs,
+            vec![OutputEvent::Keyboard {
+                code: KeyboardCode::Space,
+                event_type: KeyboardEventType::Press
+            }]
+        );
+
+        let outputs = engine.process(&InputEvent::axis_move(AxisCode::DPadY, -1)).unwrap();
+        assert_eq!(
+            outputs,
+            vec![OutputEvent::Keyboard {
+                code: KeyboardCode::Up,
+                event_type: KeyboardEventType::Press
+            }]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_button_rule() {
+        let Err(err) = MappingEngineBuilder::new()
+            .add_button_rule(ButtonCode::South, KeyboardCode::Space)
+            .add_button_rule(ButtonCode::South, KeyboardCode::Enter)
+            .build()
+        else {
+            panic!("Expected a duplicate rule error");
+        };
+
+        assert_eq!(err.to_string(), "South is mapped twice (to Space and Enter)");
+    }
+
+    #[test]
+    fn test_duplicate_axis_rule() {
+        let Err(err) = MappingEngineBuilder::new()
+            .with_hardcoded_rules()
+            .add_axis_rule(AxisCode::DPadX, AxisDirection::Positive, KeyboardCode::D)
+            .build()
+        else {
+            panic!("Expected a duplicate rule error");
+        };
+
+        assert_eq!(err.to_string(), "DPad Right is mapped twice (to Right and D)");
+    }
+
+    #[test]
+    fn test_empty_builder() {
+        let mut engine = MappingEngineBuilder::new().build().unwrap();
+        assert!(engine.process(&InputEvent::button_press(ButtonCode::South)).unwrap().is_empty());
+    }
+}
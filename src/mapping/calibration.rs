@@ -0,0 +1,11 @@
+// Axis calibration - measured ranges that override what a device reports
+use std::collections::HashMap;
+
+use crate::{event::AxisCode, input::gamepad::AxisRange};
+
+/// Per-axis ranges to use instead of the device's own (see
+/// [`MappingEngine::apply_calibration`](crate::mapping::MappingEngine::apply_calibration))
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalibrationProfile {
+    pub axes: HashMap<AxisCode, AxisRange>,
+}
@@ -0,0 +1,91 @@
+// Response curves - how far an axis output moves for a given stick position
+use serde::{Deserialize, Serialize};
+
+/// Shape applied to an axis before it is forwarded
+///
+/// Curves work on the distance from center, 0.0 to 1.0; the sign is kept.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseCurve {
+    #[default]
+    Linear,
+    /// Fine control near center, full speed at the edge
+    Quadratic,
+    /// Like `Quadratic`, with an even wider slow zone
+    Cubic,
+    /// S-shaped: slow near center and near the edge
+    Sine,
+    /// `(input, output)` control points, linearly interpolated
+    ///
+    /// An implicit `(0.0, 0.0)` point precedes the first one; past the last
+    /// point the output stays at its value.
+    Custom(Vec<(f32, f32)>),
+}
+
+/// Evaluate `curve` at `value` (-1.0 to 1.0)
+pub fn apply_curve(value: f32, curve: &ResponseCurve) -> f32 {
+    let magnitude = value.abs().min(1.0);
+    let curved = match curve {
+        ResponseCurve::Linear => magnitude,
+        ResponseCurve::Quadratic => magnitude * magnitude,
+        ResponseCurve::Cubic => magnitude * magnitude * magnitude,
+        ResponseCurve::Sine => (1.0 - (magnitude * std::f32::consts::PI).cos()) / 2.0,
+        ResponseCurve::Custom(points) => interpolate(points, magnitude),
+    };
+    curved.copysign(value)
+}
+
+fn interpolate(points: &[(f32, f32)], x: f32) -> f32 {
+    if points.is_empty() {
+        return x;
+    }
+
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut previous = (0.0, 0.0);
+    for (input, output) in points {
+        if x <= input {
+            let span = input - previous.0;
+            if span <= f32::EPSILON {
+                return output;
+            }
+            return previous.1 + (x - previous.0) / span * (output - previous.1);
+        }
+        previous = (input, output);
+    }
+    previous.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn test_presets() {
+        assert!(close(apply_curve(0.5, &ResponseCurve::Linear), 0.5));
+        assert!(close(apply_curve(0.5, &ResponseCurve::Quadratic), 0.25));
+        assert!(close(apply_curve(-0.5, &ResponseCurve::Cubic), -0.125));
+        assert!(close(apply_curve(0.5, &ResponseCurve::Sine), 0.5));
+        assert!(close(apply_curve(1.0, &ResponseCurve::Sine), 1.0));
+        assert!(close(apply_curve(2.0, &ResponseCurve::Quadratic), 1.0));
+    }
+
+    #[test]
+    fn test_custom_curve() {
+        let curve = ResponseCurve::Custom(vec![(1.0, 1.0), (0.5, 0.2)]);
+        assert!(close(apply_curve(0.25, &curve), 0.1));
+        assert!(close(apply_curve(-0.75, &curve), -0.6));
+        assert!(close(apply_curve(1.0, &curve), 1.0));
+
+        // Output holds past the last point
+        let curve = ResponseCurve::Custom(vec![(0.5, 0.8)]);
+        assert!(close(apply_curve(0.9, &curve), 0.8));
+
+        assert!(close(apply_curve(0.3, &ResponseCurve::Custom(vec![])), 0.3));
+    }
+}
@@ -1,37 +1,151 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
 
 use anyhow::Result;
 
 use crate::{
     event::{
         AxisCode, AxisDirection, ButtonCode, InputEvent, KeyboardCode, KeyboardEventType,
-        OutputEvent,
+        MediaKeyCode, OutputEvent, axis_and_direction_to_string,
     },
+    input::gamepad::{AxisRange, GamepadInfo},
     mapping::{
-        MappingRule::{self, AxisDirectionToKey, ButtonToKey},
+        CalibrationProfile, MappingEngineBuilder,
+        MappingRule::{
+            self, AxisDirectionToKey, AxisToAxis, ButtonToKey, ButtonToMedia, ButtonToRumble,
+            ConditionalButtonToKey,
+        },
+        ResponseCurve, apply_curve,
         profile::Profile,
+        rules::Condition,
+        types::RulePriority,
+        validate::Severity,
     },
 };
 
+/// Target of a `ConditionalButtonToKey` rule
+#[derive(Debug, Clone, Copy)]
+struct ConditionalKey {
+    condition: Condition,
+    target: KeyboardCode,
+    priority: RulePriority,
+}
+
+/// Target of an `AxisToAxis` rule
+#[derive(Debug, Clone)]
+struct AxisOutput {
+    target: AxisCode,
+    scale: f32,
+    invert: bool,
+    /// Applied to the source value, relative to the source's standard range
+    curve: Option<(ResponseCurve, f32)>,
+    /// Range the device reports for the source, and the source's standard range
+    device_range: Option<(AxisRange, (i32, i32))>,
+}
+
+impl AxisOutput {
+    fn apply(&self, value: i32) -> i32 {
+        let value = match &self.device_range {
+            Some((range, standard)) => range.rescale(value, *standard),
+            None => value as f32,
+        };
+        let curved = match &self.curve {
+            Some((curve, full_scale)) => apply_curve(value / full_scale, curve) * full_scale,
+            None => value,
+        };
+        let scaled = curved * self.scale;
+        (if self.invert { -scaled } else { scaled }).round() as i32
+    }
+}
+
 pub struct MappingEngine {
-    button_rules: HashMap<ButtonCode, KeyboardCode>,
+    button_rules: HashMap<ButtonCode, Vec<KeyboardCode>>, // Several only with allow_multiple
+    conditional_rules: HashMap<ButtonCode, Vec<ConditionalKey>>, // In profile order
     axis_rules: HashMap<(AxisCode, AxisDirection), KeyboardCode>,
-    axis_states: HashMap<AxisCode, i32>, // Track current axis values
+    rumble_rules: HashMap<ButtonCode, OutputEvent>,
+    media_rules: HashMap<ButtonCode, MediaKeyCode>,
+    axis_outputs: HashMap<AxisCode, AxisOutput>,
+    enabled_axes: Option<HashSet<AxisCode>>, // None = all axes
+    enabled_buttons: Option<HashSet<ButtonCode>>, // None = all buttons
+    dpad_states: HashMap<AxisCode, AxisDirection>, // Direction each D-pad axis is held in
+    held_buttons: HashSet<ButtonCode>,       // Buttons currently pressed, for conditions
+    pressed_keys: HashMap<ButtonCode, Vec<KeyboardCode>>, // Keys each held button pressed
+    timing_enabled: bool,                    // Measure process_with_timing durations
 }
 
 impl MappingEngine {
     pub fn load_from_profile(profile: &Profile) -> Result<Self> {
-        let mut button_rules = HashMap::new();
+        let mut button_rules: HashMap<ButtonCode, Vec<KeyboardCode>> = HashMap::new();
+        let mut conditional_rules: HashMap<ButtonCode, Vec<ConditionalKey>> = HashMap::new();
         let mut axis_rules = HashMap::new();
+        let mut rumble_rules = HashMap::new();
+        let mut media_rules = HashMap::new();
+        let mut axis_outputs = HashMap::new();
 
         for mapping in &profile.mappings {
+            if !mapping.enabled {
+                tracing::debug!("Skipping disabled mapping {}", mapping);
+                continue;
+            }
             match MappingRule::try_from(mapping)? {
                 ButtonToKey { source, target } => {
-                    button_rules.insert(source, target);
+                    let targets = button_rules.entry(source).or_default();
+                    if !profile.settings.allow_multiple {
+                        targets.clear();
+                    }
+                    if !targets.contains(&target) {
+                        targets.push(target);
+                    }
                 }
                 AxisDirectionToKey { source, direction, target } => {
                     axis_rules.insert((source, direction), target);
                 }
+                ButtonToRumble { source, weak, strong, duration_ms } => {
+                    if !profile.settings.vibration_enabled {
+                        continue;
+                    }
+                    let intensity = profile.settings.vibration_intensity.min(100);
+                    let scale =
+                        |magnitude: u16| (u32::from(magnitude) * u32::from(intensity) / 100) as u16;
+                    rumble_rules.insert(
+                        source,
+                        OutputEvent::Rumble {
+                            weak: scale(weak),
+                            strong: scale(strong),
+                            duration_ms,
+                        },
+                    );
+                }
+                ButtonToMedia { source, target } => {
+                    media_rules.insert(source, target);
+                }
+                AxisToAxis { source, target, scale, invert } => {
+                    let curve = profile.settings.axis_curves.get(&source).map(|curve| {
+                        let (min, max) = source.standard_range();
+                        (curve.clone(), min.abs().max(max) as f32)
+                    });
+                    axis_outputs.insert(
+                        source,
+                        AxisOutput { target, scale, invert, curve, device_range: None },
+                    );
+                }
+                ConditionalButtonToKey { source, target, condition, priority } => {
+                    conditional_rules.entry(source).or_default().push(ConditionalKey {
+                        condition,
+                        target,
+                        priority,
+                    });
+                }
+            }
+        }
+
+        for issue in profile.validate() {
+            match issue.severity {
+                Severity::Error => anyhow::bail!("Invalid profile: {}", issue),
+                Severity::Warning => tracing::warn!("Profile: {}", issue),
+                Severity::Info => tracing::debug!("Profile: {}", issue),
             }
         }
 
@@ -41,71 +155,306 @@ impl MappingEngine {
             axis_rules.len()
         );
 
-        Ok(Self { button_rules, axis_rules, axis_states: HashMap::new() })
+        Ok(Self {
+            button_rules,
+            conditional_rules,
+            axis_rules,
+            rumble_rules,
+            media_rules,
+            axis_outputs,
+            enabled_axes: profile
+                .settings
+                .enabled_axes
+                .as_ref()
+                .map(|a| a.iter().copied().collect()),
+            enabled_buttons: profile
+                .settings
+                .enabled_buttons
+                .as_ref()
+                .map(|b| b.iter().copied().collect()),
+            dpad_states: HashMap::new(),
+            held_buttons: HashSet::new(),
+            pressed_keys: HashMap::new(),
+            timing_enabled: true,
+        })
     }
 
-    pub fn new_hardcoded() -> Self {
-        let mut button_rules = HashMap::new();
-        let mut axis_rules = HashMap::new();
+    /// Rescale forwarded axes from the ranges `info` reports to the standard
+    /// ranges, so controllers with other ranges (e.g. 0-255 sticks) need no
+    /// calibration
+    pub fn set_device_ranges(&mut self, info: &GamepadInfo) {
+        for (source, output) in &mut self.axis_outputs {
+            output.device_range =
+                info.axis_ranges.get(source).map(|range| (*range, source.standard_range()));
+        }
+    }
+
+    /// Replace the range of each forwarded axis that `cal` covers, e.g. after
+    /// recalibrating; other axes keep their current range
+    pub fn apply_calibration(&mut self, cal: &CalibrationProfile) {
+        for (source, output) in &mut self.axis_outputs {
+            if let Some(range) = cal.axes.get(source) {
+                output.device_range = Some((*range, source.standard_range()));
+            }
+        }
+    }
 
-        // Button mappings
-        button_rules.insert(ButtonCode::South, KeyboardCode::S);
-        button_rules.insert(ButtonCode::East, KeyboardCode::D);
-        button_rules.insert(ButtonCode::West, KeyboardCode::A);
+    /// Whether any axis is forwarded to the virtual gamepad, i.e. whether
+    /// [`MappingEngine::set_device_ranges`] has anything to rescale
+    pub fn forwards_axes(&self) -> bool {
+        !self.axis_outputs.is_empty()
+    }
 
-        // DPad mappings
-        axis_rules.insert((AxisCode::DPadY, AxisDirection::Negative), KeyboardCode::Up);
-        axis_rules.insert((AxisCode::DPadY, AxisDirection::Positive), KeyboardCode::Down);
-        axis_rules.insert((AxisCode::DPadX, AxisDirection::Negative), KeyboardCode::Left);
-        axis_rules.insert((AxisCode::DPadX, AxisDirection::Positive), KeyboardCode::Right);
+    /// An engine with no rules; every event produces no output
+    pub fn new_empty() -> Self {
+        Self::from_rules(HashMap::new(), HashMap::new())
+    }
 
+    pub fn new_hardcoded() -> Self {
+        MappingEngineBuilder::new()
+            .with_hardcoded_rules()
+            .build()
+            .expect("hardcoded rules have no duplicates")
+    }
+
+    pub(super) fn from_rules(
+        button_rules: HashMap<ButtonCode, Vec<KeyboardCode>>,
+        axis_rules: HashMap<(AxisCode, AxisDirection), KeyboardCode>,
+    ) -> Self {
         tracing::info!(
             "Mapping engine initialized with {} button rules, {} axis rules",
             button_rules.len(),
             axis_rules.len()
         );
 
-        Self { button_rules, axis_rules, axis_states: HashMap::new() }
+        Self {
+            button_rules,
+            conditional_rules: HashMap::new(),
+            axis_rules,
+            rumble_rules: HashMap::new(),
+            media_rules: HashMap::new(),
+            axis_outputs: HashMap::new(),
+            enabled_axes: None,
+            enabled_buttons: None,
+            dpad_states: HashMap::new(),
+            held_buttons: HashSet::new(),
+            pressed_keys: HashMap::new(),
+            timing_enabled: true,
+        }
+    }
+
+    /// Every key this engine can press, for sizing the virtual keyboard
+    pub fn target_keys(&self) -> HashSet<KeyboardCode> {
+        let conditional = self.conditional_rules.values().flatten().map(|rule| &rule.target);
+        self.button_rules
+            .values()
+            .flatten()
+            .chain(self.axis_rules.values())
+            .chain(conditional)
+            .copied()
+            .chain(self.media_rules.values().map(|media| media.keyboard_code()))
+            .collect()
+    }
+
+    /// Plain button rules, for exporting to other formats
+    pub(super) fn button_rules(&self) -> &HashMap<ButtonCode, Vec<KeyboardCode>> {
+        &self.button_rules
+    }
+
+    /// Axis direction rules, for exporting to other formats
+    pub(super) fn axis_rules(&self) -> &HashMap<(AxisCode, AxisDirection), KeyboardCode> {
+        &self.axis_rules
+    }
+
+    /// Every gamepad axis this engine can move, for sizing the virtual gamepad
+    pub fn target_axes(&self) -> HashSet<AxisCode> {
+        self.axis_outputs.values().map(|output| output.target).collect()
+    }
+
+    /// Turn off timing so `process_with_timing` reports zero durations and
+    /// skips the clock reads, for callers that time events themselves
+    pub fn set_timing_enabled(&mut self, enabled: bool) {
+        self.timing_enabled = enabled;
+    }
+
+    /// Forget held buttons, the keys they pressed and held D-pad directions
+    ///
+    /// For when the virtual keyboard released every key (e.g. the gamepad
+    /// disconnected), so the next press of a button or direction is sent again.
+    pub fn reset_state(&mut self) {
+        self.dpad_states.clear();
+        self.held_buttons.clear();
+        self.pressed_keys.clear();
     }
 
     pub fn process(&mut self, event: &InputEvent) -> Result<Vec<OutputEvent>> {
+        self.process_with_timing(event).map(|(events, _)| events)
+    }
+
+    /// Process `events` in order, returning all their outputs
+    ///
+    /// Stops at the first event that fails.
+    pub fn process_batch(&mut self, events: &[InputEvent]) -> Result<Vec<OutputEvent>> {
+        let mut outputs = Vec::new();
+        for event in events {
+            outputs.extend(self.process(event)?);
+        }
+        Ok(outputs)
+    }
+
+    /// Like [`MappingEngine::process`], also returning how long processing took
+    ///
+    /// The duration is zero when timing is disabled.
+    pub fn process_with_timing(
+        &mut self,
+        event: &InputEvent,
+    ) -> Result<(Vec<OutputEvent>, Duration)> {
+        if !self.timing_enabled {
+            return Ok((self.process_event(event)?, Duration::ZERO));
+        }
+
+        let start = Instant::now();
+        let events = self.process_event(event)?;
+        Ok((events, start.elapsed()))
+    }
+
+    fn process_event(&mut self, event: &InputEvent) -> Result<Vec<OutputEvent>> {
+        let _span = tracing::trace_span!("process_event", event = %event).entered();
+
         match event {
             InputEvent::Button { code, pressed, .. } => self.process_button(*code, *pressed),
-            InputEvent::Axis { code, value, .. } => self.process_axis(*code, *value),
+            InputEvent::Axis { code, value, .. } => self.process_axis(event, *code, *value),
             InputEvent::Sync { .. } => Ok(vec![]),
         }
     }
 
-    fn process_button(&self, code: ButtonCode, pressed: bool) -> Result<Vec<OutputEvent>> {
-        if let Some(&target_key) = self.button_rules.get(&code) {
-            let event = OutputEvent::Keyboard {
-                code: target_key,
-                event_type: if pressed {
-                    KeyboardEventType::Press
-                } else {
-                    KeyboardEventType::Release
-                },
-            };
-            Ok(vec![event])
+    fn process_button(&mut self, code: ButtonCode, pressed: bool) -> Result<Vec<OutputEvent>> {
+        let events = self.button_outputs(code, pressed);
+
+        if pressed {
+            self.held_buttons.insert(code);
+            let keys: Vec<KeyboardCode> = events
+                .iter()
+                .filter_map(|event| match event {
+                    OutputEvent::Keyboard { code: key, .. } => Some(*key),
+                    _ => None,
+                })
+                .collect();
+            if !keys.is_empty() {
+                self.pressed_keys.insert(code, keys);
+            }
         } else {
-            Ok(vec![])
+            self.held_buttons.remove(&code);
+            self.pressed_keys.remove(&code);
         }
+
+        Ok(events)
     }
 
-    fn process_axis(&mut self, code: AxisCode, new_value: i32) -> Result<Vec<OutputEvent>> {
-        // Skip if not a DPad axis or if in deadzone
-        if !matches!(code, AxisCode::DPadX | AxisCode::DPadY) {
-            return Ok(vec![]);
+    /// The keys a press of `button` sends with the buttons currently held
+    ///
+    /// More than one key only for buttons with several plain mappings in a
+    /// profile with `allow_multiple` set.
+    pub fn route_to_multiple_outputs(&self, button: ButtonCode) -> Vec<KeyboardCode> {
+        let conditional = |priority: RulePriority| {
+            self.conditional_rules
+                .get(&button)
+                .into_iter()
+                .flatten()
+                .find(|rule| rule.priority == priority && rule.condition.is_met(&self.held_buttons))
+                .map(|rule| vec![rule.target])
+        };
+
+        conditional(RulePriority::High)
+            .or_else(|| self.button_rules.get(&button).cloned())
+            .or_else(|| conditional(RulePriority::Normal))
+            .unwrap_or_default()
+    }
+
+    /// Outputs for a button event, without updating the held-button state
+    fn button_outputs(&self, code: ButtonCode, pressed: bool) -> Vec<OutputEvent> {
+        if !self.is_button_enabled(code) {
+            return vec![];
         }
 
-        let old_value = self.axis_states.get(&code).copied().unwrap_or(0);
-        self.axis_states.insert(code, new_value);
+        let mut events = Vec::new();
+
+        // A release sends the keys its press sent, even if a modifier changed since
+        let target_keys = if pressed {
+            self.route_to_multiple_outputs(code)
+        } else if self.held_buttons.contains(&code) {
+            self.pressed_keys.get(&code).cloned().unwrap_or_default()
+        } else {
+            self.route_to_multiple_outputs(code) // Press happened before this engine started
+        };
+
+        let event_type =
+            if pressed { KeyboardEventType::Press } else { KeyboardEventType::Release };
+        for target_key in target_keys {
+            events.push(OutputEvent::Keyboard { code: target_key, event_type });
+        }
+
+        // Rumble fires once per press; media keys are held with the button
+        if pressed && let Some(rumble) = self.rumble_rules.get(&code) {
+            events.push(rumble.clone());
+        }
+        if let Some(&media) = self.media_rules.get(&code) {
+            events.push(OutputEvent::MediaKey { code: media, event_type });
+        }
+
+        events
+    }
+
+    fn process_axis(
+        &mut self,
+        event: &InputEvent,
+        code: AxisCode,
+        new_value: i32,
+    ) -> Result<Vec<OutputEvent>> {
+        if !self.is_axis_enabled(code) {
+            return Ok(vec![]);
+        }
 
         let mut events = Vec::new();
+        if let Some(output) = self.axis_outputs.get(&code) {
+            events.push(OutputEvent::GamepadAxis {
+                code: output.target,
+                value: output.apply(new_value),
+            });
+        }
 
-        // Detect direction changes and generate press/release events
-        let old_direction = Self::value_to_direction(old_value);
-        let new_direction = Self::value_to_direction(new_value);
+        // Only D-pad axes turn into key presses
+        if event.is_dpad() {
+            let old_direction = self.dpad_states.get(&code).copied();
+            let new_direction = event.dpad_direction().map(|(_, direction)| direction);
+            match new_direction {
+                Some(direction) => self.dpad_states.insert(code, direction),
+                None => self.dpad_states.remove(&code),
+            };
+            events.extend(self.axis_transition(code, old_direction, new_direction));
+        }
+
+        Ok(events)
+    }
+
+    fn is_axis_enabled(&self, code: AxisCode) -> bool {
+        self.enabled_axes.as_ref().is_none_or(|axes| axes.contains(&code))
+    }
+
+    fn is_button_enabled(&self, code: ButtonCode) -> bool {
+        self.enabled_buttons.as_ref().is_none_or(|buttons| buttons.contains(&code))
+    }
+
+    /// Compute the key events for a D-pad axis moving from `old_direction` to `new_direction`
+    ///
+    /// This is a pure lookup; the caller is responsible for updating `dpad_states`.
+    fn axis_transition(
+        &self,
+        code: AxisCode,
+        old_direction: Option<AxisDirection>,
+        new_direction: Option<AxisDirection>,
+    ) -> Vec<OutputEvent> {
+        let mut events = Vec::new();
 
         // Release old direction if it changed
         #[allow(clippy::collapsible_if)]
@@ -133,18 +482,90 @@ impl MappingEngine {
             }
         }
 
-        Ok(events)
+        events
     }
 
-    fn value_to_direction(value: i32) -> Option<AxisDirection> {
-        const THRESHOLD: i32 = 0;
+    /// Describe how an event would be mapped, for troubleshooting missing mappings
+    ///
+    /// Unlike [`MappingEngine::process`] this does not update axis state, so it can
+    /// be called for any event without affecting later processing.
+    pub fn explain(&self, event: &InputEvent) -> String {
+        match event {
+            InputEvent::Button { code, pressed, .. } => {
+                if !self.is_button_enabled(*code) {
+                    return format!("{} → (button disabled by profile)", code);
+                }
 
-        if value > THRESHOLD {
-            Some(AxisDirection::Positive)
-        } else if value < -THRESHOLD {
-            Some(AxisDirection::Negative)
-        } else {
-            None // Centered/neutral
+                let outputs = self.button_outputs(*code, *pressed);
+                if outputs.is_empty() {
+                    if self.rumble_rules.contains_key(code) {
+                        return format!("{} → (rumble only fires on press)", code);
+                    }
+                    return format!("{} → (no rule for button)", code);
+                }
+
+                let key_rule = match outputs.first() {
+                    Some(OutputEvent::Keyboard { code: key, .. })
+                        if self.button_rules.get(code).is_some_and(|keys| keys.contains(key)) =>
+                    {
+                        Some("ButtonToKey")
+                    }
+                    Some(OutputEvent::Keyboard { .. }) => Some("ConditionalButtonToKey"),
+                    _ => None,
+                };
+                let extra_rules = outputs.iter().filter_map(|output| match output {
+                    OutputEvent::Rumble { .. } => Some("ButtonToRumble"),
+                    OutputEvent::MediaKey { .. } => Some("ButtonToMedia"),
+                    _ => None,
+                });
+                let rule = key_rule.into_iter().chain(extra_rules).collect::<Vec<_>>().join(" + ");
+                let outputs: Vec<String> = outputs.iter().map(OutputEvent::summary).collect();
+                format!("{} → {} [matched {} rule]", code, outputs.join(", "), rule)
+            }
+            InputEvent::Axis { code, value, .. } => {
+                let prefix = format!("{} = {}", code, value);
+
+                if !self.is_axis_enabled(*code) {
+                    return format!("{} → (axis disabled by profile)", prefix);
+                }
+                if let Some(output) = self.axis_outputs.get(code) {
+                    return format!(
+                        "{} → gamepad {} = {} [matched AxisToAxis rule]",
+                        prefix,
+                        output.target,
+                        output.apply(*value)
+                    );
+                }
+                if !event.is_dpad() {
+                    return format!("{} → (no rule, non-DPad axis)", prefix);
+                }
+
+                let old_direction = self.dpad_states.get(code).copied();
+                let new_direction = event.dpad_direction().map(|(_, direction)| direction);
+                let outputs = self.axis_transition(*code, old_direction, new_direction);
+
+                if !outputs.is_empty() {
+                    let outputs: Vec<String> = outputs.iter().map(OutputEvent::summary).collect();
+                    return format!(
+                        "{} → {} [matched AxisDirectionToKey rule]",
+                        prefix,
+                        outputs.join(", ")
+                    );
+                }
+
+                match new_direction {
+                    None => format!("{} → (centered, nothing to release)", prefix),
+                    Some(direction) if old_direction == Some(direction) => {
+                        format!("{} → (direction unchanged)", prefix)
+                    }
+                    Some(direction) => format!(
+                        "{} → (no rule for {})",
+                        prefix,
+                        axis_and_direction_to_string(*code, direction)
+                    ),
+                }
+            }
+            InputEvent::Sync { .. } => "Sync → (ignored, frame boundary)".to_string(),
         }
     }
 }
@@ -154,6 +575,19 @@ mod tests {
     use super::*;
     use crate::event::{AxisCode, ButtonCode};
 
+    #[test]
+    fn test_target_keys() {
+        let engine = MappingEngineBuilder::new()
+            .add_button_rule(ButtonCode::South, KeyboardCode::Space)
+            .add_button_rule(ButtonCode::East, KeyboardCode::Space)
+            .add_axis_rule(AxisCode::DPadY, AxisDirection::Negative, KeyboardCode::Up)
+            .build()
+            .unwrap();
+
+        assert_eq!(engine.target_keys(), HashSet::from([KeyboardCode::Space, KeyboardCode::Up]));
+        assert!(MappingEngine::new_empty().target_keys().is_empty());
+    }
+
     #[test]
     fn test_mapping_engine_hardcoded_press() {
         let mut engine = MappingEngine::new_hardcoded();
@@ -162,7 +596,9 @@ mod tests {
         let result = engine.process(&input).unwrap();
 
         assert_eq!(result.len(), 1);
-        let OutputEvent::Keyboard { code, event_type } = result[0];
+        let OutputEvent::Keyboard { code, event_type } = result[0] else {
+            panic!("Expected a keyboard event");
+        };
         assert_eq!(code, KeyboardCode::S);
         assert_eq!(event_type, KeyboardEventType::Press);
     }
@@ -175,7 +611,9 @@ mod tests {
         let result = engine.process(&input).unwrap();
 
         assert_eq!(result.len(), 1);
-        let OutputEvent::Keyboard { code, event_type } = result[0];
+        let OutputEvent::Keyboard { code, event_type } = result[0] else {
+            panic!("Expected a keyboard event");
+        };
         assert_eq!(code, KeyboardCode::D);
         assert_eq!(event_type, KeyboardEventType::Release);
     }
@@ -215,7 +653,9 @@ mod tests {
         let events = engine.process(&input).unwrap();
         assert_eq!(events.len(), 1);
 
-        let OutputEvent::Keyboard { code, event_type } = events[0];
+        let OutputEvent::Keyboard { code, event_type } = events[0] else {
+            panic!("Expected a keyboard event");
+        };
         assert_eq!(code, KeyboardCode::Up);
         assert_eq!(event_type, KeyboardEventType::Press);
     }
@@ -231,7 +671,9 @@ mod tests {
         let events = engine.process(&InputEvent::axis_move(AxisCode::DPadY, 0)).unwrap();
 
         assert_eq!(events.len(), 1);
-        let OutputEvent::Keyboard { code, event_type } = events[0];
+        let OutputEvent::Keyboard { code, event_type } = events[0] else {
+            panic!("Expected a keyboard event");
+        };
         assert_eq!(code, KeyboardCode::Up);
         assert_eq!(event_type, KeyboardEventType::Release);
     }
@@ -248,11 +690,15 @@ mod tests {
 
         assert_eq!(events.len(), 2);
 
-        let OutputEvent::Keyboard { code: code1, event_type: type1 } = events[0];
+        let OutputEvent::Keyboard { code: code1, event_type: type1 } = events[0] else {
+            panic!("Expected a keyboard event");
+        };
         assert_eq!(code1, KeyboardCode::Up);
         assert_eq!(type1, KeyboardEventType::Release);
 
-        let OutputEvent::Keyboard { code: code2, event_type: type2 } = events[1];
+        let OutputEvent::Keyboard { code: code2, event_type: type2 } = events[1] else {
+            panic!("Expected a keyboard event");
+        };
         assert_eq!(code2, KeyboardCode::Down);
         assert_eq!(type2, KeyboardEventType::Press);
     }
@@ -266,13 +712,27 @@ mod tests {
         assert_eq!(engine.axis_rules.len(), 4);
 
         // Verify some specific mappings from default profile
-        assert_eq!(engine.button_rules.get(&ButtonCode::North), Some(&KeyboardCode::W));
+        assert_eq!(engine.button_rules.get(&ButtonCode::North), Some(&vec![KeyboardCode::W]));
         assert_eq!(
             engine.axis_rules.get(&(AxisCode::DPadY, AxisDirection::Negative)),
             Some(&KeyboardCode::Up)
         );
     }
 
+    #[test]
+    fn test_load_from_profile_skips_disabled_mappings() {
+        let mut profile = Profile::default_profile();
+        let north = profile.mappings.iter_mut().find(|m| m.source_name == "North").unwrap();
+        north.enabled = false;
+        // A disabled mapping is not even parsed
+        north.source_direction = Some("Invalid".to_string());
+
+        let engine = MappingEngine::load_from_profile(&profile).unwrap();
+
+        assert_eq!(engine.button_rules.len(), 5);
+        assert!(!engine.button_rules.contains_key(&ButtonCode::North));
+    }
+
     #[test]
     fn test_load_from_invalid_profile() {
         use crate::mapping::Mapping;
@@ -287,11 +747,478 @@ mod tests {
                 source_direction: Some("Invalid".to_string()),
                 target_type: TargetType::Keyboard,
                 target_name: "A".to_string(),
+                scale: None,
+                invert: None,
+                condition_modifier: None,
+                priority: None,
+                enabled: true,
             }],
             settings: Default::default(),
+            metadata: Default::default(),
         };
 
         let result = MappingEngine::load_from_profile(&profile);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_explain_mapped_button() {
+        let engine = MappingEngine::new_hardcoded();
+
+        assert_eq!(
+            engine.explain(&InputEvent::button_press(ButtonCode::South)),
+            "South → S (press) [matched ButtonToKey rule]"
+        );
+        assert_eq!(
+            engine.explain(&InputEvent::button_release(ButtonCode::South)),
+            "South → S (release) [matched ButtonToKey rule]"
+        );
+    }
+
+    #[test]
+    fn test_explain_unmapped_button() {
+        let engine = MappingEngine::new_hardcoded();
+
+        assert_eq!(
+            engine.explain(&InputEvent::button_press(ButtonCode::North)),
+            "North → (no rule for button)"
+        );
+    }
+
+    #[test]
+    fn test_explain_non_dpad_axis() {
+        let engine = MappingEngine::new_hardcoded();
+
+        assert_eq!(
+            engine.explain(&InputEvent::axis_move(AxisCode::LeftX, 140)),
+            "Left X = 140 → (no rule, non-DPad axis)"
+        );
+    }
+
+    #[test]
+    fn test_explain_dpad_is_side_effect_free() {
+        let mut engine = MappingEngine::new_hardcoded();
+        let up = InputEvent::axis_move(AxisCode::DPadY, -1);
+
+        assert_eq!(
+            engine.explain(&up),
+            "DPad Y = -1 → Up (press) [matched AxisDirectionToKey rule]"
+        );
+        // Explaining twice gives the same answer because state was not updated
+        assert_eq!(
+            engine.explain(&up),
+            "DPad Y = -1 → Up (press) [matched AxisDirectionToKey rule]"
+        );
+
+        engine.process(&up).unwrap();
+        assert_eq!(engine.explain(&up), "DPad Y = -1 → (direction unchanged)");
+        assert_eq!(
+            engine.explain(&InputEvent::axis_move(AxisCode::DPadY, 0)),
+            "DPad Y = 0 → Up (release) [matched AxisDirectionToKey rule]"
+        );
+    }
+
+    #[test]
+    fn test_explain_sync() {
+        let engine = MappingEngine::new_hardcoded();
+
+        assert_eq!(engine.explain(&InputEvent::sync()), "Sync → (ignored, frame boundary)");
+    }
+
+    fn rumble_profile() -> Profile {
+        let mut profile = Profile::default_profile();
+        profile.mappings.push(crate::mapping::Mapping {
+            source_name: ButtonCode::RightShoulder.to_string(),
+            source_direction: None,
+            target_type: crate::mapping::types::TargetType::Rumble,
+            target_name: "100:100:250".to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        });
+        profile
+    }
+
+    #[test]
+    fn test_media_rule_fires_on_press_only() {
+        let toml = r#"
+name = "Couch"
+description = ""
+
+[[mappings]]
+source_name = "Mode"
+target_type = "Media"
+target_name = "play pause"
+"#;
+        let profile =
+            Profile::from_str_as(toml, crate::mapping::profile::ProfileFormat::Toml).unwrap();
+        assert_eq!(profile.mappings[0].target_name, "Play/Pause");
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+
+        assert!(engine.target_keys().contains(&KeyboardCode::PlayPause));
+        let press = InputEvent::button_press(ButtonCode::Mode);
+        assert_eq!(
+            engine.process(&press).unwrap(),
+            vec![OutputEvent::MediaKey {
+                code: MediaKeyCode::PlayPause,
+                event_type: KeyboardEventType::Press
+            }]
+        );
+        assert_eq!(
+            engine.explain(&press),
+            "Mode → media Play/Pause (press) [matched ButtonToMedia rule]"
+        );
+        assert_eq!(
+            engine.process(&InputEvent::button_release(ButtonCode::Mode)).unwrap(),
+            vec![OutputEvent::MediaKey {
+                code: MediaKeyCode::PlayPause,
+                event_type: KeyboardEventType::Release
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rumble_rule_fires_on_press_only() {
+        let mut profile = rumble_profile();
+        profile.settings.vibration_intensity = 50;
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+
+        let events = engine.process(&InputEvent::button_press(ButtonCode::RightShoulder)).unwrap();
+        assert_eq!(
+            events,
+            vec![OutputEvent::Rumble { weak: 32767, strong: 32767, duration_ms: 250 }]
+        );
+
+        let events =
+            engine.process(&InputEvent::button_release(ButtonCode::RightShoulder)).unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_rumble_rule_skipped_when_vibration_disabled() {
+        let mut profile = rumble_profile();
+        profile.settings.vibration_enabled = false;
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+
+        let events = engine.process(&InputEvent::button_press(ButtonCode::RightShoulder)).unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_enabled_axes_and_buttons_filter_events() {
+        let mut profile = Profile::default_profile();
+        profile.settings.enabled_axes = Some(vec![AxisCode::DPadX]);
+        profile.settings.enabled_buttons = Some(vec![ButtonCode::East]);
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+
+        // Disabled
+        assert!(engine.process(&InputEvent::axis_move(AxisCode::DPadY, -1)).unwrap().is_empty());
+        assert!(engine.process(&InputEvent::button_press(ButtonCode::South)).unwrap().is_empty());
+        assert_eq!(
+            engine.explain(&InputEvent::button_press(ButtonCode::South)),
+            "South → (button disabled by profile)"
+        );
+
+        // Enabled
+        assert_eq!(engine.process(&InputEvent::axis_move(AxisCode::DPadX, 1)).unwrap().len(), 1);
+        assert_eq!(engine.process(&InputEvent::button_press(ButtonCode::East)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_axis_to_axis_rule() {
+        let mut profile = Profile::default_profile();
+        profile.mappings.push(crate::mapping::Mapping {
+            source_name: AxisCode::LeftX.to_string(),
+            source_direction: None,
+            target_type: crate::mapping::types::TargetType::GamepadAxis,
+            target_name: "RightX".to_string(),
+            scale: Some(0.5),
+            invert: Some(true),
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        });
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+
+        assert_eq!(engine.target_axes(), HashSet::from([AxisCode::RightX]));
+        assert_eq!(
+            engine.process(&InputEvent::axis_move(AxisCode::LeftX, 1001)).unwrap(),
+            vec![OutputEvent::GamepadAxis { code: AxisCode::RightX, value: -501 }]
+        );
+        assert_eq!(
+            engine.explain(&InputEvent::axis_move(AxisCode::LeftX, 200)),
+            "Left X = 200 → gamepad Right X = -100 [matched AxisToAxis rule]"
+        );
+
+        profile.settings.axis_curves.insert(AxisCode::LeftX, ResponseCurve::Quadratic);
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+        assert_eq!(
+            engine.process(&InputEvent::axis_move(AxisCode::LeftX, 16384)).unwrap(),
+            vec![OutputEvent::GamepadAxis { code: AxisCode::RightX, value: -4096 }]
+        );
+        assert_eq!(
+            engine.process(&InputEvent::axis_move(AxisCode::LeftX, -32768)).unwrap(),
+            vec![OutputEvent::GamepadAxis { code: AxisCode::RightX, value: 16384 }]
+        );
+    }
+
+    #[test]
+    fn test_set_device_ranges_rescales_axes() {
+        use crate::input::gamepad::{BusType, GamepadType};
+
+        let mut profile = Profile::default_profile();
+        profile.mappings.push(crate::mapping::Mapping {
+            source_name: AxisCode::LeftX.to_string(),
+            source_direction: None,
+            target_type: crate::mapping::types::TargetType::GamepadAxis,
+            target_name: "LeftX".to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        });
+        let info = GamepadInfo {
+            path: "/dev/input/event3".to_string(),
+            name: "Wireless Controller".to_string(),
+            gamepad_type: GamepadType::DualShock4,
+            vendor_id: 0x054C,
+            vendor_name: "Sony".to_string(),
+            product_id: 0x09CC,
+            version: 0,
+            bus_type: BusType::Usb,
+            capabilities: vec![],
+            axis_ranges: HashMap::from([(
+                AxisCode::LeftX,
+                AxisRange { min: 0, max: 255, flat: 15, fuzz: 0 },
+            )]),
+        };
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+        engine.set_device_ranges(&info);
+
+        let forward = |engine: &mut MappingEngine, value| {
+            engine.process(&InputEvent::axis_move(AxisCode::LeftX, value)).unwrap()
+        };
+        let axis = |value| vec![OutputEvent::GamepadAxis { code: AxisCode::LeftX, value }];
+        assert_eq!(forward(&mut engine, 255), axis(32767));
+        assert_eq!(forward(&mut engine, 0), axis(-32768));
+        // Within `flat` of center
+        assert_eq!(forward(&mut engine, 140), axis(0));
+    }
+
+    /// Default profile plus North → R, and North → F while LeftShoulder is held
+    #[test]
+    fn test_apply_calibration_moves_center() {
+        let mut profile = Profile::default_profile();
+        for axis in [AxisCode::LeftX, AxisCode::LeftY] {
+            profile.mappings.push(crate::mapping::Mapping {
+                source_name: axis.to_string(),
+                source_direction: None,
+                target_type: crate::mapping::types::TargetType::GamepadAxis,
+                target_name: format!("{:?}", axis),
+                scale: None,
+                invert: None,
+                condition_modifier: None,
+                priority: None,
+                enabled: true,
+            });
+        }
+        let info = GamepadInfo {
+            axis_ranges: HashMap::from([
+                (AxisCode::LeftX, AxisRange { min: 0, max: 255, flat: 2, fuzz: 0 }),
+                (AxisCode::LeftY, AxisRange { min: 0, max: 255, flat: 2, fuzz: 0 }),
+            ]),
+            ..GamepadInfo::test("/dev/input/event3", "Pad")
+        };
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+        engine.set_device_ranges(&info);
+
+        let forward = |engine: &mut MappingEngine, code, value| {
+            engine.process(&InputEvent::axis_move(code, value)).unwrap()
+        };
+        let axis = |code, value| vec![OutputEvent::GamepadAxis { code, value }];
+        assert_eq!(forward(&mut engine, AxisCode::LeftX, 127), axis(AxisCode::LeftX, 0));
+
+        // This stick rests at 100, not 127
+        let cal = CalibrationProfile {
+            axes: HashMap::from([(
+                AxisCode::LeftX,
+                AxisRange { min: 0, max: 200, flat: 10, fuzz: 0 },
+            )]),
+        };
+        engine.apply_calibration(&cal);
+
+        assert_eq!(forward(&mut engine, AxisCode::LeftX, 100), axis(AxisCode::LeftX, 0));
+        assert_eq!(forward(&mut engine, AxisCode::LeftX, 200), axis(AxisCode::LeftX, 32767));
+        // Not in `cal`, still uses the device range
+        assert_eq!(forward(&mut engine, AxisCode::LeftY, 127), axis(AxisCode::LeftY, 0));
+    }
+
+    fn conditional_profile(plain_north: bool, priority: Option<RulePriority>) -> Profile {
+        let north = |target: &str, condition_modifier: Option<&str>| crate::mapping::Mapping {
+            source_name: ButtonCode::North.to_string(),
+            source_direction: None,
+            target_type: crate::mapping::types::TargetType::Keyboard,
+            target_name: target.to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: condition_modifier.map(str::to_string),
+            priority,
+            enabled: true,
+        };
+
+        let mut profile = Profile::default_profile();
+        profile.mappings.retain(|mapping| mapping.source_name != "North");
+        if plain_north {
+            profile.mappings.push(crate::mapping::Mapping { priority: None, ..north("R", None) });
+        }
+        profile.mappings.push(north("F", Some("LeftShoulder")));
+        profile
+    }
+
+    fn key(code: KeyboardCode, event_type: KeyboardEventType) -> Vec<OutputEvent> {
+        vec![OutputEvent::Keyboard { code, event_type }]
+    }
+
+    #[test]
+    fn test_high_priority_conditional_rule() {
+        let profile = conditional_profile(true, Some(RulePriority::High));
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+        assert!(engine.target_keys().contains(&KeyboardCode::F));
+
+        let north = InputEvent::button_press(ButtonCode::North);
+        assert_eq!(engine.process(&north).unwrap(), key(KeyboardCode::R, KeyboardEventType::Press));
+        engine.process(&InputEvent::button_release(ButtonCode::North)).unwrap();
+
+        engine.process(&InputEvent::button_press(ButtonCode::LeftShoulder)).unwrap();
+        assert_eq!(
+            engine.explain(&north),
+            "North → F (press) [matched ConditionalButtonToKey rule]"
+        );
+        assert_eq!(engine.process(&north).unwrap(), key(KeyboardCode::F, KeyboardEventType::Press));
+
+        // Releasing the modifier first still releases the key that was pressed
+        engine.process(&InputEvent::button_release(ButtonCode::LeftShoulder)).unwrap();
+        assert_eq!(
+            engine.process(&InputEvent::button_release(ButtonCode::North)).unwrap(),
+            key(KeyboardCode::F, KeyboardEventType::Release)
+        );
+    }
+
+    #[test]
+    fn test_normal_priority_conditional_rule_yields_to_plain_rule() {
+        let profile = conditional_profile(true, None);
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+
+        engine.process(&InputEvent::button_press(ButtonCode::LeftShoulder)).unwrap();
+        assert_eq!(
+            engine.process(&InputEvent::button_press(ButtonCode::North)).unwrap(),
+            key(KeyboardCode::R, KeyboardEventType::Press)
+        );
+    }
+
+    #[test]
+    fn test_reset_state_forgets_held_inputs() {
+        let mut engine =
+            MappingEngine::load_from_profile(&conditional_profile(false, None)).unwrap();
+        let up = InputEvent::axis_move(AxisCode::DPadY, -1);
+        engine.process(&up).unwrap();
+        engine.process(&InputEvent::button_press(ButtonCode::LeftShoulder)).unwrap();
+
+        engine.reset_state();
+
+        // The held direction is pressed again and the modifier no longer counts
+        assert_eq!(engine.process(&up).unwrap(), key(KeyboardCode::Up, KeyboardEventType::Press));
+        let north = InputEvent::button_press(ButtonCode::North);
+        assert!(engine.process(&north).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_conditional_rule_without_plain_rule() {
+        let profile = conditional_profile(false, None);
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+
+        let north = InputEvent::button_press(ButtonCode::North);
+        assert!(engine.process(&north).unwrap().is_empty());
+        engine.process(&InputEvent::button_release(ButtonCode::North)).unwrap();
+
+        engine.process(&InputEvent::button_press(ButtonCode::LeftShoulder)).unwrap();
+        assert_eq!(engine.process(&north).unwrap(), key(KeyboardCode::F, KeyboardEventType::Press));
+    }
+
+    #[test]
+    fn test_route_to_multiple_outputs() {
+        let mut profile = Profile::default_profile();
+        profile.mappings.retain(|m| m.source_name == "South");
+        let mut enter = profile.mappings[0].clone();
+        enter.target_name = KeyboardCode::Enter.to_string();
+        profile.mappings.push(enter);
+
+        // Without allow_multiple the last mapping wins
+        let engine = MappingEngine::load_from_profile(&profile).unwrap();
+        assert_eq!(engine.route_to_multiple_outputs(ButtonCode::South), vec![KeyboardCode::Enter]);
+
+        profile.settings.allow_multiple = true;
+        assert!(profile.validate().iter().all(|issue| issue.code != "duplicate_source"));
+        let mut engine = MappingEngine::load_from_profile(&profile).unwrap();
+        assert_eq!(
+            engine.route_to_multiple_outputs(ButtonCode::South),
+            vec![KeyboardCode::S, KeyboardCode::Enter]
+        );
+
+        let press = engine.process(&InputEvent::button_press(ButtonCode::South)).unwrap();
+        assert_eq!(
+            press,
+            [
+                key(KeyboardCode::S, KeyboardEventType::Press),
+                key(KeyboardCode::Enter, KeyboardEventType::Press)
+            ]
+            .concat()
+        );
+        let release = engine.process(&InputEvent::button_release(ButtonCode::South)).unwrap();
+        assert_eq!(release.len(), 2);
+    }
+
+    #[test]
+    fn test_process_batch() {
+        let mut engine = MappingEngine::new_hardcoded();
+
+        let outputs = engine
+            .process_batch(&[
+                InputEvent::button_press(ButtonCode::South),
+                InputEvent::sync(),
+                InputEvent::axis_move(AxisCode::DPadY, i32::MIN),
+                InputEvent::axis_move(AxisCode::LeftX, i32::MAX),
+                InputEvent::axis_move(AxisCode::DPadY, 0),
+                InputEvent::button_release(ButtonCode::South),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            outputs,
+            [
+                key(KeyboardCode::S, KeyboardEventType::Press),
+                key(KeyboardCode::Up, KeyboardEventType::Press),
+                key(KeyboardCode::Up, KeyboardEventType::Release),
+                key(KeyboardCode::S, KeyboardEventType::Release),
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_process_with_timing() {
+        let mut engine = MappingEngine::new_hardcoded();
+        let (events, _) =
+            engine.process_with_timing(&InputEvent::button_press(ButtonCode::South)).unwrap();
+        assert_eq!(events.len(), 1);
+
+        engine.set_timing_enabled(false);
+        let (events, elapsed) =
+            engine.process_with_timing(&InputEvent::button_release(ButtonCode::South)).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(elapsed, Duration::ZERO);
+    }
 }
@@ -0,0 +1,179 @@
+// Built-in example profiles for common game genres
+use crate::{
+    event::{AxisCode, AxisDirection, ButtonCode, KeyboardCode},
+    mapping::{
+        Mapping,
+        profile::{Profile, ProfileSettings},
+        types::TargetType,
+    },
+};
+
+impl Profile {
+    /// Built-in example profiles, one per game genre
+    ///
+    /// Profile names are the identifiers used by `profile show --example <name>`.
+    pub fn examples() -> Vec<Profile> {
+        vec![platformer(), fps(), fighting(), rpg()]
+    }
+
+    /// Look up a built-in example profile by name (case-insensitive)
+    pub fn example(name: &str) -> Option<Profile> {
+        Self::examples().into_iter().find(|profile| profile.name.eq_ignore_ascii_case(name))
+    }
+}
+
+fn button(source: ButtonCode, target: KeyboardCode) -> Mapping {
+    Mapping {
+        source_name: format!("{:?}", source),
+        source_direction: None,
+        target_type: TargetType::Keyboard,
+        target_name: target.to_string(),
+        scale: None,
+        invert: None,
+        condition_modifier: None,
+        priority: None,
+        enabled: true,
+    }
+}
+
+fn axis(source: AxisCode, direction: AxisDirection, target: KeyboardCode) -> Mapping {
+    Mapping {
+        source_name: format!("{:?}", source),
+        source_direction: Some(direction.to_string()),
+        target_type: TargetType::Keyboard,
+        target_name: target.to_string(),
+        scale: None,
+        invert: None,
+        condition_modifier: None,
+        priority: None,
+        enabled: true,
+    }
+}
+
+/// D-pad mapped to the given up/left/down/right keys
+fn dpad(
+    up: KeyboardCode,
+    left: KeyboardCode,
+    down: KeyboardCode,
+    right: KeyboardCode,
+) -> [Mapping; 4] {
+    [
+        axis(AxisCode::DPadY, AxisDirection::Negative, up),
+        axis(AxisCode::DPadX, AxisDirection::Negative, left),
+        axis(AxisCode::DPadY, AxisDirection::Positive, down),
+        axis(AxisCode::DPadX, AxisDirection::Positive, right),
+    ]
+}
+
+fn example(name: &str, description: &str, mappings: Vec<Mapping>) -> Profile {
+    Profile {
+        name: name.to_string(),
+        description: description.to_string(),
+        game_name: None,
+        mappings,
+        settings: ProfileSettings::default(),
+        metadata: Default::default(),
+    }
+}
+
+fn platformer() -> Profile {
+    let mut mappings = vec![
+        button(ButtonCode::South, KeyboardCode::Space), // Jump
+        button(ButtonCode::West, KeyboardCode::J),      // Attack
+        button(ButtonCode::East, KeyboardCode::K),      // Dash
+        button(ButtonCode::Start, KeyboardCode::Escape),
+    ];
+    mappings.extend(dpad(KeyboardCode::W, KeyboardCode::A, KeyboardCode::S, KeyboardCode::D));
+
+    example("platformer", "Jump and run: Space to jump, A/D to move, J to attack", mappings)
+}
+
+fn fps() -> Profile {
+    // The mapping engine only handles D-pad axes, so movement lives on the D-pad
+    let mut mappings = vec![
+        button(ButtonCode::South, KeyboardCode::Space), // Jump
+        button(ButtonCode::East, KeyboardCode::LeftControl), // Crouch
+        button(ButtonCode::West, KeyboardCode::R),      // Reload
+        button(ButtonCode::North, KeyboardCode::E),     // Use
+        button(ButtonCode::LeftStick, KeyboardCode::LeftShift), // Sprint
+        button(ButtonCode::LeftShoulder, KeyboardCode::Q), // Previous weapon
+        button(ButtonCode::RightShoulder, KeyboardCode::G), // Grenade
+        button(ButtonCode::Select, KeyboardCode::Tab),  // Scoreboard
+        button(ButtonCode::Start, KeyboardCode::Escape),
+    ];
+    mappings.extend(dpad(KeyboardCode::W, KeyboardCode::A, KeyboardCode::S, KeyboardCode::D));
+
+    example("fps", "First-person shooter: WASD movement on the D-pad, common action keys", mappings)
+}
+
+fn fighting() -> Profile {
+    let mut mappings = vec![
+        button(ButtonCode::West, KeyboardCode::U),  // Light punch
+        button(ButtonCode::North, KeyboardCode::I), // Heavy punch
+        button(ButtonCode::South, KeyboardCode::J), // Light kick
+        button(ButtonCode::East, KeyboardCode::K),  // Heavy kick
+        button(ButtonCode::LeftShoulder, KeyboardCode::O), // Special
+        button(ButtonCode::RightShoulder, KeyboardCode::L), // Super
+        button(ButtonCode::Start, KeyboardCode::Escape),
+    ];
+    mappings.extend(dpad(KeyboardCode::W, KeyboardCode::A, KeyboardCode::S, KeyboardCode::D));
+
+    example("fighting", "Fighting game: punches on U/I, kicks on J/K, specials on O/L", mappings)
+}
+
+fn rpg() -> Profile {
+    let mut mappings = vec![
+        button(ButtonCode::South, KeyboardCode::Space), // Action
+        button(ButtonCode::West, KeyboardCode::E),      // Interact
+        button(ButtonCode::East, KeyboardCode::Escape), // Cancel / menu
+        button(ButtonCode::North, KeyboardCode::M),     // Map
+        button(ButtonCode::Select, KeyboardCode::I),    // Inventory
+        button(ButtonCode::Start, KeyboardCode::Tab),   // Pause menu
+    ];
+    mappings.extend(dpad(
+        KeyboardCode::Up,
+        KeyboardCode::Left,
+        KeyboardCode::Down,
+        KeyboardCode::Right,
+    ));
+
+    example(
+        "rpg",
+        "Role-playing game: action, interact, menu and map on the face buttons",
+        mappings,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::{MappingEngine, profile::ProfileFormat};
+
+    #[test]
+    fn test_example_names() {
+        let names: Vec<String> = Profile::examples().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["platformer", "fps", "fighting", "rpg"]);
+    }
+
+    #[test]
+    fn test_examples_are_valid() {
+        for profile in Profile::examples() {
+            assert!(
+                MappingEngine::load_from_profile(&profile).is_ok(),
+                "Example '{}' has invalid mappings",
+                profile.name
+            );
+
+            // Survives a round trip through the on-disk format unchanged
+            let toml = profile.to_string_as(ProfileFormat::Toml).unwrap();
+            let parsed = Profile::from_str_as(&toml, ProfileFormat::Toml).unwrap();
+            assert_eq!(parsed.to_string_as(ProfileFormat::Toml).unwrap(), toml);
+        }
+    }
+
+    #[test]
+    fn test_example_lookup() {
+        assert_eq!(Profile::example("FPS").unwrap().name, "fps");
+        assert!(Profile::example("racing").is_none());
+    }
+}
@@ -0,0 +1,175 @@
+// Export the loaded rules to other remappers' formats
+use std::{fmt::Write as _, path::Path};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    event::{AxisCode, AxisDirection, ButtonCode, KeyboardCode, axis_and_direction_to_string},
+    mapping::engine::MappingEngine,
+    platform::kernel_key_name,
+};
+
+/// Where the xpadneo driver exposes its module parameters
+pub const XPADNEO_PARAMETERS: &str = "/sys/module/xpadneo/parameters";
+
+impl MappingEngine {
+    /// Write a shell script that applies the button rules through xpadneo's sysfs nodes
+    ///
+    /// Rules without an xpadneo equivalent are kept as `# unsupported: ...` comments.
+    /// The script is made executable; it has to be run as root.
+    pub fn export_as_xpad_config(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.xpad_script())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+                .with_context(|| format!("Failed to make {} executable", path.display()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Contents of the script written by [`MappingEngine::export_as_xpad_config`],
+    /// buttons first, then D-pad and stick directions, each in declaration order
+    pub fn xpad_script(&self) -> String {
+        let mut script = String::from("#!/bin/sh\n# Generated by blazeremap\nset -e\n\n");
+        let _ = writeln!(script, "PARAMS={}", XPADNEO_PARAMETERS);
+
+        for &button in ButtonCode::all() {
+            for &key in self.button_rules().get(&button).into_iter().flatten() {
+                let line = format!("{} → {}", button, key);
+                push_rule(&mut script, xpadneo_button(button), key, &line);
+            }
+        }
+
+        for &axis in AxisCode::all() {
+            for direction in [AxisDirection::Negative, AxisDirection::Positive] {
+                if let Some(&key) = self.axis_rules().get(&(axis, direction)) {
+                    let line =
+                        format!("{} → {}", axis_and_direction_to_string(axis, direction), key);
+                    push_rule(&mut script, xpadneo_dpad(axis, direction), key, &line);
+                }
+            }
+        }
+
+        script
+    }
+}
+
+fn push_rule(script: &mut String, source: Option<&str>, key: KeyboardCode, rule: &str) {
+    let _ = match source {
+        Some(source) => writeln!(
+            script,
+            "echo \"button_{}=key_{}\" > \"$PARAMS/button_map\"",
+            source,
+            xpadneo_key(key)
+        ),
+        None => writeln!(script, "# unsupported: {}", rule),
+    };
+}
+
+/// xpadneo's name for a button, `None` if it has no equivalent
+fn xpadneo_button(button: ButtonCode) -> Option<&'static str> {
+    match button {
+        ButtonCode::South => Some("A"),
+        ButtonCode::East => Some("B"),
+        ButtonCode::North => Some("Y"),
+        ButtonCode::West => Some("X"),
+        ButtonCode::LeftShoulder => Some("LB"),
+        ButtonCode::RightShoulder => Some("RB"),
+        ButtonCode::Select => Some("BACK"),
+        ButtonCode::Start => Some("START"),
+        ButtonCode::LeftStick => Some("LS"),
+        ButtonCode::RightStick => Some("RS"),
+        ButtonCode::Mode => Some("GUIDE"),
+        ButtonCode::Misc1 => Some("SHARE"),
+        ButtonCode::Paddle1 => Some("P1"),
+        ButtonCode::Paddle2 => Some("P2"),
+        ButtonCode::Paddle3 => Some("P3"),
+        ButtonCode::Paddle4 => Some("P4"),
+        // Triggers are analog on xpadneo; no touchpad on Xbox controllers
+        ButtonCode::LeftTrigger
+        | ButtonCode::RightTrigger
+        | ButtonCode::Touchpad
+        | ButtonCode::Unknown => None,
+    }
+}
+
+/// xpadneo's name for a D-pad direction; stick directions have none
+fn xpadneo_dpad(axis: AxisCode, direction: AxisDirection) -> Option<&'static str> {
+    match (axis, direction) {
+        (AxisCode::DPadX, AxisDirection::Negative) => Some("DPAD_LEFT"),
+        (AxisCode::DPadX, AxisDirection::Positive) => Some("DPAD_RIGHT"),
+        (AxisCode::DPadY, AxisDirection::Negative) => Some("DPAD_UP"),
+        (AxisCode::DPadY, AxisDirection::Positive) => Some("DPAD_DOWN"),
+        _ => None,
+    }
+}
+
+/// Kernel key name without the `KEY_` prefix, e.g. `SPACE` or `LEFTSHIFT`
+fn xpadneo_key(key: KeyboardCode) -> String {
+    let name = kernel_key_name(key);
+    name.strip_prefix("KEY_").unwrap_or(&name).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::{MappingEngineBuilder, profile::Profile};
+
+    #[test]
+    fn test_xpad_script_for_default_profile() {
+        let engine = MappingEngine::load_from_profile(&Profile::default_profile()).unwrap();
+        let script = engine.xpad_script();
+
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("PARAMS=/sys/module/xpadneo/parameters\n"));
+        assert!(script.contains("echo \"button_A=key_S\" > \"$PARAMS/button_map\"\n"));
+        assert!(script.contains("echo \"button_DPAD_UP=key_UP\" > \"$PARAMS/button_map\"\n"));
+        assert!(!script.contains("unsupported"));
+        // South comes before the D-pad
+        assert!(script.find("button_A=").unwrap() < script.find("button_DPAD_LEFT=").unwrap());
+    }
+
+    #[test]
+    fn test_xpad_script_comments_out_unsupported_rules() {
+        let script = MappingEngineBuilder::new()
+            .add_button_rule(ButtonCode::LeftTrigger, KeyboardCode::LeftShift)
+            .add_axis_rule(AxisCode::LeftX, AxisDirection::Positive, KeyboardCode::D)
+            .build()
+            .unwrap()
+            .xpad_script();
+
+        assert!(script.contains("# unsupported: Left Trigger → Left Shift\n"));
+        assert!(script.contains("# unsupported: Left X Right → D\n"));
+        assert!(!script.contains("echo"));
+    }
+
+    #[test]
+    fn test_xpadneo_key_uses_kernel_names() {
+        assert_eq!(xpadneo_key(KeyboardCode::Escape), "ESC");
+        assert_eq!(xpadneo_key(KeyboardCode::Num1), "1");
+        assert_eq!(xpadneo_key(KeyboardCode::LeftControl), "LEFTCTRL");
+        assert_eq!(xpadneo_key(KeyboardCode::Space), "SPACE");
+    }
+
+    #[test]
+    fn test_export_writes_executable_script() {
+        let path =
+            std::env::temp_dir().join(format!("blazeremap-xpadneo-{}.sh", std::process::id()));
+        let engine = MappingEngine::new_hardcoded();
+
+        engine.export_as_xpad_config(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::metadata(&path).unwrap().permissions().mode()
+        };
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(contents, engine.xpad_script());
+        assert_eq!(mode & 0o777, 0o755);
+    }
+}
@@ -1,21 +1,38 @@
+pub mod builder;
+pub mod calibration;
+pub mod curve;
 pub mod engine;
+pub mod examples;
+pub mod export;
+pub mod optimize;
 pub mod profile;
 pub mod rules;
+pub mod schema;
 pub mod types;
+pub mod validate;
 
+pub use builder::MappingEngineBuilder;
+pub use calibration::CalibrationProfile;
+pub use curve::{ResponseCurve, apply_curve};
 pub use engine::MappingEngine;
+pub use optimize::{RemovalReason, RemovedMapping};
 pub use rules::MappingRule;
 pub use rules::MappingRule::AxisDirectionToKey;
 pub use rules::MappingRule::ButtonToKey;
+pub use validate::{Severity, ValidationIssue};
+
+use std::fmt;
 
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::mapping::types::TargetType;
+use crate::event::{AxisCode, ButtonCode};
+use crate::mapping::types::{RulePriority, TargetType};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Mapping {
-    /// Source button name (for readability)
+    /// Source button or axis, e.g. `South` or `DPadY` (display names like
+    /// `DPad Y` are accepted too)
     pub source_name: String,
 
     /// Source direction (up, right, left, down)
@@ -27,4 +44,105 @@ pub struct Mapping {
 
     /// Target key name (for readability)
     pub target_name: String,
+
+    /// Multiplier for `GamepadAxis` targets (0.0-2.0, default 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f32>,
+
+    /// Negate the value for `GamepadAxis` targets (default false)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invert: Option<bool>,
+
+    /// Keyboard targets only: apply this mapping only while the named button is held
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition_modifier: Option<String>,
+
+    /// `high` lets a conditional mapping win over the button's plain mapping
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<RulePriority>,
+
+    /// `false` keeps the mapping in the profile without loading it
+    #[serde(default = "default_enabled", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+/// See [`Mapping::override_key`]
+pub type OverrideKey<'a> = (
+    (&'a str, Option<&'a str>, Option<&'a str>),
+    TargetType,
+    Option<RulePriority>,
+    Option<&'a str>,
+);
+
+impl Mapping {
+    /// Two mappings with the same key bind the same physical input under the same condition
+    pub fn source_key(&self) -> (&str, Option<&str>, Option<&str>) {
+        (&self.source_name, self.source_direction.as_deref(), self.condition_modifier.as_deref())
+    }
+
+    /// Display name of the source, e.g. `DPad Y` for `DPadY`; unknown names as written
+    fn source_label(&self) -> String {
+        if self.source_direction.is_some() || self.target_type == TargetType::GamepadAxis {
+            match AxisCode::from(self.source_name.as_str()) {
+                AxisCode::Unknown => self.source_name.clone(),
+                axis => axis.to_string(),
+            }
+        } else {
+            match ButtonCode::from(self.source_name.as_str()) {
+                ButtonCode::Unknown => self.source_name.clone(),
+                button => button.to_string(),
+            }
+        }
+    }
+
+    /// Only one of several mappings with the same key is used (see [`Mapping::first_wins`])
+    ///
+    /// Mappings with different target types (key, rumble, media key, axis) all fire,
+    /// as do conditional mappings with different priorities. With `allow_multiple`,
+    /// plain button-to-key mappings are also told apart by their target.
+    pub fn override_key(&self, allow_multiple: bool) -> OverrideKey<'_> {
+        let plain_key = self.target_type == TargetType::Keyboard
+            && self.source_direction.is_none()
+            && self.condition_modifier.is_none()
+            && self.priority.is_none();
+        let priority = self.condition_modifier.is_some().then(|| self.priority.unwrap_or_default());
+        let target = (allow_multiple && plain_key).then_some(self.target_name.as_str());
+        (self.source_key(), self.target_type, priority, target)
+    }
+
+    /// Whether the first of several mappings with the same override key is used
+    /// rather than the last: the engine tries conditional mappings in profile order
+    pub fn first_wins(&self) -> bool {
+        self.condition_modifier.is_some()
+    }
+}
+
+impl fmt::Display for Mapping {
+    /// e.g. `South → S` or `DPad Y Negative → Up`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source_label())?;
+        if let Some(direction) = &self.source_direction {
+            write!(f, " {}", direction)?;
+        }
+
+        match self.target_type {
+            TargetType::Keyboard => write!(f, " → {}", self.target_name)?,
+            other => write!(f, " → {:?} {}", other, self.target_name)?,
+        }
+
+        if let Some(modifier) = &self.condition_modifier {
+            write!(f, " while {} held", modifier)?;
+        }
+        if self.priority == Some(RulePriority::High) {
+            write!(f, " (high priority)")?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,272 @@
+// Profile optimization - drop mappings that can never fire and sort the rest
+use std::{cmp::Ordering, collections::HashMap, fmt};
+
+use crate::{
+    event::{AxisCode, ButtonCode, KeyboardCode, MediaKeyCode},
+    mapping::{Mapping, profile::Profile, types::TargetType},
+};
+
+/// Why [`Profile::optimize`] dropped a mapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalReason {
+    /// A later mapping binds the same source, and the last mapping wins
+    Duplicate,
+    /// An earlier conditional mapping binds the same source and condition, and
+    /// conditional mappings are tried in order
+    Shadowed,
+    /// The source is not a known button or axis
+    UnknownSource,
+    /// The target is not a known key or axis
+    UnknownTarget,
+}
+
+impl fmt::Display for RemovalReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate => write!(f, "overridden by a later mapping"),
+            Self::Shadowed => write!(f, "shadowed by an earlier mapping"),
+            Self::UnknownSource => write!(f, "unknown source"),
+            Self::UnknownTarget => write!(f, "unknown target"),
+        }
+    }
+}
+
+/// A mapping removed by [`Profile::optimize`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemovedMapping {
+    pub mapping: Mapping,
+    pub reason: RemovalReason,
+    /// 1-based position in the original profile
+    pub index: usize,
+}
+
+impl fmt::Display for RemovedMapping {
+    /// e.g. `mapping 3: South → Space (overridden by a later mapping)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mapping {}: {} ({})", self.index, self.mapping, self.reason)
+    }
+}
+
+impl Profile {
+    /// A copy of the profile without duplicate or unreachable mappings, in canonical order
+    ///
+    /// Of several mappings for the same source and target type only the one the
+    /// engine uses is kept: the last, or the first for conditional mappings (unless
+    /// `allow_multiple` lets them all fire). Mappings with an unknown source or target
+    /// are dropped. The rest are sorted buttons first, then axes, alphabetically within
+    /// each group; conditional mappings keep their order, which decides which one fires.
+    pub fn optimize(&self) -> (Profile, Vec<RemovedMapping>) {
+        let allow_multiple = self.settings.allow_multiple;
+
+        // Index of the mapping the engine uses for each override key
+        let mut used = HashMap::new();
+        // A disabled mapping never overrides anything, so it cannot be a duplicate
+        for (index, mapping) in self.mappings.iter().enumerate().filter(|(_, m)| m.enabled) {
+            let key = mapping.override_key(allow_multiple);
+            if !(mapping.first_wins() && used.contains_key(&key)) {
+                used.insert(key, index);
+            }
+        }
+
+        let mut removed = Vec::new();
+        let mut mappings = Vec::new();
+        for (index, mapping) in self.mappings.iter().enumerate() {
+            let used_index = used.get(&mapping.override_key(allow_multiple));
+            let reason = match used_index {
+                Some(&used) if mapping.enabled && used != index => Some(if mapping.first_wins() {
+                    RemovalReason::Shadowed
+                } else {
+                    RemovalReason::Duplicate
+                }),
+                _ if source_group(mapping).is_none() => Some(RemovalReason::UnknownSource),
+                _ if !has_known_target(mapping) => Some(RemovalReason::UnknownTarget),
+                _ => None,
+            };
+
+            match reason {
+                Some(reason) => removed.push(RemovedMapping {
+                    mapping: mapping.clone(),
+                    reason,
+                    index: index + 1,
+                }),
+                None => mappings.push(mapping.clone()),
+            }
+        }
+
+        // Stable, so conditional mappings for a button stay in profile order
+        mappings.sort_by(canonical_order);
+
+        (Profile { mappings, ..self.clone() }, removed)
+    }
+}
+
+/// 0 for buttons, 1 for axes, `None` if the source is neither
+fn source_group(mapping: &Mapping) -> Option<u8> {
+    let name = mapping.source_name.as_str();
+    if ButtonCode::from(name) != ButtonCode::Unknown {
+        Some(0)
+    } else if AxisCode::from(name) != AxisCode::Unknown {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+fn has_known_target(mapping: &Mapping) -> bool {
+    match mapping.target_type {
+        TargetType::Keyboard => {
+            KeyboardCode::from(mapping.target_name.as_str()) != KeyboardCode::Unknown
+        }
+        TargetType::GamepadAxis => {
+            AxisCode::from(mapping.target_name.as_str()) != AxisCode::Unknown
+        }
+        TargetType::Media => MediaKeyCode::try_from_display(&mapping.target_name).is_some(),
+        _ => true,
+    }
+}
+
+fn canonical_order(a: &Mapping, b: &Mapping) -> Ordering {
+    source_group(a)
+        .cmp(&source_group(b))
+        .then_with(|| a.source_name.cmp(&b.source_name))
+        .then_with(|| a.source_direction.cmp(&b.source_direction))
+        .then_with(|| a.condition_modifier.is_some().cmp(&b.condition_modifier.is_some()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources(profile: &Profile) -> Vec<String> {
+        profile
+            .mappings
+            .iter()
+            .map(|m| match &m.source_direction {
+                Some(direction) => format!("{} {}", m.source_name, direction),
+                None => m.source_name.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_optimize_sorts_buttons_before_axes() {
+        let (optimized, removed) = Profile::default_profile().optimize();
+
+        assert!(removed.is_empty());
+        assert_eq!(
+            sources(&optimized),
+            vec![
+                "East",
+                "North",
+                "Select",
+                "South",
+                "Start",
+                "West",
+                "DPadX Negative",
+                "DPadX Positive",
+                "DPadY Negative",
+                "DPadY Positive",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_optimize_removes_duplicates_and_unknowns() {
+        let mut profile = Profile::default_profile();
+        profile.mappings.retain(|m| m.source_name == "South");
+        let mut replacement = profile.mappings[0].clone();
+        replacement.target_name = KeyboardCode::Space.to_string();
+        let mut unknown_source = replacement.clone();
+        unknown_source.source_name = "Turbo".to_string();
+        let mut unknown_target = replacement.clone();
+        unknown_target.source_name = ButtonCode::Mode.to_string();
+        unknown_target.target_name = KeyboardCode::Unknown.to_string();
+        profile.mappings.extend([replacement, unknown_source, unknown_target]);
+
+        let (optimized, removed) = profile.optimize();
+
+        assert_eq!(optimized.mappings.len(), 1);
+        assert_eq!(optimized.mappings[0].target_name, "Space");
+        assert_eq!(
+            removed.iter().map(|r| (r.index, r.reason)).collect::<Vec<_>>(),
+            vec![
+                (1, RemovalReason::Duplicate),
+                (3, RemovalReason::UnknownSource),
+                (4, RemovalReason::UnknownTarget),
+            ]
+        );
+        assert_eq!(removed[0].to_string(), "mapping 1: South → S (overridden by a later mapping)");
+    }
+
+    #[test]
+    fn test_optimize_disabled_mapping_does_not_override() {
+        let mut profile = Profile::default_profile();
+        profile.mappings.retain(|m| m.source_name == "South");
+        let mut disabled = profile.mappings[0].clone();
+        disabled.target_name = KeyboardCode::Space.to_string();
+        disabled.enabled = false;
+        profile.mappings.push(disabled);
+
+        let (optimized, removed) = profile.optimize();
+
+        assert!(removed.is_empty());
+        assert_eq!(optimized.mappings.len(), 2);
+    }
+
+    #[test]
+    fn test_optimize_keeps_mappings_with_other_target_types() {
+        let mut profile = Profile::default_profile();
+        profile.mappings.retain(|m| m.source_name == "South");
+        let mut rumble = profile.mappings[0].clone();
+        rumble.target_type = TargetType::Rumble;
+        rumble.target_name = "50:50:100".to_string();
+        profile.mappings.push(rumble);
+
+        let (optimized, removed) = profile.optimize();
+
+        assert!(removed.is_empty());
+        assert_eq!(optimized.mappings.len(), 2);
+    }
+
+    #[test]
+    fn test_optimize_keeps_conditional_order() {
+        let mut profile = Profile::default_profile();
+        profile.mappings.retain(|m| m.source_name == "North");
+        let conditional = |modifier: ButtonCode, target: KeyboardCode| Mapping {
+            condition_modifier: Some(modifier.to_string()),
+            target_name: target.to_string(),
+            ..profile.mappings[0].clone()
+        };
+        profile.mappings.extend([
+            conditional(ButtonCode::RightShoulder, KeyboardCode::F),
+            conditional(ButtonCode::LeftShoulder, KeyboardCode::G),
+            // Never used: the first mapping with this condition wins
+            conditional(ButtonCode::RightShoulder, KeyboardCode::H),
+        ]);
+
+        let (optimized, removed) = profile.optimize();
+
+        assert_eq!(
+            removed.iter().map(|r| (r.index, r.reason)).collect::<Vec<_>>(),
+            vec![(4, RemovalReason::Shadowed)]
+        );
+        let targets: Vec<&str> =
+            optimized.mappings.iter().map(|m| m.target_name.as_str()).collect();
+        assert_eq!(targets, ["W", "F", "G"]);
+    }
+
+    #[test]
+    fn test_optimize_keeps_conditional_mappings() {
+        let mut profile = Profile::default_profile();
+        profile.mappings.retain(|m| m.source_name == "North");
+        let mut shifted = profile.mappings[0].clone();
+        shifted.condition_modifier = Some(ButtonCode::LeftShoulder.to_string());
+        profile.mappings.insert(0, shifted);
+
+        let (optimized, removed) = profile.optimize();
+
+        assert!(removed.is_empty());
+        assert_eq!(optimized.mappings[0].condition_modifier, None);
+        assert_eq!(optimized.mappings[1].condition_modifier.as_deref(), Some("Left Shoulder"));
+    }
+}
@@ -1,12 +1,135 @@
 // src/mapping/profile.rs
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    event::{AxisCode, AxisDirection, ButtonCode, KeyboardCode},
-    mapping::{Mapping, types::TargetType},
+    event::{AxisCode, AxisDirection, ButtonCode, KeyboardCode, MediaKeyCode},
+    mapping::{Mapping, ResponseCurve, types::TargetType},
 };
 
+/// File formats a profile can be stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ProfileFormat {
+    /// Guess the format from a file extension (`.toml`, `.yaml`/`.yml`, `.json`)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        extension.parse().ok()
+    }
+}
+
+impl FromStr for ProfileFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "toml" => Ok(Self::Toml),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            "json" => Ok(Self::Json),
+            _ => anyhow::bail!("Unsupported profile format '{}' (expected toml, yaml or json)", s),
+        }
+    }
+}
+
+impl fmt::Display for ProfileFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Toml => write!(f, "TOML"),
+            Self::Yaml => write!(f, "YAML"),
+            Self::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+/// Why a profile could not be loaded, with a stable code for `--error-format json`
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileError {
+    #[error("Failed to parse profile {format}")]
+    Parse {
+        format: ProfileFormat,
+        /// 1-based position of the error, when the parser reports one
+        line: Option<usize>,
+        column: Option<usize>,
+        #[source]
+        source: anyhow::Error,
+    },
+
+    #[error("Unknown key name '{name}' in mapping #{index}")]
+    UnknownKey {
+        name: String,
+        /// 1-based mapping number
+        index: usize,
+    },
+}
+
+impl ProfileError {
+    /// Machine-readable error code, e.g. `profile_parse`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Parse { .. } => "profile_parse",
+            Self::UnknownKey { .. } => "profile_unknown_key",
+        }
+    }
+
+    /// 1-based line and column in the profile source, if known
+    pub fn location(&self) -> Option<(usize, usize)> {
+        match self {
+            Self::Parse { line: Some(line), column: Some(column), .. } => Some((*line, *column)),
+            _ => None,
+        }
+    }
+
+    fn toml(contents: &str, error: toml::de::Error) -> Self {
+        let position = error.span().map(|span| line_column(contents, span.start));
+        Self::Parse {
+            format: ProfileFormat::Toml,
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+            source: error.into(),
+        }
+    }
+
+    fn yaml(error: serde_yaml::Error) -> Self {
+        let location = error.location();
+        Self::Parse {
+            format: ProfileFormat::Yaml,
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            source: error.into(),
+        }
+    }
+
+    fn json(error: serde_json::Error) -> Self {
+        // serde_json reports line 0 when the error has no position
+        let known = error.line() > 0;
+        Self::Parse {
+            format: ProfileFormat::Json,
+            line: known.then(|| error.line()),
+            column: known.then(|| error.column()),
+            source: error.into(),
+        }
+    }
+}
+
+/// 1-based line and column (in characters) of a byte offset into `contents`
+fn line_column(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
 /// Complete controller profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
@@ -19,15 +142,43 @@ pub struct Profile {
 
     #[serde(default)]
     pub settings: ProfileSettings,
+
+    /// Free-form tags for tooling, e.g. `author` or `imported_from` (not validated)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProfileSettings {
     #[serde(default = "default_vibration_enabled")]
     pub vibration_enabled: bool,
 
     #[serde(default = "default_vibration_intensity")]
     pub vibration_intensity: u8, // 0-100
+
+    /// Name of the virtual keyboard created for this profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_device_name: Option<String>,
+
+    /// Only process these axes (all axes if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled_axes: Option<Vec<AxisCode>>,
+
+    /// Only process these buttons (all buttons if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled_buttons: Option<Vec<ButtonCode>>,
+
+    /// Warn when processing one event takes longer than this (no budget if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_budget_us: Option<u64>,
+
+    /// Let several keyboard mappings for one button all fire instead of the last one winning
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_multiple: bool,
+
+    /// Response curve per source axis of `GamepadAxis` mappings (linear if not set)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub axis_curves: HashMap<AxisCode, ResponseCurve>,
 }
 
 fn default_vibration_enabled() -> bool {
@@ -42,11 +193,200 @@ impl Default for ProfileSettings {
         Self {
             vibration_enabled: default_vibration_enabled(),
             vibration_intensity: default_vibration_intensity(),
+            output_device_name: None,
+            enabled_axes: None,
+            enabled_buttons: None,
+            latency_budget_us: None,
+            allow_multiple: false,
+            axis_curves: HashMap::new(),
+        }
+    }
+}
+
+/// Setting names accepted by [`ProfileSettings::get`] and [`ProfileSettings::set`]
+pub const SETTING_NAMES: &[&str] = &[
+    "vibration_enabled",
+    "vibration_intensity",
+    "output_device_name",
+    "enabled_axes",
+    "enabled_buttons",
+    "latency_budget_us",
+    "allow_multiple",
+];
+
+fn unknown_setting(name: &str) -> anyhow::Error {
+    anyhow::anyhow!("Unknown setting '{}'. Valid settings: {}", name, SETTING_NAMES.join(", "))
+}
+
+/// Comma-separated list, or `all` when every code is enabled
+fn format_code_list<T: fmt::Debug>(codes: &Option<Vec<T>>) -> String {
+    match codes {
+        Some(codes) => codes.iter().map(|c| format!("{:?}", c)).collect::<Vec<_>>().join(","),
+        None => "all".to_string(),
+    }
+}
+
+/// Parse `all` or a comma-separated list of names, rejecting names that map to `unknown`
+fn parse_code_list<T>(value: &str, unknown: T) -> Result<Option<Vec<T>>>
+where
+    T: for<'a> From<&'a str> + PartialEq,
+{
+    if value.trim().eq_ignore_ascii_case("all") {
+        return Ok(None);
+    }
+
+    let mut codes = Vec::new();
+    for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let code = T::from(name);
+        if code == unknown {
+            anyhow::bail!("Unknown name '{}'", name);
         }
+        codes.push(code);
+    }
+    Ok(Some(codes))
+}
+
+impl ProfileSettings {
+    /// Current value of the setting called `name`, formatted as `set` accepts it
+    pub fn get(&self, name: &str) -> Result<String> {
+        Ok(match name {
+            "vibration_enabled" => self.vibration_enabled.to_string(),
+            "vibration_intensity" => self.vibration_intensity.to_string(),
+            "output_device_name" => self.output_device_name.clone().unwrap_or_default(),
+            "enabled_axes" => format_code_list(&self.enabled_axes),
+            "enabled_buttons" => format_code_list(&self.enabled_buttons),
+            "latency_budget_us" => {
+                self.latency_budget_us.map(|us| us.to_string()).unwrap_or_else(|| "none".into())
+            }
+            "allow_multiple" => self.allow_multiple.to_string(),
+            _ => return Err(unknown_setting(name)),
+        })
+    }
+
+    /// Parse `value` and store it in the setting called `name`
+    ///
+    /// An empty `output_device_name`, `all` for the axis/button lists and
+    /// `none` for `latency_budget_us` restore the defaults.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        let invalid = || format!("Invalid value '{}' for {}", value, name);
+
+        match name {
+            "vibration_enabled" => {
+                self.vibration_enabled = value.trim().parse().with_context(invalid)?;
+            }
+            "vibration_intensity" => {
+                let intensity: u8 = value.trim().parse().with_context(invalid)?;
+                if intensity > 100 {
+                    anyhow::bail!("{} (expected 0 to 100)", invalid());
+                }
+                self.vibration_intensity = intensity;
+            }
+            "output_device_name" => {
+                self.output_device_name = Some(value.to_string()).filter(|v| !v.is_empty());
+            }
+            "enabled_axes" => {
+                self.enabled_axes =
+                    parse_code_list(value, AxisCode::Unknown).with_context(invalid)?;
+            }
+            "enabled_buttons" => {
+                self.enabled_buttons =
+                    parse_code_list(value, ButtonCode::Unknown).with_context(invalid)?;
+            }
+            "latency_budget_us" => {
+                self.latency_budget_us = match value.trim() {
+                    "none" => None,
+                    value => Some(value.parse().with_context(invalid)?),
+                };
+            }
+            "allow_multiple" => {
+                self.allow_multiple = value.trim().parse().with_context(invalid)?;
+            }
+            _ => return Err(unknown_setting(name)),
+        }
+        Ok(())
+    }
+}
+
+/// Differences between two versions of a profile
+///
+/// Mappings are matched by their source (`source_name`, `source_direction`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProfileDiff {
+    pub added: Vec<Mapping>,
+    pub removed: Vec<Mapping>,
+    /// `(old, new)` pairs with the same source but a different target
+    pub changed: Vec<(Mapping, Mapping)>,
+    pub settings_changed: bool,
+}
+
+impl ProfileDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && !self.settings_changed
     }
 }
 
 impl Profile {
+    /// Whether every `(key, value)` filter matches this profile's metadata exactly
+    pub fn matches_metadata(&self, filters: &[(String, String)]) -> bool {
+        filters.iter().all(|(key, value)| self.metadata.get(key) == Some(value))
+    }
+
+    /// Compare two profiles mapping by mapping
+    pub fn diff(base: &Profile, modified: &Profile) -> ProfileDiff {
+        let find = |profile: &Profile, mapping: &Mapping| {
+            profile.mappings.iter().find(|m| m.source_key() == mapping.source_key()).cloned()
+        };
+
+        let mut diff = ProfileDiff::default();
+
+        for new in &modified.mappings {
+            match find(base, new) {
+                None => diff.added.push(new.clone()),
+                Some(old) if old != *new => diff.changed.push((old, new.clone())),
+                Some(_) => {}
+            }
+        }
+
+        for old in &base.mappings {
+            if find(modified, old).is_none() {
+                diff.removed.push(old.clone());
+            }
+        }
+
+        diff.settings_changed = base.settings != modified.settings;
+        diff
+    }
+
+    /// Layer `overlay` on top of `base`
+    ///
+    /// Overlay mappings replace base mappings with the same source and are
+    /// appended otherwise. Name, description, game and settings come from the
+    /// overlay; metadata is combined, with overlay values winning.
+    pub fn merge(base: &Profile, overlay: &Profile) -> Profile {
+        let mut mappings = base.mappings.clone();
+        for mapping in &overlay.mappings {
+            match mappings.iter_mut().find(|m| m.source_key() == mapping.source_key()) {
+                Some(existing) => *existing = mapping.clone(),
+                None => mappings.push(mapping.clone()),
+            }
+        }
+
+        let mut metadata = base.metadata.clone();
+        metadata.extend(overlay.metadata.clone());
+
+        Profile {
+            name: overlay.name.clone(),
+            description: overlay.description.clone(),
+            game_name: overlay.game_name.clone().or_else(|| base.game_name.clone()),
+            mappings,
+            settings: overlay.settings.clone(),
+            metadata,
+        }
+    }
+
     /// Create a default profile (hardcoded mappings)
     pub fn default_profile() -> Self {
         Self {
@@ -55,95 +395,354 @@ impl Profile {
             game_name: None,
             mappings: vec![
                 Mapping {
-                    source_name: ButtonCode::North.to_string(),
+                    source_name: format!("{:?}", ButtonCode::North),
                     source_direction: None,
                     target_type: TargetType::Keyboard,
                     target_name: KeyboardCode::W.to_string(),
+                    scale: None,
+                    invert: None,
+                    condition_modifier: None,
+                    priority: None,
+                    enabled: true,
                 },
                 Mapping {
-                    source_name: ButtonCode::West.to_string(),
+                    source_name: format!("{:?}", ButtonCode::West),
                     source_direction: None,
                     target_type: TargetType::Keyboard,
                     target_name: KeyboardCode::A.to_string(),
+                    scale: None,
+                    invert: None,
+                    condition_modifier: None,
+                    priority: None,
+                    enabled: true,
                 },
                 Mapping {
-                    source_name: ButtonCode::South.to_string(),
+                    source_name: format!("{:?}", ButtonCode::South),
                     source_direction: None,
                     target_type: TargetType::Keyboard,
                     target_name: KeyboardCode::S.to_string(),
+                    scale: None,
+                    invert: None,
+                    condition_modifier: None,
+                    priority: None,
+                    enabled: true,
                 },
                 Mapping {
-                    source_name: ButtonCode::East.to_string(),
+                    source_name: format!("{:?}", ButtonCode::East),
                     source_direction: None,
                     target_type: TargetType::Keyboard,
                     target_name: KeyboardCode::D.to_string(),
+                    scale: None,
+                    invert: None,
+                    condition_modifier: None,
+                    priority: None,
+                    enabled: true,
                 },
                 Mapping {
-                    source_name: ButtonCode::Select.to_string(),
+                    source_name: format!("{:?}", ButtonCode::Select),
                     source_direction: None,
                     target_type: TargetType::Keyboard,
                     target_name: KeyboardCode::Escape.to_string(),
+                    scale: None,
+                    invert: None,
+                    condition_modifier: None,
+                    priority: None,
+                    enabled: true,
                 },
                 Mapping {
-                    source_name: ButtonCode::Start.to_string(),
+                    source_name: format!("{:?}", ButtonCode::Start),
                     source_direction: None,
                     target_type: TargetType::Keyboard,
                     target_name: KeyboardCode::Enter.to_string(),
+                    scale: None,
+                    invert: None,
+                    condition_modifier: None,
+                    priority: None,
+                    enabled: true,
                 },
                 //
                 Mapping {
-                    source_name: AxisCode::DPadY.to_string(),
+                    source_name: format!("{:?}", AxisCode::DPadY),
                     source_direction: Some(AxisDirection::Negative.to_string()),
                     target_type: TargetType::Keyboard,
                     target_name: KeyboardCode::Up.to_string(),
+                    scale: None,
+                    invert: None,
+                    condition_modifier: None,
+                    priority: None,
+                    enabled: true,
                 },
                 Mapping {
-                    source_name: AxisCode::DPadY.to_string(),
+                    source_name: format!("{:?}", AxisCode::DPadY),
                     source_direction: Some(AxisDirection::Positive.to_string()),
                     target_type: TargetType::Keyboard,
                     target_name: KeyboardCode::Down.to_string(),
+                    scale: None,
+                    invert: None,
+                    condition_modifier: None,
+                    priority: None,
+                    enabled: true,
                 },
                 Mapping {
-                    source_name: AxisCode::DPadX.to_string(),
+                    source_name: format!("{:?}", AxisCode::DPadX),
                     source_direction: Some(AxisDirection::Negative.to_string()),
                     target_type: TargetType::Keyboard,
                     target_name: KeyboardCode::Left.to_string(),
+                    scale: None,
+                    invert: None,
+                    condition_modifier: None,
+                    priority: None,
+                    enabled: true,
                 },
                 Mapping {
-                    source_name: AxisCode::DPadX.to_string(),
+                    source_name: format!("{:?}", AxisCode::DPadX),
                     source_direction: Some(AxisDirection::Positive.to_string()),
                     target_type: TargetType::Keyboard,
                     target_name: KeyboardCode::Right.to_string(),
+                    scale: None,
+                    invert: None,
+                    condition_modifier: None,
+                    priority: None,
+                    enabled: true,
                 },
             ],
             settings: ProfileSettings::default(),
+            metadata: Default::default(),
         }
     }
 
-    /// Save profile to TOML file
-    pub fn save_to_file(&self, path: &std::path::Path) -> Result<()> {
-        let toml_string = toml::to_string_pretty(self).context("Failed to serialize profile")?;
+    /// Save a profile, picking the format from the file extension (TOML if unknown)
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        self.save_to_file_as(path, ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Toml))
+    }
 
-        std::fs::write(path, toml_string).context("Failed to write profile file")?;
+    /// Save profile to a file in the given format
+    pub fn save_to_file_as(&self, path: &Path, format: ProfileFormat) -> Result<()> {
+        let contents = self.to_string_as(format)?;
+
+        std::fs::write(path, contents).context("Failed to write profile file")?;
 
         Ok(())
     }
 
-    /// Load profile from TOML file
-    pub fn load_from_file(path: &std::path::Path) -> Result<Self> {
-        let toml_string = std::fs::read_to_string(path).context("Failed to read profile file")?;
+    /// Load a profile, picking the format from the file extension (TOML if unknown)
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        Self::load_from_file_as(path, ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Toml))
+    }
+
+    /// Load profile from a file in the given format
+    pub fn load_from_file_as(path: &Path, format: ProfileFormat) -> Result<Self> {
+        if is_backup_path(path) {
+            tracing::warn!("{} is a profile backup, not the live profile", path.display());
+        }
+
+        let bytes = std::fs::read(path).context("Failed to read profile file")?;
+
+        let contents = String::from_utf8(bytes).map_err(|e| {
+            anyhow::anyhow!(
+                "Profile file {} is not valid UTF-8 (invalid byte at offset {})",
+                path.display(),
+                e.utf8_error().valid_up_to()
+            )
+        })?;
+
+        Self::from_str_as(&contents, format)
+    }
+
+    /// Serialize the profile in the given format
+    pub fn to_string_as(&self, format: ProfileFormat) -> Result<String> {
+        let contents = match format {
+            ProfileFormat::Toml => toml::to_string_pretty(self)?,
+            ProfileFormat::Yaml => serde_yaml::to_string(self)?,
+            ProfileFormat::Json => serde_json::to_string_pretty(self)?,
+        };
+
+        Ok(contents)
+    }
+
+    /// Serialize the profile as pretty-printed JSON, with the same field names as TOML
+    pub fn to_json(&self) -> Result<String> {
+        self.to_string_as(ProfileFormat::Json)
+    }
+
+    /// Parse a profile from JSON
+    pub fn from_json(contents: &str) -> Result<Self> {
+        Self::from_str_as(contents, ProfileFormat::Json)
+    }
+
+    /// Parse a profile from a string in the given format
+    pub fn from_str_as(contents: &str, format: ProfileFormat) -> Result<Self> {
+        let mut profile: Profile = match format {
+            ProfileFormat::Toml => {
+                toml::from_str(contents).map_err(|e| ProfileError::toml(contents, e))
+            }
+            ProfileFormat::Yaml => serde_yaml::from_str(contents).map_err(ProfileError::yaml),
+            ProfileFormat::Json => serde_json::from_str(contents).map_err(ProfileError::json),
+        }?;
 
-        let profile: Profile =
-            toml::from_str(&toml_string).context("Failed to parse profile JSON")?;
+        // Accept key names in any case ("space", "SPACE", "left_shift") and store
+        // them in their canonical display form
+        for (index, mapping) in profile.mappings.iter_mut().enumerate() {
+            let name = match mapping.target_type {
+                TargetType::Keyboard => {
+                    KeyboardCode::try_from_display(&mapping.target_name).map(|c| c.to_string())
+                }
+                TargetType::Media => {
+                    MediaKeyCode::try_from_display(&mapping.target_name).map(|c| c.to_string())
+                }
+                _ => continue,
+            };
+            mapping.target_name = name.ok_or_else(|| ProfileError::UnknownKey {
+                name: mapping.target_name.clone(),
+                index: index + 1,
+            })?;
+        }
 
         Ok(profile)
     }
 }
 
+/// Per-user configuration directory, `$XDG_CONFIG_HOME/blazeremap` or
+/// `~/.config/blazeremap`
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            PathBuf::from(std::env::var_os("HOME").filter(|dir| !dir.is_empty())?).join(".config")
+        }
+    };
+    Some(base.join("blazeremap"))
+}
+
+/// Directory user profiles are kept in, `<config_dir>/profiles`
+pub fn profiles_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("profiles"))
+}
+
+/// Suffix `profile backup` appends to a file name, followed by a UTC timestamp
+pub const BACKUP_MARKER: &str = ".bak.";
+
+/// Whether `path` looks like `<name>.bak.YYYY-MM-DDTHH:MM:SS`
+pub fn is_backup_path(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    name.rsplit_once(BACKUP_MARKER).is_some_and(|(_, stamp)| is_backup_timestamp(stamp))
+}
+
+fn is_backup_timestamp(stamp: &str) -> bool {
+    const SHAPE: &[u8] = b"0000-00-00T00:00:00";
+    stamp.len() == SHAPE.len()
+        && stamp.bytes().zip(SHAPE).all(|(c, shape)| match shape {
+            b'0' => c.is_ascii_digit(),
+            _ => c == *shape,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_metadata_round_trip_and_filter() {
+        let mut profile = Profile::default_profile();
+        let toml = profile.to_string_as(ProfileFormat::Toml).unwrap();
+        assert!(!toml.contains("[metadata]"));
+
+        profile.metadata.insert("game".to_string(), "Dark Souls".to_string());
+        profile.metadata.insert("author".to_string(), "sam".to_string());
+        let toml = profile.to_string_as(ProfileFormat::Toml).unwrap();
+        let loaded = Profile::from_str_as(&toml, ProfileFormat::Toml).unwrap();
+        assert_eq!(loaded.metadata, profile.metadata);
+
+        let filter = |key: &str, value: &str| (key.to_string(), value.to_string());
+        assert!(loaded.matches_metadata(&[]));
+        assert!(loaded.matches_metadata(&[filter("game", "Dark Souls")]));
+        assert!(!loaded.matches_metadata(&[filter("game", "Dark Souls"), filter("author", "kim")]));
+        assert!(!loaded.matches_metadata(&[filter("tags", "rpg")]));
+    }
+
+    #[test]
+    fn test_merge_overrides_by_source() {
+        let base = Profile::default_profile();
+        let mut overlay = Profile::default_profile();
+        overlay.name = "Game".to_string();
+        overlay.mappings.retain(|m| m.source_name == "South");
+        overlay.mappings[0].target_name = "Space".to_string();
+        let mut extra = overlay.mappings[0].clone();
+        extra.source_name = "Mode".to_string();
+        overlay.mappings.push(extra);
+        overlay.metadata.insert("game".to_string(), "Celeste".to_string());
+
+        let merged = Profile::merge(&base, &overlay);
+
+        assert_eq!(merged.name, "Game");
+        assert_eq!(merged.mappings.len(), base.mappings.len() + 1);
+        let south = merged.mappings.iter().find(|m| m.source_name == "South").unwrap();
+        assert_eq!(south.target_name, "Space");
+        assert_eq!(merged.mappings.last().unwrap().source_name, "Mode");
+        assert_eq!(merged.metadata.get("game").map(String::as_str), Some("Celeste"));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let json = Profile::default_profile().to_json().unwrap();
+        assert!(json.contains("\"source_name\""));
+
+        let loaded = Profile::from_json(&json).unwrap();
+        assert_eq!(loaded.to_json().unwrap(), json);
+    }
+
+    #[test]
+    fn test_load_from_file_detects_json() {
+        let path = std::env::temp_dir().join(format!("blazeremap_{}.json", std::process::id()));
+        std::fs::write(&path, Profile::default_profile().to_json().unwrap()).unwrap();
+
+        let loaded = Profile::load_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap().name, "Default");
+    }
+
+    #[test]
+    fn test_settings_get_set() {
+        let mut settings = ProfileSettings::default();
+        assert_eq!(settings.get("vibration_intensity").unwrap(), "100");
+        assert_eq!(settings.get("enabled_axes").unwrap(), "all");
+
+        settings.set("vibration_intensity", "80").unwrap();
+        settings.set("vibration_enabled", "false").unwrap();
+        settings.set("output_device_name", "Pad").unwrap();
+        settings.set("enabled_buttons", "South, East").unwrap();
+        settings.set("latency_budget_us", "500").unwrap();
+
+        assert_eq!(settings.vibration_intensity, 80);
+        assert!(!settings.vibration_enabled);
+        assert_eq!(settings.output_device_name.as_deref(), Some("Pad"));
+        assert_eq!(settings.get("enabled_buttons").unwrap(), "South,East");
+        assert_eq!(settings.latency_budget_us, Some(500));
+
+        settings.set("output_device_name", "").unwrap();
+        settings.set("enabled_buttons", "all").unwrap();
+        settings.set("latency_budget_us", "none").unwrap();
+        assert_eq!(settings.get("latency_budget_us").unwrap(), "none");
+        assert_eq!(settings.output_device_name, None);
+        assert_eq!(settings.enabled_buttons, None);
+    }
+
+    #[test]
+    fn test_settings_set_rejects_bad_values() {
+        let mut settings = ProfileSettings::default();
+        assert!(settings.set("vibration_intensity", "101").is_err());
+        assert!(settings.set("vibration_intensity", "loud").is_err());
+        assert!(settings.set("vibration_enabled", "yes").is_err());
+        assert!(settings.set("enabled_axes", "LeftX,Wheel").is_err());
+        assert_eq!(settings, ProfileSettings::default());
+
+        let err = settings.get("volume").unwrap_err().to_string();
+        assert!(err.contains("Valid settings: vibration_enabled, vibration_intensity"));
+    }
+
     #[test]
     fn test_default_profile() {
         let profile = Profile::default_profile();
@@ -190,25 +789,25 @@ target_type = "Keyboard"
 target_name = "Enter"
 
 [[mappings]]
-source_name = "DPad Y"
+source_name = "DPadY"
 source_direction = "Negative"
 target_type = "Keyboard"
 target_name = "Up"
 
 [[mappings]]
-source_name = "DPad Y"
+source_name = "DPadY"
 source_direction = "Positive"
 target_type = "Keyboard"
 target_name = "Down"
 
 [[mappings]]
-source_name = "DPad X"
+source_name = "DPadX"
 source_direction = "Negative"
 target_type = "Keyboard"
 target_name = "Left"
 
 [[mappings]]
-source_name = "DPad X"
+source_name = "DPadX"
 source_direction = "Positive"
 target_type = "Keyboard"
 target_name = "Right"
@@ -253,4 +852,229 @@ vibration_intensity = 100
         // Cleanup
         std::fs::remove_file(path).ok();
     }
+
+    #[test]
+    fn test_profile_format_from_path() {
+        assert_eq!(ProfileFormat::from_path(Path::new("a.toml")), Some(ProfileFormat::Toml));
+        assert_eq!(ProfileFormat::from_path(Path::new("a.yaml")), Some(ProfileFormat::Yaml));
+        assert_eq!(ProfileFormat::from_path(Path::new("a.YML")), Some(ProfileFormat::Yaml));
+        assert_eq!(ProfileFormat::from_path(Path::new("a.json")), Some(ProfileFormat::Json));
+        assert_eq!(ProfileFormat::from_path(Path::new("a.txt")), None);
+        assert_eq!(ProfileFormat::from_path(Path::new("profile")), None);
+    }
+
+    #[test]
+    fn test_profile_round_trip_all_formats() {
+        let profile = Profile::default_profile();
+
+        for format in [ProfileFormat::Toml, ProfileFormat::Yaml, ProfileFormat::Json] {
+            let contents = profile.to_string_as(format).unwrap();
+            let loaded = Profile::from_str_as(&contents, format).unwrap();
+
+            assert_eq!(profile.name, loaded.name);
+            assert_eq!(profile.mappings.len(), loaded.mappings.len());
+            assert_eq!(profile.mappings[6].source_direction, loaded.mappings[6].source_direction);
+        }
+    }
+
+    #[test]
+    fn test_load_rejects_non_utf8() {
+        let path = std::env::temp_dir().join("blazeremap_test_non_utf8.toml");
+        std::fs::write(&path, b"name = \"\xff\xfe\"").unwrap();
+
+        let result = Profile::load_from_file(&path);
+        std::fs::remove_file(&path).ok();
+
+        assert!(result.unwrap_err().to_string().contains("not valid UTF-8"));
+    }
+
+    #[test]
+    fn test_load_normalizes_target_names() {
+        let path = std::env::temp_dir().join("blazeremap_test_normalize_targets.toml");
+        std::fs::write(
+            &path,
+            r#"name = "Normalize"
+description = "Mixed-case key names"
+
+[[mappings]]
+source_name = "South"
+target_type = "Keyboard"
+target_name = "SPACE"
+
+[[mappings]]
+source_name = "West"
+target_type = "Keyboard"
+target_name = "left_shift"
+"#,
+        )
+        .unwrap();
+
+        let loaded = Profile::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.mappings[0].target_name, "Space");
+        assert_eq!(loaded.mappings[1].target_name, "Left Shift");
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_target_name() {
+        let path = std::env::temp_dir().join("blazeremap_test_unknown_target.toml");
+        std::fs::write(
+            &path,
+            r#"name = "Broken"
+description = "Unknown key name"
+
+[[mappings]]
+source_name = "South"
+target_type = "Keyboard"
+target_name = "NotAKey"
+"#,
+        )
+        .unwrap();
+
+        let result = Profile::load_from_file(&path);
+        std::fs::remove_file(&path).ok();
+
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("Unknown key name 'NotAKey'"));
+    }
+
+    #[test]
+    fn test_diff_identical_profiles() {
+        let profile = Profile::default_profile();
+        assert!(Profile::diff(&profile, &profile).is_empty());
+    }
+
+    #[test]
+    fn test_diff_added_removed_changed() {
+        let base = Profile::default_profile();
+        let mut modified = base.clone();
+
+        // Change South → S to South → Space
+        modified.mappings[2].target_name = KeyboardCode::Space.to_string();
+        // Remove North → W
+        let removed = modified.mappings.remove(0);
+        // Add Mode → Tab
+        let added = Mapping {
+            source_name: ButtonCode::Mode.to_string(),
+            source_direction: None,
+            target_type: TargetType::Keyboard,
+            target_name: KeyboardCode::Tab.to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        };
+        modified.mappings.push(added.clone());
+        modified.settings.vibration_intensity = 50;
+
+        let diff = Profile::diff(&base, &modified);
+
+        assert_eq!(diff.added, vec![added]);
+        assert_eq!(diff.removed, vec![removed]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].0.target_name, "S");
+        assert_eq!(diff.changed[0].1.target_name, "Space");
+        assert!(diff.settings_changed);
+    }
+
+    #[test]
+    fn test_diff_matches_axis_mappings_by_direction() {
+        let base = Profile::default_profile();
+        let mut modified = base.clone();
+
+        // DPad Y Positive → Down becomes DPad Y Positive → S
+        let index = modified
+            .mappings
+            .iter()
+            .position(|m| m.source_key() == ("DPadY", Some("Positive"), None))
+            .unwrap();
+        modified.mappings[index].target_name = KeyboardCode::S.to_string();
+
+        let diff = Profile::diff(&base, &modified);
+
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].0.source_direction.as_deref(), Some("Positive"));
+    }
+
+    #[test]
+    fn test_load_enabled_axes_and_buttons() {
+        let contents = r#"name = "Filtered"
+description = "Ignores the slider"
+mappings = []
+
+[settings]
+enabled_axes = ["DPadX", "DPadY"]
+enabled_buttons = ["South"]
+"#;
+
+        let profile = Profile::from_str_as(contents, ProfileFormat::Toml).unwrap();
+
+        assert_eq!(profile.settings.enabled_axes, Some(vec![AxisCode::DPadX, AxisCode::DPadY]));
+        assert_eq!(profile.settings.enabled_buttons, Some(vec![ButtonCode::South]));
+        assert!(Profile::default_profile().settings.enabled_axes.is_none());
+    }
+
+    #[test]
+    fn test_mapping_enabled_defaults_to_true() {
+        let contents = r#"name = "Toggled"
+description = "One mapping switched off"
+
+[[mappings]]
+source_name = "South"
+target_type = "Keyboard"
+target_name = "Space"
+
+[[mappings]]
+source_name = "East"
+target_type = "Keyboard"
+target_name = "E"
+enabled = false
+"#;
+
+        let profile = Profile::from_str_as(contents, ProfileFormat::Toml).unwrap();
+        let saved = profile.to_string_as(ProfileFormat::Toml).unwrap();
+
+        assert!(profile.mappings[0].enabled);
+        assert!(!profile.mappings[1].enabled);
+        assert_eq!(saved.matches("enabled = false").count(), 1);
+        assert!(!saved.contains("\nenabled = true"));
+    }
+
+    #[test]
+    fn test_load_axis_curves() {
+        let contents = r#"name = "Curved"
+description = "Slow aim"
+mappings = []
+
+[settings.axis_curves]
+LeftX = "quadratic"
+RightX = { custom = [[0.5, 0.2], [1.0, 1.0]] }
+"#;
+
+        let profile = Profile::from_str_as(contents, ProfileFormat::Toml).unwrap();
+
+        let curves = &profile.settings.axis_curves;
+        assert_eq!(curves.get(&AxisCode::LeftX), Some(&ResponseCurve::Quadratic));
+        assert_eq!(
+            curves.get(&AxisCode::RightX),
+            Some(&ResponseCurve::Custom(vec![(0.5, 0.2), (1.0, 1.0)]))
+        );
+
+        let saved = profile.to_string_as(ProfileFormat::Toml).unwrap();
+        let reloaded = Profile::from_str_as(&saved, ProfileFormat::Toml).unwrap();
+        assert_eq!(reloaded.settings, profile.settings);
+    }
+
+    #[test]
+    fn test_is_backup_path() {
+        assert!(is_backup_path(Path::new("/tmp/foo.toml.bak.2025-01-15T14:32:00")));
+        assert!(!is_backup_path(Path::new("/tmp/foo.toml")));
+        assert!(!is_backup_path(Path::new("foo.toml.bak")));
+        assert!(!is_backup_path(Path::new("foo.toml.bak.yesterday")));
+        assert!(!is_backup_path(Path::new("foo.toml.bak.2025-01-15 14:32:00")));
+    }
 }
@@ -1,14 +1,75 @@
+use std::collections::HashSet;
+
 use thiserror::Error;
 
 use crate::{
-    event::{AxisCode, AxisDirection, ButtonCode, KeyboardCode},
-    mapping::Mapping,
+    event::{
+        AxisCode, AxisDirection, ButtonCode, KeyboardCode, MediaKeyCode,
+        axis_and_direction_to_string,
+    },
+    mapping::{
+        Mapping,
+        types::{RulePriority, TargetType},
+    },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// When a `ConditionalButtonToKey` rule applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    /// While this button is held
+    ModifierHeld(ButtonCode),
+    Always,
+}
+
+impl Condition {
+    pub fn is_met(&self, held_buttons: &HashSet<ButtonCode>) -> bool {
+        match self {
+            Self::ModifierHeld(button) => held_buttons.contains(button),
+            Self::Always => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum MappingRule {
-    ButtonToKey { source: ButtonCode, target: KeyboardCode },
-    AxisDirectionToKey { source: AxisCode, direction: AxisDirection, target: KeyboardCode },
+    ButtonToKey {
+        source: ButtonCode,
+        target: KeyboardCode,
+    },
+    AxisDirectionToKey {
+        source: AxisCode,
+        direction: AxisDirection,
+        target: KeyboardCode,
+    },
+    /// Rumble the gamepad when the button is pressed (magnitudes are 0-65535)
+    ButtonToRumble {
+        source: ButtonCode,
+        weak: u16,
+        strong: u16,
+        duration_ms: u32,
+    },
+    /// Hold a media key while the button is held
+    ButtonToMedia {
+        source: ButtonCode,
+        target: MediaKeyCode,
+    },
+    /// Forward an axis to an axis of the virtual gamepad, `value * scale`, negated if `invert`
+    AxisToAxis {
+        source: AxisCode,
+        target: AxisCode,
+        scale: f32,
+        invert: bool,
+    },
+    /// Press `target` for `source` only while `condition` holds
+    ///
+    /// A `Normal` priority rule is only used when `source` has no `ButtonToKey`
+    /// rule; a `High` priority rule is tried first.
+    ConditionalButtonToKey {
+        source: ButtonCode,
+        target: KeyboardCode,
+        condition: Condition,
+        priority: RulePriority,
+    },
 }
 
 impl MappingRule {
@@ -23,20 +84,296 @@ impl MappingRule {
     ) -> Self {
         Self::AxisDirectionToKey { source, direction, target }
     }
+
+    /// Parse a rule from the string fields of a profile mapping
+    ///
+    /// Stricter than `TryFrom<&Mapping>`: an unknown button, axis, direction or
+    /// target type, or a target that doesn't fit the target type, is a
+    /// [`MappingRuleError::Parse`] naming the field.
+    pub fn try_from_str(
+        source: &str,
+        direction: Option<&str>,
+        target_type: &str,
+        target: &str,
+    ) -> Result<Self, MappingRuleError> {
+        let parse_error = |field: &'static str, value: &str| MappingRuleError::Parse {
+            field,
+            value: value.to_string(),
+        };
+
+        let target_type = match target_type {
+            "Keyboard" => TargetType::Keyboard,
+            "Rumble" => TargetType::Rumble,
+            "GamepadAxis" => TargetType::GamepadAxis,
+            "Media" => TargetType::Media,
+            other => return Err(parse_error("target_type", other)),
+        };
+
+        let source_is_axis = direction.is_some() || target_type == TargetType::GamepadAxis;
+        let source_name = if source_is_axis {
+            match AxisCode::from(source) {
+                AxisCode::Unknown => return Err(parse_error("source", source)),
+                axis => axis.to_string(),
+            }
+        } else {
+            match ButtonCode::from(source) {
+                ButtonCode::Unknown => return Err(parse_error("source", source)),
+                button => button.to_string(),
+            }
+        };
+
+        if let Some(direction) = direction.filter(|d| !matches!(*d, "Positive" | "Negative")) {
+            return Err(parse_error("direction", direction));
+        }
+
+        let target_name = match target_type {
+            TargetType::Keyboard => KeyboardCode::try_from_display(target)
+                .ok_or_else(|| parse_error("target", target))?
+                .to_string(),
+            TargetType::Media => MediaKeyCode::try_from_display(target)
+                .ok_or_else(|| parse_error("target", target))?
+                .to_string(),
+            TargetType::GamepadAxis => match AxisCode::from(target) {
+                AxisCode::Unknown => return Err(parse_error("target", target)),
+                axis => axis.to_string(),
+            },
+            TargetType::Rumble => {
+                parse_rumble_target(target).map_err(|_| parse_error("target", target))?;
+                target.to_string()
+            }
+            _ => target.to_string(),
+        };
+
+        MappingRule::try_from(&Mapping {
+            source_name,
+            source_direction: direction.map(str::to_string),
+            target_type,
+            target_name,
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        })
+    }
+
+    /// What the rule does in a sentence, e.g. `When the South (A/Cross) button is
+    /// pressed, emit the S key`
+    pub fn description(&self) -> String {
+        match self {
+            Self::ButtonToKey { source, target } => format!(
+                "When the {} button is pressed, emit the {} key",
+                button_label(*source),
+                key_label(*target)
+            ),
+            Self::AxisDirectionToKey { source, direction, target } => {
+                let direction = match direction_hint(*source, *direction) {
+                    Some(hint) => format!("{} direction ({})", direction_name(*direction), hint),
+                    None => format!("{} direction", direction_name(*direction)),
+                };
+                format!(
+                    "When the {} axis moves in the {}, emit the {} key",
+                    source,
+                    direction,
+                    key_label(*target)
+                )
+            }
+            Self::ButtonToRumble { source, weak, strong, duration_ms } => format!(
+                "When the {} button is pressed, rumble for {}ms ({}% weak, {}% strong)",
+                button_label(*source),
+                duration_ms,
+                magnitude_to_percent(*weak),
+                magnitude_to_percent(*strong)
+            ),
+            Self::ButtonToMedia { source, target } => format!(
+                "When the {} button is pressed, emit the {} media key",
+                button_label(*source),
+                target
+            ),
+            Self::AxisToAxis { source, target, scale, invert } => {
+                let mut description =
+                    format!("Forward the {} axis to the virtual gamepad's {} axis", source, target);
+                if *scale != 1.0 {
+                    description += &format!(", scaled by {}", scale);
+                }
+                if *invert {
+                    description += ", inverted";
+                }
+                description
+            }
+            Self::ConditionalButtonToKey { source, target, condition, priority } => {
+                let mut description = match condition {
+                    Condition::ModifierHeld(modifier) => format!(
+                        "While {} is held, pressing the {} button emits the {} key",
+                        button_label(*modifier),
+                        button_label(*source),
+                        key_label(*target)
+                    ),
+                    Condition::Always => format!(
+                        "When the {} button is pressed, emit the {} key",
+                        button_label(*source),
+                        key_label(*target)
+                    ),
+                };
+                description += match priority {
+                    RulePriority::High => ", even if the button has a plain mapping",
+                    RulePriority::Normal => ", unless the button has a plain mapping",
+                };
+                description
+            }
+        }
+    }
 }
 
-#[derive(Error, Debug)]
-#[error("Invalid source direction for mapping")]
-pub struct InvalidSourceDirectionError;
+/// `South (A/Cross)`: the button with its Xbox and PlayStation labels, if any
+fn button_label(button: ButtonCode) -> String {
+    let labels: Vec<&str> =
+        [button.to_xbox_name(), button.to_playstation_name()].into_iter().flatten().collect();
+    if labels.is_empty() || labels.iter().all(|label| *label == button.to_string()) {
+        button.to_string()
+    } else {
+        format!("{} ({})", button, labels.join("/"))
+    }
+}
+
+fn key_label(key: KeyboardCode) -> String {
+    match key {
+        KeyboardCode::Up | KeyboardCode::Down | KeyboardCode::Left | KeyboardCode::Right => {
+            format!("{} arrow", key)
+        }
+        _ => key.to_string(),
+    }
+}
+
+fn direction_name(direction: AxisDirection) -> &'static str {
+    match direction {
+        AxisDirection::Negative => "negative",
+        AxisDirection::Positive => "positive",
+    }
+}
+
+/// `up`, `left`, ... for axes where the direction has a name; `None` for triggers
+fn direction_hint(axis: AxisCode, direction: AxisDirection) -> Option<String> {
+    let named = axis_and_direction_to_string(axis, direction);
+    if named == axis.to_string() {
+        return None;
+    }
+    named.rsplit(' ').next().map(str::to_lowercase)
+}
+
+/// Inverse of `percent_to_magnitude`, rounded
+fn magnitude_to_percent(magnitude: u16) -> u32 {
+    (u32::from(magnitude) * 100 + u32::from(u16::MAX) / 2) / u32::from(u16::MAX)
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MappingRuleError {
+    #[error("Invalid source direction for mapping")]
+    InvalidSourceDirection,
+
+    #[error("Invalid rumble target '{0}' (expected weak:strong:duration_ms, e.g. 50:100:200)")]
+    InvalidRumbleTarget(String),
+
+    #[error("Invalid axis scale {0} (expected 0.0 to 2.0)")]
+    InvalidAxisScale(f32),
+
+    #[error("Unknown gamepad axis '{0}'")]
+    UnknownAxis(String),
+
+    #[error("Unknown media key '{0}'")]
+    UnknownMediaKey(String),
+
+    #[error("Unknown modifier button '{0}'")]
+    UnknownModifier(String),
+
+    #[error("Conditions and priority only apply to button-to-keyboard mappings")]
+    UnsupportedCondition,
+
+    #[error("Invalid {field} '{value}'")]
+    Parse { field: &'static str, value: String },
+}
+
+/// Range accepted for `scale` on `GamepadAxis` mappings
+pub const AXIS_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.0..=2.0;
+
+/// Parse a rumble target of the form `weak:strong:duration_ms`
+///
+/// `weak` and `strong` are percentages (0-100) and are scaled to the full
+/// force-feedback magnitude range.
+fn parse_rumble_target(target: &str) -> Result<(u16, u16, u32), MappingRuleError> {
+    let invalid = || MappingRuleError::InvalidRumbleTarget(target.to_string());
+
+    let parts: Vec<&str> = target.split(':').map(str::trim).collect();
+    let [weak, strong, duration_ms] = parts.as_slice() else {
+        return Err(invalid());
+    };
+
+    let weak: u8 = weak.parse().map_err(|_| invalid())?;
+    let strong: u8 = strong.parse().map_err(|_| invalid())?;
+    let duration_ms: u32 = duration_ms.parse().map_err(|_| invalid())?;
+
+    if weak > 100 || strong > 100 {
+        return Err(invalid());
+    }
+
+    Ok((percent_to_magnitude(weak), percent_to_magnitude(strong), duration_ms))
+}
+
+fn percent_to_magnitude(percent: u8) -> u16 {
+    (u32::from(percent) * u32::from(u16::MAX) / 100) as u16
+}
 
 impl TryFrom<&Mapping> for MappingRule {
-    type Error = InvalidSourceDirectionError;
+    type Error = MappingRuleError;
     fn try_from(mapping: &Mapping) -> Result<Self, Self::Error> {
+        if mapping.condition_modifier.is_some() || mapping.priority.is_some() {
+            return conditional_rule(mapping);
+        }
+
+        if mapping.target_type == TargetType::Rumble {
+            let (weak, strong, duration_ms) = parse_rumble_target(&mapping.target_name)?;
+            return Ok(MappingRule::ButtonToRumble {
+                source: ButtonCode::from(mapping.source_name.as_str()),
+                weak,
+                strong,
+                duration_ms,
+            });
+        }
+
+        if mapping.target_type == TargetType::Media {
+            let target = MediaKeyCode::try_from_display(&mapping.target_name)
+                .ok_or_else(|| MappingRuleError::UnknownMediaKey(mapping.target_name.clone()))?;
+            return Ok(MappingRule::ButtonToMedia {
+                source: ButtonCode::from(mapping.source_name.as_str()),
+                target,
+            });
+        }
+
+        if mapping.target_type == TargetType::GamepadAxis {
+            let scale = mapping.scale.unwrap_or(1.0);
+            if !AXIS_SCALE_RANGE.contains(&scale) {
+                return Err(MappingRuleError::InvalidAxisScale(scale));
+            }
+            let source = AxisCode::from(mapping.source_name.as_str());
+            let target = AxisCode::from(mapping.target_name.as_str());
+            for (axis, name) in [(source, &mapping.source_name), (target, &mapping.target_name)] {
+                if axis == AxisCode::Unknown {
+                    return Err(MappingRuleError::UnknownAxis(name.clone()));
+                }
+            }
+            return Ok(MappingRule::AxisToAxis {
+                source,
+                target,
+                scale,
+                invert: mapping.invert.unwrap_or(false),
+            });
+        }
+
         if mapping.source_direction.is_some() {
             let direction = match mapping.source_direction.as_deref().unwrap_or_default() {
                 "Positive" => AxisDirection::Positive,
                 "Negative" => AxisDirection::Negative,
-                _ => return Err(InvalidSourceDirectionError),
+                _ => return Err(MappingRuleError::InvalidSourceDirection),
             };
 
             Ok(MappingRule::AxisDirectionToKey {
@@ -53,6 +390,28 @@ impl TryFrom<&Mapping> for MappingRule {
     }
 }
 
+/// A `ConditionalButtonToKey` rule; without `condition_modifier` the condition is `Always`
+fn conditional_rule(mapping: &Mapping) -> Result<MappingRule, MappingRuleError> {
+    if mapping.target_type != TargetType::Keyboard || mapping.source_direction.is_some() {
+        return Err(MappingRuleError::UnsupportedCondition);
+    }
+
+    let condition = match &mapping.condition_modifier {
+        Some(name) => match ButtonCode::from(name.as_str()) {
+            ButtonCode::Unknown => return Err(MappingRuleError::UnknownModifier(name.clone())),
+            button => Condition::ModifierHeld(button),
+        },
+        None => Condition::Always,
+    };
+
+    Ok(MappingRule::ConditionalButtonToKey {
+        source: ButtonCode::from(mapping.source_name.as_str()),
+        target: KeyboardCode::from(mapping.target_name.as_str()),
+        condition,
+        priority: mapping.priority.unwrap_or_default(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::mapping::{MappingRule::AxisDirectionToKey, rules::MappingRule::ButtonToKey};
@@ -93,4 +452,265 @@ mod tests {
         assert_eq!(rule1, rule2);
         assert_ne!(rule1, rule3);
     }
+
+    #[test]
+    fn test_mapping_button_to_rumble() {
+        let mapping = Mapping {
+            source_name: ButtonCode::South.to_string(),
+            source_direction: None,
+            target_type: TargetType::Rumble,
+            target_name: "50:100:200".to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        };
+
+        assert_eq!(
+            MappingRule::try_from(&mapping),
+            Ok(MappingRule::ButtonToRumble {
+                source: ButtonCode::South,
+                weak: 32767,
+                strong: u16::MAX,
+                duration_ms: 200
+            })
+        );
+    }
+
+    #[test]
+    fn test_mapping_invalid_rumble_target() {
+        for target in ["50:100", "50:101:200", "a:b:c", "50:100:200:1"] {
+            let mapping = Mapping {
+                source_name: ButtonCode::South.to_string(),
+                source_direction: None,
+                target_type: TargetType::Rumble,
+                target_name: target.to_string(),
+                scale: None,
+                invert: None,
+                condition_modifier: None,
+                priority: None,
+                enabled: true,
+            };
+
+            assert_eq!(
+                MappingRule::try_from(&mapping),
+                Err(MappingRuleError::InvalidRumbleTarget(target.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_mapping_axis_to_axis() {
+        let mut mapping = Mapping {
+            source_name: AxisCode::LeftX.to_string(),
+            source_direction: None,
+            target_type: TargetType::GamepadAxis,
+            target_name: "RightX".to_string(),
+            scale: Some(0.5),
+            invert: Some(true),
+            condition_modifier: None,
+            priority: None,
+            enabled: true,
+        };
+
+        assert_eq!(
+            MappingRule::try_from(&mapping),
+            Ok(MappingRule::AxisToAxis {
+                source: AxisCode::LeftX,
+                target: AxisCode::RightX,
+                scale: 0.5,
+                invert: true
+            })
+        );
+
+        // Defaults
+        mapping.scale = None;
+        mapping.invert = None;
+        let Ok(MappingRule::AxisToAxis { scale, invert, .. }) = MappingRule::try_from(&mapping)
+        else {
+            panic!("expected an AxisToAxis rule");
+        };
+        assert_eq!((scale, invert), (1.0, false));
+
+        for scale in [-0.1, 2.5, f32::NAN] {
+            mapping.scale = Some(scale);
+            assert!(matches!(
+                MappingRule::try_from(&mapping),
+                Err(MappingRuleError::InvalidAxisScale(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_rule_descriptions() {
+        assert_eq!(
+            MappingRule::button_to_key(ButtonCode::South, KeyboardCode::S).description(),
+            "When the South (A/Cross) button is pressed, emit the S key"
+        );
+        assert_eq!(
+            MappingRule::axis_direction_to_key(
+                AxisCode::DPadY,
+                AxisDirection::Negative,
+                KeyboardCode::Up
+            )
+            .description(),
+            "When the DPad Y axis moves in the negative direction (up), emit the Up arrow key"
+        );
+        assert_eq!(
+            MappingRule::axis_direction_to_key(
+                AxisCode::LeftTrigger,
+                AxisDirection::Positive,
+                KeyboardCode::LeftShift
+            )
+            .description(),
+            "When the Left Trigger axis moves in the positive direction, emit the Left Shift key"
+        );
+        assert_eq!(
+            MappingRule::ButtonToRumble {
+                source: ButtonCode::Paddle1,
+                weak: percent_to_magnitude(50),
+                strong: percent_to_magnitude(100),
+                duration_ms: 200,
+            }
+            .description(),
+            "When the Paddle 1 button is pressed, rumble for 200ms (50% weak, 100% strong)"
+        );
+        assert_eq!(
+            MappingRule::AxisToAxis {
+                source: AxisCode::LeftX,
+                target: AxisCode::RightX,
+                scale: 0.5,
+                invert: true,
+            }
+            .description(),
+            "Forward the Left X axis to the virtual gamepad's Right X axis, scaled by 0.5, inverted"
+        );
+        assert_eq!(
+            MappingRule::ConditionalButtonToKey {
+                source: ButtonCode::North,
+                target: KeyboardCode::F,
+                condition: Condition::ModifierHeld(ButtonCode::LeftShoulder),
+                priority: RulePriority::High,
+            }
+            .description(),
+            "While Left Shoulder (LB/L1) is held, pressing the North (Y/Triangle) button emits \
+             the F key, even if the button has a plain mapping"
+        );
+    }
+
+    fn conditional_mapping(source_direction: Option<&str>, modifier: &str) -> Mapping {
+        Mapping {
+            source_name: ButtonCode::North.to_string(),
+            source_direction: source_direction.map(str::to_string),
+            target_type: TargetType::Keyboard,
+            target_name: "F".to_string(),
+            scale: None,
+            invert: None,
+            condition_modifier: Some(modifier.to_string()),
+            priority: Some(RulePriority::High),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_mapping_conditional_button_to_key() {
+        let mut mapping = conditional_mapping(None, "LeftShoulder");
+
+        assert_eq!(
+            MappingRule::try_from(&mapping),
+            Ok(MappingRule::ConditionalButtonToKey {
+                source: ButtonCode::North,
+                target: KeyboardCode::F,
+                condition: Condition::ModifierHeld(ButtonCode::LeftShoulder),
+                priority: RulePriority::High
+            })
+        );
+
+        // priority alone makes an always-on conditional rule
+        mapping.condition_modifier = None;
+        assert!(matches!(
+            MappingRule::try_from(&mapping),
+            Ok(MappingRule::ConditionalButtonToKey { condition: Condition::Always, .. })
+        ));
+    }
+
+    #[test]
+    fn test_mapping_invalid_condition() {
+        assert_eq!(
+            MappingRule::try_from(&conditional_mapping(None, "Shift")),
+            Err(MappingRuleError::UnknownModifier("Shift".to_string()))
+        );
+        assert_eq!(
+            MappingRule::try_from(&conditional_mapping(Some("Positive"), "LeftShoulder")),
+            Err(MappingRuleError::UnsupportedCondition)
+        );
+    }
+
+    #[test]
+    fn test_try_from_str() {
+        assert_eq!(
+            MappingRule::try_from_str("South", None, "Keyboard", "space"),
+            Ok(MappingRule::button_to_key(ButtonCode::South, KeyboardCode::Space))
+        );
+        assert_eq!(
+            MappingRule::try_from_str("DPadY", Some("Negative"), "Keyboard", "Up"),
+            Ok(MappingRule::axis_direction_to_key(
+                AxisCode::DPadY,
+                AxisDirection::Negative,
+                KeyboardCode::Up
+            ))
+        );
+        assert!(matches!(
+            MappingRule::try_from_str("LeftX", None, "GamepadAxis", "RightX"),
+            Ok(MappingRule::AxisToAxis { source: AxisCode::LeftX, target: AxisCode::RightX, .. })
+        ));
+        assert_eq!(
+            MappingRule::try_from_str("Start", None, "Media", "play/pause"),
+            Ok(MappingRule::ButtonToMedia {
+                source: ButtonCode::Start,
+                target: MediaKeyCode::PlayPause
+            })
+        );
+
+        let parse_error =
+            |field, value: &str| Err(MappingRuleError::Parse { field, value: value.to_string() });
+        assert_eq!(
+            MappingRule::try_from_str("Turbo", None, "Keyboard", "A"),
+            parse_error("source", "Turbo")
+        );
+        assert_eq!(
+            MappingRule::try_from_str("DPadY", Some("Up"), "Keyboard", "A"),
+            parse_error("direction", "Up")
+        );
+        assert_eq!(
+            MappingRule::try_from_str("South", None, "Mouse", "A"),
+            parse_error("target_type", "Mouse")
+        );
+        assert_eq!(
+            MappingRule::try_from_str("South", None, "Keyboard", "NotAKey"),
+            parse_error("target", "NotAKey")
+        );
+        assert_eq!(
+            MappingRule::try_from_str("South", None, "Rumble", "lots"),
+            parse_error("target", "lots")
+        );
+        assert_eq!(
+            MappingRule::try_from_str("South", None, "Rumble", "50:150:200"),
+            parse_error("target", "50:150:200")
+        );
+        assert_eq!(
+            MappingRule::try_from_str("LeftX", None, "GamepadAxis", "Throttle"),
+            parse_error("target", "Throttle")
+        );
+    }
+
+    #[test]
+    fn test_condition_is_met() {
+        let held = HashSet::from([ButtonCode::LeftShoulder]);
+
+        assert!(Condition::ModifierHeld(ButtonCode::LeftShoulder).is_met(&held));
+        assert!(!Condition::ModifierHeld(ButtonCode::RightShoulder).is_met(&held));
+        assert!(Condition::Always.is_met(&HashSet::new()));
+    }
 }
@@ -0,0 +1,222 @@
+// JSON Schema for the profile format, for editor validation and autocompletion
+use serde_json::{Value, json};
+
+use crate::event::{AxisCode, ButtonCode, KEYBOARD_CODE_NAMES, MediaKeyCode};
+
+/// JSON Schema (draft-07) describing a profile file
+///
+/// TOML-aware editors apply it to `.toml` profiles as well as JSON ones.
+pub fn profile_schema() -> Value {
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "BlazeRemap profile",
+        "type": "object",
+        "required": ["name", "description", "mappings"],
+        "properties": {
+            "name": { "type": "string", "description": "Profile name" },
+            "description": { "type": "string", "description": "What the profile is for" },
+            "game_name": { "type": "string", "description": "Game this profile was made for" },
+            "mappings": {
+                "type": "array",
+                "description": "Gamepad inputs and what they trigger",
+                "items": { "$ref": "#/definitions/mapping" }
+            },
+            "settings": { "$ref": "#/definitions/settings" },
+            "metadata": {
+                "type": "object",
+                "description": "Free-form tags for tooling, e.g. author or game",
+                "additionalProperties": { "type": "string" }
+            }
+        },
+        "definitions": {
+            "mapping": mapping_schema(),
+            "settings": settings_schema()
+        }
+    })
+}
+
+fn mapping_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["source_name", "target_type", "target_name"],
+        "properties": {
+            "source_name": {
+                "description": "Gamepad button or axis, e.g. South or DPadY",
+                "enum": source_names()
+            },
+            "source_direction": {
+                "description": "Axis direction; only set for axis sources",
+                "enum": ["Positive", "Negative"]
+            },
+            "target_type": {
+                "description": "Kind of output",
+                "enum": ["Keyboard", "Mouse", "Gamepad", "Rumble", "GamepadAxis", "Media"]
+            },
+            "target_name": {
+                "description": "Key name, weak:strong:duration_ms for Rumble, axis name for GamepadAxis, or media key for Media",
+                "anyOf": [
+                    { "enum": keyboard_names() },
+                    { "type": "string", "pattern": "^[0-9]+:[0-9]+:[0-9]+$" },
+                    { "enum": axis_names() },
+                    { "enum": MediaKeyCode::all().iter().map(ToString::to_string).collect::<Vec<_>>() }
+                ]
+            },
+            "scale": {
+                "type": "number",
+                "minimum": 0.0,
+                "maximum": 2.0,
+                "default": 1.0,
+                "description": "GamepadAxis only: multiplier applied to the source value"
+            },
+            "invert": {
+                "type": "boolean",
+                "default": false,
+                "description": "GamepadAxis only: negate the output value"
+            },
+            "condition_modifier": {
+                "description": "Button-to-keyboard only: apply this mapping only while this button is held",
+                "enum": button_names()
+            },
+            "priority": {
+                "description": "normal: used only if the button has no plain mapping; high: wins over it",
+                "enum": ["normal", "high"],
+                "default": "normal"
+            },
+            "enabled": {
+                "type": "boolean",
+                "default": true,
+                "description": "false keeps the mapping in the profile without loading it"
+            }
+        }
+    })
+}
+
+fn settings_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "vibration_enabled": { "type": "boolean", "default": true },
+            "vibration_intensity": {
+                "type": "integer",
+                "minimum": 0,
+                "maximum": 100,
+                "default": 100,
+                "description": "Rumble strength in percent"
+            },
+            "output_device_name": {
+                "type": "string",
+                "description": "Name of the virtual keyboard created for this profile"
+            },
+            "enabled_axes": {
+                "type": "array",
+                "description": "Only process these axes (all axes if not set)",
+                "items": { "enum": axis_names() }
+            },
+            "enabled_buttons": {
+                "type": "array",
+                "description": "Only process these buttons (all buttons if not set)",
+                "items": { "enum": button_names() }
+            },
+            "latency_budget_us": {
+                "type": "integer",
+                "minimum": 0,
+                "description": "Warn when processing one event takes longer than this many microseconds"
+            },
+            "allow_multiple": {
+                "type": "boolean",
+                "default": false,
+                "description": "Send every key mapped to a button instead of only the last mapping's"
+            },
+            "axis_curves": {
+                "type": "object",
+                "description": "Response curve per source axis of GamepadAxis mappings",
+                "propertyNames": { "enum": axis_names() },
+                "additionalProperties": {
+                    "oneOf": [
+                        { "enum": ["linear", "quadratic", "cubic", "sine"] },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "custom": {
+                                    "type": "array",
+                                    "description": "[input, output] points from 0.0 to 1.0",
+                                    "items": { "type": "array", "items": { "type": "number" }, "minItems": 2, "maxItems": 2 }
+                                }
+                            },
+                            "required": ["custom"]
+                        }
+                    ]
+                }
+            }
+        }
+    })
+}
+
+/// Variant names of every button, e.g. `LeftShoulder`
+///
+/// Buttons and axes are spelled the same way in every field, including the
+/// settings lists, which accept nothing else.
+fn button_names() -> Vec<String> {
+    ButtonCode::all().iter().map(|button| format!("{:?}", button)).collect()
+}
+
+/// Variant names of every axis, e.g. `DPadY`
+fn axis_names() -> Vec<String> {
+    AxisCode::all().iter().map(|axis| format!("{:?}", axis)).collect()
+}
+
+/// Every button and axis (triggers appear as both)
+fn source_names() -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in button_names().into_iter().chain(axis_names()) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Display names of every keyboard key, as written by `profile show`
+fn keyboard_names() -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (_, code) in KEYBOARD_CODE_NAMES {
+        let name = code.to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::profile::Profile;
+
+    #[test]
+    fn test_schema_covers_example_profiles() {
+        let sources = source_names();
+        let keys = keyboard_names();
+
+        for profile in Profile::examples().into_iter().chain([Profile::default_profile()]) {
+            for mapping in &profile.mappings {
+                assert!(sources.contains(&mapping.source_name), "{}", mapping.source_name);
+                assert!(keys.contains(&mapping.target_name), "{}", mapping.target_name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_source_names_are_unique() {
+        let names = source_names();
+        assert_eq!(names.iter().filter(|name| *name == "LeftTrigger").count(), 1);
+        assert!(names.contains(&"DPadY".to_string()));
+    }
+
+    #[test]
+    fn test_schema_is_valid_json() {
+        let schema = serde_json::to_string(&profile_schema()).unwrap();
+        assert!(schema.contains(r#""$schema":"http://json-schema.org/draft-07/schema#""#));
+        assert!(schema.contains(r##""#/definitions/mapping""##));
+    }
+}
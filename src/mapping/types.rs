@@ -1,8 +1,24 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TargetType {
     Keyboard,
     Mouse,
     Gamepad,
+    Rumble,
+    /// An axis on the virtual gamepad
+    GamepadAxis,
+    /// A media control key, e.g. Play/Pause
+    Media,
+}
+
+/// Whether a conditional mapping is tried before the button's plain mapping
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RulePriority {
+    /// Only used when the button has no plain mapping
+    #[default]
+    Normal,
+    /// Wins over the plain mapping while its condition holds
+    High,
 }
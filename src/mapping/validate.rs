@@ -0,0 +1,370 @@
+// Profile validation - problems and advice, graded by severity
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    event::{AxisCode, ButtonCode, KeyboardCode},
+    mapping::{MappingRule, profile::Profile},
+};
+
+/// How serious a validation issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The profile cannot be loaded
+    Error,
+    /// The profile loads, but probably not as intended
+    Warning,
+    /// Advice only
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+            Self::Info => write!(f, "info"),
+        }
+    }
+}
+
+/// One problem found by [`Profile::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// Stable identifier, e.g. `duplicate_source`
+    pub code: &'static str,
+    pub message: String,
+    /// 1-based position of the mapping the issue is about
+    pub mapping_index: Option<usize>,
+}
+
+impl ValidationIssue {
+    fn new(severity: Severity, code: &'static str, message: String) -> Self {
+        Self { severity, code, message, mapping_index: None }
+    }
+
+    fn at(mut self, index: usize) -> Self {
+        self.mapping_index = Some(index + 1);
+        self
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    /// e.g. `mapping 3: South is mapped more than once; the last mapping wins`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mapping_index {
+            Some(index) => write!(f, "mapping {}: {}", index, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl Profile {
+    /// Check the profile, most severe issues first
+    ///
+    /// Profiles with `Error` issues are rejected by `MappingEngine::load_from_profile`;
+    /// warnings and info are only reported.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if self.mappings.is_empty() {
+            issues.push(ValidationIssue::new(
+                Severity::Warning,
+                "no_mappings",
+                "Profile has no mappings".to_string(),
+            ));
+        }
+
+        let mut sources = HashMap::new();
+        let mut targets: HashMap<KeyboardCode, Vec<String>> = HashMap::new();
+
+        for (index, mapping) in self.mappings.iter().enumerate() {
+            if !mapping.enabled {
+                issues.push(
+                    ValidationIssue::new(
+                        Severity::Info,
+                        "disabled_mapping",
+                        format!("{} is disabled", mapping),
+                    )
+                    .at(index),
+                );
+                continue;
+            }
+
+            let rule = match MappingRule::try_from(mapping) {
+                Ok(rule) => rule,
+                Err(e) => {
+                    issues.push(
+                        ValidationIssue::new(
+                            Severity::Error,
+                            "invalid_mapping",
+                            format!("{} ({})", e, mapping),
+                        )
+                        .at(index),
+                    );
+                    continue;
+                }
+            };
+
+            if sources.insert(mapping.override_key(self.settings.allow_multiple), index).is_some() {
+                let winner = if mapping.first_wins() { "first" } else { "last" };
+                issues.push(
+                    ValidationIssue::new(
+                        Severity::Warning,
+                        "duplicate_source",
+                        format!(
+                            "{} is mapped more than once; the {} mapping wins",
+                            mapping, winner
+                        ),
+                    )
+                    .at(index),
+                );
+            }
+
+            if let MappingRule::ButtonToKey { target, .. }
+            | MappingRule::AxisDirectionToKey { target, .. } = &rule
+            {
+                targets.entry(*target).or_default().push(mapping.source_name.clone());
+            }
+
+            let unknown_source = match &rule {
+                MappingRule::ButtonToKey { source, .. }
+                | MappingRule::ButtonToRumble { source, .. }
+                | MappingRule::ButtonToMedia { source, .. }
+                | MappingRule::ConditionalButtonToKey { source, .. } => {
+                    *source == ButtonCode::Unknown
+                }
+                MappingRule::AxisDirectionToKey { source, .. }
+                | MappingRule::AxisToAxis { source, .. } => *source == AxisCode::Unknown,
+            };
+            if unknown_source {
+                issues.push(
+                    ValidationIssue::new(
+                        Severity::Warning,
+                        "unknown_source",
+                        format!("'{}' is not a known button or axis", mapping.source_name),
+                    )
+                    .at(index),
+                );
+            }
+
+            match rule {
+                MappingRule::ButtonToKey { target: KeyboardCode::Unknown, .. }
+                | MappingRule::AxisDirectionToKey { target: KeyboardCode::Unknown, .. }
+                | MappingRule::ConditionalButtonToKey { target: KeyboardCode::Unknown, .. } => {
+                    issues.push(
+                        ValidationIssue::new(
+                            Severity::Error,
+                            "unknown_target",
+                            format!("'{}' is not a known key", mapping.target_name),
+                        )
+                        .at(index),
+                    );
+                }
+                MappingRule::ButtonToRumble { .. } if !self.settings.vibration_enabled => {
+                    issues.push(
+                        ValidationIssue::new(
+                            Severity::Info,
+                            "vibration_disabled",
+                            format!("{} does nothing while vibration is disabled", mapping),
+                        )
+                        .at(index),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        let start = ButtonCode::Start.to_string();
+        if !self.mappings.is_empty()
+            && !self.mappings.iter().any(|m| m.enabled && m.source_name == start)
+        {
+            issues.push(ValidationIssue::new(
+                Severity::Warning,
+                "unmapped_start",
+                "Start is not mapped (usually the pause menu)".to_string(),
+            ));
+        }
+
+        let mut shared: Vec<(KeyboardCode, Vec<String>)> =
+            targets.into_iter().filter(|(_, sources)| sources.len() > 1).collect();
+        shared.sort_by_key(|(key, _)| key.to_string());
+        for (key, sources) in shared {
+            issues.push(ValidationIssue::new(
+                Severity::Info,
+                "shared_target",
+                format!("{} is triggered by {}", key, sources.join(", ")),
+            ));
+        }
+
+        issues.sort_by_key(|issue| issue.severity);
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(profile: &Profile) -> Vec<(Severity, &'static str)> {
+        profile.validate().iter().map(|issue| (issue.severity, issue.code)).collect()
+    }
+
+    #[test]
+    fn test_default_profile_is_clean() {
+        assert!(Profile::default_profile().validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_severities() {
+        let mut profile = Profile::default_profile();
+        profile.mappings.retain(|m| m.source_name != "Start");
+        let mut duplicate = profile.mappings[0].clone();
+        duplicate.target_name = "Space".to_string();
+        profile.mappings.push(duplicate);
+        let mut invalid = profile.mappings[0].clone();
+        invalid.source_direction = Some("Sideways".to_string());
+        profile.mappings.push(invalid);
+
+        assert_eq!(
+            codes(&profile),
+            vec![
+                (Severity::Error, "invalid_mapping"),
+                (Severity::Warning, "duplicate_source"),
+                (Severity::Warning, "unmapped_start"),
+            ]
+        );
+
+        let issue = &profile.validate()[1];
+        assert_eq!(issue.mapping_index, Some(profile.mappings.len() - 1));
+        assert!(
+            issue.to_string().starts_with(&format!("mapping {}: ", profile.mappings.len() - 1))
+        );
+    }
+
+    #[test]
+    fn test_validate_key_and_rumble_on_one_button_is_not_duplicate() {
+        let mut profile = Profile::default_profile();
+        let mut rumble = profile.mappings[0].clone();
+        rumble.target_type = crate::mapping::types::TargetType::Rumble;
+        rumble.target_name = "50:50:100".to_string();
+        profile.mappings.push(rumble);
+
+        assert!(profile.validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_duplicate_conditional_first_wins() {
+        let mut profile = Profile::default_profile();
+        let mut shifted = profile.mappings[0].clone();
+        shifted.condition_modifier = Some("Left Shoulder".to_string());
+        profile.mappings.push(shifted.clone());
+        shifted.target_name = "Space".to_string();
+        profile.mappings.push(shifted);
+
+        let issues = profile.validate();
+
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.ends_with("is mapped more than once; the first mapping wins"));
+    }
+
+    #[test]
+    fn test_validate_unknown_source() {
+        let mut profile = Profile::default_profile();
+        profile.mappings[0].source_name = "Souht".to_string();
+
+        let issues = profile.validate();
+
+        assert_eq!(issues[0].code, "unknown_source");
+        assert_eq!(issues[0].to_string(), "mapping 1: 'Souht' is not a known button or axis");
+    }
+
+    #[test]
+    fn test_validate_unknown_source_for_every_button_rule() {
+        use crate::mapping::types::TargetType;
+
+        let mut rumble = Profile::default_profile().mappings[0].clone();
+        rumble.target_type = TargetType::Rumble;
+        rumble.target_name = "50:50:100".to_string();
+        let mut media = Profile::default_profile().mappings[0].clone();
+        media.target_type = TargetType::Media;
+        media.target_name = "Play/Pause".to_string();
+        let mut conditional = Profile::default_profile().mappings[0].clone();
+        conditional.condition_modifier = Some("Left Shoulder".to_string());
+
+        for mut mapping in [rumble, media, conditional] {
+            mapping.source_name = "Souht".to_string();
+            let mut profile = Profile::default_profile();
+            profile.mappings.push(mapping);
+
+            let issues = profile.validate();
+
+            assert_eq!(issues[0].code, "unknown_source", "{:?}", issues);
+            assert_eq!(issues[0].severity, Severity::Warning);
+        }
+    }
+
+    #[test]
+    fn test_validate_unknown_axis_to_axis_source_is_error() {
+        let mut profile = Profile::default_profile();
+        let mut mapping = profile.mappings[0].clone();
+        mapping.source_name = "Left Stik X".to_string();
+        mapping.target_type = crate::mapping::types::TargetType::GamepadAxis;
+        mapping.target_name = "RightX".to_string();
+        profile.mappings.push(mapping);
+
+        let issues = profile.validate();
+
+        assert_eq!((issues[0].severity, issues[0].code), (Severity::Error, "invalid_mapping"));
+        assert!(crate::mapping::MappingEngine::load_from_profile(&profile).is_err());
+    }
+
+    #[test]
+    fn test_validate_unknown_target_is_error() {
+        let mut profile = Profile::default_profile();
+        profile.mappings[0].target_name = "NotAKey".to_string();
+        let mut conditional = profile.mappings[1].clone();
+        conditional.condition_modifier = Some("Left Shoulder".to_string());
+        conditional.target_name = "NotAKeyEither".to_string();
+        profile.mappings.push(conditional);
+
+        let issues = profile.validate();
+
+        assert_eq!(
+            codes(&profile)[..2],
+            [(Severity::Error, "unknown_target"), (Severity::Error, "unknown_target")]
+        );
+        assert_eq!(issues[0].to_string(), "mapping 1: 'NotAKey' is not a known key");
+        let Err(error) = crate::mapping::MappingEngine::load_from_profile(&profile) else {
+            panic!("profile with an unknown key loaded");
+        };
+        assert_eq!(error.to_string(), "Invalid profile: mapping 1: 'NotAKey' is not a known key");
+    }
+
+    #[test]
+    fn test_validate_lists_disabled_mappings() {
+        let mut profile = Profile::default_profile();
+        profile.mappings[0].enabled = false;
+        profile.mappings[0].source_name = "Souht".to_string();
+
+        let issues = profile.validate();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!((issues[0].severity, issues[0].code), (Severity::Info, "disabled_mapping"));
+        assert_eq!(issues[0].to_string(), "mapping 1: Souht → W is disabled");
+    }
+
+    #[test]
+    fn test_validate_shared_target_is_info() {
+        let mut profile = Profile::default_profile();
+        let mut extra = profile.mappings[0].clone();
+        extra.source_name = "Mode".to_string();
+        profile.mappings.push(extra);
+
+        let issues = profile.validate();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Info);
+        assert_eq!(issues[0].code, "shared_target");
+    }
+}
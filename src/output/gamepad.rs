@@ -0,0 +1,13 @@
+use anyhow::Result;
+
+use crate::event::AxisCode;
+
+/// Name of the virtual gamepad created for AxisToAxis mappings
+pub const VIRTUAL_GAMEPAD_NAME: &str = "BlazeRemap Virtual Gamepad";
+
+/// Domain trait: abstract virtual gamepad operations
+#[cfg_attr(test, mockall::automock)]
+pub trait VirtualGamepad: Send {
+    /// Move an axis to `value`
+    fn set_axis(&mut self, code: AxisCode, value: i32) -> Result<()>;
+}
@@ -1,16 +1,157 @@
 use anyhow::Result;
 
-use crate::event::KeyboardCode;
+use crate::event::{KeyboardCode, char_to_key_code};
+
+/// Virtual keyboard failures callers can act on
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum VirtualKeyboardError {
+    /// The device's keys are fixed once it exists; create a new one instead
+    #[error("cannot register {key} after the virtual keyboard was created")]
+    CannotRegisterAfterCreation { key: KeyboardCode },
+}
 
 /// Domain trait: abstract virtual keyboard operations
 #[cfg_attr(test, mockall::automock)]
-pub trait VirtualKeyboard {
+pub trait VirtualKeyboard: Send {
     /// Press a key by its code
     fn press_key(&mut self, code: KeyboardCode) -> Result<()>;
     /// Release a key by its code
     fn release_key(&mut self, code: KeyboardCode) -> Result<()>;
     /// Tap a key (press then release)
     fn tap_key(&mut self, code: KeyboardCode) -> Result<()>;
+    /// Press a key, hold it for `hold_ms`, then release it
+    fn tap_key_held(&mut self, code: KeyboardCode, hold_ms: u64) -> Result<()> {
+        self.press_key(code)?;
+        std::thread::sleep(std::time::Duration::from_millis(hold_ms));
+        self.release_key(code)
+    }
     /// Get sysfs path (for debugging)
     fn sys_path(&mut self) -> Result<std::path::PathBuf>;
+    /// Make `code` pressable on a keyboard that was created without it
+    ///
+    /// Fails with [`VirtualKeyboardError::CannotRegisterAfterCreation`] unless the
+    /// device can change its keys after creation.
+    fn register_key(&mut self, code: KeyboardCode) -> Result<()> {
+        Err(VirtualKeyboardError::CannotRegisterAfterCreation { key: code }.into())
+    }
+    /// Release every key that is still held down
+    fn reset(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Type `text` one key at a time, holding Shift where needed (US layout)
+    ///
+    /// Returns the characters that have no key and were skipped.
+    fn type_string(&mut self, text: &str) -> Result<Vec<char>> {
+        let mut skipped = Vec::new();
+
+        for c in text.chars() {
+            let Some((code, shifted)) = char_to_key_code(c) else {
+                skipped.push(c);
+                continue;
+            };
+
+            let modifier = shifted.then_some(KeyboardCode::LeftShift);
+            if let Some(modifier) = modifier {
+                self.press_key(modifier)?;
+            }
+            self.tap_key(code)?;
+            if let Some(modifier) = modifier {
+                self.release_key(modifier)?;
+            }
+        }
+
+        Ok(skipped)
+    }
+}
+
+#[cfg(test)]
+impl MockVirtualKeyboard {
+    /// Mock that allows any number of `reset` calls, for tests that run an event loop
+    ///
+    /// Like uinput keyboards, it cannot register keys after creation.
+    pub fn resettable() -> Self {
+        let mut keyboard = Self::new();
+        keyboard.expect_reset().returning(|| Ok(()));
+        keyboard
+            .expect_register_key()
+            .returning(|key| Err(VirtualKeyboardError::CannotRegisterAfterCreation { key }.into()));
+        keyboard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Records every call as "press X" / "release X" / "tap X"
+    #[derive(Default)]
+    struct RecordingKeyboard {
+        calls: Vec<String>,
+    }
+
+    impl VirtualKeyboard for RecordingKeyboard {
+        fn press_key(&mut self, code: KeyboardCode) -> Result<()> {
+            self.calls.push(format!("press {}", code));
+            Ok(())
+        }
+
+        fn release_key(&mut self, code: KeyboardCode) -> Result<()> {
+            self.calls.push(format!("release {}", code));
+            Ok(())
+        }
+
+        fn tap_key(&mut self, code: KeyboardCode) -> Result<()> {
+            self.calls.push(format!("tap {}", code));
+            Ok(())
+        }
+
+        fn sys_path(&mut self) -> Result<PathBuf> {
+            Ok(PathBuf::new())
+        }
+    }
+
+    #[test]
+    fn test_type_string_shifts_uppercase() {
+        let mut keyboard = RecordingKeyboard::default();
+
+        let skipped = keyboard.type_string("Hi!").unwrap();
+
+        assert!(skipped.is_empty());
+        assert_eq!(
+            keyboard.calls,
+            vec![
+                "press Left Shift",
+                "tap H",
+                "release Left Shift",
+                "tap I",
+                "press Left Shift",
+                "tap 1",
+                "release Left Shift",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tap_key_held_default() {
+        let mut keyboard = RecordingKeyboard::default();
+        let start = std::time::Instant::now();
+
+        keyboard.tap_key_held(KeyboardCode::Space, 20).unwrap();
+
+        assert!(start.elapsed() >= std::time::Duration::from_millis(20));
+        assert_eq!(keyboard.calls, vec!["press Space", "release Space"]);
+    }
+
+    #[test]
+    fn test_type_string_skips_unsupported() {
+        let mut keyboard = RecordingKeyboard::default();
+
+        let skipped = keyboard.type_string("a€b").unwrap();
+
+        assert_eq!(skipped, vec!['€']);
+        assert_eq!(keyboard.calls, vec!["tap A", "tap B"]);
+    }
 }
@@ -1 +1,2 @@
+pub mod gamepad;
 pub mod keyboard;
@@ -54,7 +54,7 @@ pub fn evdev_to_input(ev: evdev::InputEvent) -> Option<InputEvent> {
     }
 }
 
-fn key_to_button_code(key: evdev::KeyCode) -> ButtonCode {
+pub(super) fn key_to_button_code(key: evdev::KeyCode) -> ButtonCode {
     match key {
         evdev::KeyCode::BTN_SOUTH => ButtonCode::South,
         evdev::KeyCode::BTN_EAST => ButtonCode::East,
@@ -304,7 +304,7 @@ pub fn keyboard_code_to_evdev_key(code: KeyboardCode) -> evdev::KeyCode {
     }
 }
 
-fn absolute_axis_to_axis_code(axis: evdev::AbsoluteAxisCode) -> AxisCode {
+pub(super) fn absolute_axis_to_axis_code(axis: evdev::AbsoluteAxisCode) -> AxisCode {
     match axis {
         evdev::AbsoluteAxisCode::ABS_X => AxisCode::LeftX,
         evdev::AbsoluteAxisCode::ABS_Y => AxisCode::LeftY,
@@ -314,13 +314,41 @@ fn absolute_axis_to_axis_code(axis: evdev::AbsoluteAxisCode) -> AxisCode {
         evdev::AbsoluteAxisCode::ABS_RZ => AxisCode::RightTrigger,
         evdev::AbsoluteAxisCode::ABS_HAT0X => AxisCode::DPadX,
         evdev::AbsoluteAxisCode::ABS_HAT0Y => AxisCode::DPadY,
+        evdev::AbsoluteAxisCode::ABS_HAT1X => AxisCode::Hat1X,
+        evdev::AbsoluteAxisCode::ABS_HAT1Y => AxisCode::Hat1Y,
+        evdev::AbsoluteAxisCode::ABS_HAT2X => AxisCode::Hat2X,
+        evdev::AbsoluteAxisCode::ABS_HAT2Y => AxisCode::Hat2Y,
+        evdev::AbsoluteAxisCode::ABS_HAT3X => AxisCode::Hat3X,
+        evdev::AbsoluteAxisCode::ABS_HAT3Y => AxisCode::Hat3Y,
         _ => AxisCode::Unknown,
     }
 }
 
+/// Inverse of [`absolute_axis_to_axis_code`]; `None` for `AxisCode::Unknown`
+pub(super) fn axis_code_to_absolute_axis(code: AxisCode) -> Option<evdev::AbsoluteAxisCode> {
+    match code {
+        AxisCode::LeftX => Some(evdev::AbsoluteAxisCode::ABS_X),
+        AxisCode::LeftY => Some(evdev::AbsoluteAxisCode::ABS_Y),
+        AxisCode::RightX => Some(evdev::AbsoluteAxisCode::ABS_RX),
+        AxisCode::RightY => Some(evdev::AbsoluteAxisCode::ABS_RY),
+        AxisCode::LeftTrigger => Some(evdev::AbsoluteAxisCode::ABS_Z),
+        AxisCode::RightTrigger => Some(evdev::AbsoluteAxisCode::ABS_RZ),
+        AxisCode::DPadX => Some(evdev::AbsoluteAxisCode::ABS_HAT0X),
+        AxisCode::DPadY => Some(evdev::AbsoluteAxisCode::ABS_HAT0Y),
+        AxisCode::Hat1X => Some(evdev::AbsoluteAxisCode::ABS_HAT1X),
+        AxisCode::Hat1Y => Some(evdev::AbsoluteAxisCode::ABS_HAT1Y),
+        AxisCode::Hat2X => Some(evdev::AbsoluteAxisCode::ABS_HAT2X),
+        AxisCode::Hat2Y => Some(evdev::AbsoluteAxisCode::ABS_HAT2Y),
+        AxisCode::Hat3X => Some(evdev::AbsoluteAxisCode::ABS_HAT3X),
+        AxisCode::Hat3Y => Some(evdev::AbsoluteAxisCode::ABS_HAT3Y),
+        AxisCode::Unknown => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event::{RAW_AXIS_CODES, RAW_BUTTON_CODES};
     use evdev::InputEvent as EvdevEvent;
     use std::time::Duration;
 
@@ -454,6 +482,15 @@ mod tests {
         assert_eq!(keyboard_code_to_evdev_key(KeyboardCode::Unknown), evdev::KeyCode::KEY_RESERVED);
     }
 
+    #[test]
+    fn test_axis_code_to_absolute_axis_round_trips() {
+        for code in AxisCode::all() {
+            let abs = axis_code_to_absolute_axis(*code).unwrap();
+            assert_eq!(absolute_axis_to_axis_code(abs), *code);
+        }
+        assert_eq!(axis_code_to_absolute_axis(AxisCode::Unknown), None);
+    }
+
     #[test]
     fn test_all_axis_code_mappings() {
         // Test all axis mappings
@@ -471,6 +508,19 @@ mod tests {
         );
         assert_eq!(absolute_axis_to_axis_code(evdev::AbsoluteAxisCode::ABS_HAT0X), AxisCode::DPadX);
         assert_eq!(absolute_axis_to_axis_code(evdev::AbsoluteAxisCode::ABS_HAT0Y), AxisCode::DPadY);
+        assert_eq!(absolute_axis_to_axis_code(evdev::AbsoluteAxisCode::ABS_HAT1X), AxisCode::Hat1X);
+        assert_eq!(absolute_axis_to_axis_code(evdev::AbsoluteAxisCode::ABS_HAT2Y), AxisCode::Hat2Y);
+        assert_eq!(absolute_axis_to_axis_code(evdev::AbsoluteAxisCode::ABS_HAT3X), AxisCode::Hat3X);
+    }
+
+    #[test]
+    fn test_raw_code_tables_match_converter() {
+        for &(raw, button) in RAW_BUTTON_CODES {
+            assert_eq!(key_to_button_code(evdev::KeyCode::new(raw)), button, "{}", raw);
+        }
+        for &(raw, axis) in RAW_AXIS_CODES {
+            assert_eq!(absolute_axis_to_axis_code(evdev::AbsoluteAxisCode(raw)), axis, "{}", raw);
+        }
     }
 
     #[test]
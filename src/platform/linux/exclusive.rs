@@ -0,0 +1,148 @@
+// Gamepad wrapper that holds an exclusive grab on the device
+use std::path::Path;
+
+use crate::{
+    event::InputEvent,
+    input::gamepad::{Gamepad, GamepadCapability, GamepadInfo},
+    platform::linux::LinuxGamepad,
+};
+
+/// EBUSY: another process already grabbed the device
+const EBUSY: i32 = 16;
+
+/// A `LinuxGamepad` grabbed with EVIOCGRAB, so other readers such as Steam Input
+/// stop receiving its events. The grab is released when the wrapper is dropped.
+pub struct ExclusiveGamepad {
+    inner: LinuxGamepad,
+}
+
+impl ExclusiveGamepad {
+    /// Open and grab the device at `path`
+    pub fn open(path: &str) -> anyhow::Result<Self> {
+        let mut inner = LinuxGamepad::open(path)?;
+
+        if let Err(e) = inner.grab() {
+            if e.raw_os_error() == Some(EBUSY) {
+                anyhow::bail!("{} is already grabbed{}", path, describe_holders(Path::new(path)));
+            }
+            return Err(anyhow::anyhow!("Failed to grab {}: {}", path, e));
+        }
+
+        tracing::info!("Grabbed {} exclusively", path);
+        Ok(Self { inner })
+    }
+}
+
+impl Gamepad for ExclusiveGamepad {
+    fn get_info(&self) -> GamepadInfo {
+        self.inner.get_info()
+    }
+
+    fn capabilities(&self) -> &[GamepadCapability] {
+        self.inner.capabilities()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    fn read_event(&mut self) -> anyhow::Result<Option<InputEvent>> {
+        self.inner.read_event()
+    }
+
+    fn set_rumble(&mut self, weak: u16, strong: u16, duration_ms: u32) -> anyhow::Result<()> {
+        self.inner.set_rumble(weak, strong, duration_ms)
+    }
+
+    fn close(self) -> anyhow::Result<()> {
+        // Drop releases the grab
+        Ok(())
+    }
+}
+
+impl Drop for ExclusiveGamepad {
+    fn drop(&mut self) {
+        if let Err(e) = self.inner.ungrab() {
+            tracing::warn!("Failed to release grab on {}: {}", self.inner.path(), e);
+        }
+    }
+}
+
+/// " by name (pid N), ..." for every other process with `device` open, or "" if none found
+fn describe_holders(device: &Path) -> String {
+    let holders: Vec<String> = find_holders(Path::new("/proc"), device)
+        .into_iter()
+        .map(|(pid, name)| format!("{} (pid {})", name, pid))
+        .collect();
+
+    if holders.is_empty() { String::new() } else { format!(" by {}", holders.join(", ")) }
+}
+
+/// Processes under `proc_root` that have an open file descriptor on `device`
+fn find_holders(proc_root: &Path, device: &Path) -> Vec<(u32, String)> {
+    let own_pid = std::process::id();
+    let Ok(entries) = std::fs::read_dir(proc_root) else {
+        return Vec::new();
+    };
+
+    let mut holders: Vec<(u32, String)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            if pid == own_pid {
+                return None;
+            }
+
+            let fds = std::fs::read_dir(entry.path().join("fd")).ok()?;
+            let has_device = fds
+                .flatten()
+                .any(|fd| std::fs::read_link(fd.path()).is_ok_and(|target| target == device));
+            if !has_device {
+                return None;
+            }
+
+            let name = std::fs::read_to_string(entry.path().join("comm"))
+                .map(|comm| comm.trim().to_string())
+                .unwrap_or_else(|_| "unknown".to_string());
+            Some((pid, name))
+        })
+        .collect();
+
+    holders.sort();
+    holders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_holders_in_fake_proc() {
+        let root = std::env::temp_dir().join(format!("blazeremap-proc-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let make_process = |pid: &str, comm: &str, target: &str| {
+            let fd_dir = root.join(pid).join("fd");
+            std::fs::create_dir_all(&fd_dir).unwrap();
+            std::fs::write(root.join(pid).join("comm"), format!("{}\n", comm)).unwrap();
+            std::os::unix::fs::symlink(target, fd_dir.join("3")).unwrap();
+        };
+        make_process("200", "steam", "/dev/input/event3");
+        make_process("100", "other", "/dev/input/event7");
+        std::fs::create_dir_all(root.join("self")).unwrap();
+
+        let holders = find_holders(&root, Path::new("/dev/input/event3"));
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(holders, vec![(200, "steam".to_string())]);
+    }
+
+    #[test]
+    fn test_find_holders_missing_proc() {
+        assert!(find_holders(Path::new("/nonexistent"), Path::new("/dev/input/event3")).is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+// File Watcher Module
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use crossbeam::channel::{self, Receiver};
+use notify::{
+    Event, EventKind, RecursiveMode, Watcher,
+    event::{ModifyKind, RenameMode},
+};
+
+use crate::event::FileEvent;
+
+/// Watches a single file for changes (inotify on Linux)
+pub struct FileWatcher;
+
+impl FileWatcher {
+    /// Start watching `path`, returning a channel of its changes
+    ///
+    /// The file's directory is watched rather than the file itself so that
+    /// editors which save by writing a new file and renaming it over the old
+    /// one are still seen. Watching stops once the receiver is dropped and the
+    /// next change arrives.
+    pub fn watch(path: PathBuf) -> Result<Receiver<FileEvent>> {
+        let mut path =
+            path.canonicalize().with_context(|| format!("Cannot watch {}", path.display()))?;
+        let mut dir = parent_dir(&path);
+
+        let (raw_tx, raw_rx) = std::sync::mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(raw_tx).context("Failed to create file watcher")?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {}", dir.display()))?;
+
+        let (tx, rx) = channel::unbounded();
+        std::thread::Builder::new()
+            .name("file-watcher".to_string())
+            .spawn(move || {
+                for raw in raw_rx {
+                    let event = match raw {
+                        Ok(event) => event,
+                        Err(e) => {
+                            tracing::warn!("File watcher error: {}", e);
+                            continue;
+                        }
+                    };
+                    let Some(file_event) = translate(&event, &path) else {
+                        continue;
+                    };
+
+                    if let FileEvent::Renamed(new_path) = &file_event {
+                        let new_dir = parent_dir(new_path);
+                        if new_dir != dir {
+                            let _ = watcher.unwatch(&dir);
+                            if let Err(e) = watcher.watch(&new_dir, RecursiveMode::NonRecursive) {
+                                tracing::warn!("Failed to watch {}: {}", new_dir.display(), e);
+                            }
+                            dir = new_dir;
+                        }
+                        path = new_path.clone();
+                    }
+
+                    if tx.send(file_event).is_err() {
+                        break; // Nobody is listening any more
+                    }
+                }
+            })
+            .context("Failed to start file watcher thread")?;
+
+        Ok(rx)
+    }
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// What a directory event means for the file at `path`, if anything
+fn translate(event: &Event, path: &Path) -> Option<FileEvent> {
+    let touches = |p: Option<&PathBuf>| p.is_some_and(|p| p == path);
+
+    match event.kind {
+        // paths = [from, to]
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+            if touches(event.paths.get(1)) {
+                Some(FileEvent::Modified) // Atomic save over our file
+            } else if touches(event.paths.first()) {
+                event.paths.get(1).cloned().map(FileEvent::Renamed)
+            } else {
+                None
+            }
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+            touches(event.paths.first()).then_some(FileEvent::Deleted)
+        }
+        EventKind::Create(_)
+        | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Any)
+        | EventKind::Modify(ModifyKind::Name(_)) => {
+            event.paths.iter().any(|p| p == path).then_some(FileEvent::Modified)
+        }
+        EventKind::Remove(_) => event.paths.iter().any(|p| p == path).then_some(FileEvent::Deleted),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use notify::event::{CreateKind, DataChange, MetadataKind, RemoveKind};
+
+    use super::*;
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        paths.iter().fold(Event::new(kind), |event, p| event.add_path(PathBuf::from(p)))
+    }
+
+    #[test]
+    fn test_translate_modifications() {
+        let path = Path::new("/cfg/game.toml");
+
+        let write =
+            event(EventKind::Modify(ModifyKind::Data(DataChange::Any)), &["/cfg/game.toml"]);
+        assert_eq!(translate(&write, path), Some(FileEvent::Modified));
+
+        let create = event(EventKind::Create(CreateKind::File), &["/cfg/game.toml"]);
+        assert_eq!(translate(&create, path), Some(FileEvent::Modified));
+
+        let atomic_save = event(
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+            &["/cfg/.game.toml.swp", "/cfg/game.toml"],
+        );
+        assert_eq!(translate(&atomic_save, path), Some(FileEvent::Modified));
+    }
+
+    #[test]
+    fn test_translate_delete_and_rename() {
+        let path = Path::new("/cfg/game.toml");
+
+        let remove = event(EventKind::Remove(RemoveKind::File), &["/cfg/game.toml"]);
+        assert_eq!(translate(&remove, path), Some(FileEvent::Deleted));
+
+        let rename = event(
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+            &["/cfg/game.toml", "/cfg/racing.toml"],
+        );
+        assert_eq!(
+            translate(&rename, path),
+            Some(FileEvent::Renamed(PathBuf::from("/cfg/racing.toml")))
+        );
+
+        let moved_away =
+            event(EventKind::Modify(ModifyKind::Name(RenameMode::From)), &["/cfg/game.toml"]);
+        assert_eq!(translate(&moved_away, path), Some(FileEvent::Deleted));
+    }
+
+    #[test]
+    fn test_translate_ignores_other_files_and_metadata() {
+        let path = Path::new("/cfg/game.toml");
+
+        let other =
+            event(EventKind::Modify(ModifyKind::Data(DataChange::Any)), &["/cfg/other.toml"]);
+        assert_eq!(translate(&other, path), None);
+
+        let touch =
+            event(EventKind::Modify(ModifyKind::Metadata(MetadataKind::Any)), &["/cfg/game.toml"]);
+        assert_eq!(translate(&touch, path), None);
+    }
+}
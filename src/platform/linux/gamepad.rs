@@ -1,13 +1,22 @@
 // Gamepad detection and information extraction
+use std::collections::{HashMap, HashSet};
+
 use crate::{
-    event::InputEvent,
+    event::{AxisCode, ButtonCode, DEFAULT_STICK_DEADZONE, InputEvent},
     input::gamepad::{
-        Gamepad, GamepadCapability, GamepadInfo, get_known_vendor_database, identify_gamepad,
+        AxisRange, BusType, Gamepad, GamepadCapability, GamepadInfo, GamepadType,
+        find_gamepad_by_name, get_known_vendor_database, identify_gamepad,
+    },
+    platform::linux::{
+        converter::{absolute_axis_to_axis_code, key_to_button_code},
+        evdev_to_input,
     },
-    platform::linux::evdev_to_input,
 };
 use anyhow::Context;
-use evdev::{AttributeSetRef, Device, FFEffectCode};
+use evdev::{
+    AttributeSetRef, Device, FFEffect, FFEffectCode, FFEffectData, FFEffectKind, FFReplay,
+    FFTrigger,
+};
 
 // Constants for gamepad detection
 const BTN_GAMEPAD_MIN: u16 = 0x130;
@@ -101,6 +110,9 @@ pub(super) fn is_gamepad(device: &Device) -> bool {
             AbsoluteAxisCode::ABS_Y => has_gamepad_axis = true,
             AbsoluteAxisCode::ABS_RX => has_gamepad_axis = true,
             AbsoluteAxisCode::ABS_RY => has_gamepad_axis = true,
+            // Touchpad-only controllers (Steam Controller) have no sticks on ABS_X/Y
+            AbsoluteAxisCode::ABS_HAT1X => has_gamepad_axis = true,
+            AbsoluteAxisCode::ABS_HAT1Y => has_gamepad_axis = true,
             _ => {}
         }
     }
@@ -111,14 +123,9 @@ pub(super) fn is_gamepad(device: &Device) -> bool {
 
     // Check device name
     let device_name = device.name().unwrap_or("");
+    let input_id = device.input_id();
 
-    // If name contains "controller", "gamepad", "joystick" - probably a gamepad
-    let name_lower = device_name.to_lowercase();
-    let gamepad_keywords = ["controller", "gamepad", "joystick", "pad"];
-    let is_likely_gamepad = gamepad_keywords.iter().any(|kw| name_lower.contains(kw));
-
-    // If it looks like a gamepad by name, skip exclusion check
-    if !is_likely_gamepad && is_excluded_by_name(device_name) {
+    if !passes_name_check(device_name, input_id.vendor(), input_id.product()) {
         return false;
     }
 
@@ -126,6 +133,24 @@ pub(super) fn is_gamepad(device: &Device) -> bool {
     true
 }
 
+/// Final name-based check for a device that already has gamepad buttons and axes
+///
+/// Gamepads known by vendor/product ID skip the check entirely, since some
+/// Bluetooth stacks report generic names (e.g. "054C:09CC").
+fn passes_name_check(name: &str, vendor_id: u16, product_id: u16) -> bool {
+    if identify_gamepad(vendor_id, product_id) != GamepadType::Generic {
+        return true;
+    }
+
+    // If name contains "controller", "gamepad", "joystick" - probably a gamepad
+    let name_lower = name.to_lowercase();
+    let gamepad_keywords = ["controller", "gamepad", "joystick", "pad"];
+    let is_likely_gamepad = gamepad_keywords.iter().any(|kw| name_lower.contains(kw));
+
+    // If it looks like a gamepad by name, skip exclusion check
+    is_likely_gamepad || !is_excluded_by_name(name)
+}
+
 /// Check if device supports force feedback (rumble)
 fn has_force_feedback(device: &Device) -> bool {
     use evdev::EventType;
@@ -156,6 +181,20 @@ fn has_elite_paddles(device: &Device) -> bool {
     paddle_count >= ELITE_PADDLE_COUNT
 }
 
+/// Check if device has DualSense haptics
+///
+/// The kernel exposes no dedicated effect code for them: the voice-coil actuators are
+/// driven through the same force feedback interface as rumble, so a DualSense that
+/// accepts force feedback effects has haptics.
+fn has_haptics(gamepad_type: GamepadType, device: &Device) -> bool {
+    has_dualsense_features(gamepad_type) && has_force_feedback(device)
+}
+
+/// Whether the gamepad type comes with DualSense haptics and adaptive triggers
+fn has_dualsense_features(gamepad_type: GamepadType) -> bool {
+    gamepad_type == GamepadType::DualSense
+}
+
 /// Extract gamepad information from an evdev device
 pub(super) fn extract_gamepad_info(device: &Device, path: &str) -> anyhow::Result<GamepadInfo> {
     let name = device.name().unwrap_or("Unknown").to_string();
@@ -163,6 +202,7 @@ pub(super) fn extract_gamepad_info(device: &Device, path: &str) -> anyhow::Resul
 
     let vendor_id = input_id.vendor();
     let product_id = input_id.product();
+    let bus_type = BusType::from_linux(input_id.bus_type().0);
 
     let vendor_db = get_known_vendor_database();
     let vendor_name = vendor_db
@@ -182,6 +222,22 @@ pub(super) fn extract_gamepad_info(device: &Device, path: &str) -> anyhow::Resul
         capabilities.push(GamepadCapability::ElitePaddles);
     }
 
+    if gamepad_type == GamepadType::SteamController {
+        capabilities.push(GamepadCapability::DualTouchpad);
+    }
+
+    if has_haptics(gamepad_type, device) {
+        capabilities.push(GamepadCapability::Haptics);
+    }
+
+    if has_dualsense_features(gamepad_type) {
+        capabilities.push(GamepadCapability::AdaptiveTriggers);
+    }
+
+    if bus_type == BusType::Bluetooth {
+        capabilities.push(GamepadCapability::Bluetooth);
+    }
+
     Ok(GamepadInfo {
         path: path.to_string(),
         name,
@@ -189,18 +245,42 @@ pub(super) fn extract_gamepad_info(device: &Device, path: &str) -> anyhow::Resul
         vendor_id,
         vendor_name,
         product_id,
+        version: input_id.version(),
+        bus_type,
         capabilities,
+        axis_ranges: read_axis_ranges(device),
+    })
+}
+
+/// Range of every known axis from the kernel's absinfo (empty if it can't be read)
+fn read_axis_ranges(device: &Device) -> HashMap<AxisCode, AxisRange> {
+    let Ok(axes) = device.get_absinfo() else {
+        return HashMap::new();
+    };
+    axes.map(|(axis, info)| {
+        let range = AxisRange {
+            min: info.minimum(),
+            max: info.maximum(),
+            flat: info.flat(),
+            fuzz: info.fuzz(),
+        };
+        (absolute_axis_to_axis_code(axis), range)
     })
+    .filter(|(code, _)| *code != AxisCode::Unknown)
+    .collect()
 }
 
 pub struct LinuxGamepad {
     info: GamepadInfo,
     device: Device,
+    rumble_effect: Option<FFEffect>, // Uploaded once, then updated in place
+    /// Sticks last reported at their center, see [`apply_deadzone`]
+    centered_axes: HashSet<AxisCode>,
 }
 
 impl LinuxGamepad {
     pub fn new(info: GamepadInfo, device: Device) -> Self {
-        Self { info, device }
+        Self { info, device, rumble_effect: None, centered_axes: HashSet::new() }
     }
 
     /// Open a gamepad device at the given path
@@ -217,6 +297,44 @@ impl LinuxGamepad {
         // Construct with both
         Ok(Self::new(info, device))
     }
+
+    /// Open the one gamepad whose name contains `pattern` (case-insensitive)
+    pub fn open_by_name(pattern: &str) -> anyhow::Result<Self> {
+        let gamepads: Vec<GamepadInfo> = evdev::enumerate()
+            .filter(|(_, device)| is_gamepad(device))
+            .filter_map(|(path, device)| {
+                extract_gamepad_info(&device, &path.to_string_lossy()).ok()
+            })
+            .collect();
+
+        let info = find_gamepad_by_name(&gamepads, pattern)?;
+        Self::open(&info.path)
+    }
+
+    /// Take exclusive access so no other process receives this device's events
+    pub fn grab(&mut self) -> std::io::Result<()> {
+        self.device.grab()
+    }
+
+    /// Release exclusive access taken by `grab`
+    pub fn ungrab(&mut self) -> std::io::Result<()> {
+        self.device.ungrab()
+    }
+
+    /// Buttons the device reports, in evdev code order
+    pub fn supported_buttons(&self) -> Vec<ButtonCode> {
+        let keys = self.device.supported_keys().unwrap_or_default();
+        keys.iter().map(key_to_button_code).filter(|code| *code != ButtonCode::Unknown).collect()
+    }
+
+    /// Axes the device reports, in evdev code order
+    pub fn supported_axes(&self) -> Vec<AxisCode> {
+        let axes = self.device.supported_absolute_axes().unwrap_or_default();
+        axes.iter()
+            .map(absolute_axis_to_axis_code)
+            .filter(|code| *code != AxisCode::Unknown)
+            .collect()
+    }
 }
 
 impl Gamepad for LinuxGamepad {
@@ -224,7 +342,23 @@ impl Gamepad for LinuxGamepad {
         self.info.clone()
     }
 
+    fn capabilities(&self) -> &[GamepadCapability] {
+        &self.info.capabilities
+    }
+
+    fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    fn path(&self) -> &str {
+        &self.info.path
+    }
+
     fn read_event(&mut self) -> anyhow::Result<Option<InputEvent>> {
+        // `filtered` is true when the batch held nothing to pass on (sync only, deadzone)
+        let span = tracing::trace_span!("read_event", filtered = tracing::field::Empty);
+        let _enter = span.enter();
+
         // This blocks until an event arrives - INTENTIONAL!
         match self.device.fetch_events() {
             Ok(events) => {
@@ -236,11 +370,23 @@ impl Gamepad for LinuxGamepad {
                     if ev_type == evdev::EventType::KEY || ev_type == evdev::EventType::ABSOLUTE {
                         match evdev_to_input(event) {
                             Some(input_event) => {
-                                if !input_event.is_in_deadzone() {
+                                let deadzone = match input_event {
+                                    InputEvent::Axis { code, .. } => self
+                                        .info
+                                        .axis_ranges
+                                        .get(&code)
+                                        .map(|range| (range.center(), range.flat)),
+                                    _ => None,
+                                };
+                                if let Some(input_event) =
+                                    apply_deadzone(input_event, deadzone, &mut self.centered_axes)
+                                {
+                                    span.record("filtered", false);
                                     return Ok(Some(input_event));
                                 }
                             }
                             None => {
+                                span.record("filtered", true);
                                 return Ok(None);
                             }
                         }
@@ -250,6 +396,7 @@ impl Gamepad for LinuxGamepad {
                 }
 
                 // No relevant events in this batch, continue reading
+                span.record("filtered", true);
                 Ok(None)
             }
             Err(e) => {
@@ -264,17 +411,100 @@ impl Gamepad for LinuxGamepad {
         }
     }
 
+    fn set_rumble(&mut self, weak: u16, strong: u16, duration_ms: u32) -> anyhow::Result<()> {
+        if !self.capabilities().contains(&GamepadCapability::ForceFeedback) {
+            return Ok(());
+        }
+
+        let data = rumble_effect_data(weak, strong, duration_ms);
+
+        let effect = match self.rumble_effect.as_mut() {
+            Some(effect) => {
+                effect.update(data).context("Failed to update rumble effect")?;
+                effect
+            }
+            None => {
+                let effect =
+                    self.device.upload_ff_effect(data).context("Failed to upload rumble effect")?;
+                self.rumble_effect.insert(effect)
+            }
+        };
+
+        effect.play(1).context("Failed to play rumble effect")?;
+        Ok(())
+    }
+
     fn close(self) -> anyhow::Result<()> {
         Ok(())
     }
 }
 
+/// Build an FF_RUMBLE effect (the kernel caps a single effect at u16::MAX ms)
+fn rumble_effect_data(weak: u16, strong: u16, duration_ms: u32) -> FFEffectData {
+    FFEffectData {
+        direction: 0,
+        trigger: FFTrigger { button: 0, interval: 0 },
+        replay: FFReplay { length: duration_ms.min(u16::MAX as u32) as u16, delay: 0 },
+        kind: FFEffectKind::Rumble { strong_magnitude: strong, weak_magnitude: weak },
+    }
+}
+
+/// Pass `event` on unless it is a stick movement inside the dead zone
+///
+/// The first movement into the dead zone is sent as the axis' center instead, so
+/// a stick forwarded to the virtual gamepad returns to center; `centered` holds
+/// the axes already sent there.
+fn apply_deadzone(
+    event: InputEvent,
+    deadzone: Option<(i32, i32)>,
+    centered: &mut HashSet<AxisCode>,
+) -> Option<InputEvent> {
+    let InputEvent::Axis { code, timestamp, .. } = event else {
+        return Some(event);
+    };
+    if !event.is_in_deadzone(deadzone) {
+        centered.remove(&code);
+        return Some(event);
+    }
+    if !centered.insert(code) {
+        return None;
+    }
+    let (center, _) = deadzone.unwrap_or(DEFAULT_STICK_DEADZONE);
+    Some(InputEvent::Axis { code, value: center, timestamp })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::input::gamepad::GamepadInfo;
     use crate::input::gamepad::{GamepadCapability, GamepadType};
 
+    #[test]
+    fn test_apply_deadzone_centers_once() {
+        let mut centered = HashSet::new();
+        let mut stick = |value| {
+            apply_deadzone(
+                InputEvent::axis_move(AxisCode::LeftX, value),
+                Some((0, 4000)),
+                &mut centered,
+            )
+            .map(|event| match event {
+                InputEvent::Axis { value, .. } => value,
+                _ => unreachable!(),
+            })
+        };
+
+        assert_eq!(stick(20000), Some(20000));
+        // Into the dead zone: sent once as the center
+        assert_eq!(stick(1500), Some(0));
+        assert_eq!(stick(-300), None);
+        assert_eq!(stick(-9000), Some(-9000));
+        assert_eq!(stick(100), Some(0));
+
+        let button = InputEvent::button_press(ButtonCode::South);
+        assert!(apply_deadzone(button, None, &mut centered).is_some());
+    }
+
     #[test]
     fn test_is_excluded_by_name() {
         // Test excluded keywords
@@ -289,6 +519,18 @@ mod tests {
         assert!(!is_excluded_by_name("Generic Controller"));
     }
 
+    #[test]
+    fn test_passes_name_check_known_ids_bypass_exclusion() {
+        // DualShock 4 reporting a name that would otherwise be excluded
+        assert!(passes_name_check("Wireless Device LED", 0x054c, 0x09cc));
+        assert!(passes_name_check("054C:09CC", 0x054c, 0x09cc));
+
+        // Unknown IDs still go through the name heuristics
+        assert!(!passes_name_check("Wireless Device LED", 0x1234, 0x5678));
+        assert!(passes_name_check("Wireless Controller", 0x1234, 0x5678));
+        assert!(passes_name_check("Generic USB Device", 0x1234, 0x5678));
+    }
+
     #[test]
     fn test_linux_gamepad_construction() {
         // Create mock GamepadInfo
@@ -299,7 +541,10 @@ mod tests {
             vendor_id: 0x045e,
             vendor_name: "Microsoft".to_string(),
             product_id: 0x02ea,
+            version: 0,
+            bus_type: BusType::Usb,
             capabilities: vec![GamepadCapability::ForceFeedback],
+            axis_ranges: HashMap::new(),
         };
 
         // This test would require a mock Device, which is complex
@@ -307,6 +552,17 @@ mod tests {
         // In a real test, we'd need to mock or use a test device
     }
 
+    #[test]
+    fn test_rumble_effect_data() {
+        let data = rumble_effect_data(100, 200, 300);
+
+        assert_eq!(data.replay.length, 300);
+        assert_eq!(data.kind, FFEffectKind::Rumble { strong_magnitude: 200, weak_magnitude: 100 });
+
+        // Durations beyond the kernel limit are clamped
+        assert_eq!(rumble_effect_data(0, 0, 100_000).replay.length, u16::MAX);
+    }
+
     #[test]
     fn test_has_force_feedback() {
         // This would require creating a mock Device with FF support
@@ -319,6 +575,14 @@ mod tests {
         // For now, we skip this as it requires complex mocking
     }
 
+    #[test]
+    fn test_has_dualsense_features() {
+        assert!(has_dualsense_features(GamepadType::DualSense));
+        assert!(!has_dualsense_features(GamepadType::DualShock4));
+        assert!(!has_dualsense_features(GamepadType::XboxSeries));
+        assert!(!has_dualsense_features(GamepadType::Generic));
+    }
+
     #[test]
     fn test_extract_gamepad_info() {
         // This would require creating a mock Device
@@ -333,7 +597,7 @@ mod tests {
 
     #[test]
     fn test_device_disconnect_error_handling() {
-        use std::io::{Error, ErrorKind};
+        use std::io::Error;
 
         // Test that ENODEV (19) is properly handled as graceful disconnect
         let enodev_error = Error::from_raw_os_error(19); // ENODEV
@@ -344,7 +608,7 @@ mod tests {
         assert_eq!(enodev_error.raw_os_error(), Some(19));
 
         // Test that other errors are not treated as disconnect
-        let other_error = Error::new(ErrorKind::Other, "Some other error");
+        let other_error = Error::other("Some other error");
         assert_ne!(other_error.raw_os_error(), Some(19));
     }
 }
@@ -377,8 +641,8 @@ mod debug_tests {
             let mut has_gamepad_button = false;
             for key in keys.iter() {
                 let code = key.code();
-                if (code >= BTN_GAMEPAD_MIN && code <= BTN_GAMEPAD_MAX)
-                    || (code >= BTN_JOYSTICK_MIN && code <= BTN_JOYSTICK_MAX)
+                if (BTN_GAMEPAD_MIN..=BTN_GAMEPAD_MAX).contains(&code)
+                    || (BTN_JOYSTICK_MIN..=BTN_JOYSTICK_MAX).contains(&code)
                 {
                     has_gamepad_button = true;
                     break;
@@ -1,15 +1,62 @@
 // Linux device manager implementation
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use super::errors::classify_error;
+use super::exclusive::ExclusiveGamepad;
 use super::gamepad::{LinuxGamepad, extract_gamepad_info, is_gamepad};
 use crate::input::{InputDetectionResult, InputDeviceError, InputManager, gamepad::Gamepad};
 
+/// How long `list_gamepads()` reuses the previous scan
+pub const DEFAULT_DEVICE_CACHE_TTL: Duration = Duration::from_secs(1);
+
+/// Clones share the same detection cache; `list_gamepads_fresh()` (used by hotplug
+/// polling) always rescans and replaces it
+#[derive(Clone)]
 pub struct LinuxInputManager {
-    // Fields can be added later if needed
+    cache_ttl: Duration,
+    cache: Arc<Mutex<Option<(Instant, InputDetectionResult)>>>,
 }
 
 impl LinuxInputManager {
     pub fn new() -> Self {
-        Self {}
+        Self::new_with_cache_ttl(DEFAULT_DEVICE_CACHE_TTL)
+    }
+
+    /// Reuse gamepad scans for `ttl` (`Duration::ZERO` disables caching)
+    pub fn new_with_cache_ttl(ttl: Duration) -> Self {
+        Self { cache_ttl: ttl, cache: Arc::new(Mutex::new(None)) }
+    }
+
+    /// Return the cached result if it is younger than the TTL, otherwise run `detect`
+    fn cached_or_detect(
+        &self,
+        now: Instant,
+        detect: impl FnOnce() -> anyhow::Result<InputDetectionResult>,
+    ) -> anyhow::Result<InputDetectionResult> {
+        if let Some((scanned_at, result)) = self.lock_cache().as_ref()
+            && now.saturating_duration_since(*scanned_at) < self.cache_ttl
+        {
+            tracing::debug!("Using cached gamepad list");
+            return Ok(result.clone());
+        }
+
+        self.detect_and_store(now, detect)
+    }
+
+    fn detect_and_store(
+        &self,
+        now: Instant,
+        detect: impl FnOnce() -> anyhow::Result<InputDetectionResult>,
+    ) -> anyhow::Result<InputDetectionResult> {
+        let result = detect()?;
+        *self.lock_cache() = Some((now, result.clone()));
+        Ok(result)
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, Option<(Instant, InputDetectionResult)>> {
+        // A panic while holding the lock cannot leave a half-written entry
+        self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
     }
 }
 
@@ -21,53 +68,66 @@ impl Default for LinuxInputManager {
 
 impl InputManager for LinuxInputManager {
     fn list_gamepads(&self) -> anyhow::Result<InputDetectionResult> {
-        use evdev::enumerate;
-
-        let devices: Vec<_> = enumerate().collect();
-
-        println!("Found {} input devices total", devices.len());
-
-        let mut result = InputDetectionResult { gamepad_info: Vec::new(), errors: Vec::new() };
-
-        for (path, device) in devices {
-            if is_gamepad(&device) {
-                let path_str = path.to_string_lossy().to_string();
-                match extract_gamepad_info(&device, &path_str) {
-                    Ok(info) => {
-                        println!(
-                            "✓ Detected: {} ({}) - {:?}",
-                            info.name, info.gamepad_type, info.capabilities
-                        );
-                        result.gamepad_info.push(info);
-                    }
-                    Err(err) => {
-                        let error_type = classify_error(&err);
-                        let device_err = InputDeviceError::new(path_str, error_type, err);
-                        println!("✗ Error: {}", device_err);
-                        result.errors.push(device_err);
-                    }
-                }
-            }
-        }
-
-        tracing::info!(
-            "Found {} gamepads ({} errors)",
-            result.gamepad_info.len(),
-            result.errors.len()
-        );
+        self.cached_or_detect(Instant::now(), detect_gamepads)
+    }
 
-        Ok(result)
+    fn list_gamepads_fresh(&self) -> anyhow::Result<InputDetectionResult> {
+        self.detect_and_store(Instant::now(), detect_gamepads)
     }
 
     fn open_gamepad(&self, path: &str) -> anyhow::Result<Box<dyn Gamepad>> {
         let gamepad = LinuxGamepad::open(path)?;
         Ok(Box::new(gamepad))
     }
+
+    fn open_gamepad_exclusive(&self, path: &str) -> anyhow::Result<Box<dyn Gamepad>> {
+        let gamepad = ExclusiveGamepad::open(path)?;
+        Ok(Box::new(gamepad))
+    }
+}
+
+/// Open every input device and collect the ones that look like gamepads
+fn detect_gamepads() -> anyhow::Result<InputDetectionResult> {
+    use evdev::enumerate;
+
+    let devices: Vec<_> = enumerate().collect();
+
+    tracing::debug!("Found {} input devices total", devices.len());
+
+    let mut result = InputDetectionResult { gamepad_info: Vec::new(), errors: Vec::new() };
+
+    for (path, device) in devices {
+        if is_gamepad(&device) {
+            let path_str = path.to_string_lossy().to_string();
+            match extract_gamepad_info(&device, &path_str) {
+                Ok(info) => {
+                    tracing::debug!(
+                        "Detected: {} ({}) - {:?}",
+                        info.name,
+                        info.gamepad_type,
+                        info.capabilities
+                    );
+                    result.gamepad_info.push(info);
+                }
+                Err(err) => {
+                    let error_type = classify_error(&err);
+                    let device_err = InputDeviceError::new(path_str, error_type, err);
+                    tracing::warn!("{}", device_err);
+                    result.errors.push(device_err);
+                }
+            }
+        }
+    }
+
+    tracing::info!("Found {} gamepads ({} errors)", result.gamepad_info.len(), result.errors.len());
+
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::GamepadInfo;
 
     #[test]
     fn test_list_devices() {
@@ -78,4 +138,77 @@ mod tests {
 
         println!("Result: {:?}", result);
     }
+
+    /// Detection stub that counts how often it runs
+    fn counting_detect(calls: &std::cell::Cell<u32>) -> anyhow::Result<InputDetectionResult> {
+        calls.set(calls.get() + 1);
+        Ok(InputDetectionResult::default())
+    }
+
+    #[test]
+    fn test_cache_reused_within_ttl() {
+        let manager = LinuxInputManager::new_with_cache_ttl(Duration::from_secs(1));
+        let calls = std::cell::Cell::new(0);
+        let start = Instant::now();
+
+        manager.cached_or_detect(start, || counting_detect(&calls)).unwrap();
+        manager
+            .cached_or_detect(start + Duration::from_millis(500), || counting_detect(&calls))
+            .unwrap();
+        assert_eq!(calls.get(), 1);
+
+        // Expired
+        manager
+            .cached_or_detect(start + Duration::from_secs(1), || counting_detect(&calls))
+            .unwrap();
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_fresh_scan_replaces_shared_cache() {
+        let manager = LinuxInputManager::new();
+        let calls = std::cell::Cell::new(0);
+        let now = Instant::now();
+
+        manager.cached_or_detect(now, || counting_detect(&calls)).unwrap();
+        // clones share the cache
+        manager
+            .clone()
+            .detect_and_store(now, || {
+                Ok(InputDetectionResult {
+                    gamepad_info: vec![GamepadInfo::test("/dev/input/event3", "Pad")],
+                    errors: vec![],
+                })
+            })
+            .unwrap();
+        let result = manager.cached_or_detect(now, || counting_detect(&calls)).unwrap();
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(result.gamepad_info.len(), 1);
+    }
+
+    #[test]
+    fn test_zero_ttl_disables_cache() {
+        let manager = LinuxInputManager::new_with_cache_ttl(Duration::ZERO);
+        let calls = std::cell::Cell::new(0);
+        let now = Instant::now();
+
+        manager.cached_or_detect(now, || counting_detect(&calls)).unwrap();
+        manager.cached_or_detect(now, || counting_detect(&calls)).unwrap();
+
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_failed_detection_is_not_cached() {
+        let manager = LinuxInputManager::new();
+        let calls = std::cell::Cell::new(0);
+        let now = Instant::now();
+
+        let result = manager.cached_or_detect(now, || anyhow::bail!("enumerate failed"));
+        assert!(result.is_err());
+
+        manager.cached_or_detect(now, || counting_detect(&calls)).unwrap();
+        assert_eq!(calls.get(), 1);
+    }
 }
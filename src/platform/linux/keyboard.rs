@@ -1,65 +1,98 @@
 // Virtual Keyboard Module
 
 use crate::{
-    event::KeyboardCode, output::keyboard::VirtualKeyboard,
+    event::{KEYBOARD_CODE_NAMES, KeyboardCode},
+    output::keyboard::{VirtualKeyboard, VirtualKeyboardError},
     platform::linux::converter::keyboard_code_to_evdev_key,
 };
 use anyhow::{Context, Result};
 use evdev::{AttributeSet, EventType, InputEvent as EvdevEvent, KeyCode, uinput::VirtualDevice};
-use std::path::PathBuf;
+use std::{collections::HashSet, path::PathBuf};
+
+/// How long `tap_key` holds a key down
+const TAP_HOLD_MS: u64 = 10;
 
 /// Concrete virtual keyboard backed by /dev/uinput
 pub struct LinuxVirtualKeyboard {
     device: VirtualDevice,
+    keys: AttributeSet<KeyCode>,
+    /// Keys pressed and not yet released, released again by `reset`
+    pressed_keys: HashSet<u16>,
 }
 
 impl LinuxVirtualKeyboard {
-    /// Create a new virtual keyboard device
+    /// Create a new virtual keyboard device that can emit every known key
     pub fn new(name: &str) -> Result<Self> {
-        // Build a key set including all common keyboard keys
-        let mut keys = AttributeSet::<KeyCode>::new();
-        for code in KeyCode::KEY_ESC.code()..=KeyCode::KEY_MICMUTE.code() {
-            keys.insert(KeyCode::new(code));
+        let all_keys: Vec<KeyboardCode> =
+            KEYBOARD_CODE_NAMES.iter().map(|(_, code)| *code).collect();
+        Self::with_custom_keys(name, &all_keys)
+    }
+
+    /// Create a virtual keyboard that registers only `keys` with the kernel
+    ///
+    /// Pressing any other key fails.
+    pub fn with_custom_keys(name: &str, keys: &[KeyboardCode]) -> Result<Self> {
+        let mut key_set = AttributeSet::<KeyCode>::new();
+        for code in keys {
+            key_set.insert(keyboard_code_to_evdev_key(*code));
         }
 
         // Build virtual device
         let device = VirtualDevice::builder()?
             .name(name)
-            .with_keys(&keys)?
+            .with_keys(&key_set)?
             .build()
             .context("Failed to create virtual keyboard")?;
 
-        tracing::info!("Virtual keyboard created: {}", name);
+        tracing::info!("Virtual keyboard created: {} ({} keys)", name, keys.len());
 
-        Ok(Self { device })
+        Ok(Self { device, keys: key_set, pressed_keys: HashSet::new() })
+    }
+
+    fn ensure_registered(&self, key: KeyCode) -> Result<()> {
+        if !self.keys.contains(key) {
+            anyhow::bail!("Key not registered in this virtual device: {:?}", key);
+        }
+        Ok(())
     }
 
     // Low-level helpers operating on key codes
     fn press_key_code(&mut self, code: u16) -> Result<()> {
         let key = KeyCode::new(code);
+        self.ensure_registered(key)?;
         self.device.emit(&[
             EvdevEvent::new(EventType::KEY.0, key.code(), 1),
             EvdevEvent::new(EventType::SYNCHRONIZATION.0, 0, 0),
         ])?;
+        self.pressed_keys.insert(code);
         Ok(())
     }
 
     fn release_key_code(&mut self, code: u16) -> Result<()> {
         let key = KeyCode::new(code);
+        self.ensure_registered(key)?;
         self.device.emit(&[
             EvdevEvent::new(EventType::KEY.0, key.code(), 0),
             EvdevEvent::new(EventType::SYNCHRONIZATION.0, 0, 0),
         ])?;
+        self.pressed_keys.remove(&code);
         Ok(())
     }
 
-    fn tap_key_code(&mut self, code: u16) -> Result<()> {
+    fn tap_key_code(&mut self, code: u16, hold_ms: u64) -> Result<()> {
         self.press_key_code(code)?;
-        std::thread::sleep(std::time::Duration::from_millis(10));
+        std::thread::sleep(std::time::Duration::from_millis(hold_ms));
         self.release_key_code(code)?;
         Ok(())
     }
 
+    /// Press `code`, hold it for `hold_ms`, then release it
+    ///
+    /// For games that tell a tap from a hold (e.g. roll vs run).
+    pub fn type_with_delay(&mut self, code: KeyboardCode, hold_ms: u64) -> Result<()> {
+        self.tap_key_code(keyboard_code_to_evdev_key(code).code(), hold_ms)
+    }
+
     pub fn sys_path(&mut self) -> Result<PathBuf> {
         self.device.get_syspath().context("Failed to get device sysfs path")
     }
@@ -67,24 +100,61 @@ impl LinuxVirtualKeyboard {
 
 impl Drop for LinuxVirtualKeyboard {
     fn drop(&mut self) {
-        // Cleanup handled by UInputDevice drop
+        // Don't leave keys stuck down when the device goes away
+        if let Err(e) = self.reset() {
+            tracing::warn!("Failed to release held keys: {:#}", e);
+        }
+        // Device cleanup handled by UInputDevice drop
     }
 }
 
 // Implement the domain trait for this concrete type
 impl VirtualKeyboard for LinuxVirtualKeyboard {
+    #[tracing::instrument(level = "trace", skip(self))]
     fn press_key(&mut self, code: KeyboardCode) -> Result<()> {
         self.press_key_code(keyboard_code_to_evdev_key(code).code())
     }
 
+    #[tracing::instrument(level = "trace", skip(self))]
     fn release_key(&mut self, code: KeyboardCode) -> Result<()> {
         self.release_key_code(keyboard_code_to_evdev_key(code).code())
     }
 
     fn tap_key(&mut self, code: KeyboardCode) -> Result<()> {
-        self.tap_key_code(keyboard_code_to_evdev_key(code).code())
+        self.tap_key_code(keyboard_code_to_evdev_key(code).code(), TAP_HOLD_MS)
     }
+
+    fn tap_key_held(&mut self, code: KeyboardCode, hold_ms: u64) -> Result<()> {
+        self.type_with_delay(code, hold_ms)
+    }
+
     fn sys_path(&mut self) -> Result<std::path::PathBuf> {
         self.sys_path()
     }
+
+    /// Succeeds only for keys that are already registered; uinput fixes a
+    /// device's keys when it is created
+    fn register_key(&mut self, code: KeyboardCode) -> Result<()> {
+        if self.keys.contains(keyboard_code_to_evdev_key(code)) {
+            return Ok(());
+        }
+        Err(VirtualKeyboardError::CannotRegisterAfterCreation { key: code }.into())
+    }
+
+    /// Release held keys, trying every key before reporting the first failure
+    fn reset(&mut self) -> Result<()> {
+        let held: Vec<u16> = self.pressed_keys.iter().copied().collect();
+        let mut first_error = None;
+        for code in held {
+            if let Err(e) = self.release_key_code(code) {
+                self.pressed_keys.remove(&code);
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
 }
@@ -1,11 +1,22 @@
 mod converter;
 mod errors;
+mod exclusive;
+mod file_watcher;
 mod gamepad;
 mod input_manager;
 mod keyboard;
+mod pid_file;
+mod signals;
+pub mod systemd;
+mod virtual_gamepad;
 
-pub use converter::evdev_to_input;
+pub use converter::{evdev_to_input, keyboard_code_to_evdev_key};
 pub use errors::LinuxError;
+pub use exclusive::ExclusiveGamepad;
+pub use file_watcher::FileWatcher;
 pub use gamepad::LinuxGamepad;
-pub use input_manager::LinuxInputManager;
+pub use input_manager::{DEFAULT_DEVICE_CACHE_TTL, LinuxInputManager};
 pub use keyboard::LinuxVirtualKeyboard;
+pub use pid_file::PidFile;
+pub use signals::{install_stop_handler, interrupt_stopping_thread, stop_requested};
+pub use virtual_gamepad::LinuxVirtualGamepad;
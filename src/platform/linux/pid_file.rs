@@ -0,0 +1,104 @@
+// PID File Module
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// File holding the daemon's PID, removed again when dropped
+///
+/// Only removed if it still holds this process's PID, so an instance started
+/// with `--allow-multiple` doesn't delete another instance's file.
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Write this process's PID to `path`
+    ///
+    /// Fails if the file names another process that is still running; a stale
+    /// file left by a crash is replaced.
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(pid) = read_pid(path).filter(|pid| *pid != std::process::id()) {
+            if is_running(pid) {
+                anyhow::bail!("Already running (PID {})", pid);
+            }
+            tracing::warn!("Replacing stale PID file {} (PID {})", path.display(), pid);
+        }
+        Self::overwrite(path)
+    }
+
+    /// Write this process's PID to `path` without checking for a running instance
+    pub fn overwrite(path: &Path) -> Result<Self> {
+        std::fs::write(path, format!("{}\n", std::process::id()))
+            .with_context(|| format!("Failed to write PID file {}", path.display()))?;
+        Ok(Self { path: path.to_path_buf() })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        if read_pid(&self.path) == Some(std::process::id()) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn is_running(pid: u32) -> bool {
+    Path::new(&format!("/proc/{}/status", pid)).exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pid_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("blazeremap-{}-{}.pid", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_create_writes_and_removes_pid() {
+        let path = pid_path("create");
+
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+
+        drop(pid_file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_create_refuses_running_pid() {
+        let path = pid_path("running");
+        // PID 1 always exists
+        std::fs::write(&path, "1\n").unwrap();
+
+        let err = PidFile::create(&path).unwrap_err();
+        assert_eq!(err.to_string(), "Already running (PID 1)");
+
+        let pid_file = PidFile::overwrite(&path).unwrap();
+        drop(pid_file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_create_replaces_stale_pid() {
+        let path = pid_path("stale");
+        // Above the kernel's PID limit, so never running
+        std::fs::write(&path, "99999999\n").unwrap();
+
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(read_pid(pid_file.path()), Some(std::process::id()));
+    }
+}
@@ -0,0 +1,86 @@
+// Stopping the daemon on SIGINT/SIGTERM
+use std::sync::{
+    OnceLock,
+    atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::{Context, Result};
+use nix::sys::{
+    pthread::{Pthread, pthread_kill, pthread_self},
+    signal::{SaFlags, SigAction, SigHandler, SigSet, Signal, sigaction},
+};
+
+static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Thread that installed the handler, woken up when another thread gets the signal
+static STOPPING_THREAD: OnceLock<Pthread> = OnceLock::new();
+
+extern "C" fn request_stop(signal: nix::libc::c_int) {
+    STOP_REQUESTED.store(true, Ordering::SeqCst);
+    // The kernel picks any thread for a process signal; forward it so the
+    // installing thread's blocking read is interrupted too
+    let other_thread = STOPPING_THREAD.get().filter(|&&thread| thread != pthread_self());
+    if let (Some(&thread), Ok(signal)) = (other_thread, Signal::try_from(signal)) {
+        let _ = pthread_kill(thread, signal);
+    }
+}
+
+/// Turn SIGINT and SIGTERM into a stop request instead of killing the process
+///
+/// The signals interrupt blocking reads on the calling thread (they are
+/// installed without `SA_RESTART`), so a loop waiting on a device can check
+/// [`stop_requested`] and unwind. One that arrives just before a read starts is
+/// only seen after the next event; sending it again interrupts that read.
+pub fn install_stop_handler() -> Result<()> {
+    STOPPING_THREAD.get_or_init(pthread_self);
+    let action =
+        SigAction::new(SigHandler::Handler(request_stop), SaFlags::empty(), SigSet::empty());
+    for signal in [Signal::SIGINT, Signal::SIGTERM] {
+        // SAFETY: the handler only touches atomics and calls async-signal-safe functions
+        unsafe { sigaction(signal, &action) }
+            .with_context(|| format!("Failed to install {} handler", signal))?;
+    }
+    Ok(())
+}
+
+/// Stop the thread that called [`install_stop_handler`] as if SIGTERM was
+/// received, interrupting the read it is blocked in
+///
+/// For stopping from another thread, e.g. when a timeout expires.
+pub fn interrupt_stopping_thread() -> Result<()> {
+    let thread = STOPPING_THREAD.get().context("Stop handler is not installed")?;
+    pthread_kill(*thread, Signal::SIGTERM).context("Failed to interrupt the stopping thread")
+}
+
+/// Whether SIGINT or SIGTERM was received since [`install_stop_handler`]
+pub fn stop_requested() -> bool {
+    STOP_REQUESTED.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, os::unix::net::UnixStream, time::Duration};
+
+    use nix::{sys::signal::kill, unistd::getpid};
+
+    use super::*;
+
+    #[test]
+    fn test_sigterm_interrupts_blocking_read() {
+        install_stop_handler().unwrap();
+        let (mut reader, _writer) = UnixStream::pair().unwrap();
+        // Fails the test instead of hanging if the read isn't interrupted
+        reader.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+        // Sent to the process, so any thread may get it before it is forwarded
+        let sender = std::thread::spawn(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            kill(getpid(), Signal::SIGTERM).unwrap();
+        });
+
+        let error = reader.read(&mut [0; 1]).unwrap_err();
+        sender.join().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::Interrupted);
+        assert!(stop_requested());
+    }
+}
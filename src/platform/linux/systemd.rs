@@ -0,0 +1,201 @@
+// systemd service unit management
+use std::{
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, Result};
+
+/// Where `service install` writes the unit
+pub const UNIT_PATH: &str = "/etc/systemd/system/blazeremap.service";
+
+/// Name of the unit as known to systemctl
+pub const UNIT_NAME: &str = "blazeremap";
+
+/// PID file the service writes, inside the `RuntimeDirectory` systemd creates for it
+pub const SERVICE_PID_FILE: &str = "/run/blazeremap/blazeremap.pid";
+
+/// Settings baked into the generated unit file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceConfig {
+    pub binary_path: PathBuf,
+    pub profile_path: Option<PathBuf>,
+    pub device_path: Option<String>,
+    /// Account the daemon runs as (root if not set)
+    pub user: Option<String>,
+}
+
+/// Quote one `ExecStart=` argument the way systemd splits command lines
+///
+/// `%` and `$` are doubled so systemd doesn't expand them as specifiers or
+/// environment variables. Arguments with anything beyond plain path characters
+/// are double-quoted with C-style escapes.
+fn quote_exec_arg(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "/._-+:,=@%$".contains(c);
+    let needs_quotes = arg.is_empty() || !arg.chars().all(plain);
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    if needs_quotes {
+        quoted.push('"');
+    }
+    for c in arg.chars() {
+        match c {
+            '%' => quoted.push_str("%%"),
+            '$' => quoted.push_str("$$"),
+            '\\' => quoted.push_str(r"\\"),
+            '"' => quoted.push_str(r#"\""#),
+            '\n' => quoted.push_str(r"\n"),
+            '\t' => quoted.push_str(r"\t"),
+            c => quoted.push(c),
+        }
+    }
+    if needs_quotes {
+        quoted.push('"');
+    }
+    quoted
+}
+
+/// Render the unit file contents for a config
+pub fn render_unit(config: &ServiceConfig) -> String {
+    let mut args = vec![
+        config.binary_path.to_string_lossy().into_owned(),
+        "run".to_string(),
+        "--pid-file".to_string(),
+        SERVICE_PID_FILE.to_string(),
+    ];
+    if let Some(profile) = &config.profile_path {
+        args.extend(["--profile".to_string(), profile.to_string_lossy().into_owned()]);
+    }
+    if let Some(device) = &config.device_path {
+        args.extend(["--device".to_string(), device.clone()]);
+    }
+    let exec_start = args.iter().map(|arg| quote_exec_arg(arg)).collect::<Vec<_>>().join(" ");
+
+    let mut unit = String::new();
+    unit.push_str("[Unit]\n");
+    unit.push_str("Description=BlazeRemap gamepad-to-keyboard remapper\n");
+    unit.push_str("After=systemd-udev-settle.service\n\n");
+    unit.push_str("[Service]\n");
+    unit.push_str(&format!("ExecStart={}\n", exec_start));
+    unit.push_str("Restart=on-failure\n");
+    unit.push_str("RuntimeDirectory=blazeremap\n");
+    unit.push_str(&format!("PIDFile={}\n", SERVICE_PID_FILE));
+    if let Some(user) = &config.user {
+        unit.push_str(&format!("User={}\n", user));
+    }
+    unit.push('\n');
+    unit.push_str("[Install]\n");
+    unit.push_str("WantedBy=default.target\n");
+    unit
+}
+
+/// Write the unit file for a config to `path`
+pub fn write_unit_file(path: &Path, config: &ServiceConfig) -> Result<()> {
+    std::fs::write(path, render_unit(config))
+        .with_context(|| format!("Failed to write unit file {}", path.display()))
+}
+
+/// Whether the current process runs as root
+pub fn is_root() -> bool {
+    // /proc/self is owned by the effective user of the process
+    std::fs::metadata("/proc/self").map(|m| m.uid() == 0).unwrap_or(false)
+}
+
+/// Run `systemctl <args>` and fail if it exits unsuccessfully
+pub fn systemctl(args: &[&str]) -> Result<()> {
+    let status = Command::new("systemctl")
+        .args(args)
+        .status()
+        .with_context(|| format!("Failed to run systemctl {}", args.join(" ")))?;
+
+    if !status.success() {
+        anyhow::bail!("systemctl {} failed ({})", args.join(" "), status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_unit_minimal() {
+        let config = ServiceConfig {
+            binary_path: PathBuf::from("/usr/local/bin/blazeremap"),
+            profile_path: None,
+            device_path: None,
+            user: None,
+        };
+
+        let unit = render_unit(&config);
+
+        assert!(unit.contains(
+            "ExecStart=/usr/local/bin/blazeremap run --pid-file /run/blazeremap/blazeremap.pid\n"
+        ));
+        assert!(unit.contains("Restart=on-failure\n"));
+        assert!(unit.contains("PIDFile=/run/blazeremap/blazeremap.pid\n"));
+        assert!(unit.contains("WantedBy=default.target\n"));
+        assert!(!unit.contains("User="));
+    }
+
+    #[test]
+    fn test_render_unit_full() {
+        let config = ServiceConfig {
+            binary_path: PathBuf::from("/usr/local/bin/blazeremap"),
+            profile_path: Some(PathBuf::from("/home/user/.config/blazeremap/default.toml")),
+            device_path: Some("/dev/input/event3".to_string()),
+            user: Some("user".to_string()),
+        };
+
+        let unit = render_unit(&config);
+
+        assert!(unit.contains(
+            "ExecStart=/usr/local/bin/blazeremap run --pid-file /run/blazeremap/blazeremap.pid --profile /home/user/.config/blazeremap/default.toml --device /dev/input/event3\n"
+        ));
+        assert!(unit.contains("User=user\n"));
+    }
+
+    #[test]
+    fn test_render_unit_quotes_paths() {
+        let config = ServiceConfig {
+            binary_path: PathBuf::from("/opt/Blaze Remap/blazeremap"),
+            profile_path: Some(PathBuf::from(r#"/home/user/My "Games"/100% $HOME.toml"#)),
+            device_path: None,
+            user: None,
+        };
+
+        let unit = render_unit(&config);
+
+        assert!(unit.contains(concat!(
+            r#"ExecStart="/opt/Blaze Remap/blazeremap" run --pid-file /run/blazeremap/blazeremap.pid "#,
+            r#"--profile "/home/user/My \"Games\"/100%% $$HOME.toml""#,
+            "\n"
+        )));
+    }
+
+    #[test]
+    fn test_quote_exec_arg() {
+        assert_eq!(quote_exec_arg("/dev/input/event3"), "/dev/input/event3");
+        assert_eq!(quote_exec_arg("50%"), "50%%");
+        assert_eq!(quote_exec_arg(""), r#""""#);
+        assert_eq!(quote_exec_arg(";"), r#"";""#);
+        assert_eq!(quote_exec_arg(r"a\b'c"), r#""a\\b'c""#);
+    }
+
+    #[test]
+    fn test_write_unit_file() {
+        let path = std::env::temp_dir().join("blazeremap_test_unit.service");
+        let config = ServiceConfig {
+            binary_path: PathBuf::from("/usr/bin/blazeremap"),
+            profile_path: None,
+            device_path: None,
+            user: None,
+        };
+
+        write_unit_file(&path, &config).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), render_unit(&config));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
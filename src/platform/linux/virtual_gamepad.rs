@@ -0,0 +1,65 @@
+// Virtual Gamepad Module
+
+use crate::{
+    event::AxisCode, output::gamepad::VirtualGamepad,
+    platform::linux::converter::axis_code_to_absolute_axis,
+};
+use anyhow::{Context, Result};
+use evdev::{
+    AbsInfo, AttributeSet, EventType, InputEvent as EvdevEvent, KeyCode, UinputAbsSetup,
+    uinput::VirtualDevice,
+};
+
+/// Value range the virtual gamepad reports for `code` (Xbox-style)
+pub fn axis_range(code: AxisCode) -> (i32, i32) {
+    code.standard_range()
+}
+
+/// Concrete virtual gamepad backed by /dev/uinput
+pub struct LinuxVirtualGamepad {
+    device: VirtualDevice,
+    axes: Vec<AxisCode>,
+}
+
+impl LinuxVirtualGamepad {
+    /// Create a virtual gamepad exposing `axes`
+    pub fn new(name: &str, axes: &[AxisCode]) -> Result<Self> {
+        // Face buttons make udev tag the device as a joystick so games pick it up
+        let mut keys = AttributeSet::<KeyCode>::new();
+        for key in [KeyCode::BTN_SOUTH, KeyCode::BTN_EAST, KeyCode::BTN_NORTH, KeyCode::BTN_WEST] {
+            keys.insert(key);
+        }
+
+        let mut builder = VirtualDevice::builder()?.name(name).with_keys(&keys)?;
+        for code in axes {
+            let abs = axis_code_to_absolute_axis(*code)
+                .with_context(|| format!("Cannot create virtual axis for {}", code))?;
+            let (min, max) = axis_range(*code);
+            builder = builder.with_absolute_axis(&UinputAbsSetup::new(
+                abs,
+                AbsInfo::new(0, min, max, 0, 0, 0),
+            ))?;
+        }
+        let device = builder.build().context("Failed to create virtual gamepad")?;
+
+        tracing::info!("Virtual gamepad created: {} ({} axes)", name, axes.len());
+
+        Ok(Self { device, axes: axes.to_vec() })
+    }
+}
+
+impl VirtualGamepad for LinuxVirtualGamepad {
+    fn set_axis(&mut self, code: AxisCode, value: i32) -> Result<()> {
+        if !self.axes.contains(&code) {
+            anyhow::bail!("Axis not registered in this virtual device: {}", code);
+        }
+        let abs = axis_code_to_absolute_axis(code).context("Unknown axis")?;
+        let (min, max) = axis_range(code);
+
+        self.device.emit(&[
+            EvdevEvent::new(EventType::ABSOLUTE.0, abs.0, value.clamp(min, max)),
+            EvdevEvent::new(EventType::SYNCHRONIZATION.0, 0, 0),
+        ])?;
+        Ok(())
+    }
+}
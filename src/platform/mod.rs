@@ -2,16 +2,113 @@
 
 pub mod linux;
 
+use std::{fmt, path::PathBuf};
+
+use crossbeam::channel::Receiver;
+
+use crate::event::{AxisCode, FileEvent, KeyboardCode};
 use crate::input::InputManager;
-use crate::output::keyboard::VirtualKeyboard;
+use crate::output::{gamepad::VirtualGamepad, keyboard::VirtualKeyboard};
+
+/// Operating systems BlazeRemap knows about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Linux,
+    MacOS,
+    Windows,
+    Unknown,
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Linux => write!(f, "Linux"),
+            Self::MacOS => write!(f, "macOS"),
+            Self::Windows => write!(f, "Windows"),
+            Self::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// Platform this binary was built for
+pub const fn current_platform() -> Platform {
+    if cfg!(target_os = "linux") {
+        Platform::Linux
+    } else if cfg!(target_os = "macos") {
+        Platform::MacOS
+    } else if cfg!(target_os = "windows") {
+        Platform::Windows
+    } else {
+        Platform::Unknown
+    }
+}
+
+/// Fail with a clear message on platforms without an adapter yet
+pub fn ensure_supported() -> anyhow::Result<()> {
+    let platform = current_platform();
+    if platform != Platform::Linux {
+        anyhow::bail!("Platform {} not yet supported", platform);
+    }
+    Ok(())
+}
 
 /// Create a device manager for the current platform
 /// For now, we only support Linux
-pub fn new_input_manager() -> Box<dyn InputManager> {
-    Box::new(linux::LinuxInputManager::new())
+pub fn new_input_manager() -> anyhow::Result<Box<dyn InputManager>> {
+    ensure_supported()?;
+    Ok(Box::new(linux::LinuxInputManager::new()))
 }
 
 /// Create a virtual keyboard for the current platform
 pub fn new_virtual_keyboard(name: &str) -> anyhow::Result<Box<dyn VirtualKeyboard>> {
+    ensure_supported()?;
     Ok(Box::new(linux::LinuxVirtualKeyboard::new(name)?))
 }
+
+/// Create a virtual keyboard that can only emit `keys`
+pub fn new_virtual_keyboard_with_keys(
+    name: &str,
+    keys: &[KeyboardCode],
+) -> anyhow::Result<Box<dyn VirtualKeyboard>> {
+    ensure_supported()?;
+    Ok(Box::new(linux::LinuxVirtualKeyboard::with_custom_keys(name, keys)?))
+}
+
+/// Watch a file for changes, e.g. a profile to reload
+pub fn watch_file(path: PathBuf) -> anyhow::Result<Receiver<FileEvent>> {
+    ensure_supported()?;
+    linux::FileWatcher::watch(path)
+}
+
+/// Linux input name of `key`, e.g. `KEY_SPACE`, as used by kernel drivers
+pub fn kernel_key_name(key: KeyboardCode) -> String {
+    format!("{:?}", linux::keyboard_code_to_evdev_key(key))
+}
+
+/// Create a virtual gamepad exposing `axes`
+pub fn new_virtual_gamepad(
+    name: &str,
+    axes: &[AxisCode],
+) -> anyhow::Result<Box<dyn VirtualGamepad>> {
+    ensure_supported()?;
+    Ok(Box::new(linux::LinuxVirtualGamepad::new(name, axes)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_platform_display() {
+        assert_eq!(Platform::Linux.to_string(), "Linux");
+        assert_eq!(Platform::MacOS.to_string(), "macOS");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_current_platform_linux() {
+        assert_eq!(current_platform(), Platform::Linux);
+        assert!(ensure_supported().is_ok());
+        assert!(new_input_manager().is_ok());
+    }
+}
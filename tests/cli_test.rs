@@ -13,6 +13,8 @@ fn test_cli_help() {
         .stdout(predicates::str::contains("Usage:"))
         .stdout(predicates::str::contains("Commands:"))
         .stdout(predicates::str::contains("detect"))
+        .stdout(predicates::str::contains("devices"))
+        .stdout(predicates::str::contains("controllers"))
         .stdout(predicates::str::contains("help"))
         .stdout(predicates::str::contains("Options:"))
         .stdout(predicates::str::contains("--help"))
@@ -47,7 +49,8 @@ fn test_version_flag() {
     cmd.assert()
         .success()
         .stdout(predicates::str::contains("blazeremap"))
-        .stdout(predicates::str::contains("0.1.0")); // Version from Cargo.toml
+        .stdout(predicates::str::contains("0.1.0")) // Version from Cargo.toml
+        .stdout(predicates::str::contains("(Linux)"));
 }
 
 #[test]
@@ -73,6 +76,16 @@ fn test_detect_with_verbose_flag() {
         .stdout(predicates::str::contains("Detecting gamepads"));
 }
 
+#[test]
+fn test_detect_aliases_with_verbose_flag() {
+    for alias in ["devices", "controllers"] {
+        let mut cmd = cargo_bin_cmd!("blazeremap");
+        cmd.arg(alias).arg("--verbose");
+
+        cmd.assert().success().stdout(predicates::str::contains("Detecting gamepads"));
+    }
+}
+
 #[test]
 fn test_detect_short_verbose_flag() {
     let mut cmd = cargo_bin_cmd!("blazeremap");
@@ -9,7 +9,7 @@ fn test_real_hardware_latency() {
 
     wait_for_user("Connect your controller and prepare to rapidly press buttons");
 
-    let manager = blazeremap::platform::new_input_manager();
+    let manager = blazeremap::platform::new_input_manager().unwrap();
     let gamepads = manager.list_gamepads().unwrap();
     assert!(!gamepads.gamepad_info.is_empty());
 
@@ -50,6 +50,9 @@ fn test_real_hardware_latency() {
                                 _ => Some(()),
                             };
                         }
+                        blazeremap::event::OutputEvent::Rumble { .. }
+                        | blazeremap::event::OutputEvent::GamepadAxis { .. }
+                        | blazeremap::event::OutputEvent::MediaKey { .. } => {}
                     }
                 }
 
@@ -11,14 +11,12 @@ fn find_device_by_name(name: &str) -> Option<String> {
         let entry = entry.ok()?;
         let path = entry.path();
 
-        if path.to_str()?.contains("event") {
-            if let Ok(device) = Device::open(&path) {
-                if let Some(device_name) = device.name() {
-                    if device_name.contains(name) {
-                        return Some(path.to_string_lossy().to_string());
-                    }
-                }
-            }
+        if path.to_str()?.contains("event")
+            && let Ok(device) = Device::open(&path)
+            && let Some(device_name) = device.name()
+            && device_name.contains(name)
+        {
+            return Some(path.to_string_lossy().to_string());
         }
     }
     None
@@ -74,6 +72,25 @@ fn test_virtual_keyboard_tap() {
     println!("✓ Key tap successful");
 }
 
+#[test]
+#[ignore]
+fn test_virtual_keyboard_custom_keys() {
+    let mut keyboard = LinuxVirtualKeyboard::with_custom_keys(
+        "BlazeRemap Custom Keys Test",
+        &[KeyboardCode::W, KeyboardCode::Space],
+    )
+    .expect("Failed to create virtual keyboard");
+
+    let result = keyboard.tap_key(KeyboardCode::Space);
+    assert!(result.is_ok(), "Failed to tap registered key: {:?}", result.err());
+
+    // Keys outside the custom set are rejected before reaching the kernel
+    let err = keyboard.press_key(KeyboardCode::A).unwrap_err();
+    assert!(err.to_string().contains("Key not registered in this virtual device"));
+
+    println!("✓ Custom key set enforced");
+}
+
 #[test]
 #[ignore]
 fn test_virtual_keyboard_multiple_keys() {
@@ -83,7 +100,7 @@ fn test_virtual_keyboard_multiple_keys() {
     let keys = [KeyboardCode::A, KeyboardCode::B, KeyboardCode::C, KeyboardCode::Space];
 
     for key in &keys {
-        let result = keyboard.tap_key(key.clone());
+        let result = keyboard.tap_key(*key);
         assert!(result.is_ok(), "Failed to tap {:?}: {:?}", key, result.err());
         thread::sleep(Duration::from_millis(10));
     }